@@ -0,0 +1,127 @@
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// Tries to serve a waiting client now that `key` may have something for it, returning the
+/// client's reply if it did.
+pub type Serve = Box<dyn FnMut(&mut Keyspace, &[u8]) -> Option<Frame> + Send>;
+
+/// What a command that may block produced: either its reply straight away, or a client that
+/// has to wait for one.
+pub enum Outcome {
+    Ready(Frame),
+    Blocked(Blocked),
+}
+
+/// The connection's side of a blocked client. The reply arrives on `receiver` once another
+/// client writes to one of the keys; if `timeout` runs out first the connection unblocks the
+/// client itself and sends `timeout_reply` instead.
+pub struct Blocked {
+    pub id: u64,
+    pub receiver: Receiver<Frame>,
+    pub timeout: Option<Duration>,
+    pub timeout_reply: Frame,
+}
+
+/// The keyspace's side of a blocked client.
+pub struct Waiter {
+    id: u64,
+    keys: Vec<Vec<u8>>,
+    serve: Serve,
+    reply: Sender<Frame>,
+}
+
+impl Waiter {
+    /// Returns true if the client got its reply and is no longer blocked.
+    pub fn serve(&mut self, ks: &mut Keyspace, key: &[u8]) -> bool {
+        match (self.serve)(ks, key) {
+            Some(reply) => {
+                // The connection may have gone away since; there's no one left to tell.
+                let _ = self.reply.send(reply);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Clients blocked on keys, kept in the order they blocked so that the longest waiting client
+/// is served first, along with the keys written to since they were last looked at.
+#[derive(Default)]
+pub struct BlockedClients {
+    waiters: Vec<Waiter>,
+    ready: VecDeque<Vec<u8>>,
+    next_id: u64,
+}
+
+impl BlockedClients {
+    pub fn block(
+        &mut self,
+        keys: Vec<Vec<u8>>,
+        timeout: Option<Duration>,
+        timeout_reply: Frame,
+        serve: Serve,
+    ) -> Blocked {
+        let (sender, receiver) = mpsc::channel();
+        self.next_id += 1;
+
+        self.waiters.push(Waiter {
+            id: self.next_id,
+            keys,
+            serve,
+            reply: sender,
+        });
+
+        Blocked {
+            id: self.next_id,
+            receiver,
+            timeout,
+            timeout_reply,
+        }
+    }
+
+    /// Stops a client waiting. Returns false if it isn't waiting any more because it has
+    /// already been served.
+    pub fn unblock(&mut self, id: u64) -> bool {
+        self.take(id).is_some()
+    }
+
+    /// Notes that `key` was written to, if anyone is waiting on it.
+    pub fn signal_ready(&mut self, key: &[u8]) {
+        let waited_on = self
+            .waiters
+            .iter()
+            .any(|waiter| waiter.keys.iter().any(|k| k == key));
+
+        if waited_on && !self.ready.iter().any(|k| k == key) {
+            self.ready.push_back(key.to_vec());
+        }
+    }
+
+    pub fn next_ready(&mut self) -> Option<Vec<u8>> {
+        self.ready.pop_front()
+    }
+
+    /// The clients waiting on `key`, longest waiting first.
+    pub fn waiting_on(&self, key: &[u8]) -> Vec<u64> {
+        self.waiters
+            .iter()
+            .filter(|waiter| waiter.keys.iter().any(|k| k == key))
+            .map(|waiter| waiter.id)
+            .collect()
+    }
+
+    pub fn take(&mut self, id: u64) -> Option<Waiter> {
+        let index = self.waiters.iter().position(|waiter| waiter.id == id)?;
+        Some(self.waiters.remove(index))
+    }
+
+    /// Puts back a client that couldn't be served, keeping its place in the queue.
+    pub fn restore(&mut self, waiter: Waiter) {
+        let index = self.waiters.partition_point(|w| w.id < waiter.id);
+        self.waiters.insert(index, waiter);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::pubsub::Subscriber;
+use crate::resp::types::{Encoded, Frame, Protocol};
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// State that belongs to a single connection and lives for as long as it does.
+pub struct Client {
+    pub id: u64,
+    pub protocol: Protocol,
+    /// How many channels, patterns and shard channels the client is subscribed to.
+    pub subscriptions: usize,
+    /// The transaction begun with MULTI, until EXEC or DISCARD.
+    pub transaction: Option<Transaction>,
+    /// Everything sent to the client, replies and published messages alike, goes through here
+    /// so that it is written out in the order it was sent.
+    output: Sender<Vec<u8>>,
+}
+
+impl Client {
+    pub fn new(output: Sender<Vec<u8>>) -> Client {
+        Client {
+            id: NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed),
+            protocol: Protocol::default(),
+            subscriptions: 0,
+            transaction: None,
+            output,
+        }
+    }
+
+    /// Sends a reply, encoded for the protocol the client speaks.
+    pub fn send(&self, frame: &Frame) {
+        // The connection's writer only goes away once the client has hung up.
+        let _ = self.output.send(frame.encode(self.protocol));
+    }
+
+    /// Where messages published to the client's channels go.
+    pub fn subscriber(&self) -> Subscriber {
+        Subscriber {
+            id: self.id,
+            protocol: self.protocol,
+            sender: self.output.clone(),
+        }
+    }
+
+    /// A RESP2 client that is subscribed to anything may only manage its subscriptions, since
+    /// it couldn't tell replies apart from messages. RESP3 clients get messages as pushes.
+    pub fn in_subscribed_mode(&self) -> bool {
+        self.protocol == Protocol::Resp2 && self.subscriptions > 0
+    }
+}
+
+/// Commands queued between MULTI and EXEC, as their names and arguments.
+#[derive(Default)]
+pub struct Transaction {
+    pub commands: Vec<(String, VecDeque<Vec<u8>>)>,
+    /// Set when a command couldn't be queued, which makes EXEC discard the transaction.
+    pub failed: bool,
+}
@@ -1,2 +1,689 @@
+pub mod append;
+pub mod bgsave;
+pub mod bitcount;
+pub mod bitfield;
+pub mod bitfield_ro;
+pub mod bitop;
+pub mod bitpos;
+pub mod blmove;
+pub mod blmpop;
+pub mod blpop;
+pub mod brpop;
+pub mod bzpopmax;
+pub mod bzpopmin;
+pub mod command;
+pub mod config;
+pub mod decr;
+pub mod decrby;
+pub mod discard;
 pub mod echo;
+pub mod eval;
+pub mod eval_ro;
+pub mod evalsha;
+pub mod evalsha_ro;
+pub mod exec;
+pub mod expire;
+pub mod expireat;
+pub mod expiretime;
+pub mod fcall;
+pub mod fcall_ro;
+pub mod flushall;
+pub mod flushdb;
+pub mod function;
+pub mod geoadd;
+pub mod geodist;
+pub mod geohash;
+pub mod geopos;
+pub mod geosearch;
+pub mod geosearchstore;
+pub mod get;
+pub mod getbit;
+pub mod getdel;
+pub mod getex;
+pub mod getrange;
+pub mod getset;
+pub mod hdel;
+pub mod hello;
+pub mod hexists;
+pub mod hexpire;
+pub mod hget;
+pub mod hgetall;
+pub mod hincrby;
+pub mod hincrbyfloat;
+pub mod hkeys;
+pub mod hlen;
+pub mod hmget;
+pub mod hpersist;
+pub mod hpexpire;
+pub mod hpttl;
+pub mod hrandfield;
+pub mod hscan;
+pub mod hset;
+pub mod hsetnx;
+pub mod hstrlen;
+pub mod httl;
+pub mod hvals;
+pub mod incr;
+pub mod incrby;
+pub mod incrbyfloat;
+pub mod lastsave;
+pub mod lcs;
+pub mod lindex;
+pub mod linsert;
+pub mod llen;
+pub mod lmove;
+pub mod lmpop;
+pub mod lpop;
+pub mod lpos;
+pub mod lpush;
+pub mod lpushx;
+pub mod lrange;
+pub mod lrem;
+pub mod lset;
+pub mod ltrim;
+pub mod mget;
+pub mod mset;
+pub mod msetnx;
+pub mod multi;
+pub mod persist;
+pub mod pexpire;
+pub mod pexpireat;
+pub mod pexpiretime;
+pub mod pfadd;
+pub mod pfcount;
+pub mod pfmerge;
 pub mod ping;
+pub mod psetex;
+pub mod psubscribe;
+pub mod pttl;
+pub mod publish;
+pub mod pubsub;
+pub mod punsubscribe;
+pub mod rpop;
+pub mod rpush;
+pub mod rpushx;
+pub mod sadd;
+pub mod save;
+pub mod scard;
+pub mod script;
+pub mod sdiff;
+pub mod sdiffstore;
+pub mod set;
+pub mod setbit;
+pub mod setex;
+pub mod setnx;
+pub mod setrange;
+pub mod shutdown;
+pub mod sinter;
+pub mod sintercard;
+pub mod sinterstore;
+pub mod sismember;
+pub mod smembers;
+pub mod smismember;
+pub mod smove;
+pub mod spop;
+pub mod spublish;
+pub mod srandmember;
+pub mod srem;
+pub mod sscan;
+pub mod ssubscribe;
+pub mod strlen;
+pub mod subscribe;
+pub mod sunion;
+pub mod sunionstore;
+pub mod sunsubscribe;
+pub mod ttl;
+pub mod unsubscribe;
+pub mod unwatch;
+pub mod watch;
+pub mod xack;
+pub mod xadd;
+pub mod xautoclaim;
+pub mod xclaim;
+pub mod xdel;
+pub mod xgroup;
+pub mod xinfo;
+pub mod xlen;
+pub mod xpending;
+pub mod xrange;
+pub mod xread;
+pub mod xreadgroup;
+pub mod xrevrange;
+pub mod xtrim;
+pub mod zadd;
+pub mod zcard;
+pub mod zcount;
+pub mod zincrby;
+pub mod zinterstore;
+pub mod zmscore;
+pub mod zpopmax;
+pub mod zpopmin;
+pub mod zrange;
+pub mod zrangestore;
+pub mod zrank;
+pub mod zrem;
+pub mod zrevrank;
+pub mod zscan;
+pub mod zscore;
+pub mod zunionstore;
+
+use crate::db::{now_ms, Hash, Keyspace, SortedSet, Stream, StreamId, Value};
+use crate::glob::glob_match;
+use crate::hyperloglog;
+use crate::resp::decoder::{parse_integer, MAX_BULK_LEN};
+use crate::resp::types::Frame;
+
+use indexmap::IndexSet;
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Range;
+use std::time::Duration;
+
+/// Why a command was refused. Each one becomes an error reply worded the way Redis words it, so
+/// clients that match on the messages keep working.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    WrongArity(&'static str),
+    Syntax,
+    NotAnInteger,
+    NotAFloat,
+    Overflow,
+    StringTooLong,
+    InvalidExpireTime(&'static str),
+    WrongType,
+    /// Anything else, already prefixed with its error code.
+    Other(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::WrongArity(name) => {
+                write!(f, "ERR wrong number of arguments for '{}' command", name)
+            }
+            CommandError::Syntax => write!(f, "ERR syntax error"),
+            CommandError::NotAnInteger => write!(f, "ERR value is not an integer or out of range"),
+            CommandError::NotAFloat => write!(f, "ERR value is not a valid float"),
+            CommandError::Overflow => write!(f, "ERR increment or decrement would overflow"),
+            CommandError::StringTooLong => write!(
+                f,
+                "ERR string exceeds maximum allowed size (proto-max-bulk-len)"
+            ),
+            CommandError::InvalidExpireTime(name) => {
+                write!(f, "ERR invalid expire time in '{}' command", name)
+            }
+            CommandError::WrongType => write!(
+                f,
+                "WRONGTYPE Operation against a key holding the wrong kind of value"
+            ),
+            CommandError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<CommandError> for Frame {
+    fn from(e: CommandError) -> Frame {
+        Frame::Error(e.to_string())
+    }
+}
+
+impl From<hyperloglog::Error> for CommandError {
+    fn from(e: hyperloglog::Error) -> CommandError {
+        CommandError::Other(String::from(match e {
+            hyperloglog::Error::Invalid => "WRONGTYPE Key is not a valid HyperLogLog string value.",
+            hyperloglog::Error::Corrupted => "INVALIDOBJ Corrupted HLL object detected",
+        }))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, CommandError>;
+
+/// The string stored at `key`, if there is one. A key holding another type is an error.
+pub fn get_string<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut Vec<u8>>> {
+    match ks.get_mut(key) {
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(CommandError::WrongType),
+        None => Ok(None),
+    }
+}
+
+/// The HyperLogLog stored at `key`, if there is one. A string that isn't one is an error.
+pub fn get_hll<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut Vec<u8>>> {
+    match get_string(ks, key)? {
+        Some(hll) => {
+            hyperloglog::validate(hll)?;
+            Ok(Some(hll))
+        }
+        None => Ok(None),
+    }
+}
+
+/// The list stored at `key`, if there is one. A key holding another type is an error.
+pub fn get_list<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut VecDeque<Vec<u8>>>> {
+    match ks.get_mut(key) {
+        Some(Value::List(list)) => Ok(Some(list)),
+        Some(_) => Err(CommandError::WrongType),
+        None => Ok(None),
+    }
+}
+
+/// The hash stored at `key`, if there is one. A key holding another type is an error.
+pub fn get_hash<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut Hash>> {
+    match ks.get_mut(key) {
+        Some(Value::Hash(hash)) => Ok(Some(hash)),
+        Some(_) => Err(CommandError::WrongType),
+        None => Ok(None),
+    }
+}
+
+/// The set stored at `key`, if there is one. A key holding another type is an error.
+pub fn get_set<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut IndexSet<Vec<u8>>>> {
+    match ks.get_mut(key) {
+        Some(Value::Set(set)) => Ok(Some(set)),
+        Some(_) => Err(CommandError::WrongType),
+        None => Ok(None),
+    }
+}
+
+/// Copies of the sets at `keys` for the set algebra commands, where a missing key counts as an
+/// empty set. Any key holding another type is an error.
+pub fn get_sets(ks: &mut Keyspace, keys: &[Vec<u8>]) -> Result<Vec<IndexSet<Vec<u8>>>> {
+    keys.iter()
+        .map(|key| Ok(get_set(ks, key)?.cloned().unwrap_or_default()))
+        .collect()
+}
+
+/// The sorted set stored at `key`, if there is one. A key holding another type is an error.
+pub fn get_zset<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut SortedSet>> {
+    match ks.get_mut(key) {
+        Some(Value::SortedSet(zset)) => Ok(Some(zset)),
+        Some(_) => Err(CommandError::WrongType),
+        None => Ok(None),
+    }
+}
+
+/// The stream stored at `key`, if there is one. A key holding another type is an error.
+pub fn get_stream<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<Option<&'a mut Stream>> {
+    match ks.get_mut(key) {
+        Some(Value::Stream(stream)) => Ok(Some(stream)),
+        Some(_) => Err(CommandError::WrongType),
+        None => Ok(None),
+    }
+}
+
+/// Parses a stream ID, where a missing sequence number is taken to be `missing_seq`.
+pub fn parse_stream_id(arg: &[u8], missing_seq: u64) -> Result<StreamId> {
+    StreamId::parse(arg, missing_seq).ok_or_else(|| {
+        CommandError::Other(String::from(
+            "ERR Invalid stream ID specified as stream command argument",
+        ))
+    })
+}
+
+pub fn parse_i64(arg: &[u8]) -> Result<i64> {
+    parse_integer(arg).ok_or(CommandError::NotAnInteger)
+}
+
+/// Parses a float the way Redis does: no surrounding spaces, and no NaN or infinities.
+pub fn parse_f64(arg: &[u8]) -> Result<f64> {
+    let text = std::str::from_utf8(arg).map_err(|_| CommandError::NotAFloat)?;
+    if text.is_empty() || text.trim() != text {
+        return Err(CommandError::NotAFloat);
+    }
+
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(CommandError::NotAFloat),
+    }
+}
+
+/// Parses a sorted set score. Unlike other floats, scores may be infinite.
+pub fn parse_score(arg: &[u8]) -> Result<f64> {
+    let text = std::str::from_utf8(arg).map_err(|_| CommandError::NotAFloat)?;
+    if text.is_empty() || text.trim() != text {
+        return Err(CommandError::NotAFloat);
+    }
+
+    match text.parse::<f64>() {
+        Ok(value) if !value.is_nan() => Ok(value),
+        _ => Err(CommandError::NotAFloat),
+    }
+}
+
+/// A range of scores such as `(1 +inf`, where a `(` makes that end exclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreRange {
+    pub min: f64,
+    pub min_exclusive: bool,
+    pub max: f64,
+    pub max_exclusive: bool,
+}
+
+impl ScoreRange {
+    pub fn parse(min: &[u8], max: &[u8]) -> Result<ScoreRange> {
+        let bound = |arg: &[u8]| -> Result<(f64, bool)> {
+            let (exclusive, score) = match arg.strip_prefix(b"(") {
+                Some(score) => (true, score),
+                None => (false, arg),
+            };
+            let score = parse_score(score)
+                .map_err(|_| CommandError::Other(String::from("ERR min or max is not a float")))?;
+            Ok((score, exclusive))
+        };
+
+        let (min, min_exclusive) = bound(min)?;
+        let (max, max_exclusive) = bound(max)?;
+        Ok(ScoreRange {
+            min,
+            min_exclusive,
+            max,
+            max_exclusive,
+        })
+    }
+
+    /// The ranks of the elements of `zset` within the range.
+    pub fn ranks(&self, zset: &SortedSet) -> Range<usize> {
+        let start = zset
+            .count_before(|score, _| score < self.min || (self.min_exclusive && score == self.min));
+        let end = zset.count_before(|score, _| {
+            score < self.max || (!self.max_exclusive && score == self.max)
+        });
+        start..end.max(start)
+    }
+}
+
+/// One end of a range of members, for sorted sets whose members all have the same score.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexBound {
+    Min,
+    Max,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    /// Parses `-`, `+`, `[member` or `(member`.
+    pub fn parse(arg: &[u8]) -> Result<LexBound> {
+        match arg.split_first() {
+            Some((b'-', [])) => Ok(LexBound::Min),
+            Some((b'+', [])) => Ok(LexBound::Max),
+            Some((b'[', member)) => Ok(LexBound::Inclusive(member.to_vec())),
+            Some((b'(', member)) => Ok(LexBound::Exclusive(member.to_vec())),
+            _ => Err(CommandError::Other(String::from(
+                "ERR min or max not valid string range item",
+            ))),
+        }
+    }
+
+    /// Whether `member` sorts before a range starting at this bound.
+    fn before_start(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(bound) => member < bound.as_slice(),
+            LexBound::Exclusive(bound) => member <= bound.as_slice(),
+        }
+    }
+
+    /// Whether `member` sorts before the end of a range ending at this bound.
+    fn before_end(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(bound) => member <= bound.as_slice(),
+            LexBound::Exclusive(bound) => member < bound.as_slice(),
+        }
+    }
+}
+
+/// The ranks of the elements of `zset` between `min` and `max`.
+pub fn lex_ranks(zset: &SortedSet, min: &LexBound, max: &LexBound) -> Range<usize> {
+    let start = zset.count_before(|_, member| min.before_start(member));
+    let end = zset.count_before(|_, member| max.before_end(member));
+    start..end.max(start)
+}
+
+/// The arguments shared by HSCAN, SSCAN and ZSCAN: `cursor [MATCH pattern] [COUNT count]`,
+/// plus a flag some of them accept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanArgs {
+    pub cursor: u64,
+    pub pattern: Option<Vec<u8>>,
+    pub count: usize,
+    pub flag: bool,
+}
+
+impl ScanArgs {
+    pub fn parse(args: &mut VecDeque<Vec<u8>>, flag: Option<&[u8]>) -> Result<ScanArgs> {
+        let cursor = std::str::from_utf8(&args.pop_front().unwrap())
+            .ok()
+            .and_then(|cursor| cursor.parse::<u64>().ok())
+            .ok_or_else(|| CommandError::Other(String::from("ERR invalid cursor")))?;
+
+        let mut scan = ScanArgs {
+            cursor,
+            pattern: None,
+            count: 10,
+            flag: false,
+        };
+
+        while let Some(option) = args.pop_front() {
+            let option = option.to_ascii_uppercase();
+            match option.as_slice() {
+                b"MATCH" if !args.is_empty() => scan.pattern = args.pop_front(),
+                b"COUNT" if !args.is_empty() => match parse_i64(&args.pop_front().unwrap())? {
+                    count if count < 1 => return Err(CommandError::Syntax),
+                    count => scan.count = count as usize,
+                },
+                _ if Some(option.as_slice()) == flag => scan.flag = true,
+                _ => return Err(CommandError::Syntax),
+            }
+        }
+
+        Ok(scan)
+    }
+
+    /// Picks the next batch of items and returns it along with the cursor to continue from,
+    /// which is 0 once everything has been seen. `item` gives the item at each position below
+    /// `len`, with the name it is matched by, or `None` for a position that is empty.
+    ///
+    /// Positions are visited from the top down, `count` of them per call, and the cursor is the
+    /// position to carry on below, so a call costs O(count). Removing an element only ever moves
+    /// another one down from above, and new elements go on top, so an element left where it is
+    /// stays below the cursor until it is returned. Anything present for the whole scan is
+    /// therefore returned, however the collection changes in between calls, if sometimes twice.
+    pub fn scan<'a, T>(
+        &self,
+        len: usize,
+        item: impl Fn(usize) -> Option<(&'a [u8], T)>,
+    ) -> (u64, Vec<T>) {
+        let end = match self.cursor {
+            0 => len,
+            cursor => len.min(usize::try_from(cursor).unwrap_or(usize::MAX)),
+        };
+        let start = end.saturating_sub(self.count);
+
+        let batch = (start..end)
+            .rev()
+            .filter_map(item)
+            .filter(|(name, _)| match &self.pattern {
+                Some(pattern) => glob_match(pattern, name),
+                None => true,
+            })
+            .map(|(_, item)| item)
+            .collect();
+
+        (start as u64, batch)
+    }
+}
+
+/// Parses the timeout of a blocking command, given in seconds. A timeout of 0 means waiting
+/// forever, which is returned as `None`.
+pub fn parse_timeout(arg: &[u8]) -> Result<Option<Duration>> {
+    let out_of_range =
+        || CommandError::Other(String::from("ERR timeout is not a float or out of range"));
+
+    let timeout = parse_f64(arg).map_err(|_| out_of_range())?;
+    if timeout < 0.0 {
+        return Err(CommandError::Other(String::from("ERR timeout is negative")));
+    }
+    if timeout == 0.0 {
+        return Ok(None);
+    }
+
+    Duration::try_from_secs_f64(timeout)
+        .map(Some)
+        .map_err(|_| out_of_range())
+}
+
+/// Strings can grow to the same size as the largest bulk string a client may send.
+pub fn check_string_length(len: usize) -> Result<()> {
+    if len > MAX_BULK_LEN {
+        return Err(CommandError::StringTooLong);
+    }
+    Ok(())
+}
+
+/// Parses an offset into a string in bits. BITFIELD also takes `#n` for the `n`th field of
+/// `width` bits, which it passes as `width`.
+pub fn parse_bit_offset(arg: &[u8], width: Option<u64>) -> Result<u64> {
+    let invalid = || {
+        CommandError::Other(String::from(
+            "ERR bit offset is not an integer or out of range",
+        ))
+    };
+
+    let offset = match (arg.strip_prefix(b"#"), width) {
+        (Some(index), Some(width)) => parse_integer(index)
+            .and_then(|index| index.checked_mul(width as i64))
+            .ok_or_else(invalid)?,
+        _ => parse_integer(arg).ok_or_else(invalid)?,
+    };
+    if offset < 0 || (offset >> 3) as u64 >= MAX_BULK_LEN as u64 {
+        return Err(invalid());
+    }
+    Ok(offset as u64)
+}
+
+/// Resolves the inclusive `start` and `stop` offsets of a range over `len` items, where negative
+/// offsets count back from the end. Returns `None` if the range is empty.
+pub fn clamp_range(start: i64, stop: i64, len: usize) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+
+    if start > stop || start >= len {
+        return None;
+    }
+    Some((start as usize, stop as usize))
+}
+
+/// Resolves a single offset into `len` items, where negative offsets count back from the end.
+pub fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    if index < 0 || index >= len as i64 {
+        return None;
+    }
+    Some(index as usize)
+}
+
+/// Which end of a list to push to or pop from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListEnd {
+    Left,
+    Right,
+}
+
+pub fn parse_list_end(arg: &[u8]) -> Result<ListEnd> {
+    match arg.to_ascii_uppercase().as_slice() {
+        b"LEFT" => Ok(ListEnd::Left),
+        b"RIGHT" => Ok(ListEnd::Right),
+        _ => Err(CommandError::Syntax),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeUnit {
+    Seconds,
+    Milliseconds,
+}
+
+/// Recognises the EX, PX, EXAT and PXAT options shared by commands that set an expiry time,
+/// returning the unit the time is given in and whether it counts from now.
+pub fn expire_option(option: &[u8]) -> Option<(TimeUnit, bool)> {
+    match option.to_ascii_uppercase().as_slice() {
+        b"EX" => Some((TimeUnit::Seconds, true)),
+        b"PX" => Some((TimeUnit::Milliseconds, true)),
+        b"EXAT" => Some((TimeUnit::Seconds, false)),
+        b"PXAT" => Some((TimeUnit::Milliseconds, false)),
+        _ => None,
+    }
+}
+
+/// Turns the expire time given to a command into milliseconds since the epoch. `relative` times
+/// count from now, the rest are Unix timestamps. Times that aren't positive or that overflow are
+/// refused the way Redis refuses them, naming `command`.
+pub fn expire_at(
+    amount: i64,
+    unit: TimeUnit,
+    relative: bool,
+    command: &'static str,
+) -> Result<u64> {
+    let invalid = CommandError::InvalidExpireTime(command);
+    if amount <= 0 {
+        return Err(invalid);
+    }
+
+    let ms = match unit {
+        TimeUnit::Seconds => amount.checked_mul(1000).ok_or(invalid.clone())?,
+        TimeUnit::Milliseconds => amount,
+    };
+
+    if relative {
+        ms.checked_add(now_ms() as i64)
+            .map(|when| when as u64)
+            .ok_or(invalid)
+    } else {
+        Ok(ms as u64)
+    }
+}
+
+/// Builds an argument list for calling a command directly.
+#[cfg(test)]
+pub fn args(list: &[&str]) -> VecDeque<Vec<u8>> {
+    list.iter().map(|arg| arg.as_bytes().to_vec()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_range() {
+        assert_eq!(clamp_range(0, -1, 5), Some((0, 4)));
+        assert_eq!(clamp_range(-100, 100, 5), Some((0, 4)));
+        assert_eq!(clamp_range(-2, -1, 5), Some((3, 4)));
+        assert_eq!(clamp_range(3, 1, 5), None);
+        assert_eq!(clamp_range(5, 10, 5), None);
+        assert_eq!(clamp_range(0, -6, 5), None);
+        assert_eq!(clamp_range(0, -1, 0), None);
+    }
+
+    #[test]
+    fn test_resolve_index() {
+        assert_eq!(resolve_index(0, 3), Some(0));
+        assert_eq!(resolve_index(-1, 3), Some(2));
+        assert_eq!(resolve_index(3, 3), None);
+        assert_eq!(resolve_index(-4, 3), None);
+    }
+}
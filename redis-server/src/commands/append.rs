@@ -0,0 +1,47 @@
+use crate::commands::{check_string_length, get_string, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// APPEND key value
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let value = args.pop_front().unwrap();
+
+    match get_string(ks, &key)? {
+        Some(current) => {
+            check_string_length(current.len() + value.len())?;
+            current.extend(value);
+            Ok(Frame::Integer(current.len() as i64))
+        }
+        None => {
+            let len = value.len();
+            ks.set(key, Value::String(value));
+            Ok(Frame::Integer(len as i64))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_append() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["key", "Hello"]), &mut ks),
+            Ok(Frame::Integer(5))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", " World"]), &mut ks),
+            Ok(Frame::Integer(11))
+        );
+        assert_eq!(
+            ks.get(b"key"),
+            Some(&Value::String(b"Hello World".to_vec()))
+        );
+    }
+}
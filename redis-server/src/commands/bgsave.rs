@@ -0,0 +1,41 @@
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::persistence;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BGSAVE [SCHEDULE]
+///
+/// Saves the dataset on a thread of its own and replies straight away. With SCHEDULE, a save
+/// asked for while another is running starts once that one is done, instead of failing.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let schedule = match args.pop_front() {
+        None => false,
+        Some(arg) if arg.eq_ignore_ascii_case(b"SCHEDULE") && args.is_empty() => true,
+        Some(_) => return Err(CommandError::Syntax),
+    };
+
+    let persistence = ks.persistence();
+    if persistence.status().in_progress() {
+        if !schedule {
+            return Err(CommandError::Other(String::from(
+                "ERR Background save already in progress",
+            )));
+        }
+        persistence.schedule_save();
+        return Ok(Frame::SimpleString(String::from(
+            "Background saving scheduled",
+        )));
+    }
+
+    let snapshot = persistence::snapshot(ks);
+    match ks.persistence().background_save(snapshot) {
+        Ok(_) => Ok(Frame::SimpleString(String::from(
+            "Background saving started",
+        ))),
+        Err(e) => {
+            eprintln!("Can't save in background: {}", e);
+            Err(CommandError::Other(String::from("ERR")))
+        }
+    }
+}
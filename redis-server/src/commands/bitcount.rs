@@ -0,0 +1,143 @@
+use crate::commands::{get_string, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BITCOUNT key [start end [BYTE | BIT]]
+///
+/// Counts the set bits in the string, or in the part between the inclusive offsets `start` and
+/// `end`, which count bytes unless BIT is given. Negative offsets count back from the end.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let range = match args.len() {
+        0 => None,
+        2 | 3 => {
+            let start = parse_i64(&args[0])?;
+            let end = parse_i64(&args[1])?;
+            let unit = args
+                .get(2)
+                .map_or(Ok(Unit::Byte), |unit| Unit::parse(unit))?;
+            Some((start, end, unit))
+        }
+        _ => return Err(CommandError::Syntax),
+    };
+
+    let value = match get_string(ks, &key)? {
+        Some(value) => value,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    let bits = match range {
+        None => bit_range(0, -1, Unit::Byte, value.len()),
+        Some((start, end, _)) if start < 0 && end < 0 && start > end => None,
+        Some((start, end, unit)) => bit_range(start, end, unit, value.len()),
+    };
+    let count = match bits {
+        Some((first, last)) => count_ones(value, first, last),
+        None => 0,
+    };
+    Ok(Frame::Integer(count as i64))
+}
+
+/// What the offsets of a BITCOUNT or BITPOS range count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Byte,
+    Bit,
+}
+
+impl Unit {
+    pub fn parse(arg: &[u8]) -> Result<Unit> {
+        match arg.to_ascii_uppercase().as_slice() {
+            b"BYTE" => Ok(Unit::Byte),
+            b"BIT" => Ok(Unit::Bit),
+            _ => Err(CommandError::Syntax),
+        }
+    }
+}
+
+/// Resolves the inclusive `start` and `end` offsets over a string of `len` bytes to the first
+/// and last bit they cover, clamping them to the string the way Redis does. Returns `None` if
+/// the range is empty.
+pub fn bit_range(start: i64, end: i64, unit: Unit, len: usize) -> Option<(u64, u64)> {
+    let total = match unit {
+        Unit::Byte => len as i64,
+        Unit::Bit => len as i64 * 8,
+    };
+
+    let start = if start < 0 {
+        (total + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 { (total + end).max(0) } else { end };
+    let end = end.min(total - 1);
+    if start > end {
+        return None;
+    }
+
+    match unit {
+        Unit::Byte => Some((start as u64 * 8, end as u64 * 8 + 7)),
+        Unit::Bit => Some((start as u64, end as u64)),
+    }
+}
+
+/// Counts the set bits from `first` to `last`, both inclusive.
+fn count_ones(value: &[u8], first: u64, last: u64) -> u64 {
+    let (first_byte, last_byte) = ((first >> 3) as usize, (last >> 3) as usize);
+
+    let mut count: u64 = value[first_byte..=last_byte]
+        .iter()
+        .map(|byte| byte.count_ones() as u64)
+        .sum();
+    // Leave out the bits of the end bytes that are outside the range.
+    count -= (value[first_byte] as u32 >> (8 - (first & 7))).count_ones() as u64;
+    count -= (value[last_byte] & (0xff >> (last & 7) >> 1)).count_ones() as u64;
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::Value;
+
+    #[test]
+    fn test_bitcount() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"foobar".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::Integer(26))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "0", "0"]), &mut ks),
+            Ok(Frame::Integer(4))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "1", "1"]), &mut ks),
+            Ok(Frame::Integer(6))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "1", "1", "BYTE"]), &mut ks),
+            Ok(Frame::Integer(6))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "5", "30", "BIT"]), &mut ks),
+            Ok(Frame::Integer(17))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "-1", "-2"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "0"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+    }
+}
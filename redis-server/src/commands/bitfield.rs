@@ -0,0 +1,315 @@
+use crate::commands::{get_string, parse_bit_offset, parse_i64, setbit, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BITFIELD key [GET encoding offset | [OVERFLOW WRAP | SAT | FAIL] SET encoding offset value |
+/// [OVERFLOW WRAP | SAT | FAIL] INCRBY encoding offset increment ...]
+///
+/// Runs each operation on the integer fields of the string in turn, replying with a result
+/// for each: the value for GET, the old value for SET and the new one for INCRBY. An operation
+/// that overflows under OVERFLOW FAIL does nothing and gets nil.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, "bitfield")
+}
+
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    name: &'static str,
+) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let mut operations = Vec::new();
+    let mut overflow = Overflow::Wrap;
+    while let Some(subcommand) = args.pop_front() {
+        let subcommand = subcommand.to_ascii_uppercase();
+        if subcommand == b"OVERFLOW" {
+            overflow = match args
+                .pop_front()
+                .map(|arg| arg.to_ascii_uppercase())
+                .as_deref()
+            {
+                Some(b"WRAP") => Overflow::Wrap,
+                Some(b"SAT") => Overflow::Sat,
+                Some(b"FAIL") => Overflow::Fail,
+                Some(_) => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR Invalid OVERFLOW type specified",
+                    )))
+                }
+                None => return Err(CommandError::Syntax),
+            };
+            continue;
+        }
+
+        let (kind, arity) = match subcommand.as_slice() {
+            b"GET" => (Kind::Get, 2),
+            b"SET" => (Kind::Set, 3),
+            b"INCRBY" => (Kind::IncrBy, 3),
+            _ => return Err(CommandError::Syntax),
+        };
+        if args.len() < arity {
+            return Err(CommandError::Syntax);
+        }
+
+        let field = Field::parse(&args.pop_front().unwrap())?;
+        let offset = parse_bit_offset(&args.pop_front().unwrap(), Some(field.bits as u64))?;
+        let value = match kind {
+            Kind::Get => 0,
+            _ => parse_i64(&args.pop_front().unwrap())?,
+        };
+        if kind != Kind::Get && name == "bitfield_ro" {
+            return Err(CommandError::Other(String::from(
+                "ERR BITFIELD_RO only supports the GET subcommand",
+            )));
+        }
+
+        operations.push(Operation {
+            kind,
+            field,
+            offset,
+            value,
+            overflow,
+        });
+    }
+
+    // The string only needs creating or growing if something is written to it.
+    let highest_write = operations
+        .iter()
+        .filter(|op| op.kind != Kind::Get)
+        .map(|op| op.offset + op.field.bits as u64 - 1)
+        .max();
+    let value = match highest_write {
+        Some(offset) => setbit::grow(ks, key, offset)?,
+        None => match get_string(ks, &key)? {
+            Some(value) => value,
+            None => &mut Vec::new(),
+        },
+    };
+
+    let replies = operations
+        .iter()
+        .map(|op| {
+            let current = op.field.get(value, op.offset);
+            let (new, reply) = match op.kind {
+                Kind::Get => return Frame::Integer(current as i64),
+                Kind::Set => (op.field.fit(op.value as i128, op.overflow), current),
+                Kind::IncrBy => {
+                    let new = op.field.fit(current + op.value as i128, op.overflow);
+                    (new, new.unwrap_or(0))
+                }
+            };
+            match new {
+                Some(new) => {
+                    op.field.set(value, op.offset, new);
+                    Frame::Integer(reply as i64)
+                }
+                None => Frame::NullBulkString,
+            }
+        })
+        .collect();
+
+    Ok(Frame::Array(replies))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Get,
+    Set,
+    IncrBy,
+}
+
+/// What to do when a value doesn't fit its field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Overflow {
+    /// Keep the low bits, as integer arithmetic in C would.
+    Wrap,
+    /// Clamp to the smallest or largest value the field holds.
+    Sat,
+    /// Leave the field alone and reply with nil.
+    Fail,
+}
+
+struct Operation {
+    kind: Kind,
+    field: Field,
+    offset: u64,
+    value: i64,
+    overflow: Overflow,
+}
+
+/// An integer field type such as `i8` or `u16`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Field {
+    signed: bool,
+    bits: u32,
+}
+
+impl Field {
+    fn parse(arg: &[u8]) -> Result<Field> {
+        let invalid = || {
+            CommandError::Other(String::from(
+                "ERR Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is.",
+            ))
+        };
+
+        let (signed, bits) = match arg.split_first() {
+            Some((b'i' | b'I', bits)) => (true, bits),
+            Some((b'u' | b'U', bits)) => (false, bits),
+            _ => return Err(invalid()),
+        };
+        let bits = std::str::from_utf8(bits)
+            .ok()
+            .and_then(|bits| bits.parse::<u32>().ok())
+            .ok_or_else(invalid)?;
+        let max = if signed { 64 } else { 63 };
+        if bits == 0 || bits > max {
+            return Err(invalid());
+        }
+
+        Ok(Field { signed, bits })
+    }
+
+    fn min(&self) -> i128 {
+        if self.signed {
+            -(1 << (self.bits - 1))
+        } else {
+            0
+        }
+    }
+
+    fn max(&self) -> i128 {
+        if self.signed {
+            (1 << (self.bits - 1)) - 1
+        } else {
+            (1 << self.bits) - 1
+        }
+    }
+
+    /// Fits `value` into the field, or returns `None` if it overflows under FAIL.
+    fn fit(&self, value: i128, overflow: Overflow) -> Option<i128> {
+        if (self.min()..=self.max()).contains(&value) {
+            return Some(value);
+        }
+
+        match overflow {
+            Overflow::Wrap => {
+                let wrapped = value & ((1 << self.bits) - 1);
+                if self.signed && wrapped > self.max() {
+                    Some(wrapped - (1 << self.bits))
+                } else {
+                    Some(wrapped)
+                }
+            }
+            Overflow::Sat if value < self.min() => Some(self.min()),
+            Overflow::Sat => Some(self.max()),
+            Overflow::Fail => None,
+        }
+    }
+
+    /// Reads the field at `offset`, with the most significant bit first. Bits past the end of
+    /// the string are 0.
+    fn get(&self, value: &[u8], offset: u64) -> i128 {
+        let mut raw: u64 = 0;
+        for bit in offset..offset + self.bits as u64 {
+            let byte = value.get((bit >> 3) as usize).copied().unwrap_or(0);
+            raw = (raw << 1) | ((byte >> (7 - (bit & 7))) & 1) as u64;
+        }
+
+        if self.signed && raw >> (self.bits - 1) & 1 == 1 {
+            raw as i128 - (1i128 << self.bits)
+        } else {
+            raw as i128
+        }
+    }
+
+    /// Writes `new`, which must fit, at `offset`. The string must be long enough.
+    fn set(&self, value: &mut [u8], offset: u64, new: i128) {
+        let raw = new as u64;
+        for i in 0..self.bits as u64 {
+            let bit = offset + i;
+            let on = (raw >> (self.bits as u64 - 1 - i)) & 1 == 1;
+            let byte = &mut value[(bit >> 3) as usize];
+            let mask = 1 << (7 - (bit & 7));
+            if on {
+                *byte |= mask;
+            } else {
+                *byte &= !mask;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, get};
+
+    #[test]
+    fn test_bitfield() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(
+                &mut args(&["key", "SET", "u8", "0", "255", "GET", "u4", "4", "GET", "i8", "0"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::Integer(0),
+                Frame::Integer(15),
+                Frame::Integer(-1),
+            ]))
+        );
+        assert_eq!(
+            get::execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::bulk(vec![0xff]))
+        );
+
+        // The second field of 16 bits starts at bit 16.
+        assert_eq!(
+            execute(&mut args(&["key", "SET", "i16", "#1", "-2"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(0)]))
+        );
+        assert_eq!(
+            get::execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::bulk(vec![0xff, 0x00, 0xff, 0xfe]))
+        );
+    }
+
+    #[test]
+    fn test_bitfield_overflow() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "key", "INCRBY", "u2", "0", "5", "OVERFLOW", "SAT", "INCRBY", "u2", "0", "5",
+                    "OVERFLOW", "FAIL", "INCRBY", "u2", "0", "1", "INCRBY", "i8", "8", "-129",
+                    "OVERFLOW", "WRAP", "INCRBY", "i8", "8", "128",
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::Integer(1),
+                Frame::Integer(3),
+                Frame::NullBulkString,
+                Frame::NullBulkString,
+                Frame::Integer(-128),
+            ]))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "SET", "u8", "16", "-1"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(0)]))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "GET", "u8", "16"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(255)]))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "GET", "u64", "0"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is."
+            )))
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use crate::commands::{bitfield, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BITFIELD_RO key [GET encoding offset [GET encoding offset ...]]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    bitfield::generic(args, ks, "bitfield_ro")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, CommandError};
+
+    #[test]
+    fn test_bitfield_ro() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["key", "GET", "i8", "0"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(0)]))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "SET", "i8", "0", "1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR BITFIELD_RO only supports the GET subcommand"
+            )))
+        );
+        assert!(!ks.contains_key(b"key"));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::commands::{get_string, CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operation {
+    And,
+    Or,
+    Xor,
+    Not,
+    /// The bits set in the first key and in none of the others.
+    Diff,
+}
+
+/// BITOP AND | OR | XOR | NOT | DIFF destkey key [key ...]
+///
+/// Stores the result at `destkey` and replies with its length, which is that of the longest
+/// source. Shorter sources count as padded with zero bytes. An empty result deletes `destkey`.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let operation = match args.pop_front().unwrap().to_ascii_uppercase().as_slice() {
+        b"AND" => Operation::And,
+        b"OR" => Operation::Or,
+        b"XOR" => Operation::Xor,
+        b"NOT" => Operation::Not,
+        b"DIFF" => Operation::Diff,
+        _ => return Err(CommandError::Syntax),
+    };
+    let destination = args.pop_front().unwrap();
+
+    if operation == Operation::Not && args.len() != 1 {
+        return Err(CommandError::Other(String::from(
+            "ERR BITOP NOT must be called with a single source key.",
+        )));
+    }
+    if operation == Operation::Diff && args.len() < 2 {
+        return Err(CommandError::Other(String::from(
+            "ERR BITOP DIFF must be called with multiple source keys.",
+        )));
+    }
+
+    let mut sources = Vec::with_capacity(args.len());
+    for key in args.iter() {
+        sources.push(get_string(ks, key)?.cloned().unwrap_or_default());
+    }
+
+    let len = sources.iter().map(Vec::len).max().unwrap_or(0);
+    if len == 0 {
+        ks.remove(&destination);
+        return Ok(Frame::Integer(0));
+    }
+
+    let byte = |source: &Vec<u8>, i: usize| source.get(i).copied().unwrap_or(0);
+    let result: Vec<u8> = (0..len)
+        .map(|i| {
+            let rest = sources[1..].iter().map(|source| byte(source, i));
+            let first = byte(&sources[0], i);
+            match operation {
+                Operation::And => rest.fold(first, |acc, b| acc & b),
+                Operation::Or => rest.fold(first, |acc, b| acc | b),
+                Operation::Xor => rest.fold(first, |acc, b| acc ^ b),
+                Operation::Not => !first,
+                Operation::Diff => first & !rest.fold(0, |acc, b| acc | b),
+            }
+        })
+        .collect();
+
+    ks.set(destination, Value::String(result));
+    Ok(Frame::Integer(len as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, get};
+
+    #[test]
+    fn test_bitop() {
+        let mut ks = Keyspace::new();
+        ks.set(b"a".to_vec(), Value::String(vec![0b1100, 0xff]));
+        ks.set(b"b".to_vec(), Value::String(vec![0b1010]));
+
+        let result = |ks: &mut Keyspace| get::execute(&mut args(&["dest"]), ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["AND", "dest", "a", "b"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(result(&mut ks), Frame::bulk(vec![0b1000, 0x00]));
+        execute(&mut args(&["OR", "dest", "a", "b"]), &mut ks).unwrap();
+        assert_eq!(result(&mut ks), Frame::bulk(vec![0b1110, 0xff]));
+        execute(&mut args(&["XOR", "dest", "a", "b"]), &mut ks).unwrap();
+        assert_eq!(result(&mut ks), Frame::bulk(vec![0b0110, 0xff]));
+        execute(&mut args(&["NOT", "dest", "b"]), &mut ks).unwrap();
+        assert_eq!(result(&mut ks), Frame::bulk(vec![0b1111_0101]));
+        execute(&mut args(&["DIFF", "dest", "a", "b"]), &mut ks).unwrap();
+        assert_eq!(result(&mut ks), Frame::bulk(vec![0b0100, 0xff]));
+
+        assert_eq!(
+            execute(&mut args(&["AND", "dest", "missing"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(result(&mut ks), Frame::NullBulkString);
+        assert!(execute(&mut args(&["NOT", "dest", "a", "b"]), &mut ks).is_err());
+        assert!(execute(&mut args(&["DIFF", "dest", "a"]), &mut ks).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::commands::bitcount::{bit_range, Unit};
+use crate::commands::{get_string, getbit, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BITPOS key bit [start [end [BYTE | BIT]]]
+///
+/// Replies with the position of the first bit set to `bit`, or -1 if there isn't one. Without
+/// an explicit end the string counts as padded with zeros, so looking for a 0 in a string of
+/// ones finds the bit just past it.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let bit = match args.pop_front().unwrap().as_slice() {
+        b"0" => 0,
+        b"1" => 1,
+        _ => {
+            return Err(CommandError::Other(String::from(
+                "ERR The bit argument must be 1 or 0.",
+            )))
+        }
+    };
+
+    let value = match get_string(ks, &key)? {
+        Some(value) => value,
+        // A missing key is all zeros.
+        None => return Ok(Frame::Integer(if bit == 1 { -1 } else { 0 })),
+    };
+
+    let (range, end_given) = match args.len() {
+        0 => (bit_range(0, -1, Unit::Byte, value.len()), false),
+        1..=3 => {
+            let start = parse_i64(&args[0])?;
+            let unit = args
+                .get(2)
+                .map_or(Ok(Unit::Byte), |unit| Unit::parse(unit))?;
+            let end = match args.get(1) {
+                Some(end) => parse_i64(end)?,
+                None => -1,
+            };
+            (bit_range(start, end, unit, value.len()), args.len() > 1)
+        }
+        _ => return Err(CommandError::Syntax),
+    };
+
+    let Some((first, last)) = range else {
+        return Ok(Frame::Integer(-1));
+    };
+    let position = match find(value, bit, first, last) {
+        Some(position) => position as i64,
+        None if bit == 0 && !end_given => last as i64 + 1,
+        None => -1,
+    };
+    Ok(Frame::Integer(position))
+}
+
+/// The position of the first bit from `first` to `last`, both inclusive, that is `bit`.
+fn find(value: &[u8], bit: u8, first: u64, last: u64) -> Option<u64> {
+    // Bytes made only of the other bit are skipped whole.
+    let skip = if bit == 1 { 0x00 } else { 0xff };
+
+    let mut offset = first;
+    while offset <= last {
+        if offset & 7 == 0 && offset + 7 <= last && value[(offset >> 3) as usize] == skip {
+            offset += 8;
+            continue;
+        }
+        if getbit::bit(value, offset) == bit {
+            return Some(offset);
+        }
+        offset += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::Value;
+
+    #[test]
+    fn test_bitpos() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(vec![0xff, 0xf0, 0x00]));
+
+        assert_eq!(
+            execute(&mut args(&["key", "0"]), &mut ks),
+            Ok(Frame::Integer(12))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "1", "2"]), &mut ks),
+            Ok(Frame::Integer(-1))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "1", "7", "15", "BIT"]), &mut ks),
+            Ok(Frame::Integer(7))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "0"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+
+        ks.set(b"ones".to_vec(), Value::String(vec![0xff, 0xff]));
+        assert_eq!(
+            execute(&mut args(&["ones", "0"]), &mut ks),
+            Ok(Frame::Integer(16))
+        );
+        assert_eq!(
+            execute(&mut args(&["ones", "0", "0", "-1"]), &mut ks),
+            Ok(Frame::Integer(-1))
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use crate::blocking::Outcome;
+use crate::commands::{lmove, parse_list_end, parse_timeout, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BLMOVE source destination LEFT | RIGHT LEFT | RIGHT timeout
+///
+/// LMOVE, except that the client waits for the source list to exist. Times out with nil.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    let source = args.pop_front().unwrap();
+    let destination = args.pop_front().unwrap();
+    let from = parse_list_end(&args.pop_front().unwrap())?;
+    let to = parse_list_end(&args.pop_front().unwrap())?;
+    let timeout = parse_timeout(&args.pop_front().unwrap())?;
+
+    if let Some(element) = lmove::move_element(ks, &source, destination.clone(), from, to)? {
+        return Ok(Outcome::Ready(Frame::bulk(element)));
+    }
+
+    let serve = Box::new(move |ks: &mut Keyspace, key: &[u8]| {
+        lmove::move_element(ks, key, destination.clone(), from, to)
+            .ok()
+            .flatten()
+            .map(Frame::bulk)
+    });
+    Ok(Outcome::Blocked(ks.block(
+        vec![source],
+        timeout,
+        Frame::NullBulkString,
+        serve,
+    )))
+}
@@ -0,0 +1,27 @@
+use crate::blocking::Outcome;
+use crate::commands::{lmpop, parse_timeout, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BLMPOP timeout numkeys key [key ...] LEFT | RIGHT [COUNT count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    let timeout = parse_timeout(&args.pop_front().unwrap())?;
+    let (keys, end, count) = lmpop::parse(args)?;
+
+    if let Some(reply) = lmpop::pop_first(ks, &keys, end, count)? {
+        return Ok(Outcome::Ready(reply));
+    }
+
+    let serve = Box::new(move |ks: &mut Keyspace, key: &[u8]| {
+        lmpop::pop_first(ks, &[key.to_vec()], end, count)
+            .ok()
+            .flatten()
+    });
+    Ok(Outcome::Blocked(ks.block(
+        keys,
+        timeout,
+        Frame::NullArray,
+        serve,
+    )))
+}
@@ -0,0 +1,113 @@
+use crate::blocking::Outcome;
+use crate::commands::{lpop, parse_timeout, ListEnd, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BLPOP key [key ...] timeout
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    generic(args, ks, ListEnd::Left)
+}
+
+/// Pops from the first of the keys that holds a list, replying with the key and the element.
+/// If none of them do, the client blocks until an element is pushed to one of them or the
+/// timeout runs out, when the reply is a null array.
+pub fn generic(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace, end: ListEnd) -> Result<Outcome> {
+    let timeout = parse_timeout(&args.pop_back().unwrap())?;
+    let keys: Vec<Vec<u8>> = args.drain(..).collect();
+
+    for key in &keys {
+        if let Some(reply) = pop(ks, key, end)? {
+            return Ok(Outcome::Ready(reply));
+        }
+    }
+
+    let serve = Box::new(move |ks: &mut Keyspace, key: &[u8]| pop(ks, key, end).ok().flatten());
+    Ok(Outcome::Blocked(ks.block(
+        keys,
+        timeout,
+        Frame::NullArray,
+        serve,
+    )))
+}
+
+fn pop(ks: &mut Keyspace, key: &[u8], end: ListEnd) -> Result<Option<Frame>> {
+    Ok(lpop::pop(ks, key, end, 1)?
+        .map(|mut elements| Frame::Array(vec![Frame::bulk(key), Frame::bulk(elements.remove(0))])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, rpush, CommandError};
+
+    fn reply(outcome: Result<Outcome>) -> Frame {
+        match outcome {
+            Ok(Outcome::Ready(reply)) => reply,
+            Ok(Outcome::Blocked(_)) => panic!("blocked"),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn test_blpop_pops_straight_away() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["second", "a", "b"]), &mut ks).unwrap();
+
+        assert_eq!(
+            reply(execute(&mut args(&["first", "second", "0"]), &mut ks)),
+            Frame::bulks(["second", "a"])
+        );
+        assert_eq!(
+            reply(generic(
+                &mut args(&["first", "second", "0"]),
+                &mut ks,
+                ListEnd::Right,
+            )),
+            Frame::bulks(["second", "b"])
+        );
+    }
+
+    #[test]
+    fn test_blpop_serves_waiting_clients_in_order() {
+        let mut ks = Keyspace::new();
+
+        let first = match execute(&mut args(&["queue", "0"]), &mut ks) {
+            Ok(Outcome::Blocked(blocked)) => blocked,
+            _ => panic!("expected to block"),
+        };
+        let second = match execute(&mut args(&["other", "queue", "0"]), &mut ks) {
+            Ok(Outcome::Blocked(blocked)) => blocked,
+            _ => panic!("expected to block"),
+        };
+
+        rpush::execute(&mut args(&["queue", "a"]), &mut ks).unwrap();
+        ks.serve_blocked();
+        assert_eq!(first.receiver.try_recv(), Ok(Frame::bulks(["queue", "a"])));
+        assert!(second.receiver.try_recv().is_err());
+        assert!(!ks.unblock(first.id));
+
+        rpush::execute(&mut args(&["queue", "b", "c"]), &mut ks).unwrap();
+        ks.serve_blocked();
+        assert_eq!(second.receiver.try_recv(), Ok(Frame::bulks(["queue", "b"])));
+        assert_eq!(
+            lpop::pop(&mut ks, b"queue", ListEnd::Left, 5),
+            Ok(Some(vec![b"c".to_vec()]))
+        );
+    }
+
+    #[test]
+    fn test_blpop_bad_timeout() {
+        let mut ks = Keyspace::new();
+
+        for (timeout, message) in [
+            ("-1", "ERR timeout is negative"),
+            ("soon", "ERR timeout is not a float or out of range"),
+        ] {
+            assert!(matches!(
+                execute(&mut args(&["queue", timeout]), &mut ks),
+                Err(CommandError::Other(m)) if m == message
+            ));
+        }
+    }
+}
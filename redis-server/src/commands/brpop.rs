@@ -0,0 +1,9 @@
+use crate::blocking::Outcome;
+use crate::commands::{blpop, ListEnd, Result};
+use crate::db::Keyspace;
+use std::collections::VecDeque;
+
+/// BRPOP key [key ...] timeout
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    blpop::generic(args, ks, ListEnd::Right)
+}
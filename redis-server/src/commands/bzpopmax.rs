@@ -0,0 +1,9 @@
+use crate::blocking::Outcome;
+use crate::commands::{bzpopmin, Result};
+use crate::db::Keyspace;
+use std::collections::VecDeque;
+
+/// BZPOPMAX key [key ...] timeout
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    bzpopmin::generic(args, ks, true)
+}
@@ -0,0 +1,78 @@
+use crate::blocking::Outcome;
+use crate::commands::{parse_timeout, zpopmin, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// BZPOPMIN key [key ...] timeout
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    generic(args, ks, false)
+}
+
+/// Pops from the first of the keys that holds a sorted set, replying with the key, the member
+/// and its score. If none of them do, the client blocks until a member is added to one of
+/// them or the timeout runs out, when the reply is a null array.
+pub fn generic(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace, max: bool) -> Result<Outcome> {
+    let timeout = parse_timeout(&args.pop_back().unwrap())?;
+    let keys: Vec<Vec<u8>> = args.drain(..).collect();
+
+    for key in &keys {
+        if let Some(reply) = pop(ks, key, max)? {
+            return Ok(Outcome::Ready(reply));
+        }
+    }
+
+    let serve = Box::new(move |ks: &mut Keyspace, key: &[u8]| pop(ks, key, max).ok().flatten());
+    Ok(Outcome::Blocked(ks.block(
+        keys,
+        timeout,
+        Frame::NullArray,
+        serve,
+    )))
+}
+
+fn pop(ks: &mut Keyspace, key: &[u8], max: bool) -> Result<Option<Frame>> {
+    Ok(zpopmin::pop(ks, key, max, 1)?.map(|mut popped| {
+        let (member, score) = popped.remove(0);
+        Frame::Array(vec![
+            Frame::bulk(key),
+            Frame::bulk(member),
+            Frame::Double(score),
+        ])
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zadd};
+
+    #[test]
+    fn test_bzpopmin_waits_for_a_member() {
+        let mut ks = Keyspace::new();
+
+        let blocked = match execute(&mut args(&["z", "0"]), &mut ks) {
+            Ok(Outcome::Blocked(blocked)) => blocked,
+            _ => panic!("expected to block"),
+        };
+
+        zadd::execute(&mut args(&["z", "2", "b", "1", "a"]), &mut ks).unwrap();
+        ks.serve_blocked();
+        assert_eq!(
+            blocked.receiver.try_recv(),
+            Ok(Frame::Array(vec![
+                Frame::bulk("z"),
+                Frame::bulk("a"),
+                Frame::Double(1.0)
+            ]))
+        );
+
+        match generic(&mut args(&["z", "0"]), &mut ks, true) {
+            Ok(Outcome::Ready(reply)) => assert_eq!(
+                reply,
+                Frame::Array(vec![Frame::bulk("z"), Frame::bulk("b"), Frame::Double(2.0)])
+            ),
+            _ => panic!("expected a reply"),
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use crate::commands::{CommandError, Result};
+use crate::glob::glob_match;
+use crate::registry::{self, CommandSpec, Keys};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// COMMAND
+/// COMMAND COUNT
+/// COMMAND INFO [command-name ...]
+/// COMMAND DOCS [command-name ...]
+/// COMMAND LIST [FILTERBY MODULE module-name | ACLCAT category | PATTERN pattern]
+/// COMMAND GETKEYS command [arg ...]
+///
+/// Everything here comes from the registry, so what clients are told about a command is what
+/// the server checks its arguments against.
+pub fn execute(args: &mut VecDeque<Vec<u8>>) -> Result<Frame> {
+    let Some(subcommand) = args.pop_front() else {
+        return Ok(Frame::Array(
+            registry::commands().iter().map(describe).collect(),
+        ));
+    };
+
+    match subcommand.to_ascii_uppercase().as_slice() {
+        b"COUNT" => Ok(Frame::Integer(registry::commands().len() as i64)),
+        b"INFO" => {
+            if args.is_empty() {
+                return Ok(Frame::Array(
+                    registry::commands().iter().map(describe).collect(),
+                ));
+            }
+            let entries = args
+                .iter()
+                .map(|name| find(name).map_or(Frame::Null, describe))
+                .collect();
+            Ok(Frame::Array(entries))
+        }
+        b"DOCS" => {
+            let specs: Vec<&CommandSpec> = if args.is_empty() {
+                registry::commands().iter().collect()
+            } else {
+                args.iter().filter_map(|name| find(name)).collect()
+            };
+            Ok(Frame::Map(specs.into_iter().map(document).collect()))
+        }
+        b"LIST" => list(args),
+        _ => getkeys(args),
+    }
+}
+
+/// Finds a command, or a subcommand by its full name such as `config|get`.
+fn find(name: &[u8]) -> Option<&'static CommandSpec> {
+    let name = String::from_utf8_lossy(name).to_lowercase();
+    match name.split_once('|') {
+        None => registry::lookup(&name),
+        Some((container, _)) => registry::lookup(container)?
+            .subcommands
+            .iter()
+            .find(|spec| spec.name == name),
+    }
+}
+
+/// The names of every command, or of those that pass the filter.
+fn list(args: &mut VecDeque<Vec<u8>>) -> Result<Frame> {
+    let filter = match (args.pop_front(), args.pop_front(), args.pop_front()) {
+        (None, _, _) => None,
+        (Some(filterby), Some(kind), Some(value)) if filterby.eq_ignore_ascii_case(b"FILTERBY") => {
+            Some((kind.to_ascii_uppercase(), value))
+        }
+        _ => return Err(CommandError::Syntax),
+    };
+    if !args.is_empty() {
+        return Err(CommandError::Syntax);
+    }
+
+    let keep = |spec: &CommandSpec| match &filter {
+        None => Ok(true),
+        // No modules can be loaded, so no command comes from one.
+        Some((kind, _)) if kind == b"MODULE" => Ok(false),
+        Some((kind, category)) if kind == b"ACLCAT" => {
+            let category = format!("@{}", String::from_utf8_lossy(category).to_lowercase());
+            Ok(spec.acl_categories().contains(&category.as_str()))
+        }
+        Some((kind, pattern)) if kind == b"PATTERN" => Ok(glob_match(
+            &pattern.to_ascii_lowercase(),
+            spec.name.as_bytes(),
+        )),
+        Some(_) => Err(CommandError::Syntax),
+    };
+
+    let mut names = Vec::new();
+    let specs = registry::commands()
+        .iter()
+        .flat_map(|spec| std::iter::once(spec).chain(spec.subcommands));
+    for spec in specs {
+        if keep(spec)? {
+            names.push(Frame::bulk(spec.name));
+        }
+    }
+    Ok(Frame::Array(names))
+}
+
+/// The keys among a command's arguments, found without running it.
+fn getkeys(args: &mut VecDeque<Vec<u8>>) -> Result<Frame> {
+    let argv = args.make_contiguous();
+    let spec = find(&argv[0])
+        .and_then(|spec| spec.subcommand(&argv[1..]).ok())
+        .ok_or_else(|| CommandError::Other(String::from("ERR Invalid command specified")))?;
+    if spec.check_arity(argv.len()).is_err() {
+        return Err(CommandError::Other(String::from(
+            "ERR Invalid number of arguments specified for command",
+        )));
+    }
+
+    let positions = spec.key_positions(argv)?;
+    if positions.is_empty() {
+        return Err(CommandError::Other(String::from(
+            "ERR The command has no key arguments",
+        )));
+    }
+    Ok(Frame::Array(
+        positions
+            .into_iter()
+            .map(|position| Frame::bulk(argv[position].clone()))
+            .collect(),
+    ))
+}
+
+/// A command's entry in COMMAND and COMMAND INFO.
+fn describe(spec: &CommandSpec) -> Frame {
+    let mut flags: Vec<Frame> = spec.flags.iter().map(|flag| status(flag)).collect();
+    if spec.has_movable_keys() {
+        flags.push(status("movablekeys"));
+    }
+    let (first, last, step) = spec.key_range();
+
+    Frame::Array(vec![
+        Frame::bulk(spec.name),
+        Frame::Integer(spec.arity),
+        Frame::Set(flags),
+        Frame::Integer(first),
+        Frame::Integer(last),
+        Frame::Integer(step),
+        Frame::Set(spec.acl_categories().into_iter().map(status).collect()),
+        // Tips for clusters and proxies, which have nothing to go on here.
+        Frame::Array(vec![]),
+        Frame::Array(key_specs(spec)),
+        Frame::Array(spec.subcommands.iter().map(describe).collect()),
+    ])
+}
+
+/// Where a command's keys are, in the form of Redis 7's key specifications: where to start
+/// looking, then how to find the keys from there.
+fn key_specs(spec: &CommandSpec) -> Vec<Frame> {
+    let access = if spec.has_flag("readonly") {
+        "RO"
+    } else {
+        "RW"
+    };
+    let key_spec = |flags: &str, begin_search: Frame, find_keys: Frame| {
+        Frame::Map(vec![
+            (Frame::bulk("flags"), Frame::Set(vec![status(flags)])),
+            (Frame::bulk("begin_search"), begin_search),
+            (Frame::bulk("find_keys"), find_keys),
+        ])
+    };
+    let index = |index: usize| search("index", vec![("index", Frame::Integer(index as i64))]);
+    let range = |lastkey: i64, keystep: usize, limit: i64| {
+        search(
+            "range",
+            vec![
+                ("lastkey", Frame::Integer(lastkey)),
+                ("keystep", Frame::Integer(keystep as i64)),
+                ("limit", Frame::Integer(limit)),
+            ],
+        )
+    };
+
+    match spec.keys {
+        Keys::None => vec![],
+        Keys::Range { first, last, step } => {
+            // The last key is given relative to the first.
+            let lastkey = if last < 0 { last } else { last - first as i64 };
+            vec![key_spec(access, index(first), range(lastkey, step, 0))]
+        }
+        Keys::Counted { count, destination } => {
+            let keynum = search(
+                "keynum",
+                vec![
+                    ("keynumidx", Frame::Integer(0)),
+                    ("firstkey", Frame::Integer(1)),
+                    ("keystep", Frame::Integer(1)),
+                ],
+            );
+            let mut specs = Vec::new();
+            if destination {
+                specs.push(key_spec("OW", index(1), range(0, 1, 0)));
+            }
+            let access = if destination { "RO" } else { access };
+            specs.push(key_spec(access, index(count), keynum));
+            specs
+        }
+        Keys::Streams => {
+            let keyword = search(
+                "keyword",
+                vec![
+                    ("keyword", Frame::bulk("STREAMS")),
+                    ("startfrom", Frame::Integer(1)),
+                ],
+            );
+            // The keys are the first of the two halves that follow.
+            vec![key_spec(access, keyword, range(-1, 1, 2))]
+        }
+    }
+}
+
+fn search(kind: &str, spec: Vec<(&str, Frame)>) -> Frame {
+    Frame::Map(vec![
+        (Frame::bulk("type"), Frame::bulk(kind)),
+        (
+            Frame::bulk("spec"),
+            Frame::Map(
+                spec.into_iter()
+                    .map(|(name, value)| (Frame::bulk(name), value))
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+/// A command's entry in COMMAND DOCS.
+fn document(spec: &CommandSpec) -> (Frame, Frame) {
+    let mut docs = vec![
+        (Frame::bulk("summary"), Frame::bulk(spec.summary)),
+        (Frame::bulk("since"), Frame::bulk(spec.since)),
+        (Frame::bulk("group"), Frame::bulk(spec.group)),
+    ];
+    if !spec.subcommands.is_empty() {
+        let subcommands = spec.subcommands.iter().map(document).collect();
+        docs.push((Frame::bulk("subcommands"), Frame::Map(subcommands)));
+    }
+    (Frame::bulk(spec.name), Frame::Map(docs))
+}
+
+fn status(text: &str) -> Frame {
+    Frame::SimpleString(String::from(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(args: &[&str]) -> Result<Frame> {
+        execute(&mut args.iter().map(|arg| arg.as_bytes().to_vec()).collect())
+    }
+
+    fn bulks(values: &[&str]) -> Frame {
+        Frame::Array(values.iter().map(|value| Frame::bulk(*value)).collect())
+    }
+
+    #[test]
+    fn test_info_describes_a_command() {
+        let reply = command(&["INFO", "get", "nosuchcommand"]).unwrap();
+        let Frame::Array(entries) = reply else {
+            panic!("expected an array, got {:?}", reply);
+        };
+        assert_eq!(entries[1], Frame::Null);
+
+        let Frame::Array(get) = &entries[0] else {
+            panic!("expected an array, got {:?}", entries[0]);
+        };
+        assert_eq!(get[0], Frame::bulk("get"));
+        assert_eq!(get[1], Frame::Integer(2));
+        assert_eq!(get[2], Frame::Set(vec![status("readonly"), status("fast")]));
+        assert_eq!(
+            get[3..6],
+            [Frame::Integer(1), Frame::Integer(1), Frame::Integer(1)]
+        );
+        assert_eq!(
+            get[6],
+            Frame::Set(vec![status("@read"), status("@string"), status("@fast")])
+        );
+    }
+
+    #[test]
+    fn test_info_describes_subcommands() {
+        let reply = command(&["INFO", "xgroup", "CONFIG|GET"]).unwrap();
+        let Frame::Array(entries) = reply else {
+            panic!("expected an array, got {:?}", reply);
+        };
+        let (Frame::Array(xgroup), Frame::Array(config_get)) = (&entries[0], &entries[1]) else {
+            panic!("expected arrays, got {:?}", entries);
+        };
+        assert_eq!(
+            xgroup[3..6],
+            [Frame::Integer(0), Frame::Integer(0), Frame::Integer(0)]
+        );
+        let Frame::Array(subcommands) = &xgroup[9] else {
+            panic!("expected an array, got {:?}", xgroup[9]);
+        };
+        assert_eq!(subcommands[0], describe(find(b"xgroup|create").unwrap()));
+        let Frame::Array(create) = &subcommands[0] else {
+            panic!("expected an array, got {:?}", subcommands[0]);
+        };
+        assert_eq!(create[0], Frame::bulk("xgroup|create"));
+        assert_eq!(create[1], Frame::Integer(-5));
+        assert_eq!(
+            create[3..6],
+            [Frame::Integer(2), Frame::Integer(2), Frame::Integer(1)]
+        );
+
+        assert_eq!(config_get[0], Frame::bulk("config|get"));
+        assert_eq!(config_get[1], Frame::Integer(-3));
+    }
+
+    #[test]
+    fn test_count_matches_the_registry() {
+        assert_eq!(
+            command(&["COUNT"]),
+            Ok(Frame::Integer(registry::commands().len() as i64))
+        );
+    }
+
+    #[test]
+    fn test_getkeys() {
+        assert_eq!(
+            command(&["GETKEYS", "MSET", "a", "1", "b", "2"]),
+            Ok(bulks(&["a", "b"]))
+        );
+        assert_eq!(
+            command(&["GETKEYS", "eval", "return 1", "2", "k1", "k2", "arg"]),
+            Ok(bulks(&["k1", "k2"]))
+        );
+        assert_eq!(
+            command(&[
+                "GETKEYS",
+                "zunionstore",
+                "dest",
+                "2",
+                "a",
+                "b",
+                "WEIGHTS",
+                "1",
+                "2"
+            ]),
+            Ok(bulks(&["dest", "a", "b"]))
+        );
+        assert_eq!(
+            command(&["GETKEYS", "xread", "COUNT", "2", "STREAMS", "s1", "s2", "0", "0"]),
+            Ok(bulks(&["s1", "s2"]))
+        );
+        assert_eq!(
+            command(&["GETKEYS", "blpop", "a", "b", "0"]),
+            Ok(bulks(&["a", "b"]))
+        );
+        assert_eq!(
+            command(&["GETKEYS", "xgroup", "CREATE", "s", "g", "$"]),
+            Ok(bulks(&["s"]))
+        );
+    }
+
+    #[test]
+    fn test_getkeys_errors() {
+        let error = |message: &str| Err(CommandError::Other(String::from(message)));
+        assert_eq!(
+            command(&["GETKEYS", "nosuchcommand", "a"]),
+            error("ERR Invalid command specified")
+        );
+        assert_eq!(
+            command(&["GETKEYS", "get"]),
+            error("ERR Invalid number of arguments specified for command")
+        );
+        assert_eq!(
+            command(&["GETKEYS", "xgroup", "NOSUCH", "s"]),
+            error("ERR Invalid command specified")
+        );
+        assert_eq!(
+            command(&["GETKEYS", "xgroup", "DESTROY", "s"]),
+            error("ERR Invalid number of arguments specified for command")
+        );
+        assert_eq!(
+            command(&["GETKEYS", "ping"]),
+            error("ERR The command has no key arguments")
+        );
+        assert_eq!(
+            command(&["GETKEYS", "eval", "return 1", "3", "k1"]),
+            error("ERR Invalid arguments specified for command")
+        );
+    }
+
+    #[test]
+    fn test_list_filterby() {
+        assert_eq!(
+            command(&["LIST", "FILTERBY", "PATTERN", "xread*"]),
+            Ok(bulks(&["xread", "xreadgroup"]))
+        );
+        assert_eq!(
+            command(&["LIST", "FILTERBY", "ACLCAT", "transaction"]),
+            Ok(bulks(&["multi", "exec", "discard", "watch", "unwatch"]))
+        );
+        assert_eq!(
+            command(&["LIST", "FILTERBY", "PATTERN", "config*"]),
+            Ok(bulks(&["config", "config|get", "config|set"]))
+        );
+        assert_eq!(
+            command(&["LIST", "FILTERBY", "MODULE", "json"]),
+            Ok(Frame::Array(vec![]))
+        );
+        assert_eq!(
+            command(&["LIST", "FILTERBY", "NAME", "get"]),
+            Err(CommandError::Syntax)
+        );
+    }
+
+    #[test]
+    fn test_docs() {
+        assert_eq!(
+            command(&["DOCS", "getdel"]),
+            Ok(Frame::Map(vec![(
+                Frame::bulk("getdel"),
+                Frame::Map(vec![
+                    (
+                        Frame::bulk("summary"),
+                        Frame::bulk("Returns the string value of a key after deleting the key.")
+                    ),
+                    (Frame::bulk("since"), Frame::bulk("6.2.0")),
+                    (Frame::bulk("group"), Frame::bulk("string")),
+                ])
+            )]))
+        );
+    }
+}
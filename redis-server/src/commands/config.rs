@@ -0,0 +1,194 @@
+use crate::commands::{parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::glob::glob_match;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+/// The parameters CONFIG can get and set, in the order CONFIG GET lists them.
+const PARAMETERS: [&str; 5] = [
+    "busy-reply-threshold",
+    "dbfilename",
+    "dir",
+    "lua-time-limit",
+    "save",
+];
+
+/// CONFIG GET parameter [parameter ...]
+/// CONFIG SET parameter value [parameter value ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let subcommand = args.pop_front().unwrap().to_ascii_uppercase();
+
+    match subcommand.as_slice() {
+        b"GET" => {
+            let pairs = PARAMETERS
+                .iter()
+                .filter(|parameter| {
+                    args.iter().any(|pattern| {
+                        glob_match(&pattern.to_ascii_lowercase(), parameter.as_bytes())
+                    })
+                })
+                .map(|parameter| (Frame::bulk(*parameter), Frame::bulk(get(parameter, ks))))
+                .collect();
+            Ok(Frame::Map(pairs))
+        }
+        // SET's parameters and values come in pairs, which the registry's arity can't express.
+        _ if !args.len().is_multiple_of(2) => Err(CommandError::WrongArity("config|set")),
+        _ => {
+            while let (Some(parameter), Some(value)) = (args.pop_front(), args.pop_front()) {
+                let parameter = String::from_utf8_lossy(&parameter).to_lowercase();
+                set(&parameter, &value, ks)?;
+            }
+            Ok(Frame::ok())
+        }
+    }
+}
+
+fn get(parameter: &str, ks: &mut Keyspace) -> String {
+    match parameter {
+        "busy-reply-threshold" | "lua-time-limit" => {
+            let scripting = ks.scripting();
+            let status = scripting
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .status();
+            status.time_limit().as_millis().to_string()
+        }
+        "dbfilename" => ks.persistence().dbfilename.clone(),
+        "dir" => {
+            let dir = &ks.persistence().dir;
+            let dir = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+            dir.to_string_lossy().into_owned()
+        }
+        "save" => {
+            let policies = ks.persistence().policies.iter();
+            let policies: Vec<String> = policies
+                .map(|(seconds, changes)| format!("{} {}", seconds, changes))
+                .collect();
+            policies.join(" ")
+        }
+        _ => unreachable!("{} is not a parameter", parameter),
+    }
+}
+
+fn set(parameter: &str, value: &[u8], ks: &mut Keyspace) -> Result<()> {
+    match parameter {
+        "busy-reply-threshold" | "lua-time-limit" => {
+            let ms = parse_i64(value)
+                .ok()
+                .filter(|&ms| ms >= 0)
+                .ok_or_else(|| invalid(parameter, "argument must be a non-negative integer"))?;
+            let scripting = ks.scripting();
+            let status = scripting
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .status();
+            status.set_time_limit(Duration::from_millis(ms as u64));
+            Ok(())
+        }
+        "dbfilename" => {
+            let name = String::from_utf8_lossy(value).into_owned();
+            if name.contains('/') {
+                return Err(invalid(
+                    parameter,
+                    "dbfilename can't be a path, just a filename",
+                ));
+            }
+            ks.persistence().dbfilename = name;
+            Ok(())
+        }
+        "dir" => {
+            let dir = PathBuf::from(String::from_utf8_lossy(value).into_owned());
+            if !dir.is_dir() {
+                return Err(invalid(parameter, "No such file or directory"));
+            }
+            ks.persistence().dir = dir;
+            Ok(())
+        }
+        "save" => {
+            // Pairs of seconds and changes, or nothing at all to never save.
+            let numbers: Option<Vec<u64>> = String::from_utf8_lossy(value)
+                .split_whitespace()
+                .map(|number| number.parse().ok())
+                .collect();
+            let policies = numbers
+                .filter(|numbers| numbers.len().is_multiple_of(2))
+                .ok_or_else(|| invalid(parameter, "Invalid save parameters"))?;
+            ks.persistence().policies = policies.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+            Ok(())
+        }
+        _ => Err(CommandError::Other(format!(
+            "ERR Unknown option or number of arguments for CONFIG SET - '{}'",
+            parameter
+        ))),
+    }
+}
+
+fn invalid(parameter: &str, reason: &str) -> CommandError {
+    CommandError::Other(format!(
+        "ERR CONFIG SET failed (possibly related to argument '{}') - {}",
+        parameter, reason
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_config() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["SET", "lua-time-limit", "100"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            execute(&mut args(&["GET", "LUA-*"]), &mut ks),
+            Ok(Frame::Map(vec![(
+                Frame::bulk("lua-time-limit"),
+                Frame::bulk("100")
+            )]))
+        );
+        assert_eq!(
+            execute(&mut args(&["GET", "nothing"]), &mut ks),
+            Ok(Frame::Map(Vec::new()))
+        );
+        assert!(execute(&mut args(&["SET", "lua-time-limit", "-1"]), &mut ks).is_err());
+        assert!(execute(&mut args(&["SET", "nothing", "1"]), &mut ks).is_err());
+    }
+
+    #[test]
+    fn test_config_persistence() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["GET", "save"]), &mut ks),
+            Ok(Frame::Map(vec![(
+                Frame::bulk("save"),
+                Frame::bulk("3600 1 300 100 60 10000")
+            )]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["SET", "save", "900 1", "dbfilename", "other.rdb"]),
+                &mut ks
+            ),
+            Ok(Frame::ok())
+        );
+        assert_eq!(ks.persistence().policies, [(900, 1)]);
+        assert_eq!(ks.persistence().dbfilename, "other.rdb");
+        assert_eq!(
+            execute(&mut args(&["SET", "save", ""]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert!(ks.persistence().policies.is_empty());
+
+        assert!(execute(&mut args(&["SET", "save", "900"]), &mut ks).is_err());
+        assert!(execute(&mut args(&["SET", "dbfilename", "a/b.rdb"]), &mut ks).is_err());
+        assert!(execute(&mut args(&["SET", "dir", "/no/such/dir"]), &mut ks).is_err());
+    }
+}
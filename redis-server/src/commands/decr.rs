@@ -0,0 +1,10 @@
+use crate::commands::{incr, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// DECR key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    incr::by(ks, key, -1)
+}
@@ -0,0 +1,44 @@
+use crate::commands::{incr, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// DECRBY key decrement
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let decrement = parse_i64(&args.pop_front().unwrap())?;
+
+    // The smallest i64 has no positive counterpart to add instead.
+    let delta = decrement
+        .checked_neg()
+        .ok_or(CommandError::Other(String::from(
+            "ERR decrement would overflow",
+        )))?;
+    incr::by(ks, key, delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_decrby() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["n", "5"]), &mut ks),
+            Ok(Frame::Integer(-5))
+        );
+        assert_eq!(
+            execute(&mut args(&["n", "-9223372036854775808"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR decrement would overflow"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["n", "five"]), &mut ks),
+            Err(CommandError::NotAnInteger)
+        );
+    }
+}
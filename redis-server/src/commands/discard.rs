@@ -0,0 +1,23 @@
+use crate::client::Client;
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// DISCARD
+///
+/// Throws away the queued commands and ends the transaction, unwatching every key.
+pub fn execute(
+    _args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Frame> {
+    if client.transaction.take().is_none() {
+        return Err(CommandError::Other(String::from(
+            "ERR DISCARD without MULTI",
+        )));
+    }
+
+    ks.unwatch_all(client.id);
+    Ok(Frame::ok())
+}
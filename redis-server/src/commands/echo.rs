@@ -1,14 +1,12 @@
-use crate::resp::types::Encoded;
-use crate::SimpleString;
+use crate::commands::Result;
+use crate::resp::types::Frame;
 use std::collections::VecDeque;
 
-pub fn execute(args: &mut VecDeque<String>) -> Box<dyn Encoded> {
-    let joined: String = args
+pub fn execute(args: &mut VecDeque<Vec<u8>>) -> Result<Frame> {
+    let joined: Vec<u8> = args
         .iter()
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>()
-        .join(" ")
-        .trim()
-        .to_string();
-    SimpleString::new(joined)
+        .map(|s| s.as_slice())
+        .collect::<Vec<&[u8]>>()
+        .join(&b' ');
+    Ok(Frame::BulkString(joined))
 }
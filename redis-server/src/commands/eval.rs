@@ -0,0 +1,269 @@
+use crate::client::Client;
+use crate::commands::exec::Dispatch;
+use crate::commands::{parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+use std::sync::PoisonError;
+
+/// EVAL script numkeys [key [key ...]] [arg [arg ...]]
+///
+/// Runs a Lua script, loading it first if it isn't already. The script sees the keys in `KEYS`
+/// and the other arguments in `ARGV`, and no other client's commands run until it is done.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+) -> Result<Frame> {
+    generic(args, client, ks, dispatch, false, false)
+}
+
+/// Runs a script given either by its source or, with `by_sha`, by the SHA1 digest of a script
+/// loaded before. A `read_only` script may not call commands that write.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+    by_sha: bool,
+    read_only: bool,
+) -> Result<Frame> {
+    let script = args.pop_front().unwrap();
+    let keys = take_keys(args)?;
+    let argv = args.drain(..).collect();
+
+    let scripting = ks.scripting();
+    let mut scripting = scripting.lock().unwrap_or_else(PoisonError::into_inner);
+    let sha = match by_sha {
+        true => String::from_utf8_lossy(&script).into_owned(),
+        false => scripting.load(&script)?,
+    };
+    scripting.run(&sha, keys, argv, read_only, client.protocol, ks, dispatch)
+}
+
+/// Takes the keys from `numkeys key [key ...] arg [arg ...]`, leaving the other arguments.
+pub fn take_keys(args: &mut VecDeque<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+    let numkeys = parse_i64(&args.pop_front().unwrap_or_default())?;
+    if numkeys < 0 {
+        return Err(CommandError::Other(String::from(
+            "ERR Number of keys can't be negative",
+        )));
+    }
+    if numkeys as usize > args.len() {
+        return Err(CommandError::Other(String::from(
+            "ERR Number of keys can't be greater than number of args",
+        )));
+    }
+    Ok(args.drain(..numkeys as usize).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocking::Outcome;
+    use crate::commands::{args, get, set};
+    use crate::resp::types::Protocol;
+    use std::sync::mpsc;
+
+    fn dispatch(
+        name: &str,
+        args: &mut VecDeque<Vec<u8>>,
+        _client: &mut Client,
+        ks: &mut Keyspace,
+    ) -> Option<Outcome> {
+        let reply = match name {
+            "get" => get::execute(args, ks),
+            "set" => set::execute(args, ks),
+            _ => Ok(Frame::error("UNIMPLEMENTED")),
+        };
+        Some(Outcome::Ready(reply.unwrap_or_else(Frame::from)))
+    }
+
+    fn eval(script: &str, rest: &[&str], client: &mut Client, ks: &mut Keyspace) -> Result<Frame> {
+        let mut args = args(rest);
+        args.push_front(script.as_bytes().to_vec());
+        execute(&mut args, client, ks, dispatch)
+    }
+
+    #[test]
+    fn test_eval_converts_replies_both_ways() {
+        let mut ks = Keyspace::new();
+        let (sender, _) = mpsc::channel();
+        let mut client = Client::new(sender);
+
+        assert_eq!(
+            eval(
+                "return redis.call('set', KEYS[1], ARGV[1])",
+                &["1", "a", "1"],
+                &mut client,
+                &mut ks
+            ),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            eval(
+                "return {redis.call('get', KEYS[1]), redis.call('get', 'b'), 3.9, true, 'x'}",
+                &["1", "a"],
+                &mut client,
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::bulk("1"),
+                Frame::Null,
+                Frame::Integer(3),
+                Frame::Integer(1),
+                Frame::bulk("x"),
+            ]))
+        );
+        assert_eq!(
+            eval("return {1, nil, 2}", &["0"], &mut client, &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(1)]))
+        );
+        assert_eq!(
+            eval("return redis.pcall('get')", &["0"], &mut client, &mut ks),
+            Ok(Frame::error(
+                "ERR Wrong number of args calling Redis command from script"
+            ))
+        );
+
+        client.protocol = Protocol::Resp3;
+        assert_eq!(
+            eval(
+                "redis.setresp(3); return {redis.call('get', 'b') == nil, {double = 1.5}}",
+                &["0"],
+                &mut client,
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![Frame::Boolean(true), Frame::Double(1.5)]))
+        );
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let mut ks = Keyspace::new();
+        let (sender, _) = mpsc::channel();
+        let mut client = Client::new(sender);
+
+        assert_eq!(
+            eval("return 1", &["2", "a"], &mut client, &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Number of keys can't be greater than number of args"
+            )))
+        );
+        assert_eq!(
+            eval("return 1", &["-1"], &mut client, &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Number of keys can't be negative"
+            )))
+        );
+        assert_eq!(
+            eval(
+                "\nreturn redis.call('nosuchcommand')",
+                &["0"],
+                &mut client,
+                &mut ks
+            ),
+            Err(CommandError::Other(String::from(
+                "ERR Unknown Redis command called from script script: \
+                 36b47cff653e9c7327ec8c3b797c1fbdb96a7385, on @user_script:2."
+            )))
+        );
+        assert_eq!(
+            eval("x = 1", &["0"], &mut client, &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR user_script:1: Script attempted to create global variable 'x' script: \
+                 34bce5f775de97f557a34088509c8bfe1ea17e52, on @user_script:1."
+            )))
+        );
+        assert!(matches!(
+            eval("return (", &["0"], &mut client, &mut ks),
+            Err(CommandError::Other(e)) if e.starts_with("ERR Error compiling script")
+        ));
+    }
+
+    #[test]
+    fn test_eval_replies_and_errors_stay_on_one_line() {
+        let mut ks = Keyspace::new();
+        let (sender, _) = mpsc::channel();
+        let mut client = Client::new(sender);
+
+        assert_eq!(
+            eval(
+                "return redis.error_reply('a\\nb')",
+                &["0"],
+                &mut client,
+                &mut ks
+            ),
+            Ok(Frame::error("a b"))
+        );
+        assert_eq!(
+            eval("return {ok='x\\r\\ny'}", &["0"], &mut client, &mut ks),
+            Ok(Frame::SimpleString(String::from("x  y")))
+        );
+        assert_eq!(
+            eval("error('boom\\r\\nx')", &["0"], &mut client, &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR user_script:1: boom  x script: \
+                 14400fa1885adffe181ad8dd93f58b9777774e9d, on @user_script:1."
+            )))
+        );
+    }
+
+    #[test]
+    fn test_evalsha_and_read_only_scripts() {
+        let mut ks = Keyspace::new();
+        let (sender, _) = mpsc::channel();
+        let mut client = Client::new(sender);
+        let write = "return redis.call('set', 'a', '1')";
+        let sha = ks
+            .scripting()
+            .lock()
+            .unwrap()
+            .load(write.as_bytes())
+            .unwrap();
+
+        assert_eq!(
+            generic(&mut args(&[&sha, "0"]), &mut client, &mut ks, dispatch, true, true),
+            Err(CommandError::Other(format!(
+                "ERR Write commands are not allowed from read-only scripts. script: {}, on @user_script:1.",
+                sha
+            )))
+        );
+        assert_eq!(
+            generic(
+                &mut args(&[&sha, "0"]),
+                &mut client,
+                &mut ks,
+                dispatch,
+                true,
+                false,
+            ),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            eval(
+                "#!lua flags=no-writes\nreturn redis.pcall('set', 'a', '2')",
+                &["0"],
+                &mut client,
+                &mut ks
+            ),
+            Ok(Frame::error(
+                "ERR Write commands are not allowed from read-only scripts."
+            ))
+        );
+        assert_eq!(
+            generic(
+                &mut args(&["ffff", "0"]),
+                &mut client,
+                &mut ks,
+                dispatch,
+                true,
+                false,
+            ),
+            Err(CommandError::Other(String::from(
+                "NOSCRIPT No matching script. Please use EVAL."
+            )))
+        );
+    }
+}
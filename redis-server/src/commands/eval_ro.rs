@@ -0,0 +1,19 @@
+use crate::client::Client;
+use crate::commands::eval;
+use crate::commands::exec::Dispatch;
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// EVAL_RO script numkeys [key [key ...]] [arg [arg ...]]
+///
+/// Runs a script as EVAL does, except that it may not call commands that write.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+) -> Result<Frame> {
+    eval::generic(args, client, ks, dispatch, false, true)
+}
@@ -0,0 +1,19 @@
+use crate::client::Client;
+use crate::commands::eval;
+use crate::commands::exec::Dispatch;
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// EVALSHA sha1 numkeys [key [key ...]] [arg [arg ...]]
+///
+/// Runs a script loaded before, by the SHA1 digest of its source.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+) -> Result<Frame> {
+    eval::generic(args, client, ks, dispatch, true, false)
+}
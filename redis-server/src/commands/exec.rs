@@ -0,0 +1,192 @@
+use crate::blocking::Outcome;
+use crate::client::Client;
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// Runs a command by name against the locked keyspace.
+pub type Dispatch = fn(&str, &mut VecDeque<Vec<u8>>, &mut Client, &mut Keyspace) -> Option<Outcome>;
+
+/// EXEC
+///
+/// Runs the queued commands one after another, with no other client's commands in between, and
+/// replies with all their replies. If a watched key has changed nothing runs and the reply is a
+/// null array instead.
+pub fn execute(
+    _args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+) -> Result<Frame> {
+    let Some(transaction) = client.transaction.take() else {
+        return Err(CommandError::Other(String::from("ERR EXEC without MULTI")));
+    };
+    let touched = ks.watched_key_touched(client.id);
+    ks.unwatch_all(client.id);
+
+    if transaction.failed {
+        return Err(CommandError::Other(String::from(
+            "EXECABORT Transaction discarded because of previous errors.",
+        )));
+    }
+    if touched {
+        return Ok(Frame::NullArray);
+    }
+
+    let replies = transaction
+        .commands
+        .into_iter()
+        .map(
+            |(name, mut args)| match dispatch(&name, &mut args, client, ks) {
+                Some(Outcome::Ready(reply)) => reply,
+                // Nothing else can run until the transaction is over, so a blocking command that
+                // would have to wait times out straight away.
+                Some(Outcome::Blocked(blocked)) => {
+                    ks.unblock(blocked.id);
+                    blocked.timeout_reply
+                }
+                // Commands that send their own replies can't be queued.
+                None => Frame::Null,
+            },
+        )
+        .collect();
+    Ok(Frame::Array(replies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Transaction;
+    use crate::commands::{args, blpop, incr, multi, watch};
+    use crate::db::Value;
+    use std::sync::mpsc;
+
+    fn dispatch(
+        name: &str,
+        args: &mut VecDeque<Vec<u8>>,
+        _client: &mut Client,
+        ks: &mut Keyspace,
+    ) -> Option<Outcome> {
+        let outcome = match name {
+            "incr" => incr::execute(args, ks).map(Outcome::Ready),
+            "blpop" => blpop::execute(args, ks),
+            _ => unreachable!(),
+        };
+        Some(outcome.unwrap_or_else(|e| Outcome::Ready(e.into())))
+    }
+
+    fn client() -> Client {
+        let (sender, _receiver) = mpsc::channel();
+        Client::new(sender)
+    }
+
+    #[test]
+    fn test_exec() {
+        let mut ks = Keyspace::new();
+        let mut client = client();
+
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks, dispatch),
+            Err(CommandError::Other(String::from("ERR EXEC without MULTI")))
+        );
+
+        multi::execute(&mut args(&[]), &mut client).unwrap();
+        let transaction = client.transaction.as_mut().unwrap();
+        transaction
+            .commands
+            .push((String::from("incr"), args(&["a"])));
+        transaction
+            .commands
+            .push((String::from("blpop"), args(&["list", "0"])));
+        transaction
+            .commands
+            .push((String::from("incr"), args(&["a"])));
+
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks, dispatch),
+            Ok(Frame::Array(vec![
+                Frame::Integer(1),
+                Frame::NullArray,
+                Frame::Integer(2)
+            ]))
+        );
+        assert!(client.transaction.is_none());
+    }
+
+    #[test]
+    fn test_exec_fails_when_a_watched_key_changed() {
+        let mut ks = Keyspace::new();
+        let mut client = client();
+
+        watch::execute(&mut args(&["a"]), &mut client, &mut ks).unwrap();
+        incr::execute(&mut args(&["a"]), &mut ks).unwrap();
+        multi::execute(&mut args(&[]), &mut client).unwrap();
+        client
+            .transaction
+            .as_mut()
+            .unwrap()
+            .commands
+            .push((String::from("incr"), args(&["a"])));
+
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks, dispatch),
+            Ok(Frame::NullArray)
+        );
+        assert!(!ks.watched_key_touched(client.id));
+    }
+
+    #[test]
+    fn test_exec_fails_when_a_watched_key_changed_back() {
+        let mut ks = Keyspace::new();
+        let mut client = client();
+        incr::execute(&mut args(&["a"]), &mut ks).unwrap();
+
+        watch::execute(&mut args(&["a", "missing"]), &mut client, &mut ks).unwrap();
+        ks.set(b"a".to_vec(), Value::String(b"1".to_vec()));
+        multi::execute(&mut args(&[]), &mut client).unwrap();
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks, dispatch),
+            Ok(Frame::NullArray)
+        );
+
+        watch::execute(&mut args(&["missing"]), &mut client, &mut ks).unwrap();
+        ks.set(b"missing".to_vec(), Value::String(b"1".to_vec()));
+        ks.remove(b"missing");
+        multi::execute(&mut args(&[]), &mut client).unwrap();
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks, dispatch),
+            Ok(Frame::NullArray)
+        );
+
+        watch::execute(&mut args(&["a"]), &mut client, &mut ks).unwrap();
+        assert_eq!(ks.get(b"a"), Some(&Value::String(b"1".to_vec())));
+        ks.remove(b"missing");
+        multi::execute(&mut args(&[]), &mut client).unwrap();
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks, dispatch),
+            Ok(Frame::Array(vec![]))
+        );
+    }
+
+    #[test]
+    fn test_exec_aborts_after_a_queueing_error() {
+        let mut ks = Keyspace::new();
+        let mut client = client();
+        client.transaction = Some(Transaction {
+            commands: vec![(String::from("incr"), args(&["a"]))],
+            failed: true,
+        });
+
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks, dispatch),
+            Err(CommandError::Other(String::from(
+                "EXECABORT Transaction discarded because of previous errors."
+            )))
+        );
+        assert_eq!(
+            incr::execute(&mut args(&["a"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+    }
+}
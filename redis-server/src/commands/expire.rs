@@ -0,0 +1,194 @@
+use crate::commands::{parse_i64, CommandError, Result, TimeUnit};
+use crate::db::{now_ms, Keyspace};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// EXPIRE key seconds [NX | XX | GT | LT]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, TimeUnit::Seconds, true, "expire")
+}
+
+/// The EXPIRE family only differ in the unit of the time and whether it counts from now.
+///
+/// Replies 1 if the expiry time was set and 0 if the key doesn't exist or a flag stopped it. A
+/// time that has already passed deletes the key.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    unit: TimeUnit,
+    relative: bool,
+    name: &'static str,
+) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let amount = args.pop_front().unwrap();
+
+    let (mut nx, mut xx, mut gt, mut lt) = (false, false, false, false);
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"NX" => nx = true,
+            b"XX" => xx = true,
+            b"GT" => gt = true,
+            b"LT" => lt = true,
+            _ => {
+                return Err(CommandError::Other(format!(
+                    "ERR Unsupported option {}",
+                    String::from_utf8_lossy(&option)
+                )))
+            }
+        }
+    }
+
+    if nx && (xx || gt || lt) {
+        return Err(CommandError::Other(String::from(
+            "ERR NX and XX, GT or LT options at the same time are not compatible",
+        )));
+    }
+    if gt && lt {
+        return Err(CommandError::Other(String::from(
+            "ERR GT and LT options at the same time are not compatible",
+        )));
+    }
+
+    // Unlike SET, a negative or zero time is fine here: it just means the key is gone.
+    let amount = parse_i64(&amount)?;
+    let invalid = CommandError::InvalidExpireTime(name);
+    let ms = match unit {
+        TimeUnit::Seconds => amount.checked_mul(1000).ok_or(invalid.clone())?,
+        TimeUnit::Milliseconds => amount,
+    };
+    let when = if relative {
+        ms.checked_add(now_ms() as i64).ok_or(invalid)?
+    } else {
+        ms
+    };
+
+    if !ks.contains_key(&key) {
+        return Ok(Frame::Integer(0));
+    }
+
+    // A key without an expiry time lives forever, so it is greater than any time.
+    let allowed = match ks.expire_time(&key) {
+        Some(current) => {
+            let current = current as i64;
+            !(nx || (gt && when <= current) || (lt && when >= current))
+        }
+        None => !xx && !gt,
+    };
+    if !allowed {
+        return Ok(Frame::Integer(0));
+    }
+
+    if when <= now_ms() as i64 {
+        ks.remove(&key);
+    } else {
+        ks.set_expire_time(&key, when as u64);
+    }
+
+    Ok(Frame::Integer(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::Value;
+
+    fn keyspace() -> Keyspace {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"v".to_vec()));
+        ks
+    }
+
+    #[test]
+    fn test_expire() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["key", "100"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        let when = ks.expire_time(b"key").unwrap();
+        assert!(when > now_ms() + 99_000 && when <= now_ms() + 100_000);
+
+        assert_eq!(
+            execute(&mut args(&["missing", "100"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "1.5"]), &mut ks),
+            Err(CommandError::NotAnInteger)
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "9223372036854775807"]), &mut ks),
+            Err(CommandError::InvalidExpireTime("expire"))
+        );
+    }
+
+    #[test]
+    fn test_expire_in_the_past_deletes() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["key", "-1"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert!(!ks.contains_key(b"key"));
+    }
+
+    #[test]
+    fn test_expire_flags() {
+        let mut ks = keyspace();
+
+        // Without an expiry time, XX and GT fail and NX and LT succeed.
+        for flag in ["XX", "GT"] {
+            assert_eq!(
+                execute(&mut args(&["key", "100", flag]), &mut ks),
+                Ok(Frame::Integer(0))
+            );
+        }
+        assert_eq!(
+            execute(&mut args(&["key", "100", "LT"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "200", "NX"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "50", "gt"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "200", "GT", "XX"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "300", "LT"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+    }
+
+    #[test]
+    fn test_expire_incompatible_flags() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["key", "10", "NX", "GT"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR NX and XX, GT or LT options at the same time are not compatible"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "10", "GT", "LT"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR GT and LT options at the same time are not compatible"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "10", "SOON"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Unsupported option SOON"
+            )))
+        );
+    }
+}
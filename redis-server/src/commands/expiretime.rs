@@ -0,0 +1,56 @@
+use crate::commands::{Result, TimeUnit};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// EXPIRETIME key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, TimeUnit::Seconds)
+}
+
+/// Replies with the Unix time at which the key expires, -1 if it has no expiry time or -2 if
+/// there is no such key.
+pub fn generic(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace, unit: TimeUnit) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    if !ks.contains_key(&key) {
+        return Ok(Frame::Integer(-2));
+    }
+
+    Ok(Frame::Integer(match (ks.expire_time(&key), unit) {
+        (None, _) => -1,
+        (Some(when), TimeUnit::Seconds) => (when / 1000) as i64,
+        (Some(when), TimeUnit::Milliseconds) => when as i64,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::Value;
+
+    #[test]
+    fn test_expiretime() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"v".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::Integer(-1))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::Integer(-2))
+        );
+
+        ks.set_expire_time(b"key", 4_102_444_800_123);
+        assert_eq!(
+            execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::Integer(4_102_444_800))
+        );
+        assert_eq!(
+            generic(&mut args(&["key"]), &mut ks, TimeUnit::Milliseconds,),
+            Ok(Frame::Integer(4_102_444_800_123))
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use crate::client::Client;
+use crate::commands::eval::take_keys;
+use crate::commands::exec::Dispatch;
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use crate::scripting::{Invocation, Source};
+use std::collections::VecDeque;
+use std::sync::PoisonError;
+
+/// FCALL function numkeys [key [key ...]] [arg [arg ...]]
+///
+/// Runs a function from a loaded library, calling it with the keys and the other arguments.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+) -> Result<Frame> {
+    generic(args, client, ks, dispatch, false)
+}
+
+/// Runs a function, which has to be flagged `no-writes` if it is run `read_only`.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+    read_only: bool,
+) -> Result<Frame> {
+    let function = String::from_utf8_lossy(&args.pop_front().unwrap()).into_owned();
+    let keys = take_keys(args)?;
+    let argv = args.drain(..).collect();
+
+    let scripting = ks.scripting();
+    let scripting = scripting.lock().unwrap_or_else(PoisonError::into_inner);
+    let invocation = Invocation {
+        source: Source::Function(&function),
+        keys,
+        argv,
+        read_only,
+        protocol: client.protocol,
+    };
+    scripting
+        .functions
+        .call(&function, invocation, ks, dispatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocking::Outcome;
+    use crate::commands::{args, get, incr, CommandError};
+    use std::sync::mpsc;
+
+    fn dispatch(
+        name: &str,
+        args: &mut VecDeque<Vec<u8>>,
+        _client: &mut Client,
+        ks: &mut Keyspace,
+    ) -> Option<Outcome> {
+        let reply = match name {
+            "get" => get::execute(args, ks),
+            "incr" => incr::execute(args, ks),
+            _ => Ok(Frame::error("UNIMPLEMENTED")),
+        };
+        Some(Outcome::Ready(reply.unwrap_or_else(Frame::from)))
+    }
+
+    #[test]
+    fn test_fcall() {
+        let mut ks = Keyspace::new();
+        let (sender, _) = mpsc::channel();
+        let mut client = Client::new(sender);
+        ks.scripting()
+            .lock()
+            .unwrap()
+            .functions
+            .load(
+                b"#!lua name=lib
+redis.register_function('bump', function(keys, args)
+    return {redis.call('INCR', keys[1]), args[1]}
+end)
+redis.register_function{
+    function_name = 'read',
+    callback = function(keys) return redis.call('GET', keys[1]) end,
+    flags = {'no-writes'},
+}
+redis.register_function{
+    function_name = 'sneaky',
+    callback = function(keys) return redis.call('INCR', keys[1]) end,
+    flags = {'no-writes'},
+}",
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(
+            execute(
+                &mut args(&["bump", "1", "a", "x"]),
+                &mut client,
+                &mut ks,
+                dispatch
+            ),
+            Ok(Frame::Array(vec![Frame::Integer(1), Frame::bulk("x")]))
+        );
+        assert_eq!(
+            generic(
+                &mut args(&["read", "1", "a"]),
+                &mut client,
+                &mut ks,
+                dispatch,
+                true,
+            ),
+            Ok(Frame::bulk("1"))
+        );
+        assert_eq!(
+            generic(
+                &mut args(&["bump", "1", "a"]),
+                &mut client,
+                &mut ks,
+                dispatch,
+                true,
+            ),
+            Err(CommandError::Other(String::from(
+                "ERR Can not execute a script with write flag using *_ro command."
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["sneaky", "1", "a"]), &mut client, &mut ks, dispatch),
+            Err(CommandError::Other(String::from(
+                "ERR Write commands are not allowed from read-only scripts. script: sneaky, on @user_function:12."
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "0"]), &mut client, &mut ks, dispatch),
+            Err(CommandError::Other(String::from("ERR Function not found")))
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use crate::client::Client;
+use crate::commands::exec::Dispatch;
+use crate::commands::{fcall, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// FCALL_RO function numkeys [key [key ...]] [arg [arg ...]]
+///
+/// Runs a function as FCALL does, as long as it is flagged `no-writes`.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+) -> Result<Frame> {
+    fcall::generic(args, client, ks, dispatch, true)
+}
@@ -0,0 +1,11 @@
+use crate::commands::{flushdb, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// FLUSHALL [ASYNC | SYNC]
+///
+/// There is only the one database, so this is the same as FLUSHDB.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    flushdb::generic(args, ks, "flushall")
+}
@@ -0,0 +1,52 @@
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// FLUSHDB [ASYNC | SYNC]
+///
+/// Removes every key. Either way it happens straight away.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, "flushdb")
+}
+
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    name: &'static str,
+) -> Result<Frame> {
+    match args.pop_front() {
+        None => {}
+        Some(mode) if mode.eq_ignore_ascii_case(b"ASYNC") || mode.eq_ignore_ascii_case(b"SYNC") => {
+        }
+        Some(_) => return Err(CommandError::Syntax),
+    }
+    if !args.is_empty() {
+        return Err(CommandError::WrongArity(name));
+    }
+
+    ks.flush();
+    Ok(Frame::ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, get, set};
+
+    #[test]
+    fn test_flushdb() {
+        let mut ks = Keyspace::new();
+        set::execute(&mut args(&["a", "1", "EX", "100"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["LAZY"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(execute(&mut args(&["SYNC"]), &mut ks), Ok(Frame::ok()));
+        assert_eq!(
+            get::execute(&mut args(&["a"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::commands::{script, CommandError, Result};
+use crate::db::Keyspace;
+use crate::functions::{Library, RestorePolicy};
+use crate::glob::glob_match;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+use std::sync::PoisonError;
+
+/// FUNCTION LOAD [REPLACE] function-code
+/// FUNCTION LIST [LIBRARYNAME library-name-pattern] [WITHCODE]
+/// FUNCTION DELETE library-name
+/// FUNCTION DUMP
+/// FUNCTION RESTORE serialized-value [FLUSH | APPEND | REPLACE]
+/// FUNCTION FLUSH [ASYNC | SYNC]
+/// FUNCTION KILL
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let subcommand = args.pop_front().unwrap().to_ascii_uppercase();
+
+    let scripting = ks.scripting();
+    let mut scripting = scripting.lock().unwrap_or_else(PoisonError::into_inner);
+    let functions = &mut scripting.functions;
+    match subcommand.as_slice() {
+        b"LOAD" => {
+            let code = args.pop_back().unwrap();
+            let replace = match args.pop_front() {
+                None => false,
+                Some(option) if option.eq_ignore_ascii_case(b"REPLACE") && args.is_empty() => true,
+                Some(option) => {
+                    return Err(CommandError::Other(format!(
+                        "ERR Unknown option given: {}",
+                        String::from_utf8_lossy(&option)
+                    )))
+                }
+            };
+            Ok(Frame::bulk(functions.load(&code, replace)?))
+        }
+        b"LIST" => {
+            let mut pattern = None;
+            let mut with_code = false;
+            while let Some(option) = args.pop_front() {
+                match option.to_ascii_uppercase().as_slice() {
+                    b"WITHCODE" if !with_code => with_code = true,
+                    b"LIBRARYNAME" if pattern.is_none() => {
+                        pattern = Some(args.pop_front().ok_or_else(|| {
+                            CommandError::Other(String::from(
+                                "ERR library name argument was not given",
+                            ))
+                        })?);
+                    }
+                    _ => {
+                        return Err(CommandError::Other(format!(
+                            "ERR Unknown argument {}",
+                            String::from_utf8_lossy(&option)
+                        )))
+                    }
+                }
+            }
+
+            Ok(Frame::Array(
+                functions
+                    .libraries()
+                    .filter(|library| {
+                        pattern
+                            .as_ref()
+                            .is_none_or(|pattern| glob_match(pattern, library.name.as_bytes()))
+                    })
+                    .map(|library| describe(library, with_code))
+                    .collect(),
+            ))
+        }
+        b"DELETE" => match functions.delete(&String::from_utf8_lossy(&args[0])) {
+            true => Ok(Frame::ok()),
+            false => Err(CommandError::Other(String::from("ERR Library not found"))),
+        },
+        b"DUMP" => Ok(Frame::bulk(functions.dump())),
+        b"RESTORE" if args.len() <= 2 => {
+            let policy = match args.get(1).map(|policy| policy.to_ascii_uppercase()) {
+                None => RestorePolicy::Append,
+                Some(policy) if policy == b"APPEND" => RestorePolicy::Append,
+                Some(policy) if policy == b"REPLACE" => RestorePolicy::Replace,
+                Some(policy) if policy == b"FLUSH" => RestorePolicy::Flush,
+                Some(_) => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR Wrong restore policy given, value should be either FLUSH, APPEND or REPLACE.",
+                    )))
+                }
+            };
+            functions.restore(&args[0], policy)?;
+            Ok(Frame::ok())
+        }
+        b"FLUSH" if args.len() <= 1 => {
+            match args.front() {
+                None => {}
+                Some(mode)
+                    if mode.eq_ignore_ascii_case(b"ASYNC")
+                        || mode.eq_ignore_ascii_case(b"SYNC") => {}
+                Some(_) => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR FUNCTION FLUSH only supports SYNC|ASYNC option",
+                    )))
+                }
+            }
+            functions.flush();
+            Ok(Frame::ok())
+        }
+        // As with SCRIPT KILL, a running function is killed before the keyspace is locked.
+        b"KILL" => script::kill(&scripting.status()),
+        // RESTORE and FLUSH with more arguments than they take.
+        _ => Err(CommandError::Other(format!(
+            "ERR unknown subcommand or wrong number of arguments for '{}' command",
+            String::from_utf8_lossy(&subcommand).to_lowercase()
+        ))),
+    }
+}
+
+/// A library as FUNCTION LIST shows it.
+fn describe(library: &Library, with_code: bool) -> Frame {
+    let functions = library
+        .functions
+        .iter()
+        .map(|(name, function)| {
+            Frame::Map(vec![
+                (Frame::bulk("name"), Frame::bulk(name.as_str())),
+                (
+                    Frame::bulk("description"),
+                    function
+                        .description
+                        .as_deref()
+                        .map_or(Frame::Null, Frame::bulk),
+                ),
+                (
+                    Frame::bulk("flags"),
+                    Frame::Set(
+                        function
+                            .flags
+                            .iter()
+                            .map(|flag| Frame::bulk(flag.as_str()))
+                            .collect(),
+                    ),
+                ),
+            ])
+        })
+        .collect();
+
+    let mut fields = vec![
+        (
+            Frame::bulk("library_name"),
+            Frame::bulk(library.name.as_str()),
+        ),
+        (Frame::bulk("engine"), Frame::bulk("LUA")),
+        (Frame::bulk("functions"), Frame::Array(functions)),
+    ];
+    if with_code {
+        fields.push((
+            Frame::bulk("library_code"),
+            Frame::bulk(library.code.clone()),
+        ));
+    }
+    Frame::Map(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    const LIBRARY: &str = "#!lua name=lib\nredis.register_function('f', function() return 1 end)";
+
+    #[test]
+    fn test_function() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["LOAD", LIBRARY]), &mut ks),
+            Ok(Frame::bulk("lib"))
+        );
+        assert_eq!(
+            execute(&mut args(&["LOAD", "REPLACE", LIBRARY]), &mut ks),
+            Ok(Frame::bulk("lib"))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["LIST", "LIBRARYNAME", "l*", "WITHCODE"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![Frame::Map(vec![
+                (Frame::bulk("library_name"), Frame::bulk("lib")),
+                (Frame::bulk("engine"), Frame::bulk("LUA")),
+                (
+                    Frame::bulk("functions"),
+                    Frame::Array(vec![Frame::Map(vec![
+                        (Frame::bulk("name"), Frame::bulk("f")),
+                        (Frame::bulk("description"), Frame::Null),
+                        (Frame::bulk("flags"), Frame::Set(Vec::new())),
+                    ])])
+                ),
+                (Frame::bulk("library_code"), Frame::bulk(LIBRARY)),
+            ])]))
+        );
+        assert_eq!(
+            execute(&mut args(&["LIST", "LIBRARYNAME", "x*"]), &mut ks),
+            Ok(Frame::Array(Vec::new()))
+        );
+
+        let dump = match execute(&mut args(&["DUMP"]), &mut ks) {
+            Ok(Frame::BulkString(dump)) => dump,
+            other => panic!("expected a payload, got {:?}", other),
+        };
+        assert_eq!(
+            execute(&mut args(&["DELETE", "lib"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            execute(&mut args(&["DELETE", "lib"]), &mut ks),
+            Err(CommandError::Other(String::from("ERR Library not found")))
+        );
+
+        let mut restore = VecDeque::from([b"RESTORE".to_vec(), dump]);
+        assert_eq!(execute(&mut restore, &mut ks), Ok(Frame::ok()));
+        assert_eq!(
+            execute(&mut args(&["FLUSH", "SYNC"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            execute(&mut args(&["LIST"]), &mut ks),
+            Ok(Frame::Array(Vec::new()))
+        );
+    }
+}
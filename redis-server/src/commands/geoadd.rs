@@ -0,0 +1,92 @@
+use crate::commands::{parse_f64, zadd, CommandError, Result};
+use crate::db::Keyspace;
+use crate::geo;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GEOADD key [NX | XX] [CH] longitude latitude member [longitude latitude member ...]
+///
+/// Adds the members to the sorted set at `key` with their positions as geohash scores, and
+/// replies as ZADD would.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let mut zadd_args = VecDeque::with_capacity(args.len());
+    zadd_args.push_back(args.pop_front().unwrap());
+    while let Some(option) = args.front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"NX" | b"XX" | b"CH" => zadd_args.push_back(args.pop_front().unwrap()),
+            _ => break,
+        }
+    }
+
+    if args.is_empty() || !args.len().is_multiple_of(3) {
+        return Err(CommandError::Syntax);
+    }
+
+    while let (Some(longitude), Some(latitude), Some(member)) =
+        (args.pop_front(), args.pop_front(), args.pop_front())
+    {
+        let (longitude, latitude) = parse_position(&longitude, &latitude)?;
+        zadd_args.push_back(geo::score(longitude, latitude).to_string().into_bytes());
+        zadd_args.push_back(member);
+    }
+
+    zadd::execute(&mut zadd_args, ks)
+}
+
+/// Parses a longitude and latitude, which have to be in the range a geohash can hold.
+pub fn parse_position(longitude: &[u8], latitude: &[u8]) -> Result<(f64, f64)> {
+    let longitude = parse_f64(longitude)?;
+    let latitude = parse_f64(latitude)?;
+    if !geo::valid(longitude, latitude) {
+        return Err(CommandError::Other(format!(
+            "ERR invalid longitude,latitude pair {:.6},{:.6}",
+            longitude, latitude
+        )));
+    }
+    Ok((longitude, latitude))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zscore};
+
+    #[test]
+    fn test_geoadd() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "Sicily",
+                    "13.361389",
+                    "38.115556",
+                    "Palermo",
+                    "15.087269",
+                    "37.502669",
+                    "Catania"
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            zscore::execute(&mut args(&["Sicily", "Palermo"]), &mut ks),
+            Ok(Frame::Double(3479099956230698.0))
+        );
+        assert_eq!(
+            execute(&mut args(&["Sicily", "NX", "0", "0", "Palermo"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["Sicily", "0", "86", "North"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR invalid longitude,latitude pair 0.000000,86.000000"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["Sicily", "0", "0", "Null", "1"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+    }
+}
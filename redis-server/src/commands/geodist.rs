@@ -0,0 +1,108 @@
+use crate::commands::{get_zset, CommandError, Result};
+use crate::db::Keyspace;
+use crate::geo;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GEODIST key member1 member2 [M | KM | FT | MI]
+///
+/// Replies with the distance between the two members in the given unit, meters by default, or
+/// nil if either is missing.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if args.len() > 4 {
+        return Err(CommandError::WrongArity("geodist"));
+    }
+
+    let key = args.pop_front().unwrap();
+    let first = args.pop_front().unwrap();
+    let second = args.pop_front().unwrap();
+    let unit = match args.pop_front() {
+        Some(unit) => parse_unit(&unit)?,
+        None => 1.0,
+    };
+
+    let Some(zset) = get_zset(ks, &key)? else {
+        return Ok(Frame::NullBulkString);
+    };
+    let (Some(first), Some(second)) = (zset.score(&first), zset.score(&second)) else {
+        return Ok(Frame::NullBulkString);
+    };
+
+    let (lon1, lat1) = geo::position(first);
+    let (lon2, lat2) = geo::position(second);
+    Ok(distance_reply(geo::distance(lon1, lat1, lon2, lat2) / unit))
+}
+
+/// Parses a unit of distance into how many meters it is.
+pub fn parse_unit(arg: &[u8]) -> Result<f64> {
+    match arg.to_ascii_lowercase().as_slice() {
+        b"m" => Ok(1.0),
+        b"km" => Ok(1000.0),
+        b"ft" => Ok(0.3048),
+        b"mi" => Ok(1609.34),
+        _ => Err(CommandError::Other(String::from(
+            "ERR unsupported unit provided. please use M, KM, FT, MI",
+        ))),
+    }
+}
+
+/// Distances are replied with to four decimal places, as Redis does.
+pub fn distance_reply(distance: f64) -> Frame {
+    Frame::bulk(format!("{:.4}", distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, geoadd, geohash, geopos};
+
+    #[test]
+    fn test_geodist() {
+        let mut ks = Keyspace::new();
+        geoadd::execute(
+            &mut args(&[
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+            ]),
+            &mut ks,
+        )
+        .unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["Sicily", "Palermo", "Catania"]), &mut ks),
+            Ok(Frame::bulk("166274.1516"))
+        );
+        assert_eq!(
+            execute(&mut args(&["Sicily", "Palermo", "Catania", "km"]), &mut ks),
+            Ok(Frame::bulk("166.2742"))
+        );
+        assert_eq!(
+            execute(&mut args(&["Sicily", "Palermo", "Nowhere"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert!(execute(&mut args(&["Sicily", "Palermo", "Catania", "yd"]), &mut ks).is_err());
+
+        assert_eq!(
+            geohash::execute(&mut args(&["Sicily", "Palermo", "Nowhere"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::bulk("sqc8b49rny0"),
+                Frame::NullBulkString
+            ]))
+        );
+        match geopos::execute(&mut args(&["Sicily", "Palermo", "Nowhere"]), &mut ks) {
+            Ok(Frame::Array(positions)) => {
+                assert!(matches!(
+                    &positions[0],
+                    Frame::Array(coordinates) if matches!(coordinates[0], Frame::Double(lon) if (lon - 13.361389).abs() < 1e-5)
+                ));
+                assert_eq!(positions[1], Frame::NullArray);
+            }
+            reply => panic!("{:?}", reply),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::commands::{get_zset, Result};
+use crate::db::Keyspace;
+use crate::geo;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GEOHASH key [member [member ...]]
+///
+/// Replies with the standard geohash string of each member, or nil for those not in the set.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let zset = get_zset(ks, &key)?;
+
+    let hashes = args
+        .iter()
+        .map(
+            |member| match zset.as_ref().and_then(|zset| zset.score(member)) {
+                Some(score) => Frame::bulk(geo::geohash(score)),
+                None => Frame::NullBulkString,
+            },
+        )
+        .collect();
+
+    Ok(Frame::Array(hashes))
+}
@@ -0,0 +1,30 @@
+use crate::commands::{get_zset, Result};
+use crate::db::Keyspace;
+use crate::geo;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GEOPOS key [member [member ...]]
+///
+/// Replies with the longitude and latitude of each member, or nil for those not in the set.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let zset = get_zset(ks, &key)?;
+
+    let positions = args
+        .iter()
+        .map(
+            |member| match zset.as_ref().and_then(|zset| zset.score(member)) {
+                Some(score) => coordinates(geo::position(score)),
+                None => Frame::NullArray,
+            },
+        )
+        .collect();
+
+    Ok(Frame::Array(positions))
+}
+
+/// A longitude and latitude as Redis replies with them.
+pub fn coordinates((longitude, latitude): (f64, f64)) -> Frame {
+    Frame::Array(vec![Frame::Double(longitude), Frame::Double(latitude)])
+}
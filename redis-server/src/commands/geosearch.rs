@@ -0,0 +1,398 @@
+use crate::commands::geoadd::parse_position;
+use crate::commands::geodist::{distance_reply, parse_unit};
+use crate::commands::{geopos, get_zset, parse_f64, parse_i64, zrangestore, CommandError, Result};
+use crate::db::{Keyspace, SortedSet};
+use crate::geo::{self, Shape};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GEOSEARCH key FROMMEMBER member | FROMLONLAT longitude latitude BYRADIUS radius unit |
+/// BYBOX width height unit [ASC | DESC] [COUNT count [ANY]] [WITHCOORD] [WITHDIST] [WITHHASH]
+///
+/// Replies with the members inside the circle or box around the centre. With COUNT only the
+/// nearest are returned, or with ANY the first found, which is quicker.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, None)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Sort {
+    None,
+    Asc,
+    Desc,
+}
+
+enum Centre {
+    Member(Vec<u8>),
+    Position(f64, f64),
+}
+
+/// A member found by a search.
+struct Found {
+    member: Vec<u8>,
+    score: f64,
+    position: (f64, f64),
+    /// In meters.
+    distance: f64,
+}
+
+/// Runs GEOSEARCH, or GEOSEARCHSTORE when given where to store the result.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    destination: Option<Vec<u8>>,
+) -> Result<Frame> {
+    let name = if destination.is_some() {
+        "geosearchstore"
+    } else {
+        "geosearch"
+    };
+    let key = args.pop_front().unwrap();
+
+    let mut centre = None;
+    let mut shape = None;
+    let mut unit = 1.0;
+    let mut sort = Sort::None;
+    let mut count = None;
+    let mut any = false;
+    let (mut with_coord, mut with_dist, mut with_hash, mut store_dist) =
+        (false, false, false, false);
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"FROMMEMBER" if !args.is_empty() && centre.is_none() => {
+                centre = Some(Centre::Member(args.pop_front().unwrap()));
+            }
+            b"FROMLONLAT" if args.len() >= 2 && centre.is_none() => {
+                let (longitude, latitude) = parse_position(&args[0], &args[1])?;
+                args.drain(..2);
+                centre = Some(Centre::Position(longitude, latitude));
+            }
+            b"BYRADIUS" if args.len() >= 2 && shape.is_none() => {
+                let radius = parse_f64(&args[0])
+                    .map_err(|_| CommandError::Other(String::from("ERR need numeric radius")))?;
+                if radius < 0.0 {
+                    return Err(CommandError::Other(String::from(
+                        "ERR radius cannot be negative",
+                    )));
+                }
+                unit = parse_unit(&args[1])?;
+                args.drain(..2);
+                shape = Some(Shape::Radius(radius * unit));
+            }
+            b"BYBOX" if args.len() >= 3 && shape.is_none() => {
+                let width = parse_f64(&args[0])
+                    .map_err(|_| CommandError::Other(String::from("ERR need numeric width")))?;
+                let height = parse_f64(&args[1])
+                    .map_err(|_| CommandError::Other(String::from("ERR need numeric height")))?;
+                if width < 0.0 || height < 0.0 {
+                    return Err(CommandError::Other(String::from(
+                        "ERR height or width cannot be negative",
+                    )));
+                }
+                unit = parse_unit(&args[2])?;
+                args.drain(..3);
+                shape = Some(Shape::Box {
+                    width: width * unit,
+                    height: height * unit,
+                });
+            }
+            b"ASC" => sort = Sort::Asc,
+            b"DESC" => sort = Sort::Desc,
+            b"COUNT" if !args.is_empty() => {
+                let n = parse_i64(&args.pop_front().unwrap())?;
+                if n <= 0 {
+                    return Err(CommandError::Other(String::from("ERR COUNT must be > 0")));
+                }
+                count = Some(n as usize);
+                if args
+                    .front()
+                    .is_some_and(|arg| arg.eq_ignore_ascii_case(b"ANY"))
+                {
+                    args.pop_front();
+                    any = true;
+                }
+            }
+            b"WITHCOORD" => with_coord = true,
+            b"WITHDIST" => with_dist = true,
+            b"WITHHASH" => with_hash = true,
+            b"STOREDIST" if destination.is_some() => store_dist = true,
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    if destination.is_some() && (with_dist || with_hash || with_coord) {
+        return Err(CommandError::Other(String::from(
+            "ERR GEOSEARCHSTORE is not compatible with WITHDIST, WITHHASH and WITHCOORD options",
+        )));
+    }
+    let Some(centre) = centre else {
+        return Err(CommandError::Other(format!(
+            "ERR exactly one of FROMMEMBER or FROMLONLAT can be specified for {}",
+            name
+        )));
+    };
+    let Some(shape) = shape else {
+        return Err(CommandError::Other(format!(
+            "ERR exactly one of BYRADIUS and BYBOX can be specified for {}",
+            name
+        )));
+    };
+    if any && count.is_none() {
+        return Err(CommandError::Other(String::from(
+            "ERR the ANY argument requires COUNT argument",
+        )));
+    }
+
+    let Some(zset) = get_zset(ks, &key)? else {
+        return Ok(match destination {
+            Some(destination) => zrangestore::store(ks, destination, SortedSet::new()),
+            None => Frame::Array(Vec::new()),
+        });
+    };
+    let centre = match centre {
+        Centre::Position(longitude, latitude) => (longitude, latitude),
+        Centre::Member(member) => match zset.score(&member) {
+            Some(score) => geo::position(score),
+            None => {
+                return Err(CommandError::Other(String::from(
+                    "ERR could not decode requested zset member",
+                )))
+            }
+        },
+    };
+
+    let limit = if any { count } else { None };
+    let mut found = search(zset, shape, centre, limit);
+
+    // The nearest are wanted when there is a COUNT, unless any will do.
+    if sort == Sort::None && count.is_some() && !any {
+        sort = Sort::Asc;
+    }
+    match sort {
+        Sort::Asc => found.sort_by(|a, b| a.distance.total_cmp(&b.distance)),
+        Sort::Desc => found.sort_by(|a, b| b.distance.total_cmp(&a.distance)),
+        Sort::None => {}
+    }
+    if let Some(count) = count {
+        found.truncate(count);
+    }
+
+    if let Some(destination) = destination {
+        let mut result = SortedSet::new();
+        for found in found {
+            let score = if store_dist {
+                found.distance / unit
+            } else {
+                found.score
+            };
+            result.insert(found.member, score);
+        }
+        return Ok(zrangestore::store(ks, destination, result));
+    }
+
+    let replies = found
+        .into_iter()
+        .map(|found| {
+            if !(with_dist || with_hash || with_coord) {
+                return Frame::bulk(found.member);
+            }
+
+            let mut reply = vec![Frame::bulk(found.member)];
+            if with_dist {
+                reply.push(distance_reply(found.distance / unit));
+            }
+            if with_hash {
+                reply.push(Frame::Integer(found.score as i64));
+            }
+            if with_coord {
+                reply.push(geopos::coordinates(found.position));
+            }
+            Frame::Array(reply)
+        })
+        .collect();
+    Ok(Frame::Array(replies))
+}
+
+/// Finds the members inside `shape`, looking only at the areas around the centre, and stopping
+/// once `limit` have been found.
+fn search(zset: &SortedSet, shape: Shape, centre: (f64, f64), limit: Option<usize>) -> Vec<Found> {
+    let mut found = Vec::new();
+    for (min, max) in shape.score_ranges(centre) {
+        let start = zset.count_before(|score, _| score < min);
+        for (member, score) in zset.iter_from(start) {
+            if score >= max || limit.is_some_and(|limit| found.len() >= limit) {
+                break;
+            }
+
+            let position = geo::position(score);
+            if let Some(distance) = shape.contains(centre, position) {
+                found.push(Found {
+                    member: member.to_vec(),
+                    score,
+                    position,
+                    distance,
+                });
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, geoadd};
+
+    fn sicily() -> Keyspace {
+        let mut ks = Keyspace::new();
+        geoadd::execute(
+            &mut args(&[
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+                "12.758489",
+                "38.788135",
+                "edge1",
+                "17.241510",
+                "38.788135",
+                "edge2",
+            ]),
+            &mut ks,
+        )
+        .unwrap();
+        ks
+    }
+
+    #[test]
+    fn test_geosearch_by_radius() {
+        let mut ks = sicily();
+
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYRADIUS",
+                    "200",
+                    "km",
+                    "ASC"
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::bulk("Catania"),
+                Frame::bulk("Palermo")
+            ]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "Sicily",
+                    "FROMMEMBER",
+                    "Palermo",
+                    "BYRADIUS",
+                    "200",
+                    "km",
+                    "COUNT",
+                    "2",
+                    "WITHDIST",
+                    "WITHHASH"
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::Array(vec![
+                    Frame::bulk("Palermo"),
+                    Frame::bulk("0.0000"),
+                    Frame::Integer(3479099956230698)
+                ]),
+                Frame::Array(vec![
+                    Frame::bulk("edge1"),
+                    Frame::bulk("91.4007"),
+                    Frame::Integer(3479273021651468)
+                ]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_geosearch_by_box() {
+        let mut ks = sicily();
+
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYBOX",
+                    "400",
+                    "400",
+                    "km",
+                    "ASC"
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::bulk("Catania"),
+                Frame::bulk("Palermo"),
+                Frame::bulk("edge2"),
+                Frame::bulk("edge1"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_geosearch_errors() {
+        let mut ks = sicily();
+
+        assert_eq!(
+            execute(
+                &mut args(&["Sicily", "BYRADIUS", "1", "km", "ASC"]),
+                &mut ks
+            ),
+            Err(CommandError::Other(String::from(
+                "ERR exactly one of FROMMEMBER or FROMLONLAT can be specified for geosearch"
+            )))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["Sicily", "FROMMEMBER", "Nowhere", "BYRADIUS", "1", "km"]),
+                &mut ks
+            ),
+            Err(CommandError::Other(String::from(
+                "ERR could not decode requested zset member"
+            )))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "Sicily",
+                    "FROMLONLAT",
+                    "0",
+                    "0",
+                    "BYRADIUS",
+                    "1",
+                    "km",
+                    "ANY"
+                ]),
+                &mut ks
+            ),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["missing", "FROMLONLAT", "0", "0", "BYRADIUS", "1", "km"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![]))
+        );
+    }
+}
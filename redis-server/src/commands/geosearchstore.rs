@@ -0,0 +1,81 @@
+use crate::commands::{geosearch, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GEOSEARCHSTORE destination source FROMMEMBER member | FROMLONLAT longitude latitude
+/// BYRADIUS radius unit | BYBOX width height unit [ASC | DESC] [COUNT count [ANY]] [STOREDIST]
+///
+/// Stores what GEOSEARCH would reply with as a sorted set, scored by geohash or with STOREDIST
+/// by distance, and replies with how many members it has.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let destination = args.pop_front().unwrap();
+    geosearch::generic(args, ks, Some(destination))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, geoadd, zrange};
+
+    #[test]
+    fn test_geosearchstore() {
+        let mut ks = Keyspace::new();
+        geoadd::execute(
+            &mut args(&[
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+            ]),
+            &mut ks,
+        )
+        .unwrap();
+
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "near",
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYRADIUS",
+                    "100",
+                    "km",
+                    "STOREDIST"
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            zrange::execute(&mut args(&["near", "0", "-1", "WITHSCORES"]), &mut ks),
+            Ok(Frame::Pairs(vec![(
+                Frame::bulk("Catania"),
+                Frame::Double(56.4412578701582)
+            )]))
+        );
+
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "near",
+                    "Sicily",
+                    "FROMLONLAT",
+                    "0",
+                    "0",
+                    "BYRADIUS",
+                    "1",
+                    "km"
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Integer(0))
+        );
+        assert!(!ks.contains_key(b"near"));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::commands::{get_string, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GET key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    match get_string(ks, &key)? {
+        Some(value) => Ok(Frame::bulk(value.clone())),
+        None => Ok(Frame::NullBulkString),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, CommandError};
+    use crate::db::Value;
+
+    #[test]
+    fn test_get() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"value".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::bulk("value"))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+
+        ks.set(
+            b"list".to_vec(),
+            Value::List(VecDeque::from([b"a".to_vec()])),
+        );
+        assert_eq!(
+            execute(&mut args(&["list"]), &mut ks),
+            Err(CommandError::WrongType)
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use crate::commands::{get_string, parse_bit_offset, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GETBIT key offset
+///
+/// Bits past the end of the string are 0.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let offset = parse_bit_offset(&args.pop_front().unwrap(), None)?;
+
+    let value = get_string(ks, &key)?.map_or(&[][..], |value| value.as_slice());
+    Ok(Frame::Integer(bit(value, offset) as i64))
+}
+
+/// The bit at `offset`, counting from the most significant bit of the first byte.
+pub fn bit(value: &[u8], offset: u64) -> u8 {
+    match value.get((offset >> 3) as usize) {
+        Some(byte) => (byte >> (7 - (offset & 7))) & 1,
+        None => 0,
+    }
+}
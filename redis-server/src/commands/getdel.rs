@@ -0,0 +1,16 @@
+use crate::commands::{get_string, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GETDEL key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let value = match get_string(ks, &key)? {
+        Some(value) => std::mem::take(value),
+        None => return Ok(Frame::NullBulkString),
+    };
+
+    ks.remove(&key);
+    Ok(Frame::bulk(value))
+}
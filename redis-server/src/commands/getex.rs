@@ -0,0 +1,80 @@
+use crate::commands::{expire_at, expire_option, get_string, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GETEX key [EX seconds | PX milliseconds | EXAT unix-time-seconds |
+///   PXAT unix-time-milliseconds | PERSIST]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+
+    let mut when = None;
+    let mut persist = false;
+    match (args.pop_front(), args.len()) {
+        (None, _) => {}
+        (Some(option), 0) if option.eq_ignore_ascii_case(b"PERSIST") => persist = true,
+        (Some(option), 1) => match expire_option(&option) {
+            Some((unit, relative)) => {
+                let amount = parse_i64(&args.pop_front().unwrap())?;
+                when = Some(expire_at(amount, unit, relative, "getex")?);
+            }
+            None => return Err(CommandError::Syntax),
+        },
+        _ => return Err(CommandError::Syntax),
+    }
+
+    let value = match get_string(ks, &key)? {
+        Some(value) => value.clone(),
+        None => return Ok(Frame::NullBulkString),
+    };
+
+    if let Some(when) = when {
+        ks.set_expire_time(&key, when);
+    } else if persist {
+        ks.persist(&key);
+    }
+
+    Ok(Frame::bulk(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::{now_ms, Value};
+
+    #[test]
+    fn test_getex() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"v".to_vec()));
+
+        assert_eq!(execute(&mut args(&["key"]), &mut ks), Ok(Frame::bulk("v")));
+        assert_eq!(ks.expire_time(b"key"), None);
+
+        execute(&mut args(&["key", "px", "5000"]), &mut ks).unwrap();
+        assert!(ks.expire_time(b"key").unwrap() > now_ms());
+
+        execute(&mut args(&["key", "PERSIST"]), &mut ks).unwrap();
+        assert_eq!(ks.expire_time(b"key"), None);
+
+        assert_eq!(
+            execute(&mut args(&["missing", "EX", "10"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "EX", "10", "PERSIST"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "EX", "-1"]), &mut ks),
+            Err(CommandError::InvalidExpireTime("getex"))
+        );
+
+        // The value is still returned when the new expiry time has already passed.
+        assert_eq!(
+            execute(&mut args(&["key", "PXAT", "1"]), &mut ks),
+            Ok(Frame::bulk("v"))
+        );
+        assert_eq!(ks.get(b"key"), None);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::commands::{get_string, parse_i64, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GETRANGE key start end
+///
+/// Both ends are inclusive, and negative offsets count back from the end of the string.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let start = parse_i64(&args.pop_front().unwrap())?;
+    let end = parse_i64(&args.pop_front().unwrap())?;
+
+    let value = match get_string(ks, &key)? {
+        Some(value) => value,
+        None => return Ok(Frame::bulk("")),
+    };
+
+    Ok(Frame::bulk(substring(value, start, end)))
+}
+
+/// The part of `value` between the inclusive offsets `start` and `end`, clamped to the string.
+fn substring(value: &[u8], start: i64, end: i64) -> Vec<u8> {
+    let len = value.len() as i64;
+    if len == 0 || (start < 0 && end < 0 && start > end) {
+        return Vec::new();
+    }
+
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let end = if end < 0 {
+        (len + end).max(0)
+    } else {
+        end.min(len - 1)
+    };
+    if start > end {
+        return Vec::new();
+    }
+
+    value[start as usize..=end as usize].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substring() {
+        let value = b"This is a string";
+
+        assert_eq!(substring(value, 0, 3), b"This");
+        assert_eq!(substring(value, -3, -1), b"ing");
+        assert_eq!(substring(value, 0, -1), value);
+        assert_eq!(substring(value, 10, 100), b"string");
+        assert_eq!(substring(value, 5, 3), b"");
+        assert_eq!(substring(value, -1, -5), b"");
+        assert_eq!(substring(value, -100, 0), b"T");
+        assert_eq!(substring(b"", 0, -1), b"");
+    }
+}
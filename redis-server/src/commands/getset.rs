@@ -0,0 +1,20 @@
+use crate::commands::{get_string, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// GETSET key value
+///
+/// Like SET, this drops any expiry time the key had.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let value = args.pop_front().unwrap();
+
+    let old = match get_string(ks, &key)? {
+        Some(old) => Frame::bulk(old.clone()),
+        None => Frame::NullBulkString,
+    };
+
+    ks.set(key, Value::String(value));
+    Ok(old)
+}
@@ -0,0 +1,46 @@
+use crate::commands::{get_hash, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HDEL key field [field ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let hash = match get_hash(ks, &key)? {
+        Some(hash) => hash,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    let removed = args
+        .iter()
+        .filter(|field| hash.remove(field).is_some())
+        .count();
+
+    if hash.is_empty() {
+        ks.remove(&key);
+    }
+
+    Ok(Frame::Integer(removed as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hset};
+
+    #[test]
+    fn test_hdel() {
+        let mut ks = Keyspace::new();
+        hset::execute(&mut args(&["h", "a", "1", "b", "2"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["h", "a", "x"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "b"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert!(!ks.contains_key(b"h"));
+    }
+}
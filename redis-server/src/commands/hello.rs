@@ -0,0 +1,74 @@
+use crate::client::Client;
+use crate::commands::{CommandError, Result};
+use crate::resp::decoder::parse_integer;
+use crate::resp::types::{Frame, Protocol};
+use std::collections::VecDeque;
+
+pub const SERVER_NAME: &str = "redis";
+pub const SERVER_VERSION: &str = "7.2.4";
+
+/// HELLO [protover [AUTH username password] [SETNAME clientname]]
+///
+/// Switches the connection to the requested protocol, then replies with the server's details in
+/// that protocol.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, client: &mut Client) -> Result<Frame> {
+    if let Some(version) = args.pop_front() {
+        let protocol = match parse_integer(&version) {
+            Some(2) => Protocol::Resp2,
+            Some(3) => Protocol::Resp3,
+            Some(_) => {
+                return Err(CommandError::Other(String::from(
+                    "NOPROTO unsupported protocol version",
+                )))
+            }
+            None => {
+                return Err(CommandError::Other(String::from(
+                    "ERR Protocol version is not an integer or out of range",
+                )))
+            }
+        };
+
+        // Every option is checked before anything changes, so a bad option leaves the
+        // connection as it was.
+        while let Some(option) = args.pop_front() {
+            match option.to_ascii_lowercase().as_slice() {
+                // There are no users or passwords, so any credentials are good.
+                b"auth" if args.len() >= 2 => {
+                    args.drain(..2);
+                }
+                // Connections aren't named yet, so the name is only validated.
+                b"setname" if !args.is_empty() => {
+                    let name = args.pop_front().unwrap();
+                    if !name.iter().all(|b| (b'!'..=b'~').contains(b)) {
+                        return Err(CommandError::Other(String::from(
+                            "ERR Client names cannot contain spaces, newlines or special characters.",
+                        )));
+                    }
+                }
+                _ => {
+                    return Err(CommandError::Other(format!(
+                        "ERR Syntax error in HELLO option '{}'",
+                        String::from_utf8_lossy(&option)
+                    )))
+                }
+            }
+        }
+
+        client.protocol = protocol;
+    }
+
+    let proto = match client.protocol {
+        Protocol::Resp2 => 2,
+        Protocol::Resp3 => 3,
+    };
+
+    Ok(Frame::Map(vec![
+        (Frame::bulk("server"), Frame::bulk(SERVER_NAME)),
+        (Frame::bulk("version"), Frame::bulk(SERVER_VERSION)),
+        (Frame::bulk("proto"), Frame::Integer(proto)),
+        (Frame::bulk("id"), Frame::Integer(client.id as i64)),
+        (Frame::bulk("mode"), Frame::bulk("standalone")),
+        (Frame::bulk("role"), Frame::bulk("master")),
+        (Frame::bulk("modules"), Frame::Array(vec![])),
+    ]))
+}
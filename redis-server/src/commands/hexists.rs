@@ -0,0 +1,13 @@
+use crate::commands::{get_hash, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HEXISTS key field
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let field = args.pop_front().unwrap();
+
+    let exists = get_hash(ks, &key)?.is_some_and(|hash| hash.contains(&field));
+    Ok(Frame::Integer(exists as i64))
+}
@@ -0,0 +1,199 @@
+use crate::commands::{get_hash, parse_i64, CommandError, Result, TimeUnit};
+use crate::db::{now_ms, Keyspace};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// The latest time, in milliseconds, a hash field can be given to expire at.
+const MAX_EXPIRE_TIME_MS: i64 = (1 << 48) - 1;
+
+/// HEXPIRE key seconds [NX | XX | GT | LT] FIELDS numfields field [field ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, TimeUnit::Seconds, "hexpire")
+}
+
+/// Replies with an array holding, for each field, -2 if there is no such field, 0 if a flag
+/// stopped the expiry time being set, 1 if it was set and 2 if the time has already passed and
+/// the field was deleted.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    unit: TimeUnit,
+    name: &'static str,
+) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let amount = parse_i64(&args.pop_front().unwrap())?;
+
+    let (mut nx, mut xx, mut gt, mut lt) = (false, false, false, false);
+    match args[0].to_ascii_uppercase().as_slice() {
+        b"NX" => nx = true,
+        b"XX" => xx = true,
+        b"GT" => gt = true,
+        b"LT" => lt = true,
+        _ => {}
+    }
+    if nx || xx || gt || lt {
+        args.pop_front();
+    }
+
+    let fields = parse_fields(args)?;
+
+    if amount < 0 {
+        return Err(CommandError::Other(String::from(
+            "ERR invalid expire time, must be >= 0",
+        )));
+    }
+    let invalid = CommandError::InvalidExpireTime(name);
+    let ms = match unit {
+        TimeUnit::Seconds if amount > MAX_EXPIRE_TIME_MS / 1000 => return Err(invalid),
+        TimeUnit::Seconds => amount * 1000,
+        TimeUnit::Milliseconds => amount,
+    };
+    let now = now_ms() as i64;
+    if ms > MAX_EXPIRE_TIME_MS - now {
+        return Err(invalid);
+    }
+    let when = now + ms;
+
+    let hash = match get_hash(ks, &key)? {
+        Some(hash) => hash,
+        None => return Ok(Frame::Array(vec![Frame::Integer(-2); fields.len()])),
+    };
+
+    let mut replies = Vec::with_capacity(fields.len());
+    for field in fields {
+        if !hash.contains(&field) {
+            replies.push(Frame::Integer(-2));
+            continue;
+        }
+
+        // A field without an expiry time lives forever, so it is greater than any time.
+        let allowed = match hash.expire_time(&field) {
+            Some(current) => {
+                let current = current as i64;
+                !(nx || (gt && when <= current) || (lt && when >= current))
+            }
+            None => !xx && !gt,
+        };
+
+        if !allowed {
+            replies.push(Frame::Integer(0));
+        } else if when <= now {
+            hash.remove(&field);
+            replies.push(Frame::Integer(2));
+        } else {
+            hash.set_expire_time(&field, when as u64);
+            replies.push(Frame::Integer(1));
+        }
+    }
+
+    if hash.is_empty() {
+        ks.remove(&key);
+    }
+
+    Ok(Frame::Array(replies))
+}
+
+/// Parses the `FIELDS numfields field [field ...]` that ends the field expiry commands.
+pub fn parse_fields(args: &mut VecDeque<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+    match args.pop_front() {
+        Some(keyword) if keyword.eq_ignore_ascii_case(b"FIELDS") => {}
+        _ => {
+            return Err(CommandError::Other(String::from(
+                "ERR Mandatory argument FIELDS is missing or not at the right position",
+            )))
+        }
+    }
+
+    let count = match args.pop_front() {
+        Some(count) => parse_i64(&count)?,
+        None => return Err(CommandError::Syntax),
+    };
+    if count <= 0 {
+        return Err(CommandError::Other(String::from(
+            "ERR Parameter `numFields` should be greater than 0",
+        )));
+    }
+    if count as usize != args.len() {
+        return Err(CommandError::Other(String::from(
+            "ERR The `numfields` parameter must match the number of arguments",
+        )));
+    }
+
+    Ok(args.drain(..).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hget, hset, httl};
+
+    #[test]
+    fn test_hexpire() {
+        let mut ks = Keyspace::new();
+        hset::execute(&mut args(&["h", "a", "1", "b", "2"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["h", "100", "FIELDS", "2", "a", "x"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(1), Frame::Integer(-2)]))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "100", "NX", "FIELDS", "1", "a"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(0)]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["h", "200", "GT", "FIELDS", "2", "a", "b"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![Frame::Integer(1), Frame::Integer(0)]))
+        );
+        assert_eq!(
+            httl::execute(&mut args(&["h", "FIELDS", "2", "a", "b"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(200), Frame::Integer(-1)]))
+        );
+
+        assert_eq!(
+            execute(&mut args(&["h", "0", "FIELDS", "1", "b"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(2)]))
+        );
+        assert_eq!(
+            hget::execute(&mut args(&["h", "b"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "0", "FIELDS", "1", "a"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(2)]))
+        );
+        assert!(!ks.contains_key(b"h"));
+    }
+
+    #[test]
+    fn test_hexpire_errors() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["h", "10", "1", "a", "b"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Mandatory argument FIELDS is missing or not at the right position"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "10", "FIELDS", "2", "a"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR The `numfields` parameter must match the number of arguments"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "10", "FIELDS", "0", "a"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Parameter `numFields` should be greater than 0"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "-1", "FIELDS", "1", "a"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR invalid expire time, must be >= 0"
+            )))
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use crate::commands::{get_hash, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HGET key field
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let field = args.pop_front().unwrap();
+
+    let value = get_hash(ks, &key)?.and_then(|hash| hash.get(&field).cloned());
+    Ok(value.map(Frame::bulk).unwrap_or(Frame::NullBulkString))
+}
@@ -0,0 +1,50 @@
+use crate::commands::{get_hash, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HGETALL key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let pairs = match get_hash(ks, &key)? {
+        Some(hash) => hash
+            .iter()
+            .map(|(field, value)| (Frame::bulk(field.clone()), Frame::bulk(value.clone())))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Frame::Map(pairs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hkeys, hset, hvals};
+
+    #[test]
+    fn test_hgetall_hkeys_hvals() {
+        let mut ks = Keyspace::new();
+        hset::execute(&mut args(&["h", "a", "1", "b", "2"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["h"]), &mut ks),
+            Ok(Frame::Map(vec![
+                (Frame::bulk("a"), Frame::bulk("1")),
+                (Frame::bulk("b"), Frame::bulk("2")),
+            ]))
+        );
+        assert_eq!(
+            hkeys::execute(&mut args(&["h"]), &mut ks),
+            Ok(Frame::bulks(["a", "b"]))
+        );
+        assert_eq!(
+            hvals::execute(&mut args(&["h"]), &mut ks),
+            Ok(Frame::bulks(["1", "2"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::Map(vec![]))
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::commands::{hset, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::decoder::parse_integer;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HINCRBY key field increment
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let field = args.pop_front().unwrap();
+    let increment = parse_i64(&args.pop_front().unwrap())?;
+
+    let hash = hset::create_hash(ks, key)?;
+    let current = match hash.get(&field) {
+        Some(value) => parse_integer(value).ok_or(CommandError::Other(String::from(
+            "ERR hash value is not an integer",
+        )))?,
+        None => 0,
+    };
+
+    let result = current
+        .checked_add(increment)
+        .ok_or(CommandError::Overflow)?;
+    let value = result.to_string().into_bytes();
+    match hash.get_mut(&field) {
+        Some(current) => *current = value,
+        None => {
+            hash.insert(field, value);
+        }
+    }
+
+    Ok(Frame::Integer(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hget};
+
+    #[test]
+    fn test_hincrby() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["h", "n", "5"]), &mut ks),
+            Ok(Frame::Integer(5))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "n", "-7"]), &mut ks),
+            Ok(Frame::Integer(-2))
+        );
+        assert_eq!(
+            hget::execute(&mut args(&["h", "n"]), &mut ks),
+            Ok(Frame::bulk("-2"))
+        );
+
+        hset::execute(&mut args(&["h", "s", "abc"]), &mut ks).unwrap();
+        assert_eq!(
+            execute(&mut args(&["h", "s", "1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR hash value is not an integer"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "n", "x"]), &mut ks),
+            Err(CommandError::NotAnInteger)
+        );
+    }
+}
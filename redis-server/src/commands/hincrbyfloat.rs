@@ -0,0 +1,59 @@
+use crate::commands::{hset, parse_f64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HINCRBYFLOAT key field increment
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let field = args.pop_front().unwrap();
+    let increment = parse_f64(&args.pop_front().unwrap())?;
+
+    let hash = hset::create_hash(ks, key)?;
+    let current = match hash.get(&field) {
+        Some(value) => parse_f64(value)
+            .map_err(|_| CommandError::Other(String::from("ERR hash value is not a float")))?,
+        None => 0.0,
+    };
+
+    let result = current + increment;
+    if !result.is_finite() {
+        return Err(CommandError::Other(String::from(
+            "ERR increment would produce NaN or Infinity",
+        )));
+    }
+
+    let value = result.to_string().into_bytes();
+    match hash.get_mut(&field) {
+        Some(current) => *current = value.clone(),
+        None => {
+            hash.insert(field, value.clone());
+        }
+    }
+
+    Ok(Frame::bulk(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_hincrbyfloat() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["h", "f", "10.5"]), &mut ks),
+            Ok(Frame::bulk("10.5"))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "f", "0.1"]), &mut ks),
+            Ok(Frame::bulk("10.6"))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "f", "x"]), &mut ks),
+            Err(CommandError::NotAFloat)
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use crate::commands::{get_hash, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HKEYS key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let fields: Vec<Vec<u8>> = match get_hash(ks, &key)? {
+        Some(hash) => hash.iter().map(|(field, _)| field.clone()).collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Frame::bulks(fields))
+}
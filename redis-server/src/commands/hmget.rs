@@ -0,0 +1,22 @@
+use crate::commands::{get_hash, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HMGET key field [field ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let hash = get_hash(ks, &key)?;
+
+    let values = args
+        .iter()
+        .map(
+            |field| match hash.as_ref().and_then(|hash| hash.get(field)) {
+                Some(value) => Frame::bulk(value.clone()),
+                None => Frame::NullBulkString,
+            },
+        )
+        .collect();
+
+    Ok(Frame::Array(values))
+}
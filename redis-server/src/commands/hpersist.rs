@@ -0,0 +1,49 @@
+use crate::commands::{get_hash, hexpire, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HPERSIST key FIELDS numfields field [field ...]
+///
+/// Replies with 1 for each field whose expiry time was removed, -1 for a field that had none
+/// and -2 for one that doesn't exist.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let fields = hexpire::parse_fields(args)?;
+    let mut hash = get_hash(ks, &key)?;
+
+    let replies = fields
+        .iter()
+        .map(|field| match hash.as_mut() {
+            Some(hash) if hash.contains(field) => match hash.persist(field) {
+                true => Frame::Integer(1),
+                false => Frame::Integer(-1),
+            },
+            _ => Frame::Integer(-2),
+        })
+        .collect();
+
+    Ok(Frame::Array(replies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hexpire, hset};
+
+    #[test]
+    fn test_hpersist() {
+        let mut ks = Keyspace::new();
+        hset::execute(&mut args(&["h", "a", "1", "b", "2"]), &mut ks).unwrap();
+        hexpire::execute(&mut args(&["h", "100", "FIELDS", "1", "a"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["h", "FIELDS", "3", "a", "b", "c"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::Integer(1),
+                Frame::Integer(-1),
+                Frame::Integer(-2)
+            ]))
+        );
+    }
+}
@@ -0,0 +1,9 @@
+use crate::commands::{hexpire, Result, TimeUnit};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HPEXPIRE key milliseconds [NX | XX | GT | LT] FIELDS numfields field [field ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    hexpire::generic(args, ks, TimeUnit::Milliseconds, "hpexpire")
+}
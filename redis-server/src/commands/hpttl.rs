@@ -0,0 +1,9 @@
+use crate::commands::{httl, Result, TimeUnit};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HPTTL key FIELDS numfields field [field ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    httl::generic(args, ks, TimeUnit::Milliseconds)
+}
@@ -0,0 +1,105 @@
+use crate::commands::{get_hash, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use rand::seq::index;
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// HRANDFIELD key [count [WITHVALUES]]
+///
+/// A positive count returns distinct fields, a negative one may return the same field more
+/// than once.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if args.len() > 3 {
+        return Err(CommandError::WrongArity("hrandfield"));
+    }
+
+    let key = args.pop_front().unwrap();
+    let count = args
+        .pop_front()
+        .map(|count| parse_i64(&count))
+        .transpose()?;
+    let with_values = match args.pop_front() {
+        Some(option) if option.eq_ignore_ascii_case(b"WITHVALUES") => true,
+        Some(_) => return Err(CommandError::Syntax),
+        None => false,
+    };
+
+    if let Some(count) = count {
+        if !(-(i64::MAX / 2)..=i64::MAX / 2).contains(&count) {
+            return Err(CommandError::Other(String::from(
+                "ERR value is out of range",
+            )));
+        }
+    }
+
+    let hash = get_hash(ks, &key)?;
+    let mut rng = rand::thread_rng();
+
+    let (count, hash) = match (count, hash) {
+        (None, None) => return Ok(Frame::NullBulkString),
+        (None, Some(hash)) => {
+            let (field, _) = hash.get_index(rng.gen_range(0..hash.len())).unwrap();
+            return Ok(Frame::bulk(field.clone()));
+        }
+        (Some(_), None) => return Ok(Frame::Array(vec![])),
+        (Some(count), Some(hash)) => (count, hash),
+    };
+
+    let indices: Vec<usize> = if count < 0 {
+        (0..count.unsigned_abs())
+            .map(|_| rng.gen_range(0..hash.len()))
+            .collect()
+    } else if count as usize >= hash.len() {
+        (0..hash.len()).collect()
+    } else {
+        index::sample(&mut rng, hash.len(), count as usize).into_vec()
+    };
+
+    let picked = indices.into_iter().map(|i| hash.get_index(i).unwrap());
+    if with_values {
+        Ok(Frame::Pairs(
+            picked
+                .map(|(field, value)| (Frame::bulk(field.clone()), Frame::bulk(value.clone())))
+                .collect(),
+        ))
+    } else {
+        Ok(Frame::bulks(picked.map(|(field, _)| field.clone())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hset};
+
+    #[test]
+    fn test_hrandfield() {
+        let mut ks = Keyspace::new();
+        hset::execute(&mut args(&["h", "a", "1", "b", "2", "c", "3"]), &mut ks).unwrap();
+
+        match execute(&mut args(&["h", "2"]), &mut ks) {
+            Ok(Frame::Array(fields)) => {
+                assert_eq!(fields.len(), 2);
+                assert_ne!(fields[0], fields[1]);
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+        match execute(&mut args(&["h", "10"]), &mut ks) {
+            Ok(Frame::Array(fields)) => assert_eq!(fields.len(), 3),
+            other => panic!("unexpected reply {:?}", other),
+        }
+        match execute(&mut args(&["h", "-10", "WITHVALUES"]), &mut ks) {
+            Ok(Frame::Pairs(pairs)) => assert_eq!(pairs.len(), 10),
+            other => panic!("unexpected reply {:?}", other),
+        }
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "3"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+    }
+}
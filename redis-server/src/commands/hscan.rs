@@ -0,0 +1,87 @@
+use crate::commands::{get_hash, Result, ScanArgs};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HSCAN key cursor [MATCH pattern] [COUNT count] [NOVALUES]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let scan = ScanArgs::parse(args, Some(b"NOVALUES"))?;
+
+    let (cursor, batch) = match get_hash(ks, &key)? {
+        Some(hash) => scan.scan(hash.len(), |i| {
+            let (field, value) = hash.get_index(i)?;
+            Some((field.as_slice(), (field, value)))
+        }),
+        None => (0, Vec::new()),
+    };
+
+    let mut items = Vec::new();
+    for (field, value) in batch {
+        items.push(Frame::bulk(field.clone()));
+        if !scan.flag {
+            items.push(Frame::bulk(value.clone()));
+        }
+    }
+
+    Ok(Frame::Array(vec![
+        Frame::bulk(cursor.to_string()),
+        Frame::Array(items),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hset};
+
+    #[test]
+    fn test_hscan_visits_every_field() {
+        let mut ks = Keyspace::new();
+        for i in 0..50 {
+            let field = format!("f{}", i);
+            hset::execute(&mut args(&["h", &field, "v"]), &mut ks).unwrap();
+        }
+
+        let mut cursor = String::from("0");
+        let mut seen = Vec::new();
+        loop {
+            let reply = execute(
+                &mut args(&["h", &cursor, "COUNT", "7", "NOVALUES"]),
+                &mut ks,
+            );
+            let Ok(Frame::Array(mut parts)) = reply else {
+                panic!("unexpected reply {:?}", reply);
+            };
+            let Frame::Array(fields) = parts.pop().unwrap() else {
+                panic!("expected an array of fields");
+            };
+            seen.extend(fields);
+            let Frame::BulkString(next) = parts.pop().unwrap() else {
+                panic!("expected a cursor");
+            };
+            cursor = String::from_utf8(next).unwrap();
+            if cursor == "0" {
+                break;
+            }
+        }
+
+        seen.sort_by_key(|field| format!("{:?}", field));
+        seen.dedup();
+        assert_eq!(seen.len(), 50);
+    }
+
+    #[test]
+    fn test_hscan_match() {
+        let mut ks = Keyspace::new();
+        hset::execute(&mut args(&["h", "apple", "1", "banana", "2"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["h", "0", "MATCH", "a*"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::bulk("0"),
+                Frame::bulks(["apple", "1"]),
+            ]))
+        );
+    }
+}
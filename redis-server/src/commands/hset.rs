@@ -0,0 +1,66 @@
+use crate::commands::{get_hash, CommandError, Result};
+use crate::db::{Hash, Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HSET key field value [field value ...]
+///
+/// Replies with how many of the fields are new.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if args.len().is_multiple_of(2) {
+        return Err(CommandError::WrongArity("hset"));
+    }
+
+    let key = args.pop_front().unwrap();
+    let hash = create_hash(ks, key)?;
+
+    let mut added = 0;
+    while let (Some(field), Some(value)) = (args.pop_front(), args.pop_front()) {
+        added += hash.insert(field, value) as i64;
+    }
+
+    Ok(Frame::Integer(added))
+}
+
+/// The hash at `key`, which is created empty if there isn't one.
+pub fn create_hash(ks: &mut Keyspace, key: Vec<u8>) -> Result<&mut Hash> {
+    if get_hash(ks, &key)?.is_none() {
+        ks.set(key.clone(), Value::Hash(Hash::new()));
+    }
+
+    Ok(get_hash(ks, &key)?.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, hget};
+
+    #[test]
+    fn test_hset() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["h", "a", "1", "b", "2"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "a", "3", "c", "4"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            hget::execute(&mut args(&["h", "a"]), &mut ks),
+            Ok(Frame::bulk("3"))
+        );
+        assert_eq!(
+            execute(&mut args(&["h", "a"]), &mut ks),
+            Err(CommandError::WrongArity("hset"))
+        );
+
+        ks.set(b"s".to_vec(), Value::String(b"v".to_vec()));
+        assert_eq!(
+            execute(&mut args(&["s", "a", "1"]), &mut ks),
+            Err(CommandError::WrongType)
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use crate::commands::{get_hash, hset, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HSETNX key field value
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let field = args.pop_front().unwrap();
+    let value = args.pop_front().unwrap();
+
+    if get_hash(ks, &key)?.is_some_and(|hash| hash.contains(&field)) {
+        return Ok(Frame::Integer(0));
+    }
+
+    hset::create_hash(ks, key)?.insert(field, value);
+    Ok(Frame::Integer(1))
+}
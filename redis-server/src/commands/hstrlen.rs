@@ -0,0 +1,15 @@
+use crate::commands::{get_hash, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HSTRLEN key field
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let field = args.pop_front().unwrap();
+
+    let len = get_hash(ks, &key)?
+        .and_then(|hash| hash.get(&field))
+        .map_or(0, |value| value.len());
+    Ok(Frame::Integer(len as i64))
+}
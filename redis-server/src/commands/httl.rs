@@ -0,0 +1,37 @@
+use crate::commands::{get_hash, hexpire, Result, TimeUnit};
+use crate::db::{now_ms, Keyspace};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// HTTL key FIELDS numfields field [field ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, TimeUnit::Seconds)
+}
+
+/// Replies with the time each field has left to live, -1 for a field without an expiry time
+/// and -2 for one that doesn't exist.
+pub fn generic(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace, unit: TimeUnit) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let fields = hexpire::parse_fields(args)?;
+    let hash = get_hash(ks, &key)?;
+    let now = now_ms();
+
+    let replies = fields
+        .iter()
+        .map(|field| match hash.as_ref() {
+            Some(hash) if hash.contains(field) => match hash.expire_time(field) {
+                Some(when) => {
+                    let ttl = when.saturating_sub(now);
+                    Frame::Integer(match unit {
+                        TimeUnit::Seconds => ((ttl + 500) / 1000) as i64,
+                        TimeUnit::Milliseconds => ttl as i64,
+                    })
+                }
+                None => Frame::Integer(-1),
+            },
+            _ => Frame::Integer(-2),
+        })
+        .collect();
+
+    Ok(Frame::Array(replies))
+}
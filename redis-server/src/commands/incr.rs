@@ -0,0 +1,74 @@
+use crate::commands::{get_string, CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::decoder::parse_integer;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// INCR key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    by(ks, key, 1)
+}
+
+/// Adds `delta` to the integer stored at `key`, treating a missing key as 0, and replies with
+/// the result. The key keeps its expiry time.
+pub fn by(ks: &mut Keyspace, key: Vec<u8>, delta: i64) -> Result<Frame> {
+    let current = match get_string(ks, &key)? {
+        Some(value) => parse_integer(value).ok_or(CommandError::NotAnInteger)?,
+        None => 0,
+    };
+
+    let result = current.checked_add(delta).ok_or(CommandError::Overflow)?;
+    ks.set_keep_ttl(key, Value::String(result.to_string().into_bytes()));
+    Ok(Frame::Integer(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_incr() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(execute(&mut args(&["n"]), &mut ks), Ok(Frame::Integer(1)));
+        assert_eq!(execute(&mut args(&["n"]), &mut ks), Ok(Frame::Integer(2)));
+        assert_eq!(ks.get(b"n"), Some(&Value::String(b"2".to_vec())));
+    }
+
+    #[test]
+    fn test_incr_keeps_expiry() {
+        let mut ks = Keyspace::new();
+        ks.set(b"n".to_vec(), Value::String(b"10".to_vec()));
+        ks.set_expire_time(b"n", 4_102_444_800_000);
+
+        assert_eq!(by(&mut ks, b"n".to_vec(), -20), Ok(Frame::Integer(-10)));
+        assert_eq!(ks.expire_time(b"n"), Some(4_102_444_800_000));
+    }
+
+    #[test]
+    fn test_incr_errors() {
+        let mut ks = Keyspace::new();
+        for value in ["abc", " 1", "1.0", "", "99999999999999999999"] {
+            ks.set(b"n".to_vec(), Value::String(value.as_bytes().to_vec()));
+            assert_eq!(
+                execute(&mut args(&["n"]), &mut ks),
+                Err(CommandError::NotAnInteger)
+            );
+        }
+
+        ks.set(
+            b"n".to_vec(),
+            Value::String(i64::MAX.to_string().into_bytes()),
+        );
+        assert_eq!(
+            execute(&mut args(&["n"]), &mut ks),
+            Err(CommandError::Overflow)
+        );
+        assert_eq!(
+            ks.get(b"n"),
+            Some(&Value::String(i64::MAX.to_string().into_bytes()))
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use crate::commands::{incr, parse_i64, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// INCRBY key increment
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let increment = parse_i64(&args.pop_front().unwrap())?;
+    incr::by(ks, key, increment)
+}
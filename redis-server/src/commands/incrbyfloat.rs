@@ -0,0 +1,83 @@
+use crate::commands::{get_string, parse_f64, CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// INCRBYFLOAT key increment
+///
+/// Replies with the new value as a bulk string, written out in full without an exponent, which
+/// is also how it is stored.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let increment = parse_f64(&args.pop_front().unwrap())?;
+
+    let current = match get_string(ks, &key)? {
+        Some(value) => parse_f64(value)?,
+        None => 0.0,
+    };
+
+    let result = current + increment;
+    if !result.is_finite() {
+        return Err(CommandError::Other(String::from(
+            "ERR increment would produce NaN or Infinity",
+        )));
+    }
+
+    let text = result.to_string().into_bytes();
+    ks.set_keep_ttl(key, Value::String(text.clone()));
+    Ok(Frame::bulk(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_incrbyfloat() {
+        let mut ks = Keyspace::new();
+        ks.set(b"f".to_vec(), Value::String(b"10.50".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["f", "0.1"]), &mut ks),
+            Ok(Frame::bulk("10.6"))
+        );
+        assert_eq!(
+            execute(&mut args(&["f", "-5.6"]), &mut ks),
+            Ok(Frame::bulk("5"))
+        );
+        assert_eq!(
+            execute(&mut args(&["f", "2.0e2"]), &mut ks),
+            Ok(Frame::bulk("205"))
+        );
+        assert_eq!(
+            execute(&mut args(&["new", "3"]), &mut ks),
+            Ok(Frame::bulk("3"))
+        );
+    }
+
+    #[test]
+    fn test_incrbyfloat_errors() {
+        let mut ks = Keyspace::new();
+        ks.set(b"s".to_vec(), Value::String(b"abc".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["s", "1"]), &mut ks),
+            Err(CommandError::NotAFloat)
+        );
+        for increment in ["nan", "inf", " 1", "1x"] {
+            assert_eq!(
+                execute(&mut args(&["f", increment]), &mut ks),
+                Err(CommandError::NotAFloat)
+            );
+        }
+
+        ks.set(b"f".to_vec(), Value::String(b"1.7e308".to_vec()));
+        assert_eq!(
+            execute(&mut args(&["f", "1.7e308"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR increment would produce NaN or Infinity"
+            )))
+        );
+    }
+}
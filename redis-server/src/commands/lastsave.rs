@@ -0,0 +1,12 @@
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LASTSAVE
+///
+/// When the dataset was last saved successfully, in seconds since the epoch. Starting up
+/// counts as a save.
+pub fn execute(_args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    Ok(Frame::Integer(ks.persistence().status().last_save() as i64))
+}
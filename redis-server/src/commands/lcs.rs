@@ -0,0 +1,241 @@
+use crate::commands::{parse_i64, CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::decoder::MAX_BULK_LEN;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LCS key1 key2 [LEN] [IDX] [MINMATCHLEN min-match-len] [WITHMATCHLEN]
+///
+/// Finds the longest common subsequence of two strings. Replies with the subsequence itself,
+/// its length with LEN, or with IDX the ranges in each string that make it up, last first.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let a = string(ks, &args.pop_front().unwrap())?;
+    let b = string(ks, &args.pop_front().unwrap())?;
+
+    let mut len_only = false;
+    let mut idx = false;
+    let mut min_match_len = 0;
+    let mut with_match_len = false;
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"LEN" => len_only = true,
+            b"IDX" => idx = true,
+            b"WITHMATCHLEN" => with_match_len = true,
+            b"MINMATCHLEN" if !args.is_empty() => {
+                min_match_len = parse_i64(&args.pop_front().unwrap())?.max(0) as usize;
+            }
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    if len_only && idx {
+        return Err(CommandError::Other(String::from(
+            "ERR If you want both the length and indexes, please just use IDX.",
+        )));
+    }
+
+    // The table has an entry for every pair of positions, so refuse to build huge ones.
+    let cells = (a.len() + 1).checked_mul(b.len() + 1);
+    if cells.is_none_or(|cells| cells > MAX_BULK_LEN / 4) {
+        return Err(CommandError::Other(String::from(
+            "ERR Insufficient memory, transient memory for LCS exceeds proto-max-bulk-len",
+        )));
+    }
+
+    let table = Table::new(&a, &b);
+    let len = table.get(a.len(), b.len());
+
+    if len_only {
+        return Ok(Frame::Integer(len as i64));
+    }
+
+    // Walk back from the end of both strings, following the table, to recover the subsequence
+    // and the ranges of consecutive matches.
+    let mut subsequence = vec![0; len];
+    let mut matches = Vec::new();
+    let mut range: Option<(usize, usize, usize, usize)> = None;
+    let (mut i, mut j, mut k) = (a.len(), b.len(), len);
+
+    while i > 0 && j > 0 {
+        let emit;
+
+        if a[i - 1] == b[j - 1] {
+            subsequence[k - 1] = a[i - 1];
+            range = match range {
+                None => Some((i - 1, i - 1, j - 1, j - 1)),
+                Some((a_start, a_end, b_start, b_end)) => {
+                    Some((a_start - 1, a_end, b_start - 1, b_end))
+                }
+            };
+            let (a_start, _, b_start, _) = range.unwrap();
+            emit = a_start == 0 || b_start == 0;
+            i -= 1;
+            j -= 1;
+            k -= 1;
+        } else {
+            if table.get(i - 1, j) > table.get(i, j - 1) {
+                i -= 1;
+            } else {
+                j -= 1;
+            }
+            emit = range.is_some();
+        }
+
+        if emit {
+            let (a_start, a_end, b_start, b_end) = range.take().unwrap();
+            let match_len = a_end - a_start + 1;
+            if match_len >= min_match_len {
+                let mut entry = vec![
+                    Frame::Array(vec![
+                        Frame::Integer(a_start as i64),
+                        Frame::Integer(a_end as i64),
+                    ]),
+                    Frame::Array(vec![
+                        Frame::Integer(b_start as i64),
+                        Frame::Integer(b_end as i64),
+                    ]),
+                ];
+                if with_match_len {
+                    entry.push(Frame::Integer(match_len as i64));
+                }
+                matches.push(Frame::Array(entry));
+            }
+        }
+    }
+
+    if idx {
+        Ok(Frame::Map(vec![
+            (Frame::bulk("matches"), Frame::Array(matches)),
+            (Frame::bulk("len"), Frame::Integer(len as i64)),
+        ]))
+    } else {
+        Ok(Frame::bulk(subsequence))
+    }
+}
+
+/// Missing keys count as empty strings.
+fn string(ks: &mut Keyspace, key: &[u8]) -> Result<Vec<u8>> {
+    match ks.get(key) {
+        Some(Value::String(value)) => Ok(value.clone()),
+        Some(_) => Err(CommandError::Other(String::from(
+            "ERR The specified keys must contain string values",
+        ))),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Lengths of the longest common subsequence of every pair of prefixes of `a` and `b`.
+struct Table {
+    columns: usize,
+    lengths: Vec<u32>,
+}
+
+impl Table {
+    fn new(a: &[u8], b: &[u8]) -> Table {
+        let columns = b.len() + 1;
+        let mut lengths = vec![0; (a.len() + 1) * columns];
+
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                lengths[i * columns + j] = if a[i - 1] == b[j - 1] {
+                    lengths[(i - 1) * columns + j - 1] + 1
+                } else {
+                    lengths[(i - 1) * columns + j].max(lengths[i * columns + j - 1])
+                };
+            }
+        }
+
+        Table { columns, lengths }
+    }
+
+    fn get(&self, i: usize, j: usize) -> usize {
+        self.lengths[i * self.columns + j] as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    fn keyspace() -> Keyspace {
+        let mut ks = Keyspace::new();
+        ks.set(b"key1".to_vec(), Value::String(b"ohmytext".to_vec()));
+        ks.set(b"key2".to_vec(), Value::String(b"mynewtext".to_vec()));
+        ks
+    }
+
+    fn range(start: i64, end: i64) -> Frame {
+        Frame::Array(vec![Frame::Integer(start), Frame::Integer(end)])
+    }
+
+    #[test]
+    fn test_lcs() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["key1", "key2"]), &mut ks),
+            Ok(Frame::bulk("mytext"))
+        );
+        assert_eq!(
+            execute(&mut args(&["key1", "key2", "LEN"]), &mut ks),
+            Ok(Frame::Integer(6))
+        );
+        assert_eq!(
+            execute(&mut args(&["key1", "missing"]), &mut ks),
+            Ok(Frame::bulk(""))
+        );
+    }
+
+    #[test]
+    fn test_lcs_idx() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["key1", "key2", "IDX"]), &mut ks),
+            Ok(Frame::Map(vec![
+                (
+                    Frame::bulk("matches"),
+                    Frame::Array(vec![
+                        Frame::Array(vec![range(4, 7), range(5, 8)]),
+                        Frame::Array(vec![range(2, 3), range(0, 1)]),
+                    ])
+                ),
+                (Frame::bulk("len"), Frame::Integer(6)),
+            ]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["key1", "key2", "IDX", "MINMATCHLEN", "4", "WITHMATCHLEN"]),
+                &mut ks
+            ),
+            Ok(Frame::Map(vec![
+                (
+                    Frame::bulk("matches"),
+                    Frame::Array(vec![Frame::Array(vec![
+                        range(4, 7),
+                        range(5, 8),
+                        Frame::Integer(4)
+                    ])])
+                ),
+                (Frame::bulk("len"), Frame::Integer(6)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_lcs_errors() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["key1", "key2", "LEN", "IDX"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR If you want both the length and indexes, please just use IDX."
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["key1", "key2", "MINMATCHLEN"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+    }
+}
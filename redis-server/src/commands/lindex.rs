@@ -0,0 +1,14 @@
+use crate::commands::{get_list, parse_i64, resolve_index, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LINDEX key index
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let index = parse_i64(&args.pop_front().unwrap())?;
+
+    let element = get_list(ks, &key)?
+        .and_then(|list| resolve_index(index, list.len()).map(|index| list[index].clone()));
+    Ok(element.map(Frame::bulk).unwrap_or(Frame::NullBulkString))
+}
@@ -0,0 +1,68 @@
+use crate::commands::{get_list, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LINSERT key BEFORE | AFTER pivot element
+///
+/// Replies with the new length, 0 if there is no list, or -1 if the pivot isn't in it.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let after = match args.pop_front().unwrap().to_ascii_uppercase().as_slice() {
+        b"BEFORE" => false,
+        b"AFTER" => true,
+        _ => return Err(CommandError::Syntax),
+    };
+    let pivot = args.pop_front().unwrap();
+    let element = args.pop_front().unwrap();
+
+    let list = match get_list(ks, &key)? {
+        Some(list) => list,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    match list.iter().position(|e| *e == pivot) {
+        Some(index) => {
+            list.insert(index + after as usize, element);
+            Ok(Frame::Integer(list.len() as i64))
+        }
+        None => Ok(Frame::Integer(-1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, lrange, rpush};
+
+    #[test]
+    fn test_linsert() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["list", "a", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["list", "BEFORE", "c", "b"]), &mut ks),
+            Ok(Frame::Integer(3))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "after", "c", "d"]), &mut ks),
+            Ok(Frame::Integer(4))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "AFTER", "x", "y"]), &mut ks),
+            Ok(Frame::Integer(-1))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "AFTER", "x", "y"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "ON", "a", "b"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(
+            lrange::execute(&mut args(&["list", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["a", "b", "c", "d"]))
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use crate::commands::{get_list, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LLEN key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let len = get_list(ks, &key)?.map_or(0, |list| list.len());
+    Ok(Frame::Integer(len as i64))
+}
@@ -0,0 +1,96 @@
+use crate::commands::{get_list, lpop, lpush, parse_list_end, ListEnd, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LMOVE source destination LEFT | RIGHT LEFT | RIGHT
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let source = args.pop_front().unwrap();
+    let destination = args.pop_front().unwrap();
+    let from = parse_list_end(&args.pop_front().unwrap())?;
+    let to = parse_list_end(&args.pop_front().unwrap())?;
+
+    let element = move_element(ks, &source, destination, from, to)?;
+    Ok(element.map(Frame::bulk).unwrap_or(Frame::NullBulkString))
+}
+
+/// Pops an element from `from` of the source list and pushes it onto `to` of the destination
+/// list, returning the element. Both keys are type checked before anything moves. The two may
+/// be the same list, which rotates it.
+pub fn move_element(
+    ks: &mut Keyspace,
+    source: &[u8],
+    destination: Vec<u8>,
+    from: ListEnd,
+    to: ListEnd,
+) -> Result<Option<Vec<u8>>> {
+    if get_list(ks, source)?.is_none() {
+        return Ok(None);
+    }
+    get_list(ks, &destination)?;
+
+    let element = lpop::pop(ks, source, from, 1)?.unwrap().remove(0);
+    // A blocked BLMOVE is served outside of any command, so nothing else touches it.
+    ks.touch(&destination);
+    lpush::push(ks, destination, to, [element.clone()])?;
+    Ok(Some(element))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, lrange, rpush, CommandError};
+    use crate::db::Value;
+
+    #[test]
+    fn test_lmove() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["src", "a", "b", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["src", "dst", "RIGHT", "LEFT"]), &mut ks),
+            Ok(Frame::bulk("c"))
+        );
+        assert_eq!(
+            execute(&mut args(&["src", "dst", "LEFT", "RIGHT"]), &mut ks),
+            Ok(Frame::bulk("a"))
+        );
+        assert_eq!(
+            lrange::execute(&mut args(&["dst", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["c", "a"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "dst", "LEFT", "LEFT"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+    }
+
+    #[test]
+    fn test_lmove_rotates_one_list() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["list", "a", "b", "c"]), &mut ks).unwrap();
+
+        execute(&mut args(&["list", "list", "LEFT", "RIGHT"]), &mut ks).unwrap();
+        assert_eq!(
+            lrange::execute(&mut args(&["list", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["b", "c", "a"]))
+        );
+    }
+
+    #[test]
+    fn test_lmove_checks_destination_type_first() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["src", "a"]), &mut ks).unwrap();
+        ks.set(b"string".to_vec(), Value::String(b"v".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["src", "string", "LEFT", "LEFT"]), &mut ks),
+            Err(CommandError::WrongType)
+        );
+        assert_eq!(
+            execute(&mut args(&["src", "dst", "UP", "LEFT"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert!(ks.contains_key(b"src"));
+    }
+}
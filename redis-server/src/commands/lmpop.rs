@@ -0,0 +1,138 @@
+use crate::commands::{get_list, lpop, parse_i64, parse_list_end, CommandError, ListEnd, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LMPOP numkeys key [key ...] LEFT | RIGHT [COUNT count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let (keys, end, count) = parse(args)?;
+    Ok(pop_first(ks, &keys, end, count)?.unwrap_or(Frame::NullArray))
+}
+
+/// Parses `numkeys key [key ...] LEFT | RIGHT [COUNT count]` into the keys, the end to pop
+/// from and how many elements to pop.
+pub fn parse(args: &mut VecDeque<Vec<u8>>) -> Result<(Vec<Vec<u8>>, ListEnd, usize)> {
+    let numkeys = match parse_i64(&args.pop_front().unwrap()) {
+        Ok(numkeys) if numkeys > 0 => numkeys as usize,
+        _ => {
+            return Err(CommandError::Other(String::from(
+                "ERR numkeys should be greater than 0",
+            )))
+        }
+    };
+    if numkeys >= args.len() {
+        return Err(CommandError::Syntax);
+    }
+
+    let keys: Vec<Vec<u8>> = args.drain(..numkeys).collect();
+    let end = parse_list_end(&args.pop_front().unwrap())?;
+
+    let mut count = None;
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"COUNT" if count.is_none() && !args.is_empty() => {
+                match parse_i64(&args.pop_front().unwrap()) {
+                    Ok(value) if value > 0 => count = Some(value as usize),
+                    _ => {
+                        return Err(CommandError::Other(String::from(
+                            "ERR count should be greater than 0",
+                        )))
+                    }
+                }
+            }
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    Ok((keys, end, count.unwrap_or(1)))
+}
+
+/// Pops from the first of `keys` that holds a list, replying with its name and the popped
+/// elements. Returns `None` if none of them do.
+pub fn pop_first(
+    ks: &mut Keyspace,
+    keys: &[Vec<u8>],
+    end: ListEnd,
+    count: usize,
+) -> Result<Option<Frame>> {
+    for key in keys {
+        if get_list(ks, key)?.is_none() {
+            continue;
+        }
+
+        let elements = lpop::pop(ks, key, end, count)?.unwrap();
+        return Ok(Some(Frame::Array(vec![
+            Frame::bulk(key.clone()),
+            Frame::bulks(elements),
+        ])));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, rpush};
+
+    #[test]
+    fn test_lmpop() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["second", "a", "b", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["2", "first", "second", "LEFT"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::bulk("second"),
+                Frame::bulks(["a"])
+            ]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["2", "first", "second", "RIGHT", "COUNT", "5"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::bulk("second"),
+                Frame::bulks(["c", "b"])
+            ]))
+        );
+        assert_eq!(
+            execute(&mut args(&["2", "first", "second", "LEFT"]), &mut ks),
+            Ok(Frame::NullArray)
+        );
+    }
+
+    #[test]
+    fn test_lmpop_errors() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["0", "key", "LEFT"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR numkeys should be greater than 0"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["3", "a", "b", "LEFT"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(
+            execute(&mut args(&["1", "a", "MIDDLE"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(
+            execute(&mut args(&["1", "a", "LEFT", "COUNT", "0"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR count should be greater than 0"
+            )))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["1", "a", "LEFT", "COUNT", "1", "COUNT", "1"]),
+                &mut ks
+            ),
+            Err(CommandError::Syntax)
+        );
+    }
+}
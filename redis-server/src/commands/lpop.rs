@@ -0,0 +1,126 @@
+use crate::commands::{get_list, parse_i64, CommandError, ListEnd, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LPOP key [count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, ListEnd::Left, "lpop")
+}
+
+/// Without a count the reply is a single element or nil. With one it is an array of up to
+/// that many elements, or a null array if there is no list.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    end: ListEnd,
+    name: &'static str,
+) -> Result<Frame> {
+    if args.len() > 2 {
+        return Err(CommandError::WrongArity(name));
+    }
+
+    let key = args.pop_front().unwrap();
+    let count = match args.pop_front() {
+        Some(count) => match parse_i64(&count) {
+            Ok(count) if count >= 0 => Some(count as usize),
+            _ => {
+                return Err(CommandError::Other(String::from(
+                    "ERR value is out of range, must be positive",
+                )))
+            }
+        },
+        None => None,
+    };
+
+    let popped = pop(ks, &key, end, count.unwrap_or(1))?;
+    Ok(match (popped, count) {
+        (Some(mut elements), None) => Frame::bulk(elements.remove(0)),
+        (None, None) => Frame::NullBulkString,
+        (Some(elements), Some(_)) => Frame::bulks(elements),
+        (None, Some(_)) => Frame::NullArray,
+    })
+}
+
+/// Pops up to `count` elements from `end` of the list at `key`, deleting the key once the list
+/// is empty. Returns `None` if there is no list.
+pub fn pop(
+    ks: &mut Keyspace,
+    key: &[u8],
+    end: ListEnd,
+    count: usize,
+) -> Result<Option<Vec<Vec<u8>>>> {
+    let list = match get_list(ks, key)? {
+        Some(list) => list,
+        None => return Ok(None),
+    };
+
+    let count = count.min(list.len());
+    let elements = match end {
+        ListEnd::Left => list.drain(..count).collect(),
+        ListEnd::Right => list.drain(list.len() - count..).rev().collect(),
+    };
+
+    if list.is_empty() {
+        ks.remove(key);
+    }
+
+    Ok(Some(elements))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, lpush};
+
+    fn keyspace() -> Keyspace {
+        let mut ks = Keyspace::new();
+        lpush::execute(&mut args(&["list", "c", "b", "a"]), &mut ks).unwrap();
+        ks
+    }
+
+    #[test]
+    fn test_lpop() {
+        let mut ks = keyspace();
+
+        assert_eq!(execute(&mut args(&["list"]), &mut ks), Ok(Frame::bulk("a")));
+        assert_eq!(
+            generic(&mut args(&["list"]), &mut ks, ListEnd::Right, "rpop"),
+            Ok(Frame::bulk("c"))
+        );
+        assert_eq!(execute(&mut args(&["list"]), &mut ks), Ok(Frame::bulk("b")));
+        assert!(!ks.contains_key(b"list"));
+        assert_eq!(
+            execute(&mut args(&["list"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+    }
+
+    #[test]
+    fn test_lpop_count() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["list", "0"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+        assert_eq!(
+            generic(&mut args(&["list", "2"]), &mut ks, ListEnd::Right, "rpop"),
+            Ok(Frame::bulks(["c", "b"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "5"]), &mut ks),
+            Ok(Frame::bulks(["a"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "5"]), &mut ks),
+            Ok(Frame::NullArray)
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "-1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR value is out of range, must be positive"
+            )))
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::commands::{get_list, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LPOS key element [RANK rank] [COUNT num-matches] [MAXLEN len]
+///
+/// RANK picks which match to start from, counting from the tail when negative. COUNT asks for
+/// that many matches as an array, 0 meaning all of them, and MAXLEN limits how many elements
+/// are compared.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let element = args.pop_front().unwrap();
+
+    let mut rank = 1;
+    let mut count = None;
+    let mut max_len = 0;
+    while let Some(option) = args.pop_front() {
+        let value = match args.pop_front() {
+            Some(value) => parse_i64(&value)?,
+            None => return Err(CommandError::Syntax),
+        };
+
+        match option.to_ascii_uppercase().as_slice() {
+            b"RANK" if value == 0 => {
+                return Err(CommandError::Other(String::from(
+                    "ERR RANK can't be zero: use 1 to start from the first match, 2 from the \
+                     second ... or use negative to start from the end of the list",
+                )))
+            }
+            b"RANK" if value == i64::MIN => {
+                return Err(CommandError::Other(String::from(
+                    "ERR value is out of range",
+                )))
+            }
+            b"RANK" => rank = value,
+            b"COUNT" if value < 0 => {
+                return Err(CommandError::Other(String::from(
+                    "ERR COUNT can't be negative",
+                )))
+            }
+            b"COUNT" => count = Some(value as usize),
+            b"MAXLEN" if value < 0 => {
+                return Err(CommandError::Other(String::from(
+                    "ERR MAXLEN can't be negative",
+                )))
+            }
+            b"MAXLEN" => max_len = value as usize,
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    let list = get_list(ks, &key)?;
+    let len = list.as_ref().map_or(0, |list| list.len());
+    let compared = if max_len == 0 { len } else { max_len.min(len) };
+    let wanted = match count {
+        Some(0) => usize::MAX,
+        Some(count) => count,
+        None => 1,
+    };
+
+    let indexes: Box<dyn Iterator<Item = usize>> = if rank > 0 {
+        Box::new(0..compared)
+    } else {
+        Box::new((len - compared..len).rev())
+    };
+
+    let mut skip = rank.unsigned_abs() - 1;
+    let mut matches = Vec::new();
+    if let Some(list) = list {
+        for index in indexes {
+            if list[index] != element {
+                continue;
+            }
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+
+            matches.push(Frame::Integer(index as i64));
+            if matches.len() == wanted {
+                break;
+            }
+        }
+    }
+
+    match count {
+        Some(_) => Ok(Frame::Array(matches)),
+        None => Ok(matches.pop().unwrap_or(Frame::NullBulkString)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, rpush};
+
+    fn keyspace() -> Keyspace {
+        let mut ks = Keyspace::new();
+        rpush::execute(
+            &mut args(&["list", "a", "b", "c", "1", "2", "3", "c", "c"]),
+            &mut ks,
+        )
+        .unwrap();
+        ks
+    }
+
+    fn integers(values: &[i64]) -> Frame {
+        Frame::Array(values.iter().map(|v| Frame::Integer(*v)).collect())
+    }
+
+    #[test]
+    fn test_lpos() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["list", "c"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "c", "RANK", "2"]), &mut ks),
+            Ok(Frame::Integer(6))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "c", "RANK", "-1"]), &mut ks),
+            Ok(Frame::Integer(7))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "x"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "x"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+    }
+
+    #[test]
+    fn test_lpos_count_and_maxlen() {
+        let mut ks = keyspace();
+
+        assert_eq!(
+            execute(&mut args(&["list", "c", "COUNT", "2"]), &mut ks),
+            Ok(integers(&[2, 6]))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "c", "COUNT", "0"]), &mut ks),
+            Ok(integers(&[2, 6, 7]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["list", "c", "RANK", "-1", "COUNT", "2"]),
+                &mut ks
+            ),
+            Ok(integers(&[7, 6]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["list", "c", "COUNT", "0", "MAXLEN", "3"]),
+                &mut ks
+            ),
+            Ok(integers(&[2]))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "c", "COUNT", "0"]), &mut ks),
+            Ok(integers(&[]))
+        );
+    }
+
+    #[test]
+    fn test_lpos_errors() {
+        let mut ks = keyspace();
+
+        assert!(matches!(
+            execute(&mut args(&["list", "c", "RANK", "0"]), &mut ks),
+            Err(CommandError::Other(message)) if message.starts_with("ERR RANK can't be zero")
+        ));
+        assert_eq!(
+            execute(&mut args(&["list", "c", "COUNT", "-1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR COUNT can't be negative"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "c", "COUNT"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use crate::commands::{get_list, ListEnd, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LPUSH key element [element ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, ListEnd::Left, false)
+}
+
+/// Pushes every element in turn, so the last one given ends up at `end`. With `only_if_exists`
+/// nothing happens unless the list is already there. Replies with the length of the list.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    end: ListEnd,
+    only_if_exists: bool,
+) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    if only_if_exists && get_list(ks, &key)?.is_none() {
+        return Ok(Frame::Integer(0));
+    }
+
+    let len = push(ks, key, end, args.drain(..))?;
+    Ok(Frame::Integer(len as i64))
+}
+
+/// Pushes `elements` onto the list at `key`, creating it if needed, and returns its new length.
+pub fn push(
+    ks: &mut Keyspace,
+    key: Vec<u8>,
+    end: ListEnd,
+    elements: impl IntoIterator<Item = Vec<u8>>,
+) -> Result<usize> {
+    if get_list(ks, &key)?.is_none() {
+        ks.set(key.clone(), Value::List(VecDeque::new()));
+    }
+
+    let list = get_list(ks, &key)?.unwrap();
+    for element in elements {
+        match end {
+            ListEnd::Left => list.push_front(element),
+            ListEnd::Right => list.push_back(element),
+        }
+    }
+
+    let len = list.len();
+    ks.signal_ready(&key);
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, CommandError};
+
+    #[test]
+    fn test_lpush() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["list", "a", "b"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            generic(&mut args(&["list", "c"]), &mut ks, ListEnd::Right, true,),
+            Ok(Frame::Integer(3))
+        );
+        assert_eq!(
+            ks.get(b"list"),
+            Some(&Value::List(VecDeque::from([
+                b"b".to_vec(),
+                b"a".to_vec(),
+                b"c".to_vec()
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_pushx_needs_an_existing_list() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            generic(&mut args(&["list", "a"]), &mut ks, ListEnd::Left, true,),
+            Ok(Frame::Integer(0))
+        );
+        assert!(!ks.contains_key(b"list"));
+    }
+
+    #[test]
+    fn test_lpush_wrong_type() {
+        let mut ks = Keyspace::new();
+        ks.set(b"string".to_vec(), Value::String(b"v".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["string", "a"]), &mut ks),
+            Err(CommandError::WrongType)
+        );
+    }
+}
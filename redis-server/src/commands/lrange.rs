@@ -0,0 +1,50 @@
+use crate::commands::{clamp_range, get_list, parse_i64, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LRANGE key start stop
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let start = parse_i64(&args.pop_front().unwrap())?;
+    let stop = parse_i64(&args.pop_front().unwrap())?;
+
+    let list = match get_list(ks, &key)? {
+        Some(list) => list,
+        None => return Ok(Frame::Array(vec![])),
+    };
+
+    match clamp_range(start, stop, list.len()) {
+        Some((start, stop)) => Ok(Frame::bulks(list.range(start..=stop).cloned())),
+        None => Ok(Frame::Array(vec![])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, rpush};
+
+    #[test]
+    fn test_lrange() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["list", "a", "b", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["list", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["a", "b", "c"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "-2", "10"]), &mut ks),
+            Ok(Frame::bulks(["b", "c"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "2", "1"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "0", "-1"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::commands::{get_list, parse_i64, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LREM key count element
+///
+/// Removes the first `count` occurrences of the element, the last ones if `count` is negative,
+/// or all of them if it is 0.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let count = parse_i64(&args.pop_front().unwrap())?;
+    let element = args.pop_front().unwrap();
+
+    let list = match get_list(ks, &key)? {
+        Some(list) => list,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    let limit = if count == 0 {
+        usize::MAX
+    } else {
+        count.unsigned_abs() as usize
+    };
+    let mut removed = 0;
+
+    if count < 0 {
+        let mut i = list.len();
+        while i > 0 && removed < limit {
+            i -= 1;
+            if list[i] == element {
+                list.remove(i);
+                removed += 1;
+            }
+        }
+    } else {
+        let mut i = 0;
+        while i < list.len() && removed < limit {
+            if list[i] == element {
+                list.remove(i);
+                removed += 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    if list.is_empty() {
+        ks.remove(&key);
+    }
+
+    Ok(Frame::Integer(removed as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, lrange, rpush};
+
+    #[test]
+    fn test_lrem() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["list", "a", "b", "a", "c", "a"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["list", "-2", "a"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            lrange::execute(&mut args(&["list", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["a", "b", "c"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "1", "b"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "0", "a"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "0", "c"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert!(!ks.contains_key(b"list"));
+    }
+}
@@ -0,0 +1,52 @@
+use crate::commands::{get_list, parse_i64, resolve_index, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LSET key index element
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let index = parse_i64(&args.pop_front().unwrap())?;
+    let element = args.pop_front().unwrap();
+
+    let list =
+        get_list(ks, &key)?.ok_or_else(|| CommandError::Other(String::from("ERR no such key")))?;
+    let index = resolve_index(index, list.len())
+        .ok_or_else(|| CommandError::Other(String::from("ERR index out of range")))?;
+
+    list[index] = element;
+    Ok(Frame::ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, lindex, rpush};
+
+    #[test]
+    fn test_lset_and_lindex() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["list", "a", "b", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["list", "-1", "z"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            lindex::execute(&mut args(&["list", "2"]), &mut ks),
+            Ok(Frame::bulk("z"))
+        );
+        assert_eq!(
+            lindex::execute(&mut args(&["list", "3"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "3", "x"]), &mut ks),
+            Err(CommandError::Other(String::from("ERR index out of range")))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "0", "x"]), &mut ks),
+            Err(CommandError::Other(String::from("ERR no such key")))
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use crate::commands::{clamp_range, get_list, parse_i64, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// LTRIM key start stop
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let start = parse_i64(&args.pop_front().unwrap())?;
+    let stop = parse_i64(&args.pop_front().unwrap())?;
+
+    let list = match get_list(ks, &key)? {
+        Some(list) => list,
+        None => return Ok(Frame::ok()),
+    };
+
+    match clamp_range(start, stop, list.len()) {
+        Some((start, stop)) => {
+            list.truncate(stop + 1);
+            list.drain(..start);
+        }
+        None => {
+            ks.remove(&key);
+        }
+    }
+
+    Ok(Frame::ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, lrange, rpush};
+
+    #[test]
+    fn test_ltrim() {
+        let mut ks = Keyspace::new();
+        rpush::execute(&mut args(&["list", "a", "b", "c", "d"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["list", "1", "-2"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            lrange::execute(&mut args(&["list", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["b", "c"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "5", "10"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert!(!ks.contains_key(b"list"));
+    }
+}
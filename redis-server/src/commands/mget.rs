@@ -0,0 +1,18 @@
+use crate::commands::Result;
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// MGET key [key ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let values = args
+        .iter()
+        .map(|key| match ks.get(key) {
+            Some(Value::String(value)) => Frame::bulk(value.clone()),
+            // Keys holding other types are treated as missing rather than as errors.
+            _ => Frame::NullBulkString,
+        })
+        .collect();
+
+    Ok(Frame::Array(values))
+}
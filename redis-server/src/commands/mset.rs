@@ -0,0 +1,17 @@
+use crate::commands::{CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// MSET key value [key value ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if !args.len().is_multiple_of(2) {
+        return Err(CommandError::WrongArity("mset"));
+    }
+
+    while let (Some(key), Some(value)) = (args.pop_front(), args.pop_front()) {
+        ks.set(key, Value::String(value));
+    }
+
+    Ok(Frame::ok())
+}
@@ -0,0 +1,49 @@
+use crate::commands::{CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// MSETNX key value [key value ...]
+///
+/// Sets nothing at all if any of the keys already exists.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if !args.len().is_multiple_of(2) {
+        return Err(CommandError::WrongArity("msetnx"));
+    }
+
+    if args.iter().step_by(2).any(|key| ks.contains_key(key)) {
+        return Ok(Frame::Integer(0));
+    }
+
+    while let (Some(key), Some(value)) = (args.pop_front(), args.pop_front()) {
+        ks.set(key, Value::String(value));
+    }
+
+    Ok(Frame::Integer(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_msetnx() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["a", "1", "b", "2"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["c", "3", "b", "4"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert!(!ks.contains_key(b"c"));
+        assert_eq!(ks.get(b"b"), Some(&Value::String(b"2".to_vec())));
+        assert_eq!(
+            execute(&mut args(&["a", "1", "b"]), &mut ks),
+            Err(CommandError::WrongArity("msetnx"))
+        );
+    }
+}
@@ -0,0 +1,19 @@
+use crate::client::{Client, Transaction};
+use crate::commands::{CommandError, Result};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// MULTI
+///
+/// Starts a transaction: the commands that follow are queued rather than run, until EXEC runs
+/// them all at once or DISCARD throws them away.
+pub fn execute(_args: &mut VecDeque<Vec<u8>>, client: &mut Client) -> Result<Frame> {
+    if client.transaction.is_some() {
+        return Err(CommandError::Other(String::from(
+            "ERR MULTI calls can not be nested",
+        )));
+    }
+
+    client.transaction = Some(Transaction::default());
+    Ok(Frame::ok())
+}
@@ -0,0 +1,9 @@
+use crate::commands::{expire, Result, TimeUnit};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PEXPIREAT key unix-time-milliseconds [NX | XX | GT | LT]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    expire::generic(args, ks, TimeUnit::Milliseconds, false, "pexpireat")
+}
@@ -0,0 +1,9 @@
+use crate::commands::{expiretime, Result, TimeUnit};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PEXPIRETIME key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    expiretime::generic(args, ks, TimeUnit::Milliseconds)
+}
@@ -0,0 +1,60 @@
+use crate::commands::{get_hll, Result};
+use crate::db::{Keyspace, Value};
+use crate::hyperloglog;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PFADD key [element [element ...]]
+///
+/// Replies with 1 if the estimated cardinality may have changed, which includes creating the
+/// key, or 0 otherwise.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+
+    let mut updated = false;
+    if get_hll(ks, &key)?.is_none() {
+        ks.set(key.clone(), Value::String(hyperloglog::new()));
+        updated = true;
+    }
+
+    let hll = get_hll(ks, &key)?.unwrap();
+    for element in args.iter() {
+        updated |= hyperloglog::add(hll, element)?;
+    }
+    if updated {
+        hyperloglog::invalidate_cache(hll);
+    }
+
+    Ok(Frame::Integer(updated as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, set, CommandError};
+
+    #[test]
+    fn test_pfadd() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["hll", "a", "b", "c"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["hll", "a", "b"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(execute(&mut args(&["hll"]), &mut ks), Ok(Frame::Integer(0)));
+        assert_eq!(
+            execute(&mut args(&["other"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+
+        set::execute(&mut args(&["string", "not a hll"]), &mut ks).unwrap();
+        assert_eq!(
+            execute(&mut args(&["string", "a"]), &mut ks),
+            Err(CommandError::from(hyperloglog::Error::Invalid))
+        );
+    }
+}
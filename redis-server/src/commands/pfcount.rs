@@ -0,0 +1,50 @@
+use crate::commands::{get_hll, Result};
+use crate::db::Keyspace;
+use crate::hyperloglog;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PFCOUNT key [key ...]
+///
+/// Replies with the estimated number of distinct elements added to the HyperLogLog, or to
+/// their union if there are several. A single key's estimate is cached in the value itself.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if args.len() == 1 {
+        let count = match get_hll(ks, &args[0])? {
+            Some(hll) => hyperloglog::count(hll)?,
+            None => 0,
+        };
+        return Ok(Frame::Integer(count as i64));
+    }
+
+    let mut max = hyperloglog::registers();
+    for key in args.iter() {
+        if let Some(hll) = get_hll(ks, key)? {
+            hyperloglog::merge(&mut max, hll)?;
+        }
+    }
+    Ok(Frame::Integer(hyperloglog::count_registers(&max) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, pfadd};
+
+    #[test]
+    fn test_pfcount() {
+        let mut ks = Keyspace::new();
+        pfadd::execute(&mut args(&["a", "1", "2", "3"]), &mut ks).unwrap();
+        pfadd::execute(&mut args(&["b", "3", "4"]), &mut ks).unwrap();
+
+        assert_eq!(execute(&mut args(&["a"]), &mut ks), Ok(Frame::Integer(3)));
+        assert_eq!(
+            execute(&mut args(&["a", "b", "missing"]), &mut ks),
+            Ok(Frame::Integer(4))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+    }
+}
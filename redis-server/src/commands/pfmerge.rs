@@ -0,0 +1,52 @@
+use crate::commands::{get_hll, Result};
+use crate::db::{Keyspace, Value};
+use crate::hyperloglog;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PFMERGE destkey [sourcekey [sourcekey ...]]
+///
+/// Stores the union of the HyperLogLogs, including the destination's own, at `destkey`.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    // The result is dense if any input is, which saves converting it later.
+    let mut max = hyperloglog::registers();
+    let mut dense = false;
+    for key in args.iter() {
+        if let Some(hll) = get_hll(ks, key)? {
+            dense |= hyperloglog::is_dense(hll);
+            hyperloglog::merge(&mut max, hll)?;
+        }
+    }
+
+    let destination = args.pop_front().unwrap();
+    if get_hll(ks, &destination)?.is_none() {
+        ks.set(destination.clone(), Value::String(hyperloglog::new()));
+    }
+    let hll = get_hll(ks, &destination)?.unwrap();
+    hyperloglog::store(hll, &max, dense)?;
+
+    Ok(Frame::ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, pfadd, pfcount};
+
+    #[test]
+    fn test_pfmerge() {
+        let mut ks = Keyspace::new();
+        pfadd::execute(&mut args(&["a", "1", "2", "3"]), &mut ks).unwrap();
+        pfadd::execute(&mut args(&["b", "3", "4"]), &mut ks).unwrap();
+        pfadd::execute(&mut args(&["dest", "5"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["dest", "a", "b", "missing"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            pfcount::execute(&mut args(&["dest"]), &mut ks),
+            Ok(Frame::Integer(5))
+        );
+    }
+}
@@ -1,7 +1,27 @@
-use crate::resp::types::Encoded;
-use crate::SimpleString;
+use crate::client::Client;
+use crate::commands::{CommandError, Result};
+use crate::resp::types::Frame;
 use std::collections::VecDeque;
 
-pub fn execute(_args: &mut VecDeque<String>) -> Box<dyn Encoded> {
-    SimpleString::new(String::from("PONG"))
+/// PING [message]
+///
+/// A RESP2 client that is subscribed gets its reply in the same shape as messages, so it can
+/// tell them apart.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, client: &mut Client) -> Result<Frame> {
+    if args.len() > 1 {
+        return Err(CommandError::WrongArity("ping"));
+    }
+
+    let message = args.pop_front();
+    if client.in_subscribed_mode() {
+        return Ok(Frame::Array(vec![
+            Frame::bulk("pong"),
+            Frame::bulk(message.unwrap_or_default()),
+        ]));
+    }
+
+    match message {
+        Some(message) => Ok(Frame::bulk(message)),
+        None => Ok(Frame::SimpleString(String::from("PONG"))),
+    }
 }
@@ -0,0 +1,17 @@
+use crate::client::Client;
+use crate::commands::{subscribe, Result};
+use crate::db::Keyspace;
+use crate::pubsub::Kind;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PSUBSCRIBE pattern [pattern ...]
+///
+/// Subscribes the client to every channel matching the glob-style patterns.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Vec<Frame>> {
+    Ok(subscribe::generic(Kind::Pattern, args, client, ks))
+}
@@ -0,0 +1,9 @@
+use crate::commands::{ttl, Result, TimeUnit};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PTTL key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    ttl::generic(args, ks, TimeUnit::Milliseconds)
+}
@@ -0,0 +1,45 @@
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PUBLISH channel message
+///
+/// Sends the message to every client subscribed to the channel or to a pattern matching it,
+/// and replies with how many clients that was.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let received = ks.pubsub().publish(&args[0], &args[1]);
+    Ok(Frame::Integer(received as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::commands::{args, psubscribe, subscribe};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_publish() {
+        let mut ks = Keyspace::new();
+        let (sender, receiver) = mpsc::channel();
+        let mut client = Client::new(sender);
+        subscribe::execute(&mut args(&["news"]), &mut client, &mut ks).unwrap();
+        psubscribe::execute(&mut args(&["n*", "*s"]), &mut client, &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["news", "hello"]), &mut ks),
+            Ok(Frame::Integer(3))
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n"
+        );
+        assert_eq!(receiver.try_iter().count(), 2);
+
+        assert_eq!(
+            execute(&mut args(&["sports", "hello"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+    }
+}
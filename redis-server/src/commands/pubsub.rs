@@ -0,0 +1,101 @@
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::pubsub::Kind;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PUBSUB CHANNELS [pattern]
+/// PUBSUB NUMSUB [channel [channel ...]]
+/// PUBSUB NUMPAT
+/// PUBSUB SHARDCHANNELS [pattern]
+/// PUBSUB SHARDNUMSUB [shardchannel [shardchannel ...]]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let subcommand = args.pop_front().unwrap().to_ascii_uppercase();
+
+    let broker = ks.pubsub();
+    match subcommand.as_slice() {
+        b"CHANNELS" | b"SHARDCHANNELS" if args.len() <= 1 => {
+            let kind = if subcommand == b"CHANNELS" {
+                Kind::Channel
+            } else {
+                Kind::ShardChannel
+            };
+            Ok(Frame::bulks(
+                broker.channels(kind, args.front().map(Vec::as_slice)),
+            ))
+        }
+        b"NUMSUB" | b"SHARDNUMSUB" => {
+            let kind = if subcommand == b"NUMSUB" {
+                Kind::Channel
+            } else {
+                Kind::ShardChannel
+            };
+            Ok(Frame::Map(
+                args.drain(..)
+                    .map(|channel| {
+                        let count = broker.subscriber_count(kind, &channel);
+                        (Frame::bulk(channel), Frame::Integer(count as i64))
+                    })
+                    .collect(),
+            ))
+        }
+        b"NUMPAT" => Ok(Frame::Integer(broker.pattern_count() as i64)),
+        // CHANNELS and SHARDCHANNELS with more arguments than they take.
+        _ => Err(CommandError::Other(format!(
+            "ERR unknown subcommand or wrong number of arguments for '{}' command",
+            String::from_utf8_lossy(&subcommand).to_lowercase()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::commands::{args, psubscribe, ssubscribe, subscribe};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_pubsub() {
+        let mut ks = Keyspace::new();
+        let (sender, _receiver) = mpsc::channel();
+        let mut one = Client::new(sender.clone());
+        let mut two = Client::new(sender);
+        subscribe::execute(&mut args(&["news"]), &mut one, &mut ks).unwrap();
+        subscribe::execute(&mut args(&["news", "sport"]), &mut two, &mut ks).unwrap();
+        psubscribe::execute(&mut args(&["n*"]), &mut one, &mut ks).unwrap();
+        psubscribe::execute(&mut args(&["n*"]), &mut two, &mut ks).unwrap();
+        ssubscribe::execute(&mut args(&["orders"]), &mut one, &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["CHANNELS", "s*"]), &mut ks),
+            Ok(Frame::bulks(["sport"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["NUMSUB", "news", "sport", "other"]), &mut ks),
+            Ok(Frame::Map(vec![
+                (Frame::bulk("news"), Frame::Integer(2)),
+                (Frame::bulk("sport"), Frame::Integer(1)),
+                (Frame::bulk("other"), Frame::Integer(0)),
+            ]))
+        );
+        assert_eq!(
+            execute(&mut args(&["NUMPAT"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["SHARDCHANNELS"]), &mut ks),
+            Ok(Frame::bulks(["orders"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["SHARDNUMSUB", "orders"]), &mut ks),
+            Ok(Frame::Map(vec![(Frame::bulk("orders"), Frame::Integer(1))]))
+        );
+        assert_eq!(
+            execute(&mut args(&["CHANNELS", "a*", "b*"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR unknown subcommand or wrong number of arguments for 'channels' command"
+            )))
+        );
+    }
+}
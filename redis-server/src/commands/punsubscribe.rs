@@ -0,0 +1,17 @@
+use crate::client::Client;
+use crate::commands::{unsubscribe, Result};
+use crate::db::Keyspace;
+use crate::pubsub::Kind;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// PUNSUBSCRIBE [pattern [pattern ...]]
+///
+/// Unsubscribes the client from the patterns, or from every pattern when none are given.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Vec<Frame>> {
+    Ok(unsubscribe::generic(Kind::Pattern, args, client, ks))
+}
@@ -0,0 +1,9 @@
+use crate::commands::{lpop, ListEnd, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// RPOP key [count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    lpop::generic(args, ks, ListEnd::Right, "rpop")
+}
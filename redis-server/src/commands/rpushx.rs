@@ -0,0 +1,9 @@
+use crate::commands::{lpush, ListEnd, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// RPUSHX key element [element ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    lpush::generic(args, ks, ListEnd::Right, true)
+}
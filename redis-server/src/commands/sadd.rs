@@ -0,0 +1,58 @@
+use crate::commands::{get_set, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use indexmap::IndexSet;
+use std::collections::VecDeque;
+
+/// SADD key member [member ...]
+///
+/// Replies with how many of the members are new.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let set = create_set(ks, key)?;
+
+    let added = args
+        .drain(..)
+        .filter(|member| set.insert(member.clone()))
+        .count();
+    Ok(Frame::Integer(added as i64))
+}
+
+/// The set at `key`, which is created empty if there isn't one.
+pub fn create_set(ks: &mut Keyspace, key: Vec<u8>) -> Result<&mut IndexSet<Vec<u8>>> {
+    if get_set(ks, &key)?.is_none() {
+        ks.set(key.clone(), Value::Set(IndexSet::new()));
+    }
+
+    Ok(get_set(ks, &key)?.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, scard, CommandError};
+
+    #[test]
+    fn test_sadd() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["s", "a", "b", "a"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "b", "c"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            scard::execute(&mut args(&["s"]), &mut ks),
+            Ok(Frame::Integer(3))
+        );
+
+        ks.set(b"l".to_vec(), Value::List(VecDeque::new()));
+        assert_eq!(
+            execute(&mut args(&["l", "a"]), &mut ks),
+            Err(CommandError::WrongType)
+        );
+    }
+}
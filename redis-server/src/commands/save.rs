@@ -0,0 +1,25 @@
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::persistence;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SAVE
+///
+/// Writes the dataset to disk before replying, holding up every other client meanwhile.
+pub fn execute(_args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if ks.persistence().status().in_progress() {
+        return Err(CommandError::Other(String::from(
+            "ERR Background save already in progress",
+        )));
+    }
+
+    let snapshot = persistence::snapshot(ks);
+    match ks.persistence().save(&snapshot) {
+        Ok(()) => Ok(Frame::ok()),
+        Err(e) => {
+            eprintln!("Failed saving the DB: {}", e);
+            Err(CommandError::Other(String::from("ERR")))
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use crate::commands::{get_set, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SCARD key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let len = get_set(ks, &key)?.map_or(0, |set| set.len());
+    Ok(Frame::Integer(len as i64))
+}
@@ -0,0 +1,100 @@
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use crate::scripting::ScriptStatus;
+use std::collections::VecDeque;
+use std::sync::PoisonError;
+
+/// SCRIPT LOAD script
+/// SCRIPT EXISTS sha1 [sha1 ...]
+/// SCRIPT FLUSH [ASYNC | SYNC]
+/// SCRIPT KILL
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let subcommand = args.pop_front().unwrap().to_ascii_uppercase();
+
+    let scripting = ks.scripting();
+    let mut scripting = scripting.lock().unwrap_or_else(PoisonError::into_inner);
+    match subcommand.as_slice() {
+        b"LOAD" => Ok(Frame::bulk(scripting.load(&args[0])?)),
+        b"EXISTS" => Ok(Frame::Array(
+            args.iter()
+                .map(|sha| {
+                    let exists = scripting.exists(&String::from_utf8_lossy(sha));
+                    Frame::Integer(exists as i64)
+                })
+                .collect(),
+        )),
+        b"FLUSH" if args.len() <= 1 => {
+            match args.front() {
+                None => {}
+                Some(mode)
+                    if mode.eq_ignore_ascii_case(b"ASYNC")
+                        || mode.eq_ignore_ascii_case(b"SYNC") => {}
+                Some(_) => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR SCRIPT FLUSH only support SYNC|ASYNC option",
+                    )))
+                }
+            }
+            scripting.flush();
+            Ok(Frame::ok())
+        }
+        // A script that is running holds the keyspace, so SCRIPT KILL is handled before it is
+        // locked. Getting here means no script is running.
+        b"KILL" => kill(&scripting.status()),
+        // FLUSH with more arguments than it takes.
+        _ => Err(CommandError::Other(format!(
+            "ERR unknown subcommand or wrong number of arguments for '{}' command",
+            String::from_utf8_lossy(&subcommand).to_lowercase()
+        ))),
+    }
+}
+
+/// SCRIPT KILL
+///
+/// Stops the script that is running, as long as it hasn't written anything.
+pub fn kill(status: &ScriptStatus) -> Result<Frame> {
+    status.kill()?;
+    Ok(Frame::ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_script() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["LOAD", "return 1"]), &mut ks),
+            Ok(Frame::bulk("e0e1f9fabfc9d4800c877a703b823ac0578ff8db"))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&[
+                    "EXISTS",
+                    "E0E1F9FABFC9D4800C877A703B823AC0578FF8DB",
+                    "ffffffffffffffffffffffffffffffffffffffff"
+                ]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![Frame::Integer(1), Frame::Integer(0)]))
+        );
+        assert_eq!(execute(&mut args(&["FLUSH"]), &mut ks), Ok(Frame::ok()));
+        assert_eq!(
+            execute(
+                &mut args(&["EXISTS", "e0e1f9fabfc9d4800c877a703b823ac0578ff8db"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![Frame::Integer(0)]))
+        );
+        assert_eq!(
+            execute(&mut args(&["KILL"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "NOTBUSY No scripts in execution right now."
+            )))
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use crate::commands::{get_sets, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use indexmap::IndexSet;
+use std::collections::VecDeque;
+
+/// SDIFF key [key ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let keys: Vec<Vec<u8>> = args.drain(..).collect();
+    let members = difference(get_sets(ks, &keys)?);
+    Ok(Frame::Set(members.into_iter().map(Frame::bulk).collect()))
+}
+
+/// The members of the first of `sets` that are in none of the others.
+pub fn difference(sets: Vec<IndexSet<Vec<u8>>>) -> IndexSet<Vec<u8>> {
+    let mut sets = sets.into_iter();
+    let first = sets.next().unwrap_or_default();
+    let others: Vec<_> = sets.collect();
+
+    first
+        .into_iter()
+        .filter(|member| !others.iter().any(|set| set.contains(member)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd, sunion};
+
+    #[test]
+    fn test_sdiff_and_sunion() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["a", "x", "y", "z"]), &mut ks).unwrap();
+        sadd::execute(&mut args(&["b", "y", "w"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["a", "b", "missing"]), &mut ks),
+            Ok(Frame::Set(vec![Frame::bulk("x"), Frame::bulk("z")]))
+        );
+        assert_eq!(
+            sunion::execute(&mut args(&["a", "b"]), &mut ks),
+            Ok(Frame::Set(vec![
+                Frame::bulk("x"),
+                Frame::bulk("y"),
+                Frame::bulk("z"),
+                Frame::bulk("w")
+            ]))
+        );
+    }
+}
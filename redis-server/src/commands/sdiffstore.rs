@@ -0,0 +1,12 @@
+use crate::commands::{get_sets, sdiff, sinterstore, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SDIFFSTORE destination key [key ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let destination = args.pop_front().unwrap();
+    let keys: Vec<Vec<u8>> = args.drain(..).collect();
+    let members = sdiff::difference(get_sets(ks, &keys)?);
+    Ok(sinterstore::store(ks, destination, members))
+}
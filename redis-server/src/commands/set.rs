@@ -0,0 +1,247 @@
+use crate::commands::{
+    expire_at, expire_option, get_string, parse_i64, CommandError, Result, TimeUnit,
+};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Condition {
+    /// Only set the key if it doesn't exist.
+    Nx,
+    /// Only set the key if it already exists.
+    Xx,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expiry {
+    KeepTtl,
+    At(TimeUnit, bool, Vec<u8>),
+}
+
+/// SET key value [NX | XX] [GET] [EX seconds | PX milliseconds |
+///   EXAT unix-time-seconds | PXAT unix-time-milliseconds | KEEPTTL]
+///
+/// Replies OK, or nil when NX or XX stopped the write. With GET the reply is the old value
+/// instead, whether or not the write happened.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let value = args.pop_front().unwrap();
+
+    let mut condition = None;
+    let mut get = false;
+    let mut expiry = None;
+
+    // Options may be repeated, but not combined with ones they contradict.
+    while let Some(option) = args.pop_front() {
+        let wanted = match option.to_ascii_uppercase().as_slice() {
+            b"NX" => Some(Condition::Nx),
+            b"XX" => Some(Condition::Xx),
+            _ => None,
+        };
+        if let Some(wanted) = wanted {
+            if condition.is_some_and(|c| c != wanted) {
+                return Err(CommandError::Syntax);
+            }
+            condition = Some(wanted);
+            continue;
+        }
+
+        let wanted = match expire_option(&option) {
+            Some((unit, relative)) if !args.is_empty() => {
+                Expiry::At(unit, relative, args.pop_front().unwrap())
+            }
+            None if option.eq_ignore_ascii_case(b"KEEPTTL") => Expiry::KeepTtl,
+            None if option.eq_ignore_ascii_case(b"GET") => {
+                get = true;
+                continue;
+            }
+            _ => return Err(CommandError::Syntax),
+        };
+        let conflicts = match (&expiry, &wanted) {
+            (None, _) => false,
+            (Some(Expiry::KeepTtl), Expiry::KeepTtl) => false,
+            (Some(Expiry::At(unit, relative, _)), Expiry::At(u, r, _)) => {
+                unit != u || relative != r
+            }
+            _ => true,
+        };
+        if conflicts {
+            return Err(CommandError::Syntax);
+        }
+        expiry = Some(wanted);
+    }
+
+    // The expire time is only checked once the options are known to make sense.
+    let when = match &expiry {
+        Some(Expiry::At(unit, relative, amount)) => {
+            Some(expire_at(parse_i64(amount)?, *unit, *relative, "set")?)
+        }
+        _ => None,
+    };
+
+    // Only GET cares what type the old value was; otherwise SET replaces any value.
+    let old = if get {
+        get_string(ks, &key)?.cloned()
+    } else {
+        None
+    };
+    let exists = old.is_some() || ks.contains_key(&key);
+
+    let write = match condition {
+        Some(Condition::Nx) => !exists,
+        Some(Condition::Xx) => exists,
+        None => true,
+    };
+
+    if write {
+        if expiry == Some(Expiry::KeepTtl) {
+            ks.set_keep_ttl(key, Value::String(value));
+        } else {
+            ks.set(key.clone(), Value::String(value));
+            if let Some(when) = when {
+                ks.set_expire_time(&key, when);
+            }
+        }
+    }
+
+    if get {
+        Ok(old.map(Frame::bulk).unwrap_or(Frame::NullBulkString))
+    } else if write {
+        Ok(Frame::ok())
+    } else {
+        Ok(Frame::NullBulkString)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::now_ms;
+
+    fn get(ks: &mut Keyspace, key: &[u8]) -> Option<Vec<u8>> {
+        get_string(ks, key).unwrap().cloned()
+    }
+
+    #[test]
+    fn test_set() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(execute(&mut args(&["key", "1"]), &mut ks), Ok(Frame::ok()));
+        assert_eq!(get(&mut ks, b"key"), Some(b"1".to_vec()));
+    }
+
+    #[test]
+    fn test_set_nx_xx() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["key", "1", "xx"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(get(&mut ks, b"key"), None);
+        assert_eq!(
+            execute(&mut args(&["key", "1", "NX"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "2", "NX"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "3", "XX"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(get(&mut ks, b"key"), Some(b"3".to_vec()));
+        assert_eq!(
+            execute(&mut args(&["key", "4", "NX", "XX"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+    }
+
+    #[test]
+    fn test_set_get() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["key", "1", "GET"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "2", "GET"]), &mut ks),
+            Ok(Frame::bulk("1"))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "3", "NX", "GET"]), &mut ks),
+            Ok(Frame::bulk("2"))
+        );
+        assert_eq!(get(&mut ks, b"key"), Some(b"2".to_vec()));
+
+        ks.set(b"list".to_vec(), Value::List(VecDeque::new()));
+        assert_eq!(
+            execute(&mut args(&["list", "v", "GET"]), &mut ks),
+            Err(CommandError::WrongType)
+        );
+        assert_eq!(
+            execute(&mut args(&["list", "v", "XX"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(get(&mut ks, b"list"), Some(b"v".to_vec()));
+    }
+
+    #[test]
+    fn test_set_expiry() {
+        let mut ks = Keyspace::new();
+
+        execute(&mut args(&["key", "1", "EX", "100"]), &mut ks).unwrap();
+        let when = ks.expire_time(b"key").unwrap();
+        assert!(when > now_ms() + 99_000 && when <= now_ms() + 100_000);
+
+        execute(&mut args(&["key", "2", "KEEPTTL"]), &mut ks).unwrap();
+        assert_eq!(ks.expire_time(b"key"), Some(when));
+
+        execute(&mut args(&["key", "3"]), &mut ks).unwrap();
+        assert_eq!(ks.expire_time(b"key"), None);
+
+        execute(&mut args(&["key", "4", "PXAT", "4102444800000"]), &mut ks).unwrap();
+        assert_eq!(ks.expire_time(b"key"), Some(4_102_444_800_000));
+
+        // A time in the past is valid and the key is gone straight away.
+        assert_eq!(
+            execute(&mut args(&["key", "5", "EXAT", "1"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(get(&mut ks, b"key"), None);
+    }
+
+    #[test]
+    fn test_set_bad_expiry() {
+        let mut ks = Keyspace::new();
+
+        for bad in [
+            vec!["key", "v", "EX"],
+            vec!["key", "v", "EX", "10", "PX", "10"],
+            vec!["key", "v", "EX", "10", "KEEPTTL"],
+            vec!["key", "v", "EX", "x", "NOPE"],
+        ] {
+            assert_eq!(execute(&mut args(&bad), &mut ks), Err(CommandError::Syntax));
+        }
+        assert_eq!(
+            execute(&mut args(&["key", "v", "EX", "ten"]), &mut ks),
+            Err(CommandError::NotAnInteger)
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "v", "PX", "0"]), &mut ks),
+            Err(CommandError::InvalidExpireTime("set"))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["key", "v", "EX", "9223372036854775807"]),
+                &mut ks
+            ),
+            Err(CommandError::InvalidExpireTime("set"))
+        );
+        assert_eq!(get(&mut ks, b"key"), None);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::commands::{get_string, getbit, parse_bit_offset, CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SETBIT key offset value
+///
+/// Grows the string with zero bytes to reach `offset` if needed, and replies with the bit that
+/// was there before.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let offset = parse_bit_offset(&args.pop_front().unwrap(), None)?;
+    let on = match args.pop_front().unwrap().as_slice() {
+        b"0" => false,
+        b"1" => true,
+        _ => {
+            return Err(CommandError::Other(String::from(
+                "ERR bit is not an integer or out of range",
+            )))
+        }
+    };
+
+    let value = grow(ks, key, offset)?;
+    let old = getbit::bit(value, offset);
+    let byte = &mut value[(offset >> 3) as usize];
+    let mask = 1 << (7 - (offset & 7));
+    if on {
+        *byte |= mask;
+    } else {
+        *byte &= !mask;
+    }
+
+    Ok(Frame::Integer(old as i64))
+}
+
+/// The string at `key`, created if missing and padded with zero bytes so that the bit at
+/// `offset` is in it.
+pub fn grow(ks: &mut Keyspace, key: Vec<u8>, offset: u64) -> Result<&mut Vec<u8>> {
+    if get_string(ks, &key)?.is_none() {
+        ks.set(key.clone(), Value::String(Vec::new()));
+    }
+
+    let value = get_string(ks, &key)?.unwrap();
+    let len = (offset >> 3) as usize + 1;
+    if value.len() < len {
+        value.resize(len, 0);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, get};
+
+    #[test]
+    fn test_setbit() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["key", "7", "1"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "7", "0"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "9", "1"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            get::execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::bulk(vec![0x00, 0x40]))
+        );
+        assert_eq!(
+            getbit::execute(&mut args(&["key", "9"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            getbit::execute(&mut args(&["key", "1000"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+
+        assert!(execute(&mut args(&["key", "1", "2"]), &mut ks).is_err());
+        assert!(execute(&mut args(&["key", "-1", "1"]), &mut ks).is_err());
+        assert!(execute(&mut args(&["key", "4294967296", "1"]), &mut ks).is_err());
+    }
+}
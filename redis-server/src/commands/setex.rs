@@ -0,0 +1,16 @@
+use crate::commands::{expire_at, parse_i64, Result, TimeUnit};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SETEX key seconds value
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let amount = parse_i64(&args.pop_front().unwrap())?;
+    let value = args.pop_front().unwrap();
+    let when = expire_at(amount, TimeUnit::Seconds, true, "setex")?;
+
+    ks.set(key.clone(), Value::String(value));
+    ks.set_expire_time(&key, when);
+    Ok(Frame::ok())
+}
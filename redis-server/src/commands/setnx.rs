@@ -0,0 +1,17 @@
+use crate::commands::Result;
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SETNX key value
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let value = args.pop_front().unwrap();
+
+    if ks.contains_key(&key) {
+        return Ok(Frame::Integer(0));
+    }
+
+    ks.set(key, Value::String(value));
+    Ok(Frame::Integer(1))
+}
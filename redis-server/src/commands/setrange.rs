@@ -0,0 +1,89 @@
+use crate::commands::{check_string_length, get_string, parse_i64, CommandError, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SETRANGE key offset value
+///
+/// Overwrites part of the string at `offset`, padding it with zero bytes first if it is too
+/// short, and replies with the new length.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let offset = parse_i64(&args.pop_front().unwrap())?;
+    let value = args.pop_front().unwrap();
+
+    if offset < 0 {
+        return Err(CommandError::Other(String::from(
+            "ERR offset is out of range",
+        )));
+    }
+    let offset = offset as usize;
+
+    let len = get_string(ks, &key)?.map(|current| current.len());
+    if value.is_empty() {
+        // Writing nothing to a missing key doesn't create it.
+        return Ok(Frame::Integer(len.unwrap_or(0) as i64));
+    }
+
+    check_string_length(offset.saturating_add(value.len()))?;
+    if len.is_none() {
+        ks.set(key.clone(), Value::String(Vec::new()));
+    }
+
+    let current = get_string(ks, &key)?.unwrap();
+    if current.len() < offset + value.len() {
+        current.resize(offset + value.len(), 0);
+    }
+    current[offset..offset + value.len()].copy_from_slice(&value);
+
+    Ok(Frame::Integer(current.len() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_setrange() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"Hello World".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["key", "6", "Redis"]), &mut ks),
+            Ok(Frame::Integer(11))
+        );
+        assert_eq!(
+            ks.get(b"key"),
+            Some(&Value::String(b"Hello Redis".to_vec()))
+        );
+
+        assert_eq!(
+            execute(&mut args(&["pad", "3", "ab"]), &mut ks),
+            Ok(Frame::Integer(5))
+        );
+        assert_eq!(ks.get(b"pad"), Some(&Value::String(b"\0\0\0ab".to_vec())));
+    }
+
+    #[test]
+    fn test_setrange_edge_cases() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["missing", "10", ""]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert!(!ks.contains_key(b"missing"));
+        assert_eq!(
+            execute(&mut args(&["key", "-1", "x"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR offset is out of range"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["key", "536870912", "x"]), &mut ks),
+            Err(CommandError::StringTooLong)
+        );
+        assert!(!ks.contains_key(b"key"));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::persistence;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+use std::thread;
+use std::time::Duration;
+
+/// SHUTDOWN [NOSAVE | SAVE]
+///
+/// Saves the dataset and exits. Without either argument it saves only if save points are
+/// configured. Nothing is replied unless saving fails, since the connection goes away.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let save = parse(args)?.unwrap_or(!ks.persistence().policies.is_empty());
+
+    if save {
+        // A background save writes to the same temporary file, so it has to finish first.
+        while ks.persistence().status().in_progress() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let snapshot = persistence::snapshot(ks);
+        if let Err(e) = ks.persistence().save(&snapshot) {
+            eprintln!("Error trying to save the DB, can't exit: {}", e);
+            return Err(CommandError::Other(String::from(
+                "ERR Errors trying to SHUTDOWN. Check logs.",
+            )));
+        }
+    }
+
+    std::process::exit(0)
+}
+
+/// Whether SAVE or NOSAVE says to save, if either is given.
+pub fn parse(args: &VecDeque<Vec<u8>>) -> Result<Option<bool>> {
+    match args.iter().collect::<Vec<_>>()[..] {
+        [] => Ok(None),
+        [arg] if arg.eq_ignore_ascii_case(b"SAVE") => Ok(Some(true)),
+        [arg] if arg.eq_ignore_ascii_case(b"NOSAVE") => Ok(Some(false)),
+        _ => Err(CommandError::Syntax),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_shutdown_fails_when_it_cannot_save() {
+        let mut ks = Keyspace::new();
+        ks.persistence().dir = "/nonexistent/dir".into();
+
+        assert_eq!(
+            execute(&mut args(&["NOW"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(
+            execute(&mut args(&["SAVE"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Errors trying to SHUTDOWN. Check logs."
+            )))
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::commands::{get_sets, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use indexmap::IndexSet;
+use std::collections::VecDeque;
+
+/// SINTER key [key ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let keys: Vec<Vec<u8>> = args.drain(..).collect();
+    let members = intersect(get_sets(ks, &keys)?, None);
+    Ok(Frame::Set(members.into_iter().map(Frame::bulk).collect()))
+}
+
+/// The members found in every one of `sets`, stopping once `limit` of them have been found.
+///
+/// The smallest set is walked and the others are only probed, so the work is bounded by it.
+pub fn intersect(mut sets: Vec<IndexSet<Vec<u8>>>, limit: Option<usize>) -> IndexSet<Vec<u8>> {
+    sets.sort_by_key(|set| set.len());
+    let mut sets = sets.into_iter();
+    let smallest = sets.next().unwrap_or_default();
+    let others: Vec<_> = sets.collect();
+
+    let mut members = IndexSet::new();
+    for member in smallest {
+        if limit.is_some_and(|limit| members.len() >= limit) {
+            break;
+        }
+        if others.iter().all(|set| set.contains(&member)) {
+            members.insert(member);
+        }
+    }
+
+    members
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd};
+
+    #[test]
+    fn test_sinter() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["a", "x", "y", "z"]), &mut ks).unwrap();
+        sadd::execute(&mut args(&["b", "y", "z", "w"]), &mut ks).unwrap();
+        sadd::execute(&mut args(&["c", "z", "y"]), &mut ks).unwrap();
+
+        match execute(&mut args(&["a", "b", "c"]), &mut ks) {
+            Ok(Frame::Set(mut members)) => {
+                members.sort_by_key(|member| format!("{:?}", member));
+                assert_eq!(members, vec![Frame::bulk("y"), Frame::bulk("z")]);
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+        assert_eq!(
+            execute(&mut args(&["a", "missing"]), &mut ks),
+            Ok(Frame::Set(vec![]))
+        );
+    }
+}
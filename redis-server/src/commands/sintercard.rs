@@ -0,0 +1,78 @@
+use crate::commands::{get_sets, parse_i64, sinter, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SINTERCARD numkeys key [key ...] [LIMIT limit]
+///
+/// A limit of 0, the default, means no limit.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let numkeys = match parse_i64(&args.pop_front().unwrap()) {
+        Ok(numkeys) if numkeys > 0 => numkeys as usize,
+        _ => {
+            return Err(CommandError::Other(String::from(
+                "ERR numkeys should be greater than 0",
+            )))
+        }
+    };
+    if numkeys > args.len() {
+        return Err(CommandError::Other(String::from(
+            "ERR Number of keys can't be greater than number of args",
+        )));
+    }
+
+    let keys: Vec<Vec<u8>> = args.drain(..numkeys).collect();
+
+    let mut limit = None;
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"LIMIT" if !args.is_empty() => match parse_i64(&args.pop_front().unwrap()) {
+                Ok(0) => limit = None,
+                Ok(value) if value > 0 => limit = Some(value as usize),
+                _ => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR LIMIT can't be negative",
+                    )))
+                }
+            },
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    let members = sinter::intersect(get_sets(ks, &keys)?, limit);
+    Ok(Frame::Integer(members.len() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd};
+
+    #[test]
+    fn test_sintercard() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["a", "1", "2", "3", "4"]), &mut ks).unwrap();
+        sadd::execute(&mut args(&["b", "2", "3", "4", "5"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["2", "a", "b"]), &mut ks),
+            Ok(Frame::Integer(3))
+        );
+        assert_eq!(
+            execute(&mut args(&["2", "a", "b", "LIMIT", "2"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["3", "a", "b"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Number of keys can't be greater than number of args"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["2", "a", "b", "LIMIT", "-1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR LIMIT can't be negative"
+            )))
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use crate::commands::{get_sets, sinter, Result};
+use crate::db::{Keyspace, Value};
+use crate::resp::types::Frame;
+use indexmap::IndexSet;
+use std::collections::VecDeque;
+
+/// SINTERSTORE destination key [key ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let destination = args.pop_front().unwrap();
+    let keys: Vec<Vec<u8>> = args.drain(..).collect();
+    let members = sinter::intersect(get_sets(ks, &keys)?, None);
+    Ok(store(ks, destination, members))
+}
+
+/// Replaces whatever is at `destination` with `members`, replying with how many there are. An
+/// empty result deletes the destination instead.
+pub fn store(ks: &mut Keyspace, destination: Vec<u8>, members: IndexSet<Vec<u8>>) -> Frame {
+    let len = members.len();
+    if members.is_empty() {
+        ks.remove(&destination);
+    } else {
+        ks.set(destination, Value::Set(members));
+    }
+
+    Frame::Integer(len as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd, smembers};
+
+    #[test]
+    fn test_sinterstore() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["a", "x", "y"]), &mut ks).unwrap();
+        sadd::execute(&mut args(&["b", "y"]), &mut ks).unwrap();
+        ks.set(b"out".to_vec(), Value::String(b"old".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["out", "a", "b"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            smembers::execute(&mut args(&["out"]), &mut ks),
+            Ok(Frame::Set(vec![Frame::bulk("y")]))
+        );
+
+        assert_eq!(
+            execute(&mut args(&["out", "a", "missing"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert!(!ks.contains_key(b"out"));
+    }
+}
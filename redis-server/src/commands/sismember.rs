@@ -0,0 +1,13 @@
+use crate::commands::{get_set, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SISMEMBER key member
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let member = args.pop_front().unwrap();
+
+    let found = get_set(ks, &key)?.is_some_and(|set| set.contains(&member));
+    Ok(Frame::Integer(found as i64))
+}
@@ -0,0 +1,15 @@
+use crate::commands::{get_set, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SMEMBERS key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let members = match get_set(ks, &key)? {
+        Some(set) => set.iter().cloned().map(Frame::bulk).collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Frame::Set(members))
+}
@@ -0,0 +1,45 @@
+use crate::commands::{get_set, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SMISMEMBER key member [member ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let set = get_set(ks, &key)?;
+
+    let found = args
+        .iter()
+        .map(|member| {
+            let found = set.as_ref().is_some_and(|set| set.contains(member));
+            Frame::Integer(found as i64)
+        })
+        .collect();
+
+    Ok(Frame::Array(found))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd};
+
+    #[test]
+    fn test_smismember() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["s", "a", "b"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["s", "a", "x", "b"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::Integer(1),
+                Frame::Integer(0),
+                Frame::Integer(1)
+            ]))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing", "a"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Integer(0)]))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::commands::{get_set, sadd, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SMOVE source destination member
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let source = args.pop_front().unwrap();
+    let destination = args.pop_front().unwrap();
+    let member = args.pop_front().unwrap();
+
+    // The destination has to be a set too, even if nothing ends up moving.
+    get_set(ks, &destination)?;
+    let set = match get_set(ks, &source)? {
+        Some(set) => set,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    if source == destination {
+        return Ok(Frame::Integer(set.contains(&member) as i64));
+    }
+    if !set.swap_remove(&member) {
+        return Ok(Frame::Integer(0));
+    }
+    if set.is_empty() {
+        ks.remove(&source);
+    }
+
+    sadd::create_set(ks, destination)?.insert(member);
+    Ok(Frame::Integer(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sismember};
+
+    #[test]
+    fn test_smove() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["from", "a"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["from", "to", "x"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["from", "to", "a"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert!(!ks.contains_key(b"from"));
+        assert_eq!(
+            sismember::execute(&mut args(&["to", "a"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use crate::commands::{get_set, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// SPOP key [count]
+///
+/// Without a count the reply is a single member or nil, with one it is a set of up to that
+/// many members.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if args.len() > 2 {
+        return Err(CommandError::WrongArity("spop"));
+    }
+
+    let key = args.pop_front().unwrap();
+    let count = match args.pop_front() {
+        Some(count) => match parse_i64(&count) {
+            Ok(count) if count >= 0 => Some(count as usize),
+            _ => {
+                return Err(CommandError::Other(String::from(
+                    "ERR value is out of range, must be positive",
+                )))
+            }
+        },
+        None => None,
+    };
+
+    let set = match get_set(ks, &key)? {
+        Some(set) => set,
+        None if count.is_some() => return Ok(Frame::Set(vec![])),
+        None => return Ok(Frame::NullBulkString),
+    };
+
+    let mut rng = rand::thread_rng();
+    let mut popped = Vec::new();
+    for _ in 0..count.unwrap_or(1).min(set.len()) {
+        let index = rng.gen_range(0..set.len());
+        popped.push(set.swap_remove_index(index).unwrap());
+    }
+
+    if set.is_empty() {
+        ks.remove(&key);
+    }
+
+    Ok(match count {
+        Some(_) => Frame::Set(popped.into_iter().map(Frame::bulk).collect()),
+        None => Frame::bulk(popped.remove(0)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd, scard};
+
+    #[test]
+    fn test_spop() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["s", "a", "b", "c"]), &mut ks).unwrap();
+
+        match execute(&mut args(&["s", "2"]), &mut ks) {
+            Ok(Frame::Set(members)) => assert_eq!(members.len(), 2),
+            other => panic!("unexpected reply {:?}", other),
+        }
+        assert_eq!(
+            scard::execute(&mut args(&["s"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert!(matches!(
+            execute(&mut args(&["s"]), &mut ks),
+            Ok(Frame::BulkString(_))
+        ));
+        assert!(!ks.contains_key(b"s"));
+        assert_eq!(
+            execute(&mut args(&["s"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+    }
+}
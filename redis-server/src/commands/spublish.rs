@@ -0,0 +1,39 @@
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SPUBLISH shardchannel message
+///
+/// Sends the message to the clients subscribed to the shard channel. Patterns never match shard
+/// channels.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let received = ks.pubsub().spublish(&args[0], &args[1]);
+    Ok(Frame::Integer(received as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::commands::{args, psubscribe, ssubscribe};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_spublish() {
+        let mut ks = Keyspace::new();
+        let (sender, receiver) = mpsc::channel();
+        let mut client = Client::new(sender);
+        ssubscribe::execute(&mut args(&["orders"]), &mut client, &mut ks).unwrap();
+        psubscribe::execute(&mut args(&["*"]), &mut client, &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["orders", "1"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            b"*3\r\n$8\r\nsmessage\r\n$6\r\norders\r\n$1\r\n1\r\n"
+        );
+    }
+}
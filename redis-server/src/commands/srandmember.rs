@@ -0,0 +1,90 @@
+use crate::commands::{get_set, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use rand::seq::index;
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// SRANDMEMBER key [count]
+///
+/// A positive count returns distinct members, a negative one may return the same member more
+/// than once.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    if args.len() > 2 {
+        return Err(CommandError::WrongArity("srandmember"));
+    }
+
+    let key = args.pop_front().unwrap();
+    let count = args
+        .pop_front()
+        .map(|count| parse_i64(&count))
+        .transpose()?;
+    if let Some(count) = count {
+        if !(-(i64::MAX / 2)..=i64::MAX / 2).contains(&count) {
+            return Err(CommandError::Other(String::from(
+                "ERR value is out of range",
+            )));
+        }
+    }
+
+    let set = get_set(ks, &key)?;
+    let mut rng = rand::thread_rng();
+
+    let (count, set) = match (count, set) {
+        (None, None) => return Ok(Frame::NullBulkString),
+        (None, Some(set)) => {
+            let member = set.get_index(rng.gen_range(0..set.len())).unwrap();
+            return Ok(Frame::bulk(member.clone()));
+        }
+        (Some(_), None) => return Ok(Frame::Array(vec![])),
+        (Some(count), Some(set)) => (count, set),
+    };
+
+    let indices: Vec<usize> = if count < 0 {
+        (0..count.unsigned_abs())
+            .map(|_| rng.gen_range(0..set.len()))
+            .collect()
+    } else if count as usize >= set.len() {
+        (0..set.len()).collect()
+    } else {
+        index::sample(&mut rng, set.len(), count as usize).into_vec()
+    };
+
+    Ok(Frame::bulks(
+        indices
+            .into_iter()
+            .map(|i| set.get_index(i).unwrap().clone()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd};
+
+    #[test]
+    fn test_srandmember() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["s", "a", "b", "c"]), &mut ks).unwrap();
+
+        match execute(&mut args(&["s", "2"]), &mut ks) {
+            Ok(Frame::Array(members)) => {
+                assert_eq!(members.len(), 2);
+                assert_ne!(members[0], members[1]);
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+        match execute(&mut args(&["s", "-7"]), &mut ks) {
+            Ok(Frame::Array(members)) => assert_eq!(members.len(), 7),
+            other => panic!("unexpected reply {:?}", other),
+        }
+        match execute(&mut args(&["s", "5"]), &mut ks) {
+            Ok(Frame::Array(members)) => assert_eq!(members.len(), 3),
+            other => panic!("unexpected reply {:?}", other),
+        }
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use crate::commands::{get_set, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SREM key member [member ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let set = match get_set(ks, &key)? {
+        Some(set) => set,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    let removed = args
+        .iter()
+        .filter(|member| set.swap_remove(member.as_slice()))
+        .count();
+
+    if set.is_empty() {
+        ks.remove(&key);
+    }
+
+    Ok(Frame::Integer(removed as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd};
+
+    #[test]
+    fn test_srem() {
+        let mut ks = Keyspace::new();
+        sadd::execute(&mut args(&["s", "a", "b"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["s", "a", "x"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "b"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert!(!ks.contains_key(b"s"));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::commands::{get_set, Result, ScanArgs};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SSCAN key cursor [MATCH pattern] [COUNT count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let scan = ScanArgs::parse(args, None)?;
+
+    let (cursor, batch) = match get_set(ks, &key)? {
+        Some(set) => scan.scan(set.len(), |i| {
+            let member = set.get_index(i)?;
+            Some((member.as_slice(), member))
+        }),
+        None => (0, Vec::new()),
+    };
+
+    Ok(Frame::Array(vec![
+        Frame::bulk(cursor.to_string()),
+        Frame::bulks(batch.into_iter().cloned()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd, srem};
+
+    fn sscan(ks: &mut Keyspace, cursor: &str) -> (String, Vec<Vec<u8>>) {
+        let reply = execute(&mut args(&["s", cursor, "COUNT", "10"]), ks);
+        let Ok(Frame::Array(mut parts)) = reply else {
+            panic!("unexpected reply {:?}", reply);
+        };
+        let Some(Frame::Array(members)) = parts.pop() else {
+            panic!("expected an array of members");
+        };
+        let Some(Frame::BulkString(cursor)) = parts.pop() else {
+            panic!("expected a cursor");
+        };
+        let members = members
+            .into_iter()
+            .map(|member| match member {
+                Frame::BulkString(member) => member,
+                other => panic!("expected a member, got {:?}", other),
+            })
+            .collect();
+        (String::from_utf8(cursor).unwrap(), members)
+    }
+
+    #[test]
+    fn test_sscan_returns_members_kept_throughout_while_the_set_changes() {
+        let mut ks = Keyspace::new();
+        for i in 0..100 {
+            sadd::execute(&mut args(&["s", &format!("m{}", i)]), &mut ks).unwrap();
+        }
+
+        let (mut cursor, mut seen) = sscan(&mut ks, "0");
+        assert_eq!(seen.len(), 10);
+        let mut round = 0;
+        while cursor != "0" {
+            // Members at the bottom are removed, which moves ones from the top down, and new
+            // ones are added on top.
+            round += 1;
+            srem::execute(&mut args(&["s", &format!("m{}", round)]), &mut ks).unwrap();
+            sadd::execute(&mut args(&["s", &format!("new{}", round)]), &mut ks).unwrap();
+            let (next, members) = sscan(&mut ks, &cursor);
+            assert!(members.len() <= 10);
+            seen.extend(members);
+            cursor = next;
+        }
+
+        for i in (0..100).filter(|i| *i == 0 || *i > round) {
+            assert!(
+                seen.contains(&format!("m{}", i).into_bytes()),
+                "m{} was missed",
+                i
+            );
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use crate::client::Client;
+use crate::commands::{subscribe, Result};
+use crate::db::Keyspace;
+use crate::pubsub::Kind;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SSUBSCRIBE shardchannel [shardchannel ...]
+///
+/// Subscribes the client to the shard channels, which only SPUBLISH reaches.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Vec<Frame>> {
+    Ok(subscribe::generic(Kind::ShardChannel, args, client, ks))
+}
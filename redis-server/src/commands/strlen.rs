@@ -0,0 +1,11 @@
+use crate::commands::{get_string, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// STRLEN key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let len = get_string(ks, &key)?.map_or(0, |value| value.len());
+    Ok(Frame::Integer(len as i64))
+}
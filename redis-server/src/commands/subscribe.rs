@@ -0,0 +1,81 @@
+use crate::client::Client;
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::pubsub::Kind;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// SUBSCRIBE channel [channel ...]
+///
+/// Subscribes the client to the channels, confirming each one with a push that carries how many
+/// channels and patterns the client is now subscribed to.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Vec<Frame>> {
+    Ok(generic(Kind::Channel, args, client, ks))
+}
+
+/// Subscribes the client to every channel of a kind in `args`.
+pub fn generic(
+    kind: Kind,
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Vec<Frame> {
+    let subscriber = client.subscriber();
+    let broker = ks.pubsub();
+
+    let confirmations = args
+        .drain(..)
+        .map(|channel| {
+            broker.subscribe(kind, &subscriber, &channel);
+            Frame::Push(vec![
+                Frame::bulk(kind.subscribe_name()),
+                Frame::bulk(channel),
+                Frame::Integer(broker.count(kind, client.id) as i64),
+            ])
+        })
+        .collect();
+
+    client.subscriptions = broker.total(client.id);
+    confirmations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_subscribe() {
+        let mut ks = Keyspace::new();
+        let (sender, _receiver) = mpsc::channel();
+        let mut client = Client::new(sender);
+
+        assert_eq!(
+            execute(&mut args(&["a", "b", "a"]), &mut client, &mut ks),
+            Ok(vec![
+                Frame::Push(vec![
+                    Frame::bulk("subscribe"),
+                    Frame::bulk("a"),
+                    Frame::Integer(1)
+                ]),
+                Frame::Push(vec![
+                    Frame::bulk("subscribe"),
+                    Frame::bulk("b"),
+                    Frame::Integer(2)
+                ]),
+                Frame::Push(vec![
+                    Frame::bulk("subscribe"),
+                    Frame::bulk("a"),
+                    Frame::Integer(2)
+                ]),
+            ])
+        );
+        assert_eq!(client.subscriptions, 2);
+        assert!(client.in_subscribed_mode());
+    }
+}
@@ -0,0 +1,17 @@
+use crate::commands::{get_sets, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use indexmap::IndexSet;
+use std::collections::VecDeque;
+
+/// SUNION key [key ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let keys: Vec<Vec<u8>> = args.drain(..).collect();
+    let members = union(get_sets(ks, &keys)?);
+    Ok(Frame::Set(members.into_iter().map(Frame::bulk).collect()))
+}
+
+/// The members found in any of `sets`.
+pub fn union(sets: Vec<IndexSet<Vec<u8>>>) -> IndexSet<Vec<u8>> {
+    sets.into_iter().flatten().collect()
+}
@@ -0,0 +1,62 @@
+use crate::commands::{Result, TimeUnit};
+use crate::db::{now_ms, Keyspace};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// TTL key
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, TimeUnit::Seconds)
+}
+
+/// Replies with the time left to live, -1 if the key has no expiry time or -2 if there is no
+/// such key.
+pub fn generic(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace, unit: TimeUnit) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    if !ks.contains_key(&key) {
+        return Ok(Frame::Integer(-2));
+    }
+
+    let ttl = match ks.expire_time(&key) {
+        Some(when) => when.saturating_sub(now_ms()),
+        None => return Ok(Frame::Integer(-1)),
+    };
+
+    // Seconds are rounded to the nearest one, as Redis does.
+    Ok(Frame::Integer(match unit {
+        TimeUnit::Seconds => ((ttl + 500) / 1000) as i64,
+        TimeUnit::Milliseconds => ttl as i64,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::Value;
+
+    #[test]
+    fn test_ttl() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"v".to_vec()));
+
+        assert_eq!(
+            execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::Integer(-1))
+        );
+        assert_eq!(
+            execute(&mut args(&["missing"]), &mut ks),
+            Ok(Frame::Integer(-2))
+        );
+
+        ks.set_expire_time(b"key", now_ms() + 10_000);
+        assert_eq!(
+            execute(&mut args(&["key"]), &mut ks),
+            Ok(Frame::Integer(10))
+        );
+
+        match generic(&mut args(&["key"]), &mut ks, TimeUnit::Milliseconds) {
+            Ok(Frame::Integer(ms)) => assert!(ms > 9_000 && ms <= 10_000),
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+}
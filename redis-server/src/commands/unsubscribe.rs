@@ -0,0 +1,99 @@
+use crate::client::Client;
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::pubsub::Kind;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// UNSUBSCRIBE [channel [channel ...]]
+///
+/// Unsubscribes the client from the channels, or from every channel when none are given,
+/// confirming each one with a push.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Vec<Frame>> {
+    Ok(generic(Kind::Channel, args, client, ks))
+}
+
+/// Unsubscribes the client from every channel of a kind in `args`, or from all of them.
+pub fn generic(
+    kind: Kind,
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Vec<Frame> {
+    let broker = ks.pubsub();
+    let channels: Vec<Vec<u8>> = if args.is_empty() {
+        broker.subscribed(kind, client.id)
+    } else {
+        args.drain(..).collect()
+    };
+
+    // Even with nothing to unsubscribe from, the client is told how many subscriptions it has.
+    if channels.is_empty() {
+        return vec![Frame::Push(vec![
+            Frame::bulk(kind.unsubscribe_name()),
+            Frame::Null,
+            Frame::Integer(broker.count(kind, client.id) as i64),
+        ])];
+    }
+
+    let confirmations = channels
+        .into_iter()
+        .map(|channel| {
+            broker.unsubscribe(kind, client.id, &channel);
+            Frame::Push(vec![
+                Frame::bulk(kind.unsubscribe_name()),
+                Frame::bulk(channel),
+                Frame::Integer(broker.count(kind, client.id) as i64),
+            ])
+        })
+        .collect();
+
+    client.subscriptions = broker.total(client.id);
+    confirmations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, subscribe};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_unsubscribe() {
+        let mut ks = Keyspace::new();
+        let (sender, _receiver) = mpsc::channel();
+        let mut client = Client::new(sender);
+
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks),
+            Ok(vec![Frame::Push(vec![
+                Frame::bulk("unsubscribe"),
+                Frame::Null,
+                Frame::Integer(0)
+            ])])
+        );
+
+        subscribe::execute(&mut args(&["a", "b"]), &mut client, &mut ks).unwrap();
+        assert_eq!(
+            execute(&mut args(&["b"]), &mut client, &mut ks),
+            Ok(vec![Frame::Push(vec![
+                Frame::bulk("unsubscribe"),
+                Frame::bulk("b"),
+                Frame::Integer(1)
+            ])])
+        );
+        assert_eq!(
+            execute(&mut args(&[]), &mut client, &mut ks),
+            Ok(vec![Frame::Push(vec![
+                Frame::bulk("unsubscribe"),
+                Frame::bulk("a"),
+                Frame::Integer(0)
+            ])])
+        );
+        assert!(!client.in_subscribed_mode());
+    }
+}
@@ -0,0 +1,15 @@
+use crate::client::Client;
+use crate::commands::Result;
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// UNWATCH
+pub fn execute(
+    _args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Frame> {
+    ks.unwatch_all(client.id);
+    Ok(Frame::ok())
+}
@@ -0,0 +1,25 @@
+use crate::client::Client;
+use crate::commands::{CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// WATCH key [key ...]
+///
+/// Makes the next EXEC fail if any of the keys changes before it runs.
+pub fn execute(
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Result<Frame> {
+    if client.transaction.is_some() {
+        return Err(CommandError::Other(String::from(
+            "ERR WATCH inside MULTI is not allowed",
+        )));
+    }
+
+    for key in args.drain(..) {
+        ks.watch(client.id, key);
+    }
+    Ok(Frame::ok())
+}
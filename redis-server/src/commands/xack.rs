@@ -0,0 +1,27 @@
+use crate::commands::{get_stream, parse_stream_id, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XACK key group id [id ...]
+///
+/// Removes messages from the group's pending entries. Replies with how many there were.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let group = args.pop_front().unwrap();
+    let ids = args
+        .iter()
+        .map(|id| parse_stream_id(id, 0))
+        .collect::<Result<Vec<_>>>()?;
+
+    let group = match get_stream(ks, &key)?.and_then(|stream| stream.group_mut(&group)) {
+        Some(group) => group,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    let acked = ids
+        .iter()
+        .filter(|id| group.pending.remove(id).is_some())
+        .count();
+    Ok(Frame::Integer(acked as i64))
+}
@@ -0,0 +1,167 @@
+use crate::commands::{get_stream, parse_stream_id, xtrim::Trim, CommandError, Result};
+use crate::db::{now_ms, Keyspace, Stream, StreamId, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XADD key [NOMKSTREAM] [MAXLEN | MINID [= | ~] threshold [LIMIT count]] * | id field value
+/// [field value ...]
+///
+/// Replies with the ID of the new entry, or nil if NOMKSTREAM was given and there is no stream.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+
+    let mut no_mkstream = false;
+    let mut trim = None;
+    while let Some(option) = args.front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"NOMKSTREAM" => {
+                args.pop_front();
+                no_mkstream = true;
+            }
+            b"MAXLEN" | b"MINID" => {
+                let strategy = args.pop_front().unwrap();
+                trim = Some(Trim::parse(&strategy, args)?);
+            }
+            _ => break,
+        }
+    }
+
+    let id = args.pop_front();
+    let id = match id {
+        Some(id) if !args.is_empty() && args.len().is_multiple_of(2) => id,
+        _ => return Err(CommandError::WrongArity("xadd")),
+    };
+
+    let last_id = match get_stream(ks, &key)? {
+        Some(stream) => stream.last_id(),
+        None if no_mkstream => return Ok(Frame::NullBulkString),
+        None => StreamId::MIN,
+    };
+    let id = next_id(&id, last_id)?;
+
+    let mut fields = Vec::with_capacity(args.len() / 2);
+    while let (Some(field), Some(value)) = (args.pop_front(), args.pop_front()) {
+        fields.push((field, value));
+    }
+
+    let stream = create_stream(ks, key.clone())?;
+    stream.insert(id, fields);
+    if let Some(trim) = trim {
+        trim.apply(stream);
+    }
+
+    ks.signal_ready(&key);
+    Ok(Frame::bulk(id.to_string()))
+}
+
+/// Works out the ID of a new entry from the argument given, which is `*` to have it generated,
+/// `ms-*` to only have the sequence number generated, or a full ID.
+fn next_id(arg: &[u8], last_id: StreamId) -> Result<StreamId> {
+    let too_small = || {
+        CommandError::Other(String::from(
+            "ERR The ID specified in XADD is equal or smaller than the target stream top item",
+        ))
+    };
+
+    if arg == b"*" {
+        return StreamId::generate(last_id, now_ms()).ok_or_else(|| {
+            CommandError::Other(String::from(
+                "ERR The stream has exhausted the last possible ID, unable to add more items",
+            ))
+        });
+    }
+
+    let id = match arg.strip_suffix(b"-*") {
+        Some(ms) => {
+            let ms = parse_stream_id(ms, 0)?.ms;
+            if ms < last_id.ms {
+                return Err(too_small());
+            }
+            if ms > last_id.ms {
+                StreamId::new(ms, 0)
+            } else {
+                StreamId::new(ms, last_id.seq.checked_add(1).ok_or_else(too_small)?)
+            }
+        }
+        None => parse_stream_id(arg, 0)?,
+    };
+
+    if id == StreamId::MIN {
+        return Err(CommandError::Other(String::from(
+            "ERR The ID specified in XADD must be greater than 0-0",
+        )));
+    }
+    if id <= last_id {
+        return Err(too_small());
+    }
+
+    Ok(id)
+}
+
+/// The stream at `key`, which is created empty if there isn't one.
+pub fn create_stream(ks: &mut Keyspace, key: Vec<u8>) -> Result<&mut Stream> {
+    if get_stream(ks, &key)?.is_none() {
+        ks.set(key.clone(), Value::Stream(Stream::new()));
+    }
+
+    Ok(get_stream(ks, &key)?.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xlen};
+
+    #[test]
+    fn test_xadd_explicit_ids() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["s", "1-1", "f", "v"]), &mut ks),
+            Ok(Frame::bulk("1-1"))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "1-*", "f", "v"]), &mut ks),
+            Ok(Frame::bulk("1-2"))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "5", "f", "v"]), &mut ks),
+            Ok(Frame::bulk("5-0"))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "5-0", "f", "v"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR The ID specified in XADD is equal or smaller than the target stream top item"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["t", "0-0", "f", "v"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR The ID specified in XADD must be greater than 0-0"
+            )))
+        );
+        assert!(!ks.contains_key(b"t"));
+    }
+
+    #[test]
+    fn test_xadd_generated_ids_and_trimming() {
+        let mut ks = Keyspace::new();
+
+        for _ in 0..5 {
+            execute(&mut args(&["s", "MAXLEN", "3", "*", "f", "v"]), &mut ks).unwrap();
+        }
+        assert_eq!(
+            xlen::execute(&mut args(&["s"]), &mut ks),
+            Ok(Frame::Integer(3))
+        );
+
+        assert_eq!(
+            execute(&mut args(&["none", "NOMKSTREAM", "*", "f", "v"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "*", "f"]), &mut ks),
+            Err(CommandError::WrongArity("xadd"))
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::commands::{get_stream, parse_i64, xclaim::parse_min_idle, xpending::no_group, xrange};
+use crate::commands::{CommandError, Result};
+use crate::db::{now_ms, Keyspace, StreamId};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XAUTOCLAIM key group consumer min-idle-time start [COUNT count] [JUSTID]
+///
+/// Claims up to `count` messages idle for at least `min-idle-time`, scanning the group's
+/// pending messages from `start`. Replies with the ID to carry on scanning from, or 0-0 if the
+/// scan is done, the entries claimed, and the IDs of the messages dropped because their entries
+/// were deleted.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let name = args.pop_front().unwrap();
+    let consumer = args.pop_front().unwrap();
+    let min_idle = parse_min_idle(&args.pop_front().unwrap(), "XAUTOCLAIM")?;
+    let start = xrange::parse_bound(&args.pop_front().unwrap(), true)?;
+
+    let mut count = 100;
+    let mut just_id = false;
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"COUNT" if !args.is_empty() => {
+                count = match parse_i64(&args.pop_front().unwrap())? {
+                    count if count > 0 => count as usize,
+                    _ => return Err(CommandError::Other(String::from("ERR COUNT must be > 0"))),
+                };
+            }
+            b"JUSTID" => just_id = true,
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    let stream = get_stream(ks, &key)?
+        .and_then(|stream| stream.group(&name).is_some().then_some(stream))
+        .ok_or_else(|| no_group(&key, &name))?;
+
+    // As in Redis, only so many messages are looked at per call, however few get claimed.
+    let mut attempts = count.saturating_mul(10);
+    let now = now_ms();
+    let mut claimed = Vec::new();
+    let mut deleted = Vec::new();
+    let mut next = StreamId::MIN;
+    let group = stream.group(&name).unwrap();
+    for (id, entry) in group.pending.range(start..) {
+        if attempts == 0 || claimed.len() == count {
+            next = *id;
+            break;
+        }
+        attempts -= 1;
+
+        if stream.get(*id).is_none() {
+            deleted.push(*id);
+        } else if now.saturating_sub(entry.delivery_time) >= min_idle {
+            claimed.push(*id);
+        }
+    }
+
+    let entries = claimed
+        .iter()
+        .map(|id| match just_id {
+            true => Frame::bulk(id.to_string()),
+            false => xrange::entry(*id, stream.get(*id)),
+        })
+        .collect();
+
+    let group = stream.group_mut(&name).unwrap();
+    for id in &deleted {
+        group.pending.remove(id);
+    }
+    for id in &claimed {
+        let entry = group.pending.get_mut(id).unwrap();
+        entry.consumer = consumer.clone();
+        entry.delivery_time = now;
+        if !just_id {
+            entry.delivery_count += 1;
+        }
+    }
+
+    let claimer = group.consumer(&consumer, now);
+    claimer.seen_time = now;
+    if !claimed.is_empty() {
+        claimer.active_time = Some(now);
+    }
+
+    Ok(Frame::Array(vec![
+        Frame::bulk(next.to_string()),
+        Frame::Array(entries),
+        Frame::Array(
+            deleted
+                .iter()
+                .map(|id| Frame::bulk(id.to_string()))
+                .collect(),
+        ),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd, xdel, xgroup, xreadgroup};
+
+    #[test]
+    fn test_xautoclaim() {
+        let mut ks = Keyspace::new();
+        for id in ["1-1", "1-2", "1-3"] {
+            xadd::execute(&mut args(&["s", id, "f", "v"]), &mut ks).unwrap();
+        }
+        xgroup::execute(&mut args(&["CREATE", "s", "g", "0"]), &mut ks).unwrap();
+        xreadgroup::execute(
+            &mut args(&["GROUP", "g", "a", "STREAMS", "s", ">"]),
+            &mut ks,
+        )
+        .unwrap();
+        xdel::execute(&mut args(&["s", "1-1"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(
+                &mut args(&["s", "g", "b", "0", "-", "COUNT", "1", "JUSTID"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![
+                Frame::bulk("1-3"),
+                Frame::Array(vec![Frame::bulk("1-2")]),
+                Frame::Array(vec![Frame::bulk("1-1")]),
+            ]))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "g", "b", "0", "1-3", "JUSTID"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::bulk("0-0"),
+                Frame::Array(vec![Frame::bulk("1-3")]),
+                Frame::Array(vec![]),
+            ]))
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use crate::commands::{get_stream, parse_i64, parse_stream_id, xpending::no_group, xrange};
+use crate::commands::{CommandError, Result};
+use crate::db::{now_ms, Keyspace, PendingEntry, StreamId};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XCLAIM key group consumer min-idle-time id [id ...] [IDLE ms] [TIME unix-time-milliseconds]
+/// [RETRYCOUNT count] [FORCE] [JUSTID] [LASTID lastid]
+///
+/// Hands pending messages idle for at least `min-idle-time` over to `consumer`, replying with
+/// the entries claimed. Messages whose entries were deleted are dropped from the group instead.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let name = args.pop_front().unwrap();
+    let consumer = args.pop_front().unwrap();
+    let min_idle = parse_min_idle(&args.pop_front().unwrap(), "XCLAIM")?;
+
+    // The IDs run until the first argument that isn't one.
+    let mut ids = Vec::new();
+    while let Some(id) = args.front().and_then(|arg| StreamId::parse(arg, 0)) {
+        args.pop_front();
+        ids.push(id);
+    }
+    if ids.is_empty() {
+        parse_stream_id(&args[0], 0)?;
+    }
+
+    let now = now_ms();
+    let mut delivery_time = now;
+    let mut retry_count = None;
+    let mut force = false;
+    let mut just_id = false;
+    let mut last_id = None;
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"IDLE" if !args.is_empty() => {
+                let idle = parse_i64(&args.pop_front().unwrap())?;
+                delivery_time = now.saturating_sub(idle.max(0) as u64);
+            }
+            b"TIME" if !args.is_empty() => {
+                delivery_time = parse_i64(&args.pop_front().unwrap())?.max(0) as u64;
+            }
+            b"RETRYCOUNT" if !args.is_empty() => {
+                retry_count = Some(parse_i64(&args.pop_front().unwrap())?.max(0) as u64);
+            }
+            b"FORCE" => force = true,
+            b"JUSTID" => just_id = true,
+            b"LASTID" if !args.is_empty() => {
+                last_id = Some(parse_stream_id(&args.pop_front().unwrap(), 0)?);
+            }
+            _ => {
+                return Err(CommandError::Other(format!(
+                    "ERR Unrecognized XCLAIM option '{}'",
+                    String::from_utf8_lossy(&option)
+                )))
+            }
+        }
+    }
+
+    let stream = get_stream(ks, &key)?
+        .and_then(|stream| stream.group(&name).is_some().then_some(stream))
+        .ok_or_else(|| no_group(&key, &name))?;
+
+    let mut claimed = Vec::new();
+    let mut deleted = Vec::new();
+    let group = stream.group(&name).unwrap();
+    for id in ids {
+        let exists = stream.get(id).is_some();
+        match group.pending.get(&id) {
+            None if force && exists => {}
+            None => continue,
+            Some(_) if !exists => {
+                deleted.push(id);
+                continue;
+            }
+            Some(entry) if now.saturating_sub(entry.delivery_time) < min_idle => continue,
+            Some(_) => {}
+        }
+        claimed.push(id);
+    }
+
+    let entries = claimed
+        .iter()
+        .map(|id| match just_id {
+            true => Frame::bulk(id.to_string()),
+            false => xrange::entry(*id, stream.get(*id)),
+        })
+        .collect();
+
+    let group = stream.group_mut(&name).unwrap();
+    for id in deleted {
+        group.pending.remove(&id);
+    }
+    for id in &claimed {
+        let entry = group.pending.entry(*id).or_insert(PendingEntry {
+            consumer: Vec::new(),
+            delivery_time,
+            delivery_count: 0,
+        });
+        entry.consumer = consumer.clone();
+        entry.delivery_time = delivery_time;
+        match retry_count {
+            Some(count) => entry.delivery_count = count,
+            None if !just_id => entry.delivery_count += 1,
+            None => {}
+        }
+    }
+    if let Some(last_id) = last_id {
+        group.last_id = group.last_id.max(last_id);
+    }
+
+    let claimer = group.consumer(&consumer, now);
+    claimer.seen_time = now;
+    if !claimed.is_empty() {
+        claimer.active_time = Some(now);
+    }
+    Ok(Frame::Array(entries))
+}
+
+/// Parses the minimum idle time messages need to be claimed.
+pub fn parse_min_idle(arg: &[u8], command: &str) -> Result<u64> {
+    match parse_i64(arg) {
+        Ok(idle) => Ok(idle.max(0) as u64),
+        Err(_) => Err(CommandError::Other(format!(
+            "ERR Invalid min-idle-time argument for {}",
+            command
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd, xdel, xgroup, xreadgroup};
+
+    #[test]
+    fn test_xclaim() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["s", "1-1", "f", "a"]), &mut ks).unwrap();
+        xadd::execute(&mut args(&["s", "1-2", "f", "b"]), &mut ks).unwrap();
+        xgroup::execute(&mut args(&["CREATE", "s", "g", "0"]), &mut ks).unwrap();
+        xreadgroup::execute(
+            &mut args(&["GROUP", "g", "a", "STREAMS", "s", ">"]),
+            &mut ks,
+        )
+        .unwrap();
+        xdel::execute(&mut args(&["s", "1-2"]), &mut ks).unwrap();
+
+        // Not idle for long enough yet.
+        assert_eq!(
+            execute(&mut args(&["s", "g", "b", "100000", "1-1"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["s", "g", "b", "0", "1-1", "1-2", "JUSTID"]),
+                &mut ks
+            ),
+            Ok(Frame::Array(vec![Frame::bulk("1-1")]))
+        );
+
+        let group = get_stream(&mut ks, b"s")
+            .unwrap()
+            .unwrap()
+            .group(b"g")
+            .unwrap();
+        assert_eq!(group.pending.len(), 1);
+        assert_eq!(group.pending[&StreamId::new(1, 1)].consumer, b"b");
+        assert_eq!(group.pending[&StreamId::new(1, 1)].delivery_count, 1);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::commands::{get_stream, parse_stream_id, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XDEL key id [id ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    // Every ID has to be valid before anything is deleted.
+    let ids = args
+        .iter()
+        .map(|id| parse_stream_id(id, 0))
+        .collect::<Result<Vec<_>>>()?;
+
+    let deleted = match get_stream(ks, &key)? {
+        Some(stream) => ids.into_iter().filter(|id| stream.remove(*id)).count(),
+        None => 0,
+    };
+
+    Ok(Frame::Integer(deleted as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd, xlen};
+
+    #[test]
+    fn test_xdel() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["s", "1-1", "f", "v"]), &mut ks).unwrap();
+        xadd::execute(&mut args(&["s", "1-2", "f", "v"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["s", "1-1", "9-9"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "1-2"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        // An empty stream is still a stream.
+        assert_eq!(
+            xlen::execute(&mut args(&["s"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert!(ks.contains_key(b"s"));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::commands::{
+    get_stream, parse_i64, parse_stream_id, xadd::create_stream, CommandError, Result,
+};
+use crate::db::{now_ms, ConsumerGroup, Keyspace, Stream};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XGROUP CREATE key group id | $ [MKSTREAM] [ENTRIESREAD entries-read]
+/// XGROUP SETID key group id | $ [ENTRIESREAD entries-read]
+/// XGROUP DESTROY key group
+/// XGROUP CREATECONSUMER key group consumer
+/// XGROUP DELCONSUMER key group consumer
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let subcommand = args.pop_front().unwrap().to_ascii_uppercase();
+
+    // CREATE and SETID take at most four arguments after the group, a limit the registry's
+    // arity can't express.
+    if args.len() > 6 {
+        return Err(CommandError::Other(format!(
+            "ERR unknown subcommand or wrong number of arguments for '{}' command",
+            String::from_utf8_lossy(&subcommand).to_lowercase()
+        )));
+    }
+
+    let key = args.pop_front().unwrap();
+    let group = args.pop_front().unwrap();
+    match subcommand.as_slice() {
+        b"CREATE" => create(ks, key, group, args),
+        b"SETID" => set_id(ks, &key, &group, args),
+        b"DESTROY" => {
+            let destroyed = existing(ks, &key)?.destroy_group(&group);
+            Ok(Frame::Integer(destroyed as i64))
+        }
+        b"CREATECONSUMER" => {
+            let consumer = args.pop_front().unwrap();
+            let group = existing_group(ks, &key, &group)?;
+            let created = !group.consumers.contains_key(&consumer);
+            group.consumer(&consumer, now_ms());
+            Ok(Frame::Integer(created as i64))
+        }
+        _ => {
+            let consumer = args.pop_front().unwrap();
+            let group = existing_group(ks, &key, &group)?;
+            if group.consumers.remove(&consumer).is_none() {
+                return Ok(Frame::Integer(0));
+            }
+            let pending = group.pending_count(&consumer);
+            group.pending.retain(|_, entry| entry.consumer != consumer);
+            Ok(Frame::Integer(pending as i64))
+        }
+    }
+}
+
+fn create(
+    ks: &mut Keyspace,
+    key: Vec<u8>,
+    name: Vec<u8>,
+    args: &mut VecDeque<Vec<u8>>,
+) -> Result<Frame> {
+    let id = args.pop_front().unwrap();
+    let mut mkstream = false;
+    let mut entries_read = None;
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"MKSTREAM" => mkstream = true,
+            b"ENTRIESREAD" if !args.is_empty() => {
+                entries_read = parse_entries_read(&args.pop_front().unwrap())?;
+            }
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    let id = match id.as_slice() {
+        b"$" => None,
+        id => Some(parse_stream_id(id, 0)?),
+    };
+    let stream = match get_stream(ks, &key)? {
+        Some(stream) => stream,
+        None if mkstream => create_stream(ks, key)?,
+        None => return Err(no_key()),
+    };
+
+    let id = id.unwrap_or(stream.last_id());
+    if !stream.create_group(name, ConsumerGroup::new(id, entries_read)) {
+        return Err(CommandError::Other(String::from(
+            "BUSYGROUP Consumer Group name already exists",
+        )));
+    }
+    Ok(Frame::ok())
+}
+
+fn set_id(
+    ks: &mut Keyspace,
+    key: &[u8],
+    name: &[u8],
+    args: &mut VecDeque<Vec<u8>>,
+) -> Result<Frame> {
+    let id = args.pop_front().unwrap();
+    let entries_read = match args.pop_front() {
+        None => None,
+        Some(option) if option.eq_ignore_ascii_case(b"ENTRIESREAD") && args.len() == 1 => {
+            parse_entries_read(&args.pop_front().unwrap())?
+        }
+        Some(_) => return Err(CommandError::Syntax),
+    };
+
+    let stream = existing(ks, key)?;
+    let id = match id.as_slice() {
+        b"$" => stream.last_id(),
+        id => parse_stream_id(id, 0)?,
+    };
+    let group = stream.group_mut(name).ok_or_else(|| no_group(key, name))?;
+    group.last_id = id;
+    group.entries_read = entries_read;
+    Ok(Frame::ok())
+}
+
+/// Parses an ENTRIESREAD value, where -1 means the number isn't known.
+fn parse_entries_read(arg: &[u8]) -> Result<Option<u64>> {
+    match parse_i64(arg)? {
+        -1 => Ok(None),
+        read if read >= 0 => Ok(Some(read as u64)),
+        _ => Err(CommandError::Other(String::from(
+            "ERR value for ENTRIESREAD must be positive or -1",
+        ))),
+    }
+}
+
+fn no_key() -> CommandError {
+    CommandError::Other(String::from(
+        "ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.",
+    ))
+}
+
+fn no_group(key: &[u8], group: &[u8]) -> CommandError {
+    CommandError::Other(format!(
+        "NOGROUP No such consumer group '{}' for key name '{}'",
+        String::from_utf8_lossy(group),
+        String::from_utf8_lossy(key)
+    ))
+}
+
+fn existing<'a>(ks: &'a mut Keyspace, key: &[u8]) -> Result<&'a mut Stream> {
+    get_stream(ks, key)?.ok_or_else(no_key)
+}
+
+fn existing_group<'a>(
+    ks: &'a mut Keyspace,
+    key: &[u8],
+    name: &[u8],
+) -> Result<&'a mut ConsumerGroup> {
+    existing(ks, key)?
+        .group_mut(name)
+        .ok_or_else(|| no_group(key, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+    use crate::db::StreamId;
+
+    #[test]
+    fn test_xgroup() {
+        let mut ks = Keyspace::new();
+        assert!(matches!(
+            execute(&mut args(&["CREATE", "s", "g", "$"]), &mut ks),
+            Err(CommandError::Other(_))
+        ));
+        assert_eq!(
+            execute(&mut args(&["CREATE", "s", "g", "$", "MKSTREAM"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert!(matches!(
+            execute(&mut args(&["CREATE", "s", "g", "0"]), &mut ks),
+            Err(CommandError::Other(e)) if e.starts_with("BUSYGROUP")
+        ));
+
+        assert_eq!(
+            execute(&mut args(&["CREATECONSUMER", "s", "g", "c"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["CREATECONSUMER", "s", "g", "c"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["DELCONSUMER", "s", "g", "c"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+
+        assert_eq!(
+            execute(&mut args(&["SETID", "s", "g", "5-0"]), &mut ks),
+            Ok(Frame::ok())
+        );
+        assert_eq!(
+            get_stream(&mut ks, b"s")
+                .unwrap()
+                .unwrap()
+                .group(b"g")
+                .unwrap()
+                .last_id,
+            StreamId::new(5, 0)
+        );
+        assert_eq!(
+            execute(&mut args(&["DESTROY", "s", "g"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["DESTROY", "s", "g"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::commands::{get_stream, xpending::no_group, xrange, CommandError, Result};
+use crate::db::{now_ms, Keyspace, Stream};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XINFO STREAM key
+/// XINFO GROUPS key
+/// XINFO CONSUMERS key group
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let subcommand = args.pop_front().unwrap().to_ascii_uppercase();
+
+    let key = args.pop_front().unwrap();
+    let stream = get_stream(ks, &key)?
+        .ok_or_else(|| CommandError::Other(String::from("ERR no such key")))?;
+
+    match subcommand.as_slice() {
+        b"STREAM" => Ok(stream_info(stream)),
+        b"GROUPS" => Ok(Frame::Array(
+            stream
+                .groups()
+                .map(|(name, group)| {
+                    Frame::Map(vec![
+                        (Frame::bulk("name"), Frame::bulk(name.clone())),
+                        (
+                            Frame::bulk("consumers"),
+                            Frame::Integer(group.consumers.len() as i64),
+                        ),
+                        (
+                            Frame::bulk("pending"),
+                            Frame::Integer(group.pending.len() as i64),
+                        ),
+                        (
+                            Frame::bulk("last-delivered-id"),
+                            Frame::bulk(group.last_id.to_string()),
+                        ),
+                        (
+                            Frame::bulk("entries-read"),
+                            group
+                                .entries_read
+                                .map_or(Frame::Null, |read| Frame::Integer(read as i64)),
+                        ),
+                        (
+                            Frame::bulk("lag"),
+                            stream
+                                .lag(group)
+                                .map_or(Frame::Null, |lag| Frame::Integer(lag as i64)),
+                        ),
+                    ])
+                })
+                .collect(),
+        )),
+        _ => {
+            let name = args.pop_front().unwrap();
+            let group = stream.group(&name).ok_or_else(|| no_group(&key, &name))?;
+            let now = now_ms();
+            Ok(Frame::Array(
+                group
+                    .consumers
+                    .iter()
+                    .map(|(consumer, info)| {
+                        let inactive = info
+                            .active_time
+                            .map_or(-1, |active| now.saturating_sub(active) as i64);
+                        Frame::Map(vec![
+                            (Frame::bulk("name"), Frame::bulk(consumer.clone())),
+                            (
+                                Frame::bulk("pending"),
+                                Frame::Integer(group.pending_count(consumer) as i64),
+                            ),
+                            (
+                                Frame::bulk("idle"),
+                                Frame::Integer(now.saturating_sub(info.seen_time) as i64),
+                            ),
+                            (Frame::bulk("inactive"), Frame::Integer(inactive)),
+                        ])
+                    })
+                    .collect(),
+            ))
+        }
+    }
+}
+
+fn stream_info(stream: &Stream) -> Frame {
+    let entry = |entry: Option<(_, _)>| {
+        entry.map_or(Frame::Null, |(id, fields)| xrange::entry(id, Some(fields)))
+    };
+    let first_id = stream
+        .first()
+        .map_or(String::from("0-0"), |(id, _)| id.to_string());
+
+    Frame::Map(vec![
+        (Frame::bulk("length"), Frame::Integer(stream.len() as i64)),
+        (
+            Frame::bulk("last-generated-id"),
+            Frame::bulk(stream.last_id().to_string()),
+        ),
+        (
+            Frame::bulk("max-deleted-entry-id"),
+            Frame::bulk(stream.max_deleted_id().to_string()),
+        ),
+        (
+            Frame::bulk("entries-added"),
+            Frame::Integer(stream.entries_added() as i64),
+        ),
+        (
+            Frame::bulk("recorded-first-entry-id"),
+            Frame::bulk(first_id),
+        ),
+        (
+            Frame::bulk("groups"),
+            Frame::Integer(stream.groups().count() as i64),
+        ),
+        (Frame::bulk("first-entry"), entry(stream.first())),
+        (Frame::bulk("last-entry"), entry(stream.last())),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd, xgroup};
+
+    #[test]
+    fn test_xinfo_groups() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["s", "1-1", "f", "a"]), &mut ks).unwrap();
+        xadd::execute(&mut args(&["s", "1-2", "f", "b"]), &mut ks).unwrap();
+        xgroup::execute(&mut args(&["CREATE", "s", "g", "0"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["GROUPS", "s"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Map(vec![
+                (Frame::bulk("name"), Frame::bulk("g")),
+                (Frame::bulk("consumers"), Frame::Integer(0)),
+                (Frame::bulk("pending"), Frame::Integer(0)),
+                (Frame::bulk("last-delivered-id"), Frame::bulk("0-0")),
+                (Frame::bulk("entries-read"), Frame::Null),
+                (Frame::bulk("lag"), Frame::Integer(2)),
+            ])]))
+        );
+        assert_eq!(
+            execute(&mut args(&["STREAM", "missing"]), &mut ks),
+            Err(CommandError::Other(String::from("ERR no such key")))
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::commands::{get_stream, parse_i64, xrange, CommandError, Result};
+use crate::db::{now_ms, Keyspace};
+use crate::resp::types::Frame;
+use std::collections::{BTreeMap, VecDeque};
+
+/// XPENDING key group [[IDLE min-idle-time] start end count [consumer]]
+///
+/// Without a range, replies with a summary of the group's pending messages: how many there
+/// are, the lowest and highest IDs, and how many each consumer has. With one, replies with the
+/// pending messages in the range, each with its consumer, idle time and delivery count.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let name = args.pop_front().unwrap();
+
+    let mut min_idle = None;
+    if args
+        .front()
+        .is_some_and(|arg| arg.eq_ignore_ascii_case(b"IDLE"))
+    {
+        args.pop_front();
+        let idle = args.pop_front().ok_or(CommandError::Syntax)?;
+        min_idle = Some(parse_i64(&idle)?.max(0) as u64);
+    }
+    let range = match args.len() {
+        0 if min_idle.is_none() => None,
+        3 | 4 => {
+            let start = xrange::parse_bound(&args[0], true)?;
+            let end = xrange::parse_bound(&args[1], false)?;
+            let count = parse_i64(&args[2])?.max(0) as usize;
+            Some((start, end, count, args.get(3).cloned()))
+        }
+        _ => return Err(CommandError::Syntax),
+    };
+
+    let group = get_stream(ks, &key)?
+        .and_then(|stream| stream.group(&name))
+        .ok_or_else(|| no_group(&key, &name))?;
+
+    let Some((start, end, count, consumer)) = range else {
+        let (Some(first), Some(last)) = (
+            group.pending.keys().next(),
+            group.pending.keys().next_back(),
+        ) else {
+            return Ok(Frame::Array(vec![
+                Frame::Integer(0),
+                Frame::NullBulkString,
+                Frame::NullBulkString,
+                Frame::NullArray,
+            ]));
+        };
+
+        let mut consumers = BTreeMap::new();
+        for entry in group.pending.values() {
+            *consumers.entry(&entry.consumer).or_insert(0) += 1;
+        }
+        return Ok(Frame::Array(vec![
+            Frame::Integer(group.pending.len() as i64),
+            Frame::bulk(first.to_string()),
+            Frame::bulk(last.to_string()),
+            Frame::Array(
+                consumers
+                    .into_iter()
+                    .map(|(consumer, count)| {
+                        Frame::Array(vec![
+                            Frame::bulk(consumer.clone()),
+                            Frame::bulk(count.to_string()),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ]));
+    };
+
+    if start > end {
+        return Ok(Frame::Array(Vec::new()));
+    }
+    let now = now_ms();
+    let entries = group
+        .pending
+        .range(start..=end)
+        .filter(|(_, entry)| consumer.as_ref().is_none_or(|c| *c == entry.consumer))
+        .filter(|(_, entry)| {
+            min_idle.is_none_or(|min| now.saturating_sub(entry.delivery_time) >= min)
+        })
+        .take(count)
+        .map(|(id, entry)| {
+            Frame::Array(vec![
+                Frame::bulk(id.to_string()),
+                Frame::bulk(entry.consumer.clone()),
+                Frame::Integer(now.saturating_sub(entry.delivery_time) as i64),
+                Frame::Integer(entry.delivery_count as i64),
+            ])
+        })
+        .collect();
+    Ok(Frame::Array(entries))
+}
+
+/// The error for a missing stream or group, shared with XCLAIM and XAUTOCLAIM.
+pub fn no_group(key: &[u8], group: &[u8]) -> CommandError {
+    CommandError::Other(format!(
+        "NOGROUP No such key '{}' or consumer group '{}'",
+        String::from_utf8_lossy(key),
+        String::from_utf8_lossy(group)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocking::Outcome;
+    use crate::commands::{args, xack, xadd, xgroup, xreadgroup};
+
+    #[test]
+    fn test_xpending() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["s", "1-1", "f", "a"]), &mut ks).unwrap();
+        xadd::execute(&mut args(&["s", "1-2", "f", "b"]), &mut ks).unwrap();
+        xgroup::execute(&mut args(&["CREATE", "s", "g", "0"]), &mut ks).unwrap();
+        assert_eq!(
+            execute(&mut args(&["s", "g"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::Integer(0),
+                Frame::NullBulkString,
+                Frame::NullBulkString,
+                Frame::NullArray,
+            ]))
+        );
+
+        assert!(matches!(
+            xreadgroup::execute(
+                &mut args(&["GROUP", "g", "c", "STREAMS", "s", ">"]),
+                &mut ks
+            ),
+            Ok(Outcome::Ready(_))
+        ));
+        assert_eq!(
+            xack::execute(&mut args(&["s", "g", "1-1", "9-9"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+
+        assert_eq!(
+            execute(&mut args(&["s", "g"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::Integer(1),
+                Frame::bulk("1-2"),
+                Frame::bulk("1-2"),
+                Frame::Array(vec![Frame::Array(vec![Frame::bulk("c"), Frame::bulk("1")])]),
+            ]))
+        );
+        match execute(&mut args(&["s", "g", "-", "+", "10", "c"]), &mut ks) {
+            Ok(Frame::Array(entries)) => assert_eq!(entries.len(), 1),
+            reply => panic!("{:?}", reply),
+        }
+        assert_eq!(
+            execute(&mut args(&["s", "g", "-", "+", "10", "other"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::commands::{get_stream, parse_i64, parse_stream_id, CommandError, Result};
+use crate::db::{Fields, Keyspace, StreamId};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XRANGE key start end [COUNT count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, false, "xrange")
+}
+
+/// Replies with the entries between two IDs, oldest first or, reversed, newest first. `-` and
+/// `+` stand for the smallest and largest IDs and a `(` makes an end exclusive.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    rev: bool,
+    name: &'static str,
+) -> Result<Frame> {
+    if args.len() != 3 && args.len() != 5 {
+        return Err(CommandError::WrongArity(name));
+    }
+
+    let key = args.pop_front().unwrap();
+    let (start, end) = match rev {
+        false => (args.pop_front().unwrap(), args.pop_front().unwrap()),
+        true => {
+            let end = args.pop_front().unwrap();
+            (args.pop_front().unwrap(), end)
+        }
+    };
+    let start = parse_bound(&start, true)?;
+    let end = parse_bound(&end, false)?;
+
+    let count = match args.pop_front() {
+        Some(option) if option.eq_ignore_ascii_case(b"COUNT") => {
+            parse_i64(&args.pop_front().unwrap())?.max(0) as usize
+        }
+        Some(_) => return Err(CommandError::Syntax),
+        None => usize::MAX,
+    };
+
+    let stream = match get_stream(ks, &key)? {
+        Some(stream) => stream,
+        None => return Ok(Frame::Array(vec![])),
+    };
+
+    let entries = stream.range(start..=end);
+    let entries: Vec<Frame> = match rev {
+        false => entries
+            .take(count)
+            .map(|(id, fields)| entry(id, Some(fields)))
+            .collect(),
+        true => entries
+            .rev()
+            .take(count)
+            .map(|(id, fields)| entry(id, Some(fields)))
+            .collect(),
+    };
+
+    Ok(Frame::Array(entries))
+}
+
+/// Parses one end of a range of IDs, where a missing sequence number means the lowest one at
+/// the start and the highest at the end.
+pub fn parse_bound(arg: &[u8], start: bool) -> Result<StreamId> {
+    match arg {
+        b"-" => return Ok(StreamId::MIN),
+        b"+" => return Ok(StreamId::MAX),
+        _ => {}
+    }
+
+    let missing_seq = if start { 0 } else { u64::MAX };
+    match arg.strip_prefix(b"(") {
+        Some(id) => {
+            let id = parse_stream_id(id, missing_seq)?;
+            let id = if start { id.next() } else { id.prev() };
+            id.ok_or_else(|| {
+                CommandError::Other(format!(
+                    "ERR invalid {} ID for the interval",
+                    if start { "start" } else { "end" }
+                ))
+            })
+        }
+        None => parse_stream_id(arg, missing_seq),
+    }
+}
+
+/// An entry as replied with: its ID and its fields and values, which are nil for an entry
+/// that has been deleted.
+pub fn entry(id: StreamId, fields: Option<&Fields>) -> Frame {
+    let fields = match fields {
+        Some(fields) => Frame::bulks(
+            fields
+                .iter()
+                .flat_map(|(field, value)| [field.clone(), value.clone()]),
+        ),
+        None => Frame::NullArray,
+    };
+
+    Frame::Array(vec![Frame::bulk(id.to_string()), fields])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd};
+
+    fn ids(reply: Result<Frame>) -> Vec<String> {
+        match reply {
+            Ok(Frame::Array(entries)) => entries
+                .into_iter()
+                .map(|entry| match entry {
+                    Frame::Array(mut parts) => match parts.remove(0) {
+                        Frame::BulkString(id) => String::from_utf8(id).unwrap(),
+                        other => panic!("unexpected ID {:?}", other),
+                    },
+                    other => panic!("unexpected entry {:?}", other),
+                })
+                .collect(),
+            other => panic!("unexpected reply {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_xrange() {
+        let mut ks = Keyspace::new();
+        for id in ["1-1", "1-2", "2-1", "3-1"] {
+            xadd::execute(&mut args(&["s", id, "f", "v"]), &mut ks).unwrap();
+        }
+
+        assert_eq!(
+            execute(&mut args(&["s", "-", "+", "COUNT", "1"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::Array(vec![
+                Frame::bulk("1-1"),
+                Frame::bulks(["f", "v"])
+            ])]))
+        );
+        assert_eq!(
+            ids(execute(&mut args(&["s", "1", "2"]), &mut ks)),
+            ["1-1", "1-2", "2-1"]
+        );
+        assert_eq!(
+            ids(execute(&mut args(&["s", "(1-1", "(3-1"]), &mut ks)),
+            ["1-2", "2-1"]
+        );
+        assert_eq!(
+            ids(generic(
+                &mut args(&["s", "+", "-", "COUNT", "2"]),
+                &mut ks,
+                true,
+                "xrevrange"
+            )),
+            ["3-1", "2-1"]
+        );
+        assert_eq!(
+            ids(execute(&mut args(&["s", "3", "1"]), &mut ks)),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "x", "+"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR Invalid stream ID specified as stream command argument"
+            )))
+        );
+    }
+}
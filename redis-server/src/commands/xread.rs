@@ -0,0 +1,216 @@
+use crate::blocking::Outcome;
+use crate::commands::{get_stream, parse_i64, parse_stream_id, xrange, CommandError, Result};
+use crate::db::{Keyspace, Stream, StreamId};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// XREAD [COUNT count] [BLOCK milliseconds] STREAMS key [key ...] id [id ...]
+///
+/// Replies with the entries after each ID, for every stream that has any, as a list of the key
+/// and its entries. `$` stands for the last ID in the stream. With BLOCK, when there are none,
+/// the client waits for an entry to be added to one of the streams.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    let options = Options::parse(args, "xread")?;
+
+    let mut after = Vec::with_capacity(options.keys.len());
+    for (key, id) in options.keys.iter().zip(&options.ids) {
+        let stream = get_stream(ks, key)?;
+        let id = match id.as_slice() {
+            b"$" => stream.map_or(StreamId::MIN, |stream| stream.last_id()),
+            b">" => {
+                return Err(CommandError::Other(String::from(
+                    "ERR The > ID can be specified only when calling XREADGROUP using the GROUP <group> <consumer> option.",
+                )))
+            }
+            id => parse_stream_id(id, 0)?,
+        };
+        after.push((key.clone(), id));
+    }
+
+    let count = options.count;
+    let mut streams = Vec::new();
+    for (key, id) in &after {
+        if let Some(stream) = get_stream(ks, key)? {
+            let entries = read_after(stream, *id, count);
+            if !entries.is_empty() {
+                streams.push(Frame::Array(vec![
+                    Frame::bulk(key.clone()),
+                    Frame::Array(entries),
+                ]));
+            }
+        }
+    }
+
+    if !streams.is_empty() {
+        return Ok(Outcome::Ready(Frame::Array(streams)));
+    }
+    let timeout = match options.block {
+        Some(timeout) => timeout,
+        None => return Ok(Outcome::Ready(Frame::NullArray)),
+    };
+
+    let serve = Box::new(move |ks: &mut Keyspace, key: &[u8]| {
+        let (_, id) = after.iter().find(|(k, _)| k == key)?;
+        let stream = get_stream(ks, key).ok().flatten()?;
+        let entries = read_after(stream, *id, count);
+        match entries.is_empty() {
+            true => None,
+            false => Some(Frame::Array(vec![Frame::Array(vec![
+                Frame::bulk(key),
+                Frame::Array(entries),
+            ])])),
+        }
+    });
+    Ok(Outcome::Blocked(ks.block(
+        options.keys,
+        timeout,
+        Frame::NullArray,
+        serve,
+    )))
+}
+
+/// The entries after `id`, up to `count` of them.
+fn read_after(stream: &Stream, id: StreamId, count: Option<usize>) -> Vec<Frame> {
+    let Some(start) = id.next() else {
+        return Vec::new();
+    };
+
+    stream
+        .range(start..=StreamId::MAX)
+        .take(count.unwrap_or(usize::MAX))
+        .map(|(id, fields)| xrange::entry(id, Some(fields)))
+        .collect()
+}
+
+/// The options shared by XREAD and XREADGROUP, which come before the group's name for the
+/// latter.
+pub struct Options {
+    pub count: Option<usize>,
+    /// How long to block for if the client should. `Some(None)` means waiting forever.
+    pub block: Option<Option<Duration>>,
+    pub no_ack: bool,
+    pub keys: Vec<Vec<u8>>,
+    pub ids: Vec<Vec<u8>>,
+}
+
+impl Options {
+    pub fn parse(args: &mut VecDeque<Vec<u8>>, name: &'static str) -> Result<Options> {
+        let mut options = Options {
+            count: None,
+            block: None,
+            no_ack: false,
+            keys: Vec::new(),
+            ids: Vec::new(),
+        };
+
+        while let Some(option) = args.pop_front() {
+            match option.to_ascii_uppercase().as_slice() {
+                b"COUNT" if !args.is_empty() => {
+                    // As in Redis, a count of 0 or less means no limit.
+                    let count = parse_i64(&args.pop_front().unwrap())?;
+                    options.count = (count > 0).then_some(count as usize);
+                }
+                b"BLOCK" if !args.is_empty() => {
+                    options.block = Some(parse_block(&args.pop_front().unwrap())?);
+                }
+                b"NOACK" if name == "xreadgroup" => options.no_ack = true,
+                b"STREAMS" => {
+                    if args.is_empty() || !args.len().is_multiple_of(2) {
+                        let placeholder = if name == "xread" { "$" } else { ">" };
+                        return Err(CommandError::Other(format!(
+                            "ERR Unbalanced '{}' list of streams: for each stream key an ID or '{}' must be specified.",
+                            name, placeholder
+                        )));
+                    }
+                    let keys = args.len() / 2;
+                    options.keys = args.drain(..keys).collect();
+                    options.ids = args.drain(..).collect();
+                    return Ok(options);
+                }
+                _ => return Err(CommandError::Syntax),
+            }
+        }
+
+        Err(CommandError::Syntax)
+    }
+}
+
+/// Parses a BLOCK timeout, given in milliseconds, where 0 means waiting forever.
+fn parse_block(arg: &[u8]) -> Result<Option<Duration>> {
+    match parse_i64(arg) {
+        Ok(0) => Ok(None),
+        Ok(ms) if ms > 0 => Ok(Some(Duration::from_millis(ms as u64))),
+        Ok(_) => Err(CommandError::Other(String::from("ERR timeout is negative"))),
+        Err(_) => Err(CommandError::Other(String::from(
+            "ERR timeout is not an integer or out of range",
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd};
+
+    fn reply(outcome: Result<Outcome>) -> Frame {
+        match outcome {
+            Ok(Outcome::Ready(reply)) => reply,
+            Ok(Outcome::Blocked(_)) => panic!("blocked"),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn test_xread() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["a", "1-1", "f", "1"]), &mut ks).unwrap();
+        xadd::execute(&mut args(&["a", "1-2", "f", "2"]), &mut ks).unwrap();
+
+        assert_eq!(
+            reply(execute(
+                &mut args(&["COUNT", "1", "STREAMS", "a", "b", "1-1", "0"]),
+                &mut ks
+            )),
+            Frame::Array(vec![Frame::Array(vec![
+                Frame::bulk("a"),
+                Frame::Array(vec![xrange::entry(
+                    StreamId::new(1, 2),
+                    Some(&vec![(b"f".to_vec(), b"2".to_vec())])
+                )]),
+            ])])
+        );
+        assert_eq!(
+            reply(execute(&mut args(&["STREAMS", "a", "$"]), &mut ks)),
+            Frame::NullArray
+        );
+        assert!(matches!(
+            execute(&mut args(&["STREAMS", "a", "b", "0"]), &mut ks),
+            Err(CommandError::Other(_))
+        ));
+    }
+
+    #[test]
+    fn test_xread_blocks_until_an_entry_is_added() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["a", "1-1", "f", "1"]), &mut ks).unwrap();
+
+        let blocked = match execute(&mut args(&["BLOCK", "0", "STREAMS", "a", "$"]), &mut ks) {
+            Ok(Outcome::Blocked(blocked)) => blocked,
+            _ => panic!("expected to block"),
+        };
+
+        xadd::execute(&mut args(&["a", "2-1", "f", "2"]), &mut ks).unwrap();
+        ks.serve_blocked();
+        assert_eq!(
+            blocked.receiver.try_recv(),
+            Ok(Frame::Array(vec![Frame::Array(vec![
+                Frame::bulk("a"),
+                Frame::Array(vec![xrange::entry(
+                    StreamId::new(2, 1),
+                    Some(&vec![(b"f".to_vec(), b"2".to_vec())])
+                )]),
+            ])]))
+        );
+    }
+}
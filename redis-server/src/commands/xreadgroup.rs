@@ -0,0 +1,258 @@
+use crate::blocking::Outcome;
+use crate::commands::{get_stream, parse_stream_id, xrange, xread::Options, CommandError, Result};
+use crate::db::{now_ms, Keyspace, PendingEntry, StreamId};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+use std::ops::Bound;
+
+/// XREADGROUP GROUP group consumer [COUNT count] [BLOCK milliseconds] [NOACK] STREAMS key
+/// [key ...] id [id ...]
+///
+/// With the ID `>` the consumer gets entries never delivered to the group, which become
+/// pending until acknowledged unless NOACK is given. Any other ID reads back the consumer's own
+/// pending entries after it instead.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Outcome> {
+    if !args.pop_front().unwrap().eq_ignore_ascii_case(b"GROUP") {
+        return Err(CommandError::Syntax);
+    }
+    let group = args.pop_front().unwrap();
+    let consumer = args.pop_front().unwrap();
+    let options = Options::parse(args, "xreadgroup")?;
+
+    // Every stream and group has to exist before anything is read.
+    let mut history = Vec::with_capacity(options.ids.len());
+    for (key, id) in options.keys.iter().zip(&options.ids) {
+        let stream = get_stream(ks, key)?;
+        if stream.and_then(|stream| stream.group(&group)).is_none() {
+            return Err(no_group(key, &group));
+        }
+        history.push(match id.as_slice() {
+            b">" => None,
+            b"$" => {
+                return Err(CommandError::Other(String::from(
+                    "ERR The $ ID is meaningless in the context of XREADGROUP: you want to read the history of this consumer by specifying a proper ID, or use the > ID to get new messages. The $ ID would just return an empty result set.",
+                )))
+            }
+            id => Some(parse_stream_id(id, 0)?),
+        });
+    }
+
+    let mut streams = Vec::new();
+    for (key, after) in options.keys.iter().zip(&history) {
+        let read = match after {
+            Some(after) => Some(read_pending(
+                ks,
+                key,
+                &group,
+                &consumer,
+                *after,
+                options.count,
+            )?),
+            None => read_new(ks, key, &group, &consumer, options.count, options.no_ack)?,
+        };
+        if let Some(read) = read {
+            streams.push(read);
+        }
+    }
+
+    if !streams.is_empty() {
+        return Ok(Outcome::Ready(Frame::Array(streams)));
+    }
+    let timeout = match options.block {
+        Some(timeout) if history.iter().all(Option::is_none) => timeout,
+        _ => return Ok(Outcome::Ready(Frame::NullArray)),
+    };
+
+    let (count, no_ack) = (options.count, options.no_ack);
+    let serve = Box::new(move |ks: &mut Keyspace, key: &[u8]| {
+        match read_new(ks, key, &group, &consumer, count, no_ack) {
+            Ok(Some(read)) => Some(Frame::Array(vec![read])),
+            Ok(None) => None,
+            // The group or stream went away while the client waited.
+            Err(e) => Some(e.into()),
+        }
+    });
+    Ok(Outcome::Blocked(ks.block(
+        options.keys,
+        timeout,
+        Frame::NullArray,
+        serve,
+    )))
+}
+
+fn no_group(key: &[u8], group: &[u8]) -> CommandError {
+    CommandError::Other(format!(
+        "NOGROUP No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option",
+        String::from_utf8_lossy(key),
+        String::from_utf8_lossy(group)
+    ))
+}
+
+/// Delivers the entries the group hasn't seen yet to `consumer`, replying with the key and the
+/// entries, or `None` if there weren't any.
+fn read_new(
+    ks: &mut Keyspace,
+    key: &[u8],
+    group_name: &[u8],
+    consumer: &[u8],
+    count: Option<usize>,
+    no_ack: bool,
+) -> Result<Option<Frame>> {
+    let now = now_ms();
+    let stream = get_stream(ks, key)?.ok_or_else(|| no_group(key, group_name))?;
+    let group = stream
+        .group(group_name)
+        .ok_or_else(|| no_group(key, group_name))?;
+
+    let entries: Vec<(StreamId, Frame)> = match group.last_id.next() {
+        Some(start) => stream
+            .range(start..=StreamId::MAX)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| (id, xrange::entry(id, Some(fields))))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    if let Some((last, _)) = entries.last() {
+        stream.advance_group(group_name, *last, entries.len() as u64);
+    }
+
+    let group = stream.group_mut(group_name).unwrap();
+    let reader = group.consumer(consumer, now);
+    reader.seen_time = now;
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    reader.active_time = Some(now);
+
+    if !no_ack {
+        for (id, _) in &entries {
+            group.pending.insert(
+                *id,
+                PendingEntry {
+                    consumer: consumer.to_vec(),
+                    delivery_time: now,
+                    delivery_count: 1,
+                },
+            );
+        }
+    }
+
+    Ok(Some(Frame::Array(vec![
+        Frame::bulk(key),
+        Frame::Array(entries.into_iter().map(|(_, entry)| entry).collect()),
+    ])))
+}
+
+/// Replies with the key and the entries pending for `consumer` after `after`. Entries deleted
+/// from the stream since they were delivered have nil fields.
+fn read_pending(
+    ks: &mut Keyspace,
+    key: &[u8],
+    group_name: &[u8],
+    consumer: &[u8],
+    after: StreamId,
+    count: Option<usize>,
+) -> Result<Frame> {
+    let now = now_ms();
+    let stream = get_stream(ks, key)?.ok_or_else(|| no_group(key, group_name))?;
+    let group = stream
+        .group(group_name)
+        .ok_or_else(|| no_group(key, group_name))?;
+
+    let entries: Vec<Frame> = group
+        .pending
+        .range((Bound::Excluded(after), Bound::Unbounded))
+        .filter(|(_, pending)| pending.consumer == consumer)
+        .take(count.unwrap_or(usize::MAX))
+        .map(|(id, _)| xrange::entry(*id, stream.get(*id)))
+        .collect();
+
+    stream
+        .group_mut(group_name)
+        .unwrap()
+        .consumer(consumer, now)
+        .seen_time = now;
+
+    Ok(Frame::Array(vec![Frame::bulk(key), Frame::Array(entries)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd, xgroup};
+
+    fn reply(outcome: Result<Outcome>) -> Frame {
+        match outcome {
+            Ok(Outcome::Ready(reply)) => reply,
+            Ok(Outcome::Blocked(_)) => panic!("blocked"),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn entry(id: &str, value: &str) -> Frame {
+        xrange::entry(
+            StreamId::parse(id.as_bytes(), 0).unwrap(),
+            Some(&vec![(b"f".to_vec(), value.as_bytes().to_vec())]),
+        )
+    }
+
+    #[test]
+    fn test_xreadgroup_delivers_each_entry_once() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["s", "1-1", "f", "a"]), &mut ks).unwrap();
+        xadd::execute(&mut args(&["s", "1-2", "f", "b"]), &mut ks).unwrap();
+        xgroup::execute(&mut args(&["CREATE", "s", "g", "0"]), &mut ks).unwrap();
+
+        assert_eq!(
+            reply(execute(
+                &mut args(&["GROUP", "g", "alice", "COUNT", "1", "STREAMS", "s", ">"]),
+                &mut ks
+            )),
+            Frame::Array(vec![Frame::Array(vec![
+                Frame::bulk("s"),
+                Frame::Array(vec![entry("1-1", "a")])
+            ])])
+        );
+        assert_eq!(
+            reply(execute(
+                &mut args(&["GROUP", "g", "bob", "STREAMS", "s", ">"]),
+                &mut ks
+            )),
+            Frame::Array(vec![Frame::Array(vec![
+                Frame::bulk("s"),
+                Frame::Array(vec![entry("1-2", "b")])
+            ])])
+        );
+        assert_eq!(
+            reply(execute(
+                &mut args(&["GROUP", "g", "bob", "STREAMS", "s", ">"]),
+                &mut ks
+            )),
+            Frame::NullArray
+        );
+
+        // Alice's history is only what was delivered to her.
+        assert_eq!(
+            reply(execute(
+                &mut args(&["GROUP", "g", "alice", "STREAMS", "s", "0"]),
+                &mut ks
+            )),
+            Frame::Array(vec![Frame::Array(vec![
+                Frame::bulk("s"),
+                Frame::Array(vec![entry("1-1", "a")])
+            ])])
+        );
+    }
+
+    #[test]
+    fn test_xreadgroup_needs_a_group() {
+        let mut ks = Keyspace::new();
+        xadd::execute(&mut args(&["s", "1-1", "f", "a"]), &mut ks).unwrap();
+
+        assert!(matches!(
+            execute(&mut args(&["GROUP", "g", "c", "STREAMS", "s", ">"]), &mut ks),
+            Err(CommandError::Other(e)) if e.starts_with("NOGROUP")
+        ));
+    }
+}
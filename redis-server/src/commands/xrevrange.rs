@@ -0,0 +1,9 @@
+use crate::commands::{xrange, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XREVRANGE key end start [COUNT count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    xrange::generic(args, ks, true, "xrevrange")
+}
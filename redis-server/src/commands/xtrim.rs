@@ -0,0 +1,139 @@
+use crate::commands::{get_stream, parse_i64, parse_stream_id, CommandError, Result};
+use crate::db::{Keyspace, Stream, StreamId};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// XTRIM key MAXLEN | MINID [= | ~] threshold [LIMIT count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let strategy = args.pop_front().unwrap();
+    let trim = Trim::parse(&strategy, args)?;
+    if !args.is_empty() {
+        return Err(CommandError::Syntax);
+    }
+
+    let removed = get_stream(ks, &key)?.map_or(0, |stream| trim.apply(stream));
+    Ok(Frame::Integer(removed as i64))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    MaxLen(usize),
+    MinId(StreamId),
+}
+
+/// How to trim a stream, as given to XADD and XTRIM.
+///
+/// An approximate trim, with `~`, is done exactly here. That is allowed, since it only promises
+/// to keep at least the threshold. A LIMIT caps how many entries it deletes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trim {
+    pub threshold: Threshold,
+    pub limit: Option<usize>,
+}
+
+impl Trim {
+    /// Parses `[= | ~] threshold [LIMIT count]` following the `strategy`, MAXLEN or MINID.
+    pub fn parse(strategy: &[u8], args: &mut VecDeque<Vec<u8>>) -> Result<Trim> {
+        let max_len = match strategy.to_ascii_uppercase().as_slice() {
+            b"MAXLEN" => true,
+            b"MINID" => false,
+            _ => return Err(CommandError::Syntax),
+        };
+
+        let approximate = match args.front().map(Vec::as_slice) {
+            Some(b"~") => {
+                args.pop_front();
+                true
+            }
+            Some(b"=") => {
+                args.pop_front();
+                false
+            }
+            _ => false,
+        };
+
+        let threshold = args.pop_front().ok_or(CommandError::Syntax)?;
+        let threshold = if max_len {
+            match parse_i64(&threshold)? {
+                len if len < 0 => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR The MAXLEN argument must be >= 0.",
+                    )))
+                }
+                len => Threshold::MaxLen(len as usize),
+            }
+        } else {
+            Threshold::MinId(parse_stream_id(&threshold, 0)?)
+        };
+
+        let mut limit = None;
+        if args
+            .front()
+            .is_some_and(|arg| arg.eq_ignore_ascii_case(b"LIMIT"))
+        {
+            args.pop_front();
+            let count = args.pop_front().ok_or(CommandError::Syntax)?;
+            match parse_i64(&count)? {
+                count if count < 0 => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR The LIMIT argument must be >= 0.",
+                    )))
+                }
+                _ if !approximate => {
+                    return Err(CommandError::Other(String::from(
+                        "ERR syntax error, LIMIT cannot be used without the special ~ option",
+                    )))
+                }
+                // As in Redis, a limit of 0 means no limit.
+                0 => {}
+                count => limit = Some(count as usize),
+            }
+        }
+
+        Ok(Trim { threshold, limit })
+    }
+
+    /// Trims `stream`, returning how many entries were deleted.
+    pub fn apply(&self, stream: &mut Stream) -> usize {
+        match self.threshold {
+            Threshold::MaxLen(len) => stream.trim_to_len(len, self.limit),
+            Threshold::MinId(id) => stream.trim_to_min_id(id, self.limit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, xadd};
+
+    #[test]
+    fn test_xtrim() {
+        let mut ks = Keyspace::new();
+        for id in ["1", "2", "3", "4", "5"] {
+            xadd::execute(&mut args(&["s", id, "f", "v"]), &mut ks).unwrap();
+        }
+
+        assert_eq!(
+            execute(&mut args(&["s", "MAXLEN", "=", "4"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "MINID", "~", "5", "LIMIT", "2"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "MINID", "5", "LIMIT", "2"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR syntax error, LIMIT cannot be used without the special ~ option"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["s", "MAXLEN", "-1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR The MAXLEN argument must be >= 0."
+            )))
+        );
+    }
+}
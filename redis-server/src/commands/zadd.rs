@@ -0,0 +1,196 @@
+use crate::commands::{get_zset, parse_score, CommandError, Result};
+use crate::db::{Keyspace, SortedSet, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZADD key [NX | XX] [GT | LT] [CH] [INCR] score member [score member ...]
+///
+/// Replies with how many members were added, or also changed with CH. With INCR it acts like
+/// ZINCRBY and replies with the new score, or nil if a flag stopped the update.
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+
+    let (mut nx, mut xx, mut gt, mut lt, mut ch, mut incr) =
+        (false, false, false, false, false, false);
+    while let Some(option) = args.front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"NX" => nx = true,
+            b"XX" => xx = true,
+            b"GT" => gt = true,
+            b"LT" => lt = true,
+            b"CH" => ch = true,
+            b"INCR" => incr = true,
+            _ => break,
+        }
+        args.pop_front();
+    }
+
+    if args.is_empty() || !args.len().is_multiple_of(2) {
+        return Err(CommandError::Syntax);
+    }
+    if nx && xx {
+        return Err(CommandError::Other(String::from(
+            "ERR XX and NX options at the same time are not compatible",
+        )));
+    }
+    if (nx && (gt || lt)) || (gt && lt) {
+        return Err(CommandError::Other(String::from(
+            "ERR GT, LT, and/or NX options at the same time are not compatible",
+        )));
+    }
+    if incr && args.len() > 2 {
+        return Err(CommandError::Other(String::from(
+            "ERR INCR option supports a single increment-element pair",
+        )));
+    }
+
+    let mut elements = Vec::with_capacity(args.len() / 2);
+    while let (Some(score), Some(member)) = (args.pop_front(), args.pop_front()) {
+        elements.push((parse_score(&score)?, member));
+    }
+
+    if get_zset(ks, &key)?.is_none() && xx {
+        return Ok(if incr {
+            Frame::NullBulkString
+        } else {
+            Frame::Integer(0)
+        });
+    }
+
+    let zset = create_zset(ks, key.clone())?;
+    let (mut added, mut changed) = (0, 0);
+    let mut result = None;
+    for (score, member) in elements {
+        match zset.score(&member) {
+            Some(current) => {
+                let score = if incr { current + score } else { score };
+                if score.is_nan() {
+                    return Err(CommandError::Other(String::from(
+                        "ERR resulting score is not a number (NaN)",
+                    )));
+                }
+                if nx || (gt && score <= current) || (lt && score >= current) {
+                    continue;
+                }
+                if score != current {
+                    zset.insert(member, score);
+                    changed += 1;
+                }
+                result = Some(score);
+            }
+            None if xx => continue,
+            None => {
+                zset.insert(member, score);
+                added += 1;
+                result = Some(score);
+            }
+        }
+    }
+
+    if added > 0 {
+        ks.signal_ready(&key);
+    }
+
+    Ok(match (incr, result) {
+        (true, Some(score)) => Frame::Double(score),
+        (true, None) => Frame::NullBulkString,
+        (false, _) if ch => Frame::Integer(added + changed),
+        (false, _) => Frame::Integer(added),
+    })
+}
+
+/// The sorted set at `key`, which is created empty if there isn't one.
+pub fn create_zset(ks: &mut Keyspace, key: Vec<u8>) -> Result<&mut SortedSet> {
+    if get_zset(ks, &key)?.is_none() {
+        ks.set(key.clone(), Value::SortedSet(SortedSet::new()));
+    }
+
+    Ok(get_zset(ks, &key)?.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zscore};
+
+    #[test]
+    fn test_zadd() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["z", "1", "a", "2", "b"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "CH", "5", "a", "3", "c"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "XX", "9", "d"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "GT", "CH", "1", "a", "7", "b"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            zscore::execute(&mut args(&["z", "a"]), &mut ks),
+            Ok(Frame::Double(5.0))
+        );
+        assert_eq!(
+            zscore::execute(&mut args(&["z", "b"]), &mut ks),
+            Ok(Frame::Double(7.0))
+        );
+    }
+
+    #[test]
+    fn test_zadd_incr() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["z", "INCR", "2.5", "a"]), &mut ks),
+            Ok(Frame::Double(2.5))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "INCR", "1", "a"]), &mut ks),
+            Ok(Frame::Double(3.5))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "NX", "INCR", "1", "a"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "INCR", "1", "a", "1", "b"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR INCR option supports a single increment-element pair"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_zadd_bad_arguments() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["z", "1", "a", "2"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "x", "a"]), &mut ks),
+            Err(CommandError::NotAFloat)
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "NX", "XX", "1", "a"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR XX and NX options at the same time are not compatible"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "GT", "LT", "1", "a"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR GT, LT, and/or NX options at the same time are not compatible"
+            )))
+        );
+        assert!(!ks.contains_key(b"z"));
+    }
+}
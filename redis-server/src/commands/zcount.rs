@@ -0,0 +1,48 @@
+use crate::commands::{get_zset, Result, ScoreRange};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZCOUNT key min max
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let range = ScoreRange::parse(&args[0], &args[1])?;
+
+    let count = get_zset(ks, &key)?.map_or(0, |zset| range.ranks(zset).len());
+    Ok(Frame::Integer(count as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zadd, CommandError};
+
+    #[test]
+    fn test_zcount() {
+        let mut ks = Keyspace::new();
+        zadd::execute(&mut args(&["z", "1", "a", "2", "b", "3", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["z", "-inf", "+inf"]), &mut ks),
+            Ok(Frame::Integer(3))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "(1", "3"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "(1", "(2"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "3", "1"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "x", "1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR min or max is not a float"
+            )))
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use crate::commands::{parse_score, zadd, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZINCRBY key increment member
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let increment = parse_score(&args.pop_front().unwrap())?;
+    let member = args.pop_front().unwrap();
+
+    let zset = zadd::create_zset(ks, key.clone())?;
+    let score = zset.score(&member).unwrap_or(0.0) + increment;
+    if score.is_nan() {
+        return Err(CommandError::Other(String::from(
+            "ERR resulting score is not a number (NaN)",
+        )));
+    }
+
+    if zset.insert(member, score) {
+        ks.signal_ready(&key);
+    }
+    Ok(Frame::Double(score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args;
+
+    #[test]
+    fn test_zincrby() {
+        let mut ks = Keyspace::new();
+
+        assert_eq!(
+            execute(&mut args(&["z", "2", "a"]), &mut ks),
+            Ok(Frame::Double(2.0))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "-0.5", "a"]), &mut ks),
+            Ok(Frame::Double(1.5))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "inf", "a"]), &mut ks),
+            Ok(Frame::Double(f64::INFINITY))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "-inf", "a"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR resulting score is not a number (NaN)"
+            )))
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use crate::commands::{get_zset, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZMSCORE key member [member ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let zset = get_zset(ks, &key)?;
+
+    let scores = args
+        .iter()
+        .map(
+            |member| match zset.as_ref().and_then(|zset| zset.score(member)) {
+                Some(score) => Frame::Double(score),
+                None => Frame::Null,
+            },
+        )
+        .collect();
+
+    Ok(Frame::Array(scores))
+}
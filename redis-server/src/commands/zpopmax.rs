@@ -0,0 +1,9 @@
+use crate::commands::{zpopmin, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZPOPMAX key [count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    zpopmin::generic(args, ks, true, "zpopmax")
+}
@@ -0,0 +1,113 @@
+use crate::commands::{get_zset, parse_i64, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZPOPMIN key [count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, false, "zpopmin")
+}
+
+/// Without a count the reply is the member and its score, with one it is a list of such pairs.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    max: bool,
+    name: &'static str,
+) -> Result<Frame> {
+    if args.len() > 2 {
+        return Err(CommandError::WrongArity(name));
+    }
+
+    let key = args.pop_front().unwrap();
+    let count = match args.pop_front() {
+        Some(count) => match parse_i64(&count) {
+            Ok(count) if count >= 0 => Some(count as usize),
+            _ => {
+                return Err(CommandError::Other(String::from(
+                    "ERR value is out of range, must be positive",
+                )))
+            }
+        },
+        None => None,
+    };
+
+    let popped = pop(ks, &key, max, count.unwrap_or(1))?.unwrap_or_default();
+    Ok(match count {
+        Some(_) => Frame::Pairs(
+            popped
+                .into_iter()
+                .map(|(member, score)| (Frame::bulk(member), Frame::Double(score)))
+                .collect(),
+        ),
+        None => Frame::Array(
+            popped
+                .into_iter()
+                .flat_map(|(member, score)| [Frame::bulk(member), Frame::Double(score)])
+                .collect(),
+        ),
+    })
+}
+
+/// Members and their scores, in the order they were popped.
+pub type Popped = Vec<(Vec<u8>, f64)>;
+
+/// Pops up to `count` of the lowest or highest scoring members, deleting the key once the set
+/// is empty. Returns `None` if there is no sorted set.
+pub fn pop(ks: &mut Keyspace, key: &[u8], max: bool, count: usize) -> Result<Option<Popped>> {
+    let zset = match get_zset(ks, key)? {
+        Some(zset) => zset,
+        None => return Ok(None),
+    };
+
+    let count = count.min(zset.len());
+    let popped: Popped = if max {
+        zset.iter_rev()
+            .take(count)
+            .map(|(member, score)| (member.to_vec(), score))
+            .collect()
+    } else {
+        zset.iter()
+            .take(count)
+            .map(|(member, score)| (member.to_vec(), score))
+            .collect()
+    };
+    for (member, _) in &popped {
+        zset.remove(member);
+    }
+
+    if zset.is_empty() {
+        ks.remove(key);
+    }
+
+    Ok(Some(popped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zadd};
+
+    #[test]
+    fn test_zpopmin_and_zpopmax() {
+        let mut ks = Keyspace::new();
+        zadd::execute(&mut args(&["z", "1", "a", "2", "b", "3", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["z"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::bulk("a"), Frame::Double(1.0)]))
+        );
+        assert_eq!(
+            generic(&mut args(&["z", "5"]), &mut ks, true, "zpopmax"),
+            Ok(Frame::Pairs(vec![
+                (Frame::bulk("c"), Frame::Double(3.0)),
+                (Frame::bulk("b"), Frame::Double(2.0)),
+            ]))
+        );
+        assert!(!ks.contains_key(b"z"));
+        assert_eq!(
+            execute(&mut args(&["z"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+    }
+}
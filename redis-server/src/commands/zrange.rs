@@ -0,0 +1,226 @@
+use crate::commands::{
+    clamp_range, get_zset, lex_ranks, parse_i64, CommandError, LexBound, Result, ScoreRange,
+};
+use crate::db::{Keyspace, SortedSet};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZRANGE key start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let query = Query::parse(args)?;
+
+    let elements = match get_zset(ks, &key)? {
+        Some(zset) => query.select(zset),
+        None => Vec::new(),
+    };
+
+    Ok(if query.with_scores {
+        Frame::Pairs(
+            elements
+                .into_iter()
+                .map(|(member, score)| (Frame::bulk(member), Frame::Double(score)))
+                .collect(),
+        )
+    } else {
+        Frame::bulks(elements.into_iter().map(|(member, _)| member))
+    })
+}
+
+/// What to pick the elements by.
+#[derive(Debug, Clone, PartialEq)]
+pub enum By {
+    Rank(i64, i64),
+    Score(ScoreRange),
+    Lex(LexBound, LexBound),
+}
+
+/// The `start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]` shared by ZRANGE
+/// and ZRANGESTORE.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub by: By,
+    pub rev: bool,
+    pub limit: Option<(i64, i64)>,
+    pub with_scores: bool,
+}
+
+impl Query {
+    pub fn parse(args: &mut VecDeque<Vec<u8>>) -> Result<Query> {
+        let start = args.pop_front().unwrap();
+        let stop = args.pop_front().unwrap();
+
+        let (mut by_score, mut by_lex, mut rev, mut limit, mut with_scores) =
+            (false, false, false, None, false);
+        while let Some(option) = args.pop_front() {
+            match option.to_ascii_uppercase().as_slice() {
+                b"BYSCORE" => by_score = true,
+                b"BYLEX" => by_lex = true,
+                b"REV" => rev = true,
+                b"WITHSCORES" => with_scores = true,
+                b"LIMIT" if args.len() >= 2 => {
+                    let offset = parse_i64(&args.pop_front().unwrap())?;
+                    let count = parse_i64(&args.pop_front().unwrap())?;
+                    limit = Some((offset, count));
+                }
+                _ => return Err(CommandError::Syntax),
+            }
+        }
+
+        if by_score && by_lex {
+            return Err(CommandError::Syntax);
+        }
+        if limit.is_some() && !by_score && !by_lex {
+            return Err(CommandError::Other(String::from(
+                "ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX",
+            )));
+        }
+        if with_scores && by_lex {
+            return Err(CommandError::Other(String::from(
+                "ERR syntax error, WITHSCORES not supported in combination with BYLEX",
+            )));
+        }
+
+        // Reversed score and lex ranges are given from the top, max first.
+        let (min, max) = if rev {
+            (&stop, &start)
+        } else {
+            (&start, &stop)
+        };
+        let by = if by_score {
+            By::Score(ScoreRange::parse(min, max)?)
+        } else if by_lex {
+            By::Lex(LexBound::parse(min)?, LexBound::parse(max)?)
+        } else {
+            By::Rank(parse_i64(&start)?, parse_i64(&stop)?)
+        };
+
+        Ok(Query {
+            by,
+            rev,
+            limit,
+            with_scores,
+        })
+    }
+
+    /// The members and scores picked from `zset`, in the order they are to be replied with.
+    pub fn select(&self, zset: &SortedSet) -> Vec<(Vec<u8>, f64)> {
+        let len = zset.len();
+        let ranks = match &self.by {
+            By::Rank(start, stop) => match clamp_range(*start, *stop, len) {
+                Some((start, stop)) if self.rev => (len - 1 - stop)..(len - start),
+                Some((start, stop)) => start..stop + 1,
+                None => return Vec::new(),
+            },
+            By::Score(range) => range.ranks(zset),
+            By::Lex(min, max) => lex_ranks(zset, min, max),
+        };
+
+        let (offset, count) = self.limit.unwrap_or((0, -1));
+        if offset < 0 || offset as usize >= ranks.len() {
+            return Vec::new();
+        }
+        let offset = offset as usize;
+        let available = ranks.len() - offset;
+        let count = if count < 0 {
+            available
+        } else {
+            available.min(count as usize)
+        };
+
+        let elements = if self.rev {
+            zset.iter_rev_from(ranks.end - 1 - offset)
+        } else {
+            zset.iter_from(ranks.start + offset)
+        };
+        elements
+            .take(count)
+            .map(|(member, score)| (member.to_vec(), score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zadd};
+
+    fn setup() -> Keyspace {
+        let mut ks = Keyspace::new();
+        zadd::execute(
+            &mut args(&["z", "1", "a", "2", "b", "3", "c", "4", "d"]),
+            &mut ks,
+        )
+        .unwrap();
+        ks
+    }
+
+    #[test]
+    fn test_zrange_by_rank() {
+        let mut ks = setup();
+
+        assert_eq!(
+            execute(&mut args(&["z", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["a", "b", "c", "d"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "0", "1", "REV", "WITHSCORES"]), &mut ks),
+            Ok(Frame::Pairs(vec![
+                (Frame::bulk("d"), Frame::Double(4.0)),
+                (Frame::bulk("c"), Frame::Double(3.0)),
+            ]))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "5", "10"]), &mut ks),
+            Ok(Frame::Array(vec![]))
+        );
+    }
+
+    #[test]
+    fn test_zrange_by_score() {
+        let mut ks = setup();
+
+        assert_eq!(
+            execute(&mut args(&["z", "(1", "3", "BYSCORE"]), &mut ks),
+            Ok(Frame::bulks(["b", "c"]))
+        );
+        assert_eq!(
+            execute(
+                &mut args(&["z", "+inf", "-inf", "BYSCORE", "REV", "LIMIT", "1", "2"]),
+                &mut ks
+            ),
+            Ok(Frame::bulks(["c", "b"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "0", "1", "LIMIT", "0", "1"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_zrange_by_lex() {
+        let mut ks = Keyspace::new();
+        zadd::execute(
+            &mut args(&["z", "0", "apple", "0", "banana", "0", "cherry"]),
+            &mut ks,
+        )
+        .unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["z", "[b", "+", "BYLEX"]), &mut ks),
+            Ok(Frame::bulks(["banana", "cherry"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "(banana", "-", "BYLEX", "REV"]), &mut ks),
+            Ok(Frame::bulks(["apple"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "b", "+", "BYLEX"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR min or max not valid string range item"
+            )))
+        );
+    }
+}
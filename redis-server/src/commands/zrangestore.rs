@@ -0,0 +1,63 @@
+use crate::commands::{get_zset, zrange::Query, CommandError, Result};
+use crate::db::{Keyspace, SortedSet, Value};
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZRANGESTORE destination source min max [BYSCORE | BYLEX] [REV] [LIMIT offset count]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let destination = args.pop_front().unwrap();
+    let source = args.pop_front().unwrap();
+    let query = Query::parse(args)?;
+    if query.with_scores {
+        return Err(CommandError::Syntax);
+    }
+
+    let mut zset = SortedSet::new();
+    if let Some(source) = get_zset(ks, &source)? {
+        for (member, score) in query.select(source) {
+            zset.insert(member, score);
+        }
+    }
+
+    Ok(store(ks, destination, zset))
+}
+
+/// Replaces whatever is at `destination` with `zset`, replying with its size. An empty result
+/// deletes the destination instead.
+pub fn store(ks: &mut Keyspace, destination: Vec<u8>, zset: SortedSet) -> Frame {
+    let len = zset.len();
+    if zset.is_empty() {
+        ks.remove(&destination);
+    } else {
+        ks.set(destination.clone(), Value::SortedSet(zset));
+        ks.signal_ready(&destination);
+    }
+
+    Frame::Integer(len as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zadd, zrange};
+
+    #[test]
+    fn test_zrangestore() {
+        let mut ks = Keyspace::new();
+        zadd::execute(&mut args(&["z", "1", "a", "2", "b", "3", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["top", "z", "0", "1", "REV"]), &mut ks),
+            Ok(Frame::Integer(2))
+        );
+        assert_eq!(
+            zrange::execute(&mut args(&["top", "0", "-1"]), &mut ks),
+            Ok(Frame::bulks(["b", "c"]))
+        );
+        assert_eq!(
+            execute(&mut args(&["top", "z", "10", "20", "BYSCORE"]), &mut ks),
+            Ok(Frame::Integer(0))
+        );
+        assert!(!ks.contains_key(b"top"));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::commands::{get_zset, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZRANK key member [WITHSCORE]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, false, "zrank")
+}
+
+/// Replies with the member's rank, counting from the lowest score or, reversed, the highest.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    rev: bool,
+    name: &'static str,
+) -> Result<Frame> {
+    if args.len() > 3 {
+        return Err(CommandError::WrongArity(name));
+    }
+
+    let key = args.pop_front().unwrap();
+    let member = args.pop_front().unwrap();
+    let with_score = match args.pop_front() {
+        Some(option) if option.eq_ignore_ascii_case(b"WITHSCORE") => true,
+        Some(_) => return Err(CommandError::Syntax),
+        None => false,
+    };
+
+    let found = get_zset(ks, &key)?.and_then(|zset| {
+        let rank = zset.rank(&member)?;
+        let rank = if rev { zset.len() - 1 - rank } else { rank };
+        Some((rank as i64, zset.score(&member).unwrap()))
+    });
+
+    Ok(match (found, with_score) {
+        (Some((rank, score)), true) => {
+            Frame::Array(vec![Frame::Integer(rank), Frame::Double(score)])
+        }
+        (Some((rank, _)), false) => Frame::Integer(rank),
+        (None, true) => Frame::NullArray,
+        (None, false) => Frame::NullBulkString,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zadd};
+
+    #[test]
+    fn test_zrank() {
+        let mut ks = Keyspace::new();
+        zadd::execute(&mut args(&["z", "10", "a", "20", "b", "30", "c"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["z", "b"]), &mut ks),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            generic(
+                &mut args(&["z", "a", "WITHSCORE"]),
+                &mut ks,
+                true,
+                "zrevrank"
+            ),
+            Ok(Frame::Array(vec![Frame::Integer(2), Frame::Double(10.0)]))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "x"]), &mut ks),
+            Ok(Frame::NullBulkString)
+        );
+    }
+}
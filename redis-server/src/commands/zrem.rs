@@ -0,0 +1,24 @@
+use crate::commands::{get_zset, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZREM key member [member ...]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let zset = match get_zset(ks, &key)? {
+        Some(zset) => zset,
+        None => return Ok(Frame::Integer(0)),
+    };
+
+    let removed = args
+        .iter()
+        .filter(|member| zset.remove(member).is_some())
+        .count();
+
+    if zset.is_empty() {
+        ks.remove(&key);
+    }
+
+    Ok(Frame::Integer(removed as i64))
+}
@@ -0,0 +1,9 @@
+use crate::commands::{zrank, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZREVRANK key member [WITHSCORE]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    zrank::generic(args, ks, true, "zrevrank")
+}
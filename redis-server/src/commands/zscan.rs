@@ -0,0 +1,56 @@
+use crate::commands::{get_zset, Result, ScanArgs};
+use crate::db::Keyspace;
+use crate::resp::types::{format_double, Frame};
+use std::collections::VecDeque;
+
+/// ZSCAN key cursor [MATCH pattern] [COUNT count] [NOSCORES]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let scan = ScanArgs::parse(args, Some(b"NOSCORES"))?;
+
+    let (cursor, batch) = match get_zset(ks, &key)? {
+        Some(zset) => scan.scan(zset.slots(), |i| {
+            let (member, score) = zset.slot(i)?;
+            Some((member, (member, score)))
+        }),
+        None => (0, Vec::new()),
+    };
+
+    // Scores go out as bulk strings, whatever the protocol, as Redis does for scans.
+    let mut items = Vec::new();
+    for (member, score) in batch {
+        items.push(Frame::bulk(member));
+        if !scan.flag {
+            items.push(Frame::bulk(format_double(score)));
+        }
+    }
+
+    Ok(Frame::Array(vec![
+        Frame::bulk(cursor.to_string()),
+        Frame::Array(items),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, zadd};
+
+    #[test]
+    fn test_zscan() {
+        let mut ks = Keyspace::new();
+        zadd::execute(&mut args(&["z", "1.5", "a"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(&mut args(&["z", "0"]), &mut ks),
+            Ok(Frame::Array(vec![
+                Frame::bulk("0"),
+                Frame::bulks(["a", "1.5"]),
+            ]))
+        );
+        assert_eq!(
+            execute(&mut args(&["z", "0", "NOSCORES"]), &mut ks),
+            Ok(Frame::Array(vec![Frame::bulk("0"), Frame::bulks(["a"])]))
+        );
+    }
+}
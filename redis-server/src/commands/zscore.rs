@@ -0,0 +1,13 @@
+use crate::commands::{get_zset, Result};
+use crate::db::Keyspace;
+use crate::resp::types::Frame;
+use std::collections::VecDeque;
+
+/// ZSCORE key member
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    let key = args.pop_front().unwrap();
+    let member = args.pop_front().unwrap();
+
+    let score = get_zset(ks, &key)?.and_then(|zset| zset.score(&member));
+    Ok(score.map(Frame::Double).unwrap_or(Frame::NullBulkString))
+}
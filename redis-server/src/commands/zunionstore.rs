@@ -0,0 +1,187 @@
+use crate::commands::{parse_i64, parse_score, zrangestore, CommandError, Result};
+use crate::db::{Keyspace, SortedSet, Value};
+use crate::resp::types::Frame;
+use std::collections::{HashMap, VecDeque};
+
+/// ZUNIONSTORE destination numkeys key [key ...] [WEIGHTS weight [weight ...]]
+/// [AGGREGATE SUM | MIN | MAX]
+pub fn execute(args: &mut VecDeque<Vec<u8>>, ks: &mut Keyspace) -> Result<Frame> {
+    generic(args, ks, false, "zunionstore")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Aggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn combine(self, a: f64, b: f64) -> f64 {
+        match self {
+            // Adding opposite infinities is taken to cancel out, as Redis does.
+            Aggregate::Sum => zero_if_nan(a + b),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+/// Stores the union or intersection of the input keys, which may hold sets as well as sorted
+/// sets, where a set's members count as having a score of 1. Each input's scores are first
+/// multiplied by its weight.
+pub fn generic(
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+    inter: bool,
+    name: &'static str,
+) -> Result<Frame> {
+    let destination = args.pop_front().unwrap();
+    let numkeys = parse_i64(&args.pop_front().unwrap())?;
+    if numkeys < 1 {
+        return Err(CommandError::Other(format!(
+            "ERR at least 1 input key is needed for '{}' command",
+            name
+        )));
+    }
+    let numkeys = numkeys as usize;
+    if numkeys > args.len() {
+        return Err(CommandError::Syntax);
+    }
+
+    let keys: Vec<Vec<u8>> = args.drain(..numkeys).collect();
+    let mut weights = vec![1.0; numkeys];
+    let mut aggregate = Aggregate::Sum;
+    while let Some(option) = args.pop_front() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"WEIGHTS" if args.len() >= numkeys => {
+                for weight in weights.iter_mut() {
+                    *weight = parse_score(&args.pop_front().unwrap()).map_err(|_| {
+                        CommandError::Other(String::from("ERR weight value is not a float"))
+                    })?;
+                }
+            }
+            b"AGGREGATE" if !args.is_empty() => {
+                aggregate = match args.pop_front().unwrap().to_ascii_uppercase().as_slice() {
+                    b"SUM" => Aggregate::Sum,
+                    b"MIN" => Aggregate::Min,
+                    b"MAX" => Aggregate::Max,
+                    _ => return Err(CommandError::Syntax),
+                }
+            }
+            _ => return Err(CommandError::Syntax),
+        }
+    }
+
+    let inputs = keys
+        .iter()
+        .zip(&weights)
+        .map(|(key, weight)| read(ks, key, *weight))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut zset = SortedSet::new();
+    if inter {
+        let (first, others) = inputs.split_first().unwrap();
+        let others: Vec<HashMap<&[u8], f64>> = others
+            .iter()
+            .map(|input| input.iter().map(|(m, s)| (m.as_slice(), *s)).collect())
+            .collect();
+
+        'members: for (member, score) in first {
+            let mut score = *score;
+            for other in &others {
+                match other.get(member.as_slice()) {
+                    Some(other) => score = aggregate.combine(score, *other),
+                    None => continue 'members,
+                }
+            }
+            zset.insert(member.clone(), score);
+        }
+    } else {
+        for (member, score) in inputs.into_iter().flatten() {
+            let score = match zset.score(&member) {
+                Some(current) => aggregate.combine(current, score),
+                None => score,
+            };
+            zset.insert(member, score);
+        }
+    }
+
+    Ok(zrangestore::store(ks, destination, zset))
+}
+
+/// The members and weighted scores of the set or sorted set at `key`.
+fn read(ks: &mut Keyspace, key: &[u8], weight: f64) -> Result<Vec<(Vec<u8>, f64)>> {
+    Ok(match ks.get(key) {
+        Some(Value::SortedSet(zset)) => zset
+            .iter()
+            .map(|(member, score)| (member.to_vec(), zero_if_nan(score * weight)))
+            .collect(),
+        Some(Value::Set(set)) => set.iter().map(|member| (member.clone(), weight)).collect(),
+        Some(_) => return Err(CommandError::WrongType),
+        None => Vec::new(),
+    })
+}
+
+fn zero_if_nan(score: f64) -> f64 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{args, sadd, zadd, zrange};
+
+    #[test]
+    fn test_zunionstore_and_zinterstore() {
+        let mut ks = Keyspace::new();
+        zadd::execute(&mut args(&["a", "1", "x", "2", "y"]), &mut ks).unwrap();
+        zadd::execute(&mut args(&["b", "10", "y", "20", "z"]), &mut ks).unwrap();
+        sadd::execute(&mut args(&["s", "y"]), &mut ks).unwrap();
+
+        assert_eq!(
+            execute(
+                &mut args(&["out", "2", "a", "b", "WEIGHTS", "2", "1"]),
+                &mut ks
+            ),
+            Ok(Frame::Integer(3))
+        );
+        assert_eq!(
+            zrange::execute(&mut args(&["out", "0", "-1", "WITHSCORES"]), &mut ks),
+            Ok(Frame::Pairs(vec![
+                (Frame::bulk("x"), Frame::Double(2.0)),
+                (Frame::bulk("y"), Frame::Double(14.0)),
+                (Frame::bulk("z"), Frame::Double(20.0)),
+            ]))
+        );
+
+        assert_eq!(
+            generic(
+                &mut args(&["out", "3", "a", "b", "s", "AGGREGATE", "MAX"]),
+                &mut ks,
+                true,
+                "zinterstore"
+            ),
+            Ok(Frame::Integer(1))
+        );
+        assert_eq!(
+            zrange::execute(&mut args(&["out", "0", "-1", "WITHSCORES"]), &mut ks),
+            Ok(Frame::Pairs(vec![(Frame::bulk("y"), Frame::Double(10.0))]))
+        );
+
+        assert_eq!(
+            execute(&mut args(&["out", "0", "a"]), &mut ks),
+            Err(CommandError::Other(String::from(
+                "ERR at least 1 input key is needed for 'zunionstore' command"
+            )))
+        );
+        assert_eq!(
+            execute(&mut args(&["out", "2", "a"]), &mut ks),
+            Err(CommandError::Syntax)
+        );
+    }
+}
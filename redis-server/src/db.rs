@@ -0,0 +1,588 @@
+mod hash;
+mod stream;
+mod zset;
+
+pub use hash::Hash;
+pub use stream::{Consumer, ConsumerGroup, Fields, PendingEntry, Stream, StreamId};
+pub use zset::SortedSet;
+
+use crate::blocking::{Blocked, BlockedClients, Serve};
+use crate::persistence::{self, Persistence};
+use crate::pubsub::Broker;
+use crate::rdb::Snapshot;
+use crate::resp::types::Frame;
+use crate::scripting::{ScriptStatus, Scripting};
+use crate::watched::WatchedKeys;
+
+use indexmap::{IndexMap, IndexSet};
+use rand::Rng;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How often the background thread looks for expired keys.
+const ACTIVE_EXPIRE_INTERVAL: Duration = Duration::from_millis(100);
+/// How many volatile keys are checked per round.
+const ACTIVE_EXPIRE_SAMPLES: usize = 20;
+/// The longest one pass may keep going while it keeps finding expired keys.
+const ACTIVE_EXPIRE_TIME_LIMIT: Duration = Duration::from_millis(25);
+/// How often a client waiting on a running script checks whether it has become busy.
+const SCRIPT_POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// How often the background thread checks whether the dataset is due to be saved.
+const SAVE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A value stored under a key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(Vec<u8>),
+    List(VecDeque<Vec<u8>>),
+    Hash(Hash),
+    Set(IndexSet<Vec<u8>>),
+    SortedSet(SortedSet),
+    Stream(Stream),
+}
+
+/// Milliseconds since the Unix epoch, which is how expiry times are kept.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The keys and values themselves, along with when each volatile key expires.
+///
+/// A key whose expiry time has passed is removed the next time anything looks it up, so callers
+/// never see expired keys. Keys that are never looked up again are found by sampling, which is
+/// why the expiry times are kept in an `IndexMap`: it can pick a random entry cheaply.
+#[derive(Default)]
+pub struct Keyspace {
+    entries: HashMap<Vec<u8>, Value>,
+    expires: IndexMap<Vec<u8>, u64>,
+    blocked: BlockedClients,
+    pubsub: Broker,
+    /// Shared so that a script can run while it borrows the keyspace.
+    scripting: Arc<Mutex<Scripting>>,
+    persistence: Persistence,
+    watched: WatchedKeys,
+}
+
+impl Keyspace {
+    pub fn new() -> Keyspace {
+        Keyspace::default()
+    }
+
+    pub fn get(&mut self, key: &[u8]) -> Option<&Value> {
+        self.expire_if_needed(key);
+        self.entries.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut Value> {
+        self.expire_if_needed(key);
+        self.entries.get_mut(key)
+    }
+
+    pub fn contains_key(&mut self, key: &[u8]) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Stores `value` under `key`, replacing whatever was there along with its expiry time.
+    pub fn set(&mut self, key: Vec<u8>, value: Value) {
+        self.touch(&key);
+        self.expires.swap_remove(&key);
+        self.entries.insert(key, value);
+    }
+
+    /// Stores `value` under `key` but leaves any expiry time on the key in place.
+    pub fn set_keep_ttl(&mut self, key: Vec<u8>, value: Value) {
+        self.expire_if_needed(&key);
+        self.touch(&key);
+        self.entries.insert(key, value);
+    }
+
+    pub fn remove(&mut self, key: &[u8]) -> Option<Value> {
+        self.expire_if_needed(key);
+        self.expires.swap_remove(key);
+        let removed = self.entries.remove(key);
+        if removed.is_some() {
+            self.touch(key);
+        }
+        removed
+    }
+
+    /// When `key` expires, in milliseconds since the epoch, if it is volatile.
+    pub fn expire_time(&mut self, key: &[u8]) -> Option<u64> {
+        self.expire_if_needed(key);
+        self.expires.get(key).copied()
+    }
+
+    /// Sets when an existing key expires. Returns false if there is no such key.
+    pub fn set_expire_time(&mut self, key: &[u8], when: u64) -> bool {
+        if !self.contains_key(key) {
+            return false;
+        }
+
+        self.touch(key);
+        self.expires.insert(key.to_vec(), when);
+        true
+    }
+
+    /// Every key with its value and expiry time, including keys that have expired but are yet
+    /// to be removed.
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<u8>, &Value, Option<u64>)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key, value, self.expires.get(key).copied()))
+    }
+
+    /// Removes every key.
+    pub fn flush(&mut self) {
+        let existing: Vec<Vec<u8>> = self
+            .watched
+            .all_keys()
+            .filter(|key| self.entries.contains_key(*key))
+            .cloned()
+            .collect();
+        for key in existing {
+            self.touch(&key);
+        }
+        self.entries.clear();
+        self.expires.clear();
+    }
+
+    /// Makes a key persistent again. Returns false if it had no expiry time to remove.
+    pub fn persist(&mut self, key: &[u8]) -> bool {
+        self.expire_if_needed(key);
+        let persisted = self.expires.swap_remove(key).is_some();
+        if persisted {
+            self.touch(key);
+        }
+        persisted
+    }
+
+    /// Checks up to `samples` random volatile keys and removes the ones that have expired.
+    /// Returns how many keys were checked and how many of them were removed.
+    pub fn expire_sample(&mut self, samples: usize) -> (usize, usize) {
+        let mut rng = rand::thread_rng();
+        let now = now_ms();
+        let checked = samples.min(self.expires.len());
+        let mut expired = 0;
+
+        for _ in 0..checked {
+            if self.expires.is_empty() {
+                break;
+            }
+
+            let index = rng.gen_range(0..self.expires.len());
+            if self.expires[index] <= now {
+                let (key, _) = self.expires.swap_remove_index(index).unwrap();
+                self.entries.remove(&key);
+                self.touch(&key);
+                expired += 1;
+            }
+        }
+
+        (checked, expired)
+    }
+
+    /// Parks a client until `serve` succeeds for one of `keys`.
+    pub fn block(
+        &mut self,
+        keys: Vec<Vec<u8>>,
+        timeout: Option<Duration>,
+        timeout_reply: Frame,
+        serve: Serve,
+    ) -> Blocked {
+        self.blocked.block(keys, timeout, timeout_reply, serve)
+    }
+
+    /// Returns false if the client was served before it could be unblocked.
+    pub fn unblock(&mut self, id: u64) -> bool {
+        self.blocked.unblock(id)
+    }
+
+    /// Called whenever something is added to `key` that a blocked client might be waiting for.
+    pub fn signal_ready(&mut self, key: &[u8]) {
+        self.blocked.signal_ready(key);
+    }
+
+    /// The Pub/Sub channels, which live alongside the keys so that commands publishing
+    /// messages can run like any other.
+    pub fn pubsub(&mut self) -> &mut Broker {
+        &mut self.pubsub
+    }
+
+    pub fn scripting(&self) -> Arc<Mutex<Scripting>> {
+        Arc::clone(&self.scripting)
+    }
+
+    /// Watches `key` for the client's next transaction.
+    pub fn watch(&mut self, client: u64, key: Vec<u8>) {
+        self.expire_if_needed(&key);
+        self.watched.watch(client, key);
+    }
+
+    pub fn unwatch_all(&mut self, client: u64) {
+        self.watched.unwatch_all(client);
+    }
+
+    /// Whether any key the client watches has been touched since it watched it. Keys that have
+    /// expired meanwhile count, even if nothing has looked them up yet.
+    pub fn watched_key_touched(&mut self, client: u64) -> bool {
+        for key in self.watched.keys(client).to_vec() {
+            self.expire_if_needed(&key);
+        }
+        self.watched.is_touched(client)
+    }
+
+    /// Records that `key` was written to, which a transaction watching it has to know about.
+    /// Writes through `get_mut` have to be recorded this way; the other writes are already.
+    pub fn touch(&mut self, key: &[u8]) {
+        if !self.watched.is_empty() {
+            self.watched.touch(key);
+        }
+    }
+
+    /// Whether any client is watching keys at all, which is when writes need recording.
+    pub fn has_watched_keys(&self) -> bool {
+        !self.watched.is_empty()
+    }
+
+    pub fn is_watched(&self, key: &[u8]) -> bool {
+        self.watched.is_watched(key)
+    }
+
+    pub fn persistence(&mut self) -> &mut Persistence {
+        &mut self.persistence
+    }
+
+    /// Serves clients blocked on keys that were written to, longest waiting first, until the
+    /// keys run dry. Serving a client can write to other keys, so this goes on until no key is
+    /// left to look at.
+    pub fn serve_blocked(&mut self) {
+        while let Some(key) = self.blocked.next_ready() {
+            for id in self.blocked.waiting_on(&key) {
+                let mut waiter = self.blocked.take(id).unwrap();
+                if waiter.serve(self, &key) {
+                    self.touch(&key);
+                } else {
+                    self.blocked.restore(waiter);
+                }
+            }
+        }
+    }
+
+    /// Removes the key if it has expired. A hash also loses its expired fields, and the key
+    /// goes too if that leaves it empty.
+    fn expire_if_needed(&mut self, key: &[u8]) {
+        let now = now_ms();
+
+        if let Some(when) = self.expires.get(key) {
+            if *when <= now {
+                self.expires.swap_remove(key);
+                self.entries.remove(key);
+                self.touch(key);
+                return;
+            }
+        }
+
+        if let Some(Value::Hash(hash)) = self.entries.get_mut(key) {
+            let len = hash.len();
+            hash.remove_expired(now);
+            if hash.len() < len {
+                if hash.is_empty() {
+                    self.expires.swap_remove(key);
+                    self.entries.remove(key);
+                }
+                self.touch(key);
+            }
+        }
+    }
+}
+
+/// The keyspace shared by every client connection.
+///
+/// Cloning a `Db` is cheap and yields another handle to the same keyspace, which is how each
+/// connection thread gets access to it. A command locks the keyspace for as long as it runs, so
+/// commands never see each other's partial changes.
+#[derive(Clone)]
+pub struct Db {
+    keyspace: Arc<Mutex<Keyspace>>,
+    /// Whether a script is running, which can be checked while the script holds the keyspace.
+    scripts: Arc<ScriptStatus>,
+}
+
+impl Default for Db {
+    fn default() -> Db {
+        let keyspace = Keyspace::default();
+        let scripts = keyspace
+            .scripting
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .status();
+        Db {
+            keyspace: Arc::new(Mutex::new(keyspace)),
+            scripts,
+        }
+    }
+}
+
+impl Db {
+    pub fn new() -> Db {
+        Db::default()
+    }
+
+    pub fn scripts(&self) -> &ScriptStatus {
+        &self.scripts
+    }
+
+    /// Reads a string value.
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.lock().get(key) {
+            Some(Value::String(v)) => Some(v.clone()),
+            _ => None,
+        }
+    }
+
+    /// Stores a string value.
+    pub fn set(&self, key: Vec<u8>, value: Vec<u8>) {
+        self.lock().set(key, Value::String(value));
+    }
+
+    /// Removes expired keys the way Redis's active expire cycle does: sample some volatile keys,
+    /// drop the expired ones, and go again straight away while more than a quarter of the
+    /// sample had expired. The lock is released between rounds so clients aren't held up.
+    pub fn active_expire(&self) {
+        let start = Instant::now();
+
+        loop {
+            let (checked, expired) = self.lock().expire_sample(ACTIVE_EXPIRE_SAMPLES);
+            if checked == 0 || expired * 4 <= checked || start.elapsed() > ACTIVE_EXPIRE_TIME_LIMIT
+            {
+                return;
+            }
+        }
+    }
+
+    /// Starts a thread that runs `active_expire` in the background for as long as any handle
+    /// to this keyspace is still around.
+    pub fn spawn_active_expire(&self) -> io::Result<()> {
+        let keyspace = Arc::downgrade(&self.keyspace);
+        let scripts = Arc::clone(&self.scripts);
+
+        thread::Builder::new()
+            .name(String::from("active-expire"))
+            .spawn(move || {
+                while let Some(keyspace) = keyspace.upgrade() {
+                    let scripts = Arc::clone(&scripts);
+                    Db { keyspace, scripts }.active_expire();
+                    thread::sleep(ACTIVE_EXPIRE_INTERVAL);
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// Fills the keyspace from the RDB file the configuration points at. A missing file just
+    /// means there is nothing to load.
+    pub fn load(&self) -> io::Result<()> {
+        let mut ks = self.lock();
+        let bytes = match fs::read(ks.persistence().path()) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        persistence::restore(&mut ks, Snapshot::decode(&bytes)?)
+    }
+
+    /// Starts a thread that saves the dataset in the background whenever a save point is
+    /// reached or a save was scheduled, for as long as any handle to this keyspace is still
+    /// around.
+    pub fn spawn_save_scheduler(&self) -> io::Result<()> {
+        let keyspace = Arc::downgrade(&self.keyspace);
+
+        thread::Builder::new()
+            .name(String::from("save-scheduler"))
+            .spawn(move || {
+                while let Some(keyspace) = keyspace.upgrade() {
+                    {
+                        let ks = keyspace.lock().unwrap_or_else(PoisonError::into_inner);
+                        if ks.persistence.save_due() {
+                            let snapshot = persistence::snapshot(&ks);
+                            if let Err(e) = ks.persistence.background_save(snapshot) {
+                                eprintln!("Can't save in background: {}", e);
+                            }
+                        }
+                    }
+                    drop(keyspace);
+                    thread::sleep(SAVE_CHECK_INTERVAL);
+                }
+            })?;
+
+        Ok(())
+    }
+
+    /// A panic in one connection thread must not take the keyspace down with it, so a poisoned
+    /// lock is treated as usable.
+    pub fn lock(&self) -> KeyspaceGuard<'_> {
+        KeyspaceGuard(self.keyspace.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Locks the keyspace for a client's command. While a script holds it, the lock is tried
+    /// again every so often instead, giving up once the script has run for too long so the
+    /// client can be told the server is busy.
+    pub fn lock_unless_busy(&self) -> Option<KeyspaceGuard<'_>> {
+        loop {
+            if !self.scripts.is_running() {
+                return Some(self.lock());
+            }
+            match self.keyspace.try_lock() {
+                Ok(guard) => return Some(KeyspaceGuard(guard)),
+                Err(TryLockError::Poisoned(e)) => return Some(KeyspaceGuard(e.into_inner())),
+                Err(TryLockError::WouldBlock) if self.scripts.is_busy() => return None,
+                Err(TryLockError::WouldBlock) => thread::sleep(SCRIPT_POLL_INTERVAL),
+            }
+        }
+    }
+}
+
+/// Exclusive access to the keyspace for as long as it is held.
+///
+/// Letting go of it serves any clients blocked on keys that were written to meanwhile, so they
+/// see the outcome of the whole command, just as they would in Redis.
+pub struct KeyspaceGuard<'a>(MutexGuard<'a, Keyspace>);
+
+impl Deref for KeyspaceGuard<'_> {
+    type Target = Keyspace;
+
+    fn deref(&self) -> &Keyspace {
+        &self.0
+    }
+}
+
+impl DerefMut for KeyspaceGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Keyspace {
+        &mut self.0
+    }
+}
+
+impl Drop for KeyspaceGuard<'_> {
+    fn drop(&mut self) {
+        self.0.serve_blocked();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_clones_share_the_keyspace() {
+        let db = Db::new();
+        let other = db.clone();
+
+        other.set(b"key".to_vec(), vec![0xff, 0x00]);
+
+        assert_eq!(db.get(b"key"), Some(vec![0xff, 0x00]));
+        assert_eq!(db.get(b"missing"), None);
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let db = Db::new();
+
+        let handles: Vec<_> = (0..100u8)
+            .map(|i| {
+                let db = db.clone();
+                thread::spawn(move || db.set(format!("key:{}", i).into_bytes(), vec![i]))
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        for i in 0..100u8 {
+            assert_eq!(db.get(format!("key:{}", i).as_bytes()), Some(vec![i]));
+        }
+    }
+
+    #[test]
+    fn test_expired_keys_are_invisible() {
+        let mut ks = Keyspace::new();
+        ks.set(b"gone".to_vec(), Value::String(b"v".to_vec()));
+        ks.set(b"kept".to_vec(), Value::String(b"v".to_vec()));
+
+        assert!(ks.set_expire_time(b"gone", now_ms() - 1));
+        assert!(ks.set_expire_time(b"kept", now_ms() + 60_000));
+
+        assert_eq!(ks.get(b"gone"), None);
+        assert_eq!(ks.expire_time(b"gone"), None);
+        assert!(ks.get(b"kept").is_some());
+        assert!(!ks.set_expire_time(b"missing", now_ms()));
+    }
+
+    #[test]
+    fn test_set_clears_expiry_unless_asked_to_keep_it() {
+        let mut ks = Keyspace::new();
+        let when = now_ms() + 60_000;
+
+        ks.set(b"key".to_vec(), Value::String(b"1".to_vec()));
+        ks.set_expire_time(b"key", when);
+        ks.set_keep_ttl(b"key".to_vec(), Value::String(b"2".to_vec()));
+        assert_eq!(ks.expire_time(b"key"), Some(when));
+
+        ks.set(b"key".to_vec(), Value::String(b"3".to_vec()));
+        assert_eq!(ks.expire_time(b"key"), None);
+    }
+
+    #[test]
+    fn test_active_expire_reclaims_untouched_keys() {
+        let db = Db::new();
+        {
+            let mut ks = db.lock();
+            for i in 0..1000 {
+                let key = format!("gone:{}", i).into_bytes();
+                ks.set(key.clone(), Value::String(b"v".to_vec()));
+                ks.set_expire_time(&key, now_ms() - 1);
+            }
+            ks.set(b"kept".to_vec(), Value::String(b"v".to_vec()));
+            ks.set_expire_time(b"kept", now_ms() + 60_000);
+        }
+
+        // Nothing ever reads the expired keys, but they still go.
+        while db.lock().expires.len() > 1 {
+            db.active_expire();
+        }
+
+        let ks = db.lock();
+        assert_eq!(ks.entries.len(), 1);
+        assert!(ks.entries.contains_key(b"kept".as_slice()));
+    }
+
+    #[test]
+    fn test_expire_sample_leaves_live_keys() {
+        let mut ks = Keyspace::new();
+        ks.set(b"live".to_vec(), Value::String(b"v".to_vec()));
+        ks.set_expire_time(b"live", now_ms() + 60_000);
+
+        assert_eq!(ks.expire_sample(20), (1, 0));
+        assert_eq!(Keyspace::new().expire_sample(20), (0, 0));
+    }
+
+    #[test]
+    fn test_persist() {
+        let mut ks = Keyspace::new();
+        ks.set(b"key".to_vec(), Value::String(b"1".to_vec()));
+
+        assert!(!ks.persist(b"key"));
+        ks.set_expire_time(b"key", now_ms() + 60_000);
+        assert!(ks.persist(b"key"));
+        assert_eq!(ks.expire_time(b"key"), None);
+    }
+}
@@ -0,0 +1,135 @@
+use indexmap::IndexMap;
+use std::collections::HashMap;
+
+/// A hash value: fields and their values, where each field may have an expiry time of its own.
+///
+/// Fields are kept in an `IndexMap` so a random one can be picked cheaply. Expired fields are
+/// dropped by `remove_expired`, which the keyspace calls whenever the hash is looked up, so
+/// nothing outside ever sees them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hash {
+    fields: IndexMap<Vec<u8>, Vec<u8>>,
+    expires: HashMap<Vec<u8>, u64>,
+}
+
+impl Hash {
+    pub fn new() -> Hash {
+        Hash::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get(&self, field: &[u8]) -> Option<&Vec<u8>> {
+        self.fields.get(field)
+    }
+
+    /// Changing a value this way leaves the field's expiry time alone.
+    pub fn get_mut(&mut self, field: &[u8]) -> Option<&mut Vec<u8>> {
+        self.fields.get_mut(field)
+    }
+
+    pub fn contains(&self, field: &[u8]) -> bool {
+        self.fields.contains_key(field)
+    }
+
+    /// Sets a field, dropping any expiry time it had. Returns true if the field is new.
+    pub fn insert(&mut self, field: Vec<u8>, value: Vec<u8>) -> bool {
+        self.expires.remove(&field);
+        self.fields.insert(field, value).is_none()
+    }
+
+    pub fn remove(&mut self, field: &[u8]) -> Option<Vec<u8>> {
+        self.expires.remove(field);
+        self.fields.swap_remove(field)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<u8>, &Vec<u8>)> {
+        self.fields.iter()
+    }
+
+    /// The field and value at `index`, for picking fields at random.
+    pub fn get_index(&self, index: usize) -> Option<(&Vec<u8>, &Vec<u8>)> {
+        self.fields.get_index(index)
+    }
+
+    /// When `field` expires, in milliseconds since the epoch, if it is volatile.
+    pub fn expire_time(&self, field: &[u8]) -> Option<u64> {
+        self.expires.get(field).copied()
+    }
+
+    /// When the first of the volatile fields expires, if any are.
+    pub fn earliest_expire_time(&self) -> Option<u64> {
+        self.expires.values().min().copied()
+    }
+
+    /// Sets when an existing field expires. Returns false if there is no such field.
+    pub fn set_expire_time(&mut self, field: &[u8], when: u64) -> bool {
+        if !self.fields.contains_key(field) {
+            return false;
+        }
+
+        self.expires.insert(field.to_vec(), when);
+        true
+    }
+
+    /// Makes a field persistent again. Returns false if it had no expiry time to remove.
+    pub fn persist(&mut self, field: &[u8]) -> bool {
+        self.expires.remove(field).is_some()
+    }
+
+    /// Drops every field whose expiry time is at or before `now`.
+    pub fn remove_expired(&mut self, now: u64) {
+        if self.expires.is_empty() {
+            return;
+        }
+
+        let expired: Vec<Vec<u8>> = self
+            .expires
+            .iter()
+            .filter(|(_, when)| **when <= now)
+            .map(|(field, _)| field.clone())
+            .collect();
+
+        for field in expired {
+            self.remove(&field);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_clears_field_expiry() {
+        let mut hash = Hash::new();
+
+        assert!(hash.insert(b"f".to_vec(), b"1".to_vec()));
+        assert!(hash.set_expire_time(b"f", 100));
+        assert!(!hash.insert(b"f".to_vec(), b"2".to_vec()));
+        assert_eq!(hash.expire_time(b"f"), None);
+        assert!(!hash.set_expire_time(b"missing", 100));
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let mut hash = Hash::new();
+        hash.insert(b"gone".to_vec(), b"1".to_vec());
+        hash.insert(b"later".to_vec(), b"2".to_vec());
+        hash.insert(b"kept".to_vec(), b"3".to_vec());
+        hash.set_expire_time(b"gone", 100);
+        hash.set_expire_time(b"later", 200);
+
+        hash.remove_expired(100);
+
+        assert_eq!(hash.len(), 2);
+        assert!(!hash.contains(b"gone"));
+        assert_eq!(hash.expire_time(b"later"), Some(200));
+    }
+}
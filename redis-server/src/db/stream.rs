@@ -0,0 +1,365 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// A stream entry ID: the milliseconds part and a sequence number for entries added in the
+/// same millisecond. IDs only ever grow within a stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl StreamId {
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    pub const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    pub fn new(ms: u64, seq: u64) -> StreamId {
+        StreamId { ms, seq }
+    }
+
+    /// Parses `ms-seq`, or just `ms` in which case the sequence number is `missing_seq`.
+    pub fn parse(arg: &[u8], missing_seq: u64) -> Option<StreamId> {
+        let text = std::str::from_utf8(arg).ok()?;
+        let number = |part: &str| match part.bytes().all(|b| b.is_ascii_digit()) {
+            true => part.parse::<u64>().ok(),
+            false => None,
+        };
+
+        match text.split_once('-') {
+            Some((ms, seq)) => Some(StreamId::new(number(ms)?, number(seq)?)),
+            None => Some(StreamId::new(number(text)?, missing_seq)),
+        }
+    }
+
+    /// The ID a new entry gets when none is given: the time now, unless that would not be
+    /// after `last`, as when entries come in within the same millisecond.
+    pub fn generate(last: StreamId, now: u64) -> Option<StreamId> {
+        if now > last.ms {
+            Some(StreamId::new(now, 0))
+        } else {
+            last.next()
+        }
+    }
+
+    /// The smallest ID after this one, if there is one.
+    pub fn next(self) -> Option<StreamId> {
+        match self.seq.checked_add(1) {
+            Some(seq) => Some(StreamId::new(self.ms, seq)),
+            None => Some(StreamId::new(self.ms.checked_add(1)?, 0)),
+        }
+    }
+
+    /// The largest ID before this one, if there is one.
+    pub fn prev(self) -> Option<StreamId> {
+        match self.seq.checked_sub(1) {
+            Some(seq) => Some(StreamId::new(self.ms, seq)),
+            None => Some(StreamId::new(self.ms.checked_sub(1)?, u64::MAX)),
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+/// The field value pairs of a stream entry, in the order they were given.
+pub type Fields = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// A message delivered to a consumer of a group that hasn't been acknowledged yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingEntry {
+    pub consumer: Vec<u8>,
+    /// When the message was last delivered, in milliseconds since the epoch.
+    pub delivery_time: u64,
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consumer {
+    /// When the consumer last tried to read or claim anything.
+    pub seen_time: u64,
+    /// When the consumer last actually got something, if it ever did.
+    pub active_time: Option<u64>,
+}
+
+impl Consumer {
+    pub fn new(now: u64) -> Consumer {
+        Consumer {
+            seen_time: now,
+            active_time: None,
+        }
+    }
+}
+
+/// A consumer group: the last entry delivered to it and the messages its consumers have yet to
+/// acknowledge, which is what lets a crashed consumer's work be claimed by another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsumerGroup {
+    pub last_id: StreamId,
+    /// How many entries of the stream the group has read, if that is known.
+    pub entries_read: Option<u64>,
+    pub pending: BTreeMap<StreamId, PendingEntry>,
+    pub consumers: BTreeMap<Vec<u8>, Consumer>,
+}
+
+impl ConsumerGroup {
+    pub fn new(last_id: StreamId, entries_read: Option<u64>) -> ConsumerGroup {
+        ConsumerGroup {
+            last_id,
+            entries_read,
+            pending: BTreeMap::new(),
+            consumers: BTreeMap::new(),
+        }
+    }
+
+    /// The consumer called `name`, which is created if there isn't one.
+    pub fn consumer(&mut self, name: &[u8], now: u64) -> &mut Consumer {
+        self.consumers
+            .entry(name.to_vec())
+            .or_insert_with(|| Consumer::new(now))
+    }
+
+    /// How many messages `consumer` has pending.
+    pub fn pending_count(&self, consumer: &[u8]) -> usize {
+        self.pending
+            .values()
+            .filter(|entry| entry.consumer == consumer)
+            .count()
+    }
+}
+
+/// A stream value: entries ordered by ID, plus the consumer groups reading it.
+///
+/// Unlike other types, a stream stays around once it is empty, since its groups and last ID
+/// still matter.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stream {
+    entries: BTreeMap<StreamId, Fields>,
+    last_id: StreamId,
+    max_deleted_id: StreamId,
+    entries_added: u64,
+    groups: BTreeMap<Vec<u8>, ConsumerGroup>,
+}
+
+impl Stream {
+    pub fn new() -> Stream {
+        Stream::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The ID of the last entry ever added, even if it has since been deleted.
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    pub fn max_deleted_id(&self) -> StreamId {
+        self.max_deleted_id
+    }
+
+    /// How many entries have ever been added.
+    pub fn entries_added(&self) -> u64 {
+        self.entries_added
+    }
+
+    /// Restores what the stream remembers of entries beyond those it has, as a snapshot
+    /// records it.
+    pub fn set_history(&mut self, last_id: StreamId, max_deleted_id: StreamId, entries_added: u64) {
+        self.last_id = last_id;
+        self.max_deleted_id = max_deleted_id;
+        self.entries_added = entries_added;
+    }
+
+    /// Appends an entry. The ID must be greater than the last one.
+    pub fn insert(&mut self, id: StreamId, fields: Fields) {
+        self.entries.insert(id, fields);
+        self.last_id = id;
+        self.entries_added += 1;
+    }
+
+    pub fn get(&self, id: StreamId) -> Option<&Fields> {
+        self.entries.get(&id)
+    }
+
+    pub fn first(&self) -> Option<(StreamId, &Fields)> {
+        self.entries.iter().next().map(|(id, fields)| (*id, fields))
+    }
+
+    pub fn last(&self) -> Option<(StreamId, &Fields)> {
+        self.entries
+            .iter()
+            .next_back()
+            .map(|(id, fields)| (*id, fields))
+    }
+
+    /// The entries with IDs in `range`, in order.
+    pub fn range(
+        &self,
+        range: RangeInclusive<StreamId>,
+    ) -> impl DoubleEndedIterator<Item = (StreamId, &Fields)> {
+        let range = match range.start() <= range.end() {
+            true => Some(self.entries.range(range)),
+            false => None,
+        };
+        range
+            .into_iter()
+            .flatten()
+            .map(|(id, fields)| (*id, fields))
+    }
+
+    /// Deletes an entry. Returns false if there is no such entry.
+    pub fn remove(&mut self, id: StreamId) -> bool {
+        if self.entries.remove(&id).is_none() {
+            return false;
+        }
+
+        self.max_deleted_id = self.max_deleted_id.max(id);
+        true
+    }
+
+    /// Deletes the oldest entries, at most `limit` of them, while there are more than `max_len`.
+    /// Returns how many were deleted.
+    pub fn trim_to_len(&mut self, max_len: usize, limit: Option<usize>) -> usize {
+        let mut removed = 0;
+        while self.entries.len() > max_len && limit.is_none_or(|limit| removed < limit) {
+            self.entries.pop_first();
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Deletes the entries older than `min_id`, at most `limit` of them. Returns how many were
+    /// deleted.
+    pub fn trim_to_min_id(&mut self, min_id: StreamId, limit: Option<usize>) -> usize {
+        let mut removed = 0;
+        while self.first().is_some_and(|(id, _)| id < min_id)
+            && limit.is_none_or(|limit| removed < limit)
+        {
+            self.entries.pop_first();
+            removed += 1;
+        }
+        removed
+    }
+
+    pub fn group(&self, name: &[u8]) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    pub fn group_mut(&mut self, name: &[u8]) -> Option<&mut ConsumerGroup> {
+        self.groups.get_mut(name)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (&Vec<u8>, &ConsumerGroup)> {
+        self.groups.iter()
+    }
+
+    /// Adds a group. Returns false if there already is one by that name.
+    pub fn create_group(&mut self, name: Vec<u8>, group: ConsumerGroup) -> bool {
+        if self.groups.contains_key(&name) {
+            return false;
+        }
+
+        self.groups.insert(name, group);
+        true
+    }
+
+    pub fn destroy_group(&mut self, name: &[u8]) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    /// Marks the entries up to `id` as read by `group`, keeping count of how many it has read
+    /// while that can be worked out.
+    pub fn advance_group(&mut self, name: &[u8], id: StreamId, delivered: u64) {
+        let last_id = self.last_id;
+        let entries_added = self.entries_added;
+        let Some(group) = self.groups.get_mut(name) else {
+            return;
+        };
+
+        group.last_id = group.last_id.max(id);
+        group.entries_read = if id == last_id {
+            Some(entries_added)
+        } else {
+            group.entries_read.map(|read| read + delivered)
+        };
+    }
+
+    /// How many entries `group` has yet to read, if that can be told. It can't when entries
+    /// were deleted after the group's position, as Redis doesn't keep track of those.
+    pub fn lag(&self, group: &ConsumerGroup) -> Option<u64> {
+        if self.entries_added == 0 || group.last_id >= self.last_id {
+            return Some(0);
+        }
+
+        match group.entries_read {
+            Some(read) if self.max_deleted_id < group.last_id => {
+                Some(self.entries_added.saturating_sub(read))
+            }
+            _ if self.max_deleted_id == StreamId::MIN
+                && self.first().is_none_or(|(first, _)| group.last_id < first) =>
+            {
+                Some(self.len() as u64)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ids() {
+        assert_eq!(StreamId::parse(b"5-3", 0), Some(StreamId::new(5, 3)));
+        assert_eq!(StreamId::parse(b"5", 7), Some(StreamId::new(5, 7)));
+        assert_eq!(StreamId::parse(b"5-x", 0), None);
+        assert_eq!(StreamId::parse(b"-1", 0), None);
+        assert_eq!(StreamId::new(5, u64::MAX).next(), Some(StreamId::new(6, 0)));
+        assert_eq!(StreamId::new(5, 0).prev(), Some(StreamId::new(4, u64::MAX)));
+        assert_eq!(StreamId::MAX.next(), None);
+        assert_eq!(StreamId::new(1, 2).to_string(), "1-2");
+    }
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(
+            StreamId::generate(StreamId::MIN, 100),
+            Some(StreamId::new(100, 0))
+        );
+        assert_eq!(
+            StreamId::generate(StreamId::new(100, 0), 100),
+            Some(StreamId::new(100, 1))
+        );
+        // The clock went backwards; IDs still have to grow.
+        assert_eq!(
+            StreamId::generate(StreamId::new(100, 1), 50),
+            Some(StreamId::new(100, 2))
+        );
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut stream = Stream::new();
+        for ms in 1..=5 {
+            stream.insert(StreamId::new(ms, 0), vec![]);
+        }
+
+        assert_eq!(stream.trim_to_len(4, None), 1);
+        assert_eq!(stream.trim_to_min_id(StreamId::new(4, 0), Some(1)), 1);
+        assert_eq!(stream.first().map(|(id, _)| id), Some(StreamId::new(3, 0)));
+        assert_eq!(stream.entries_added(), 5);
+    }
+}
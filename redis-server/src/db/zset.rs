@@ -0,0 +1,438 @@
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Enough levels for 2^64 elements at a quarter of the nodes per level.
+const MAX_LEVEL: usize = 32;
+/// The chance of a node reaching each level above the first.
+const LEVEL_PROBABILITY: f64 = 0.25;
+/// The header node, which holds no element.
+const HEAD: usize = 0;
+
+#[derive(Debug, Clone)]
+struct Level {
+    forward: Option<usize>,
+    /// How many elements the link to `forward` passes over, so ranks can be summed up while
+    /// walking down the levels.
+    span: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    member: Vec<u8>,
+    score: f64,
+    backward: Option<usize>,
+    levels: Vec<Level>,
+}
+
+/// A sorted set value, laid out the way Redis does it: a dict from member to score for
+/// lookups, and a skiplist ordered by score and then member for ranges and ranks, both of
+/// which take O(log n).
+///
+/// Skiplist nodes live in a `Vec` and link to each other by index. Freed slots are reused.
+#[derive(Debug, Clone)]
+pub struct SortedSet {
+    scores: HashMap<Vec<u8>, f64>,
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    tail: Option<usize>,
+    level: usize,
+}
+
+impl Default for SortedSet {
+    fn default() -> SortedSet {
+        let head = Node {
+            member: Vec::new(),
+            score: 0.0,
+            backward: None,
+            levels: vec![
+                Level {
+                    forward: None,
+                    span: 0
+                };
+                MAX_LEVEL
+            ],
+        };
+
+        SortedSet {
+            scores: HashMap::new(),
+            nodes: vec![head],
+            free: Vec::new(),
+            tail: None,
+            level: 1,
+        }
+    }
+}
+
+impl PartialEq for SortedSet {
+    fn eq(&self, other: &SortedSet) -> bool {
+        self.scores == other.scores
+    }
+}
+
+impl SortedSet {
+    pub fn new() -> SortedSet {
+        SortedSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Adds `member` or changes its score. Returns true if it is new. The score must not be NaN.
+    pub fn insert(&mut self, member: Vec<u8>, score: f64) -> bool {
+        match self.scores.get(&member).copied() {
+            Some(current) if current == score => false,
+            Some(current) => {
+                self.unlink(current, &member);
+                self.link(score, member.clone());
+                self.scores.insert(member, score);
+                false
+            }
+            None => {
+                self.link(score, member.clone());
+                self.scores.insert(member, score);
+                true
+            }
+        }
+    }
+
+    /// Removes `member`, returning its score.
+    pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        let score = self.scores.remove(member)?;
+        self.unlink(score, member);
+        Some(score)
+    }
+
+    /// How many slots the elements are kept in, some of which may be empty.
+    pub fn slots(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The element in `slot`, if there is one. An element keeps its slot for as long as it is
+    /// in the set, even when its score changes, so scans can carry on from a slot.
+    pub fn slot(&self, slot: usize) -> Option<(&[u8], f64)> {
+        let node = self.nodes.get(slot).filter(|_| slot != HEAD)?;
+        // Freed nodes have no levels.
+        (!node.levels.is_empty()).then_some((node.member.as_slice(), node.score))
+    }
+
+    /// The 0 based position of `member`, counting from the lowest score.
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
+        let score = self.score(member)?;
+        Some(self.count_before(|s, m| precedes(s, m, score, member)))
+    }
+
+    /// How many elements come before the first one for which `before` is false. `before` has to
+    /// hold for a prefix of the set in order, as it does for the start or end of a range.
+    pub fn count_before(&self, before: impl Fn(f64, &[u8]) -> bool) -> usize {
+        let mut x = HEAD;
+        let mut rank = 0;
+
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].levels[i].forward {
+                if !before(self.nodes[next].score, &self.nodes[next].member) {
+                    break;
+                }
+                rank += self.nodes[x].levels[i].span;
+                x = next;
+            }
+        }
+
+        rank
+    }
+
+    /// Iterates in order from the element at `rank`.
+    pub fn iter_from(&self, rank: usize) -> Iter<'_> {
+        Iter {
+            set: self,
+            next: self.node_at(rank),
+            rev: false,
+        }
+    }
+
+    /// Iterates in reverse order from the element at `rank`.
+    pub fn iter_rev_from(&self, rank: usize) -> Iter<'_> {
+        Iter {
+            set: self,
+            next: self.node_at(rank),
+            rev: true,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.iter_from(0)
+    }
+
+    /// Iterates from the highest scoring element down.
+    pub fn iter_rev(&self) -> Iter<'_> {
+        Iter {
+            set: self,
+            next: self.tail,
+            rev: true,
+        }
+    }
+
+    fn node_at(&self, rank: usize) -> Option<usize> {
+        let target = rank + 1;
+        let mut x = HEAD;
+        let mut traversed = 0;
+
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].levels[i].forward {
+                if traversed + self.nodes[x].levels[i].span > target {
+                    break;
+                }
+                traversed += self.nodes[x].levels[i].span;
+                x = next;
+            }
+            if traversed == target {
+                return Some(x);
+            }
+        }
+
+        None
+    }
+
+    fn link(&mut self, score: f64, member: Vec<u8>) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut rank = [0; MAX_LEVEL];
+        let mut x = HEAD;
+
+        for i in (0..self.level).rev() {
+            rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+            while let Some(next) = self.nodes[x].levels[i].forward {
+                let node = &self.nodes[next];
+                if !precedes(node.score, &node.member, score, &member) {
+                    break;
+                }
+                rank[i] += self.nodes[x].levels[i].span;
+                x = next;
+            }
+            update[i] = x;
+        }
+
+        let level = random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].levels[i].span = self.len();
+            }
+            self.level = level;
+        }
+
+        let node = self.alloc(Node {
+            member,
+            score,
+            backward: None,
+            levels: vec![
+                Level {
+                    forward: None,
+                    span: 0
+                };
+                level
+            ],
+        });
+
+        for i in 0..level {
+            let prev = update[i];
+            self.nodes[node].levels[i].forward = self.nodes[prev].levels[i].forward;
+            self.nodes[prev].levels[i].forward = Some(node);
+            self.nodes[node].levels[i].span = self.nodes[prev].levels[i].span - (rank[0] - rank[i]);
+            self.nodes[prev].levels[i].span = rank[0] - rank[i] + 1;
+        }
+        for (i, prev) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[*prev].levels[i].span += 1;
+        }
+
+        self.nodes[node].backward = if update[0] == HEAD {
+            None
+        } else {
+            Some(update[0])
+        };
+        match self.nodes[node].levels[0].forward {
+            Some(next) => self.nodes[next].backward = Some(node),
+            None => self.tail = Some(node),
+        }
+    }
+
+    fn unlink(&mut self, score: f64, member: &[u8]) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut x = HEAD;
+
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.nodes[x].levels[i].forward {
+                let node = &self.nodes[next];
+                if !precedes(node.score, &node.member, score, member) {
+                    break;
+                }
+                x = next;
+            }
+            update[i] = x;
+        }
+
+        let x = self.nodes[x].levels[0].forward.unwrap();
+        for (i, &prev) in update.iter().enumerate().take(self.level) {
+            if self.nodes[prev].levels[i].forward == Some(x) {
+                self.nodes[prev].levels[i].span += self.nodes[x].levels[i].span;
+                self.nodes[prev].levels[i].span -= 1;
+                self.nodes[prev].levels[i].forward = self.nodes[x].levels[i].forward;
+            } else {
+                self.nodes[prev].levels[i].span -= 1;
+            }
+        }
+
+        match self.nodes[x].levels[0].forward {
+            Some(next) => self.nodes[next].backward = self.nodes[x].backward,
+            None => self.tail = self.nodes[x].backward,
+        }
+        while self.level > 1 && self.nodes[HEAD].levels[self.level - 1].forward.is_none() {
+            self.level -= 1;
+        }
+
+        self.nodes[x].member = Vec::new();
+        self.nodes[x].levels = Vec::new();
+        self.free.push(x);
+    }
+
+    fn alloc(&mut self, node: Node) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+}
+
+/// Iterates over members and their scores, in either direction.
+pub struct Iter<'a> {
+    set: &'a SortedSet,
+    next: Option<usize>,
+    rev: bool,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a [u8], f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = &self.set.nodes[self.next?];
+        self.next = if self.rev {
+            node.backward
+        } else {
+            node.levels[0].forward
+        };
+        Some((&node.member, node.score))
+    }
+}
+
+/// Whether the element `(score, member)` sorts before `(other_score, other_member)`.
+fn precedes(score: f64, member: &[u8], other_score: f64, other_member: &[u8]) -> bool {
+    score < other_score || (score == other_score && member < other_member)
+}
+
+fn random_level() -> usize {
+    let mut rng = rand::thread_rng();
+    let mut level = 1;
+    while level < MAX_LEVEL && rng.gen::<f64>() < LEVEL_PROBABILITY {
+        level += 1;
+    }
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(set: &SortedSet) -> Vec<(Vec<u8>, f64)> {
+        set.iter().map(|(m, s)| (m.to_vec(), s)).collect()
+    }
+
+    #[test]
+    fn test_ordered_by_score_then_member() {
+        let mut set = SortedSet::new();
+        assert!(set.insert(b"b".to_vec(), 1.0));
+        assert!(set.insert(b"a".to_vec(), 2.0));
+        assert!(set.insert(b"c".to_vec(), 1.0));
+        assert!(!set.insert(b"a".to_vec(), 0.5));
+
+        assert_eq!(
+            members(&set),
+            vec![
+                (b"a".to_vec(), 0.5),
+                (b"b".to_vec(), 1.0),
+                (b"c".to_vec(), 1.0)
+            ]
+        );
+        assert_eq!(set.rank(b"c"), Some(2));
+        assert_eq!(set.remove(b"b"), Some(1.0));
+        assert_eq!(set.rank(b"c"), Some(1));
+        assert_eq!(
+            set.iter_rev_from(1)
+                .map(|(m, _)| m.to_vec())
+                .collect::<Vec<_>>(),
+            vec![b"c".to_vec(), b"a".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_elements_keep_their_slots() {
+        let mut set = SortedSet::new();
+        set.insert(b"a".to_vec(), 1.0);
+        set.insert(b"b".to_vec(), 2.0);
+        let slot = (0..set.slots())
+            .find(|&i| set.slot(i).is_some_and(|(member, _)| member == b"b"))
+            .unwrap();
+
+        set.insert(b"b".to_vec(), 0.5);
+        set.remove(b"a");
+        assert_eq!(set.slot(slot), Some((&b"b"[..], 0.5)));
+        assert_eq!(set.slot(0), None);
+        let filled = (0..set.slots()).filter(|&i| set.slot(i).is_some());
+        assert_eq!(filled.count(), 1);
+    }
+
+    #[test]
+    fn test_ranks_match_a_sorted_vec() {
+        let mut rng = rand::thread_rng();
+        let mut set = SortedSet::new();
+
+        for _ in 0..2000 {
+            let member = rng.gen_range(0..300u32).to_string().into_bytes();
+            if rng.gen_bool(0.3) {
+                set.remove(&member);
+            } else {
+                set.insert(member, rng.gen_range(0..50) as f64);
+            }
+        }
+
+        let mut expected: Vec<(Vec<u8>, f64)> =
+            set.scores.iter().map(|(m, s)| (m.clone(), *s)).collect();
+        expected.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+        assert_eq!(members(&set), expected);
+        for (rank, (member, score)) in expected.iter().enumerate() {
+            assert_eq!(set.rank(member), Some(rank));
+            assert_eq!(
+                set.iter_from(rank).next(),
+                Some((member.as_slice(), *score))
+            );
+        }
+        assert_eq!(set.iter_from(expected.len()).next(), None);
+        assert_eq!(set.count_before(|s, _| s < 10.0), {
+            expected.iter().filter(|(_, s)| *s < 10.0).count()
+        });
+    }
+}
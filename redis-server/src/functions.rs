@@ -0,0 +1,550 @@
+//! Function libraries: Lua code loaded with FUNCTION LOAD that registers named functions for
+//! FCALL to run. Unlike scripts, which are only a cache, libraries are part of the dataset.
+//!
+//! A library starts with a `#!lua name=<library>` line and calls `redis.register_function`
+//! for each function it provides, either as `register_function(name, callback)` or with a
+//! table of `function_name`, `callback`, and optionally `flags` and `description`.
+
+use crate::commands::exec::Dispatch;
+use crate::commands::CommandError;
+use crate::db::Keyspace;
+use crate::rdb;
+use crate::resp::types::Frame;
+use crate::scripting::{
+    find_raised, invoke, lua_message, new_interpreter, raised, watch_for_kill, Invocation, Raised,
+    ScriptStatus, KILL_CHECK_INTERVAL,
+};
+
+use indexmap::IndexMap;
+use mlua::{HookTriggers, Lua, MultiValue, RegistryKey, Table, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How long a library's code may take to register its functions.
+const LOAD_TIME_LIMIT: Duration = Duration::from_millis(500);
+/// The flags a function may be registered with.
+const FLAGS: [&str; 5] = [
+    "no-writes",
+    "allow-oom",
+    "allow-stale",
+    "no-cluster",
+    "allow-cross-slot-keys",
+];
+
+/// A library and the functions it registered, in the order it registered them.
+pub struct Library {
+    pub name: String,
+    pub code: Vec<u8>,
+    pub functions: IndexMap<String, Function>,
+}
+
+pub struct Function {
+    callback: RegistryKey,
+    pub description: Option<String>,
+    pub flags: Vec<String>,
+}
+
+impl Function {
+    /// Functions that may not write can be run with FCALL_RO.
+    pub fn no_writes(&self) -> bool {
+        self.flags.iter().any(|flag| flag == "no-writes")
+    }
+}
+
+/// What to do with the libraries already loaded when restoring others.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestorePolicy {
+    /// Keeps them, failing if a library being restored is already loaded.
+    Append,
+    /// Keeps them, but replaces any with the same name as one being restored.
+    Replace,
+    /// Drops them all first.
+    Flush,
+}
+
+/// The loaded libraries, and the interpreter their functions run in.
+pub struct Functions {
+    lua: Lua,
+    libraries: IndexMap<String, Library>,
+    /// The library each function belongs to. Function names are unique across libraries.
+    owners: HashMap<String, String>,
+    status: Arc<ScriptStatus>,
+}
+
+impl Functions {
+    pub fn new(status: &Arc<ScriptStatus>) -> Functions {
+        Functions {
+            lua: new_interpreter(status),
+            libraries: IndexMap::new(),
+            owners: HashMap::new(),
+            status: Arc::clone(status),
+        }
+    }
+
+    /// Loads a library, returning its name. With `replace` a library of the same name is
+    /// replaced, otherwise it is an error for one to exist.
+    pub fn load(&mut self, code: &[u8], replace: bool) -> Result<String, CommandError> {
+        let (name, body) = metadata(code)?;
+        if !replace && self.libraries.contains_key(&name) {
+            return Err(CommandError::Other(format!(
+                "ERR Library '{}' already exists",
+                name
+            )));
+        }
+
+        let functions = self.register(&body)?;
+        if let Some(function) = functions.keys().find(|function| {
+            self.owners
+                .get(*function)
+                .is_some_and(|owner| *owner != name)
+        }) {
+            return Err(CommandError::Other(format!(
+                "ERR Function {} already exists",
+                function
+            )));
+        }
+
+        self.delete(&name);
+        for function in functions.keys() {
+            self.owners.insert(function.clone(), name.clone());
+        }
+        self.libraries.insert(
+            name.clone(),
+            Library {
+                name: name.clone(),
+                code: code.to_vec(),
+                functions,
+            },
+        );
+        Ok(name)
+    }
+
+    /// Runs the library's code, collecting the functions it registers.
+    fn register(&self, body: &[u8]) -> Result<IndexMap<String, Function>, CommandError> {
+        let chunk = self
+            .lua
+            .load(body)
+            .set_name("@user_function")
+            .into_function()
+            .map_err(|e| {
+                CommandError::Other(format!("ERR Error compiling function: {}", lua_message(&e)))
+            })?;
+
+        let started = Instant::now();
+        self.lua.set_hook(
+            HookTriggers::new().every_nth_instruction(KILL_CHECK_INTERVAL),
+            move |_, _| match started.elapsed() > LOAD_TIME_LIMIT {
+                true => Err(raised("ERR FUNCTION LOAD timeout")),
+                false => Ok(()),
+            },
+        );
+
+        let registered = Mutex::new(IndexMap::new());
+        let result = self
+            .lua
+            .globals()
+            .raw_get("redis")
+            .and_then(|redis: Table| {
+                let result = self.lua.scope(|scope| {
+                    redis.raw_set(
+                        "register_function",
+                        scope.create_function(|lua, args| {
+                            register_function(lua, &registered, args)
+                        })?,
+                    )?;
+                    chunk.call::<_, ()>(())
+                });
+                redis.raw_set("register_function", Value::Nil)?;
+                result
+            });
+        watch_for_kill(&self.lua, &self.status);
+
+        result.map_err(|e| match find_raised(&e) {
+            Some(Raised(message)) => CommandError::Other(message.clone()),
+            None => CommandError::Other(format!(
+                "ERR Error registering functions: {}",
+                lua_message(&e)
+            )),
+        })?;
+
+        let registered = registered
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        if registered.is_empty() {
+            return Err(CommandError::Other(String::from(
+                "ERR No functions registered",
+            )));
+        }
+        Ok(registered)
+    }
+
+    /// Returns false if there was no such library.
+    pub fn delete(&mut self, name: &str) -> bool {
+        let Some(library) = self.libraries.shift_remove(name) else {
+            return false;
+        };
+        for function in library.functions.keys() {
+            self.owners.remove(function);
+        }
+        drop(library);
+        self.lua.expire_registry_values();
+        true
+    }
+
+    /// Drops every library, starting over with a fresh interpreter.
+    pub fn flush(&mut self) {
+        *self = Functions::new(&self.status);
+    }
+
+    pub fn libraries(&self) -> impl Iterator<Item = &Library> {
+        self.libraries.values()
+    }
+
+    /// Runs a function. A function that may write can't be run read-only.
+    pub fn call(
+        &self,
+        name: &str,
+        invocation: Invocation,
+        ks: &mut Keyspace,
+        dispatch: Dispatch,
+    ) -> Result<Frame, CommandError> {
+        let Some(function) = self
+            .owners
+            .get(name)
+            .and_then(|owner| self.libraries[owner].functions.get(name))
+        else {
+            return Err(CommandError::Other(String::from("ERR Function not found")));
+        };
+
+        if invocation.read_only && !function.no_writes() {
+            return Err(CommandError::Other(String::from(
+                "ERR Can not execute a script with write flag using *_ro command.",
+            )));
+        }
+        let invocation = Invocation {
+            read_only: function.no_writes(),
+            ..invocation
+        };
+        invoke(
+            &self.lua,
+            &function.callback,
+            invocation,
+            &self.status,
+            ks,
+            dispatch,
+        )
+    }
+
+    /// Serializes every library the way FUNCTION DUMP does.
+    pub fn dump(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        for library in self.libraries.values() {
+            payload.push(rdb::OPCODE_FUNCTION2);
+            rdb::write_string(&mut payload, &library.code);
+        }
+        rdb::seal(&mut payload);
+        payload
+    }
+
+    /// Restores libraries from a FUNCTION DUMP payload. Nothing changes unless every library
+    /// can be restored.
+    pub fn restore(&mut self, payload: &[u8], policy: RestorePolicy) -> Result<(), CommandError> {
+        let body = rdb::unseal(payload).ok_or_else(|| {
+            CommandError::Other(String::from("ERR payload version or checksum are wrong"))
+        })?;
+
+        let mut reader = rdb::Reader::new(body);
+        let mut codes = Vec::new();
+        while !reader.is_empty() {
+            if reader.read_u8() != Some(rdb::OPCODE_FUNCTION2) {
+                return Err(CommandError::Other(String::from(
+                    "ERR given type is not a function",
+                )));
+            }
+            let code = reader.read_string().ok_or_else(|| {
+                CommandError::Other(String::from("ERR function library is corrupted"))
+            })?;
+            codes.push(code);
+        }
+
+        // Everything is loaded into a fresh interpreter, which only replaces this one once it
+        // all worked.
+        let mut restored = Functions::new(&self.status);
+        if policy != RestorePolicy::Flush {
+            for library in self.libraries.values() {
+                restored.load(&library.code, false)?;
+            }
+        }
+        for code in codes {
+            restored.load(&code, policy == RestorePolicy::Replace)?;
+        }
+        *self = restored;
+        Ok(())
+    }
+}
+
+/// `redis.register_function`, which libraries call while they load.
+fn register_function(
+    lua: &Lua,
+    registered: &Mutex<IndexMap<String, Function>>,
+    args: MultiValue,
+) -> mlua::Result<()> {
+    let args: Vec<Value> = args.into_iter().collect();
+    let (name, callback, flags, description) =
+        match args.as_slice() {
+            [Value::Table(named)] => {
+                let mut name = Value::Nil;
+                let mut callback = Value::Nil;
+                let mut flags = Value::Nil;
+                let mut description = Value::Nil;
+                for pair in named.clone().pairs::<String, Value>() {
+                    let (key, value) = pair?;
+                    match key.as_str() {
+                        "function_name" => name = value,
+                        "callback" => callback = value,
+                        "flags" => flags = value,
+                        "description" => description = value,
+                        _ => {
+                            return Err(raised(
+                                "ERR unknown argument given to redis.register_function",
+                            ))
+                        }
+                    }
+                }
+                (name, callback, flags, description)
+            }
+            [_] => return Err(raised(
+                "ERR calling redis.register_function with a single argument is only applicable \
+                 to Lua table (representing named arguments).",
+            )),
+            [name, callback] => (name.clone(), callback.clone(), Value::Nil, Value::Nil),
+            _ => {
+                return Err(raised(
+                    "ERR wrong number of arguments to redis.register_function",
+                ))
+            }
+        };
+
+    let name = match name {
+        Value::String(name) if valid_name(name.as_bytes()) => name.to_str()?.to_string(),
+        _ => {
+            return Err(raised(
+                "ERR Function names can only contain letters, numbers, or underscores(_) and \
+                 must be at least one character long",
+            ))
+        }
+    };
+    let Value::Function(callback) = callback else {
+        return Err(raised(
+            "ERR callback argument given to redis.register_function must be a function",
+        ));
+    };
+    let description = match description {
+        Value::Nil => None,
+        Value::String(description) => Some(description.to_str()?.to_string()),
+        _ => return Err(raised("ERR description given was not a string")),
+    };
+    let flags = match flags {
+        Value::Nil => Vec::new(),
+        Value::Table(flags) => flags
+            .sequence_values::<String>()
+            .map(|flag| match flag {
+                Ok(flag) if FLAGS.contains(&flag.as_str()) => Ok(flag),
+                _ => Err(raised("ERR unknown flag given")),
+            })
+            .collect::<mlua::Result<_>>()?,
+        _ => return Err(raised("ERR flags argument to redis.register_function must be a table representing function flags")),
+    };
+
+    let mut registered = registered.lock().unwrap_or_else(PoisonError::into_inner);
+    if registered.contains_key(&name) {
+        return Err(raised("ERR Function already exists in the library"));
+    }
+    let function = Function {
+        callback: lua.create_registry_value(callback)?,
+        description,
+        flags,
+    };
+    registered.insert(name, function);
+    Ok(())
+}
+
+/// Splits the library's name off the `#!lua name=<library>` line it has to start with. The line
+/// itself is left empty, so line numbers in errors still match the code.
+fn metadata(code: &[u8]) -> Result<(String, Vec<u8>), CommandError> {
+    if !code.starts_with(b"#!") {
+        return Err(CommandError::Other(String::from(
+            "ERR Missing library metadata",
+        )));
+    }
+
+    let end = code.iter().position(|&b| b == b'\n').unwrap_or(code.len());
+    let line = String::from_utf8_lossy(&code[2..end]);
+    let mut parts = line.split_whitespace();
+    let engine = parts.next().unwrap_or_default();
+    if !engine.eq_ignore_ascii_case("lua") {
+        return Err(CommandError::Other(format!(
+            "ERR Engine '{}' not found",
+            engine
+        )));
+    }
+
+    let mut name = None;
+    for part in parts {
+        match part.strip_prefix("name=") {
+            Some(value) => name = Some(value.to_string()),
+            None => {
+                return Err(CommandError::Other(format!(
+                    "ERR Invalid metadata value given: {}",
+                    part
+                )))
+            }
+        }
+    }
+
+    let Some(name) = name else {
+        return Err(CommandError::Other(String::from(
+            "ERR Library name was not given",
+        )));
+    };
+    if !valid_name(name.as_bytes()) {
+        return Err(CommandError::Other(String::from(
+            "ERR Library names can only contain letters, numbers, or underscores(_) and must be \
+             at least one character long",
+        )));
+    }
+    Ok((name, code[end..].to_vec()))
+}
+
+fn valid_name(name: &[u8]) -> bool {
+    !name.is_empty() && name.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY: &str = "#!lua name=counters
+local function incr(keys, args)
+    return redis.call('INCRBY', keys[1], args[1])
+end
+redis.register_function('incr', incr)
+redis.register_function{
+    function_name = 'peek',
+    callback = function(keys) return redis.call('GET', keys[1]) end,
+    flags = {'no-writes'},
+    description = 'Reads a counter',
+}";
+
+    fn load_error(functions: &mut Functions, code: &str) -> String {
+        match functions.load(code.as_bytes(), false) {
+            Err(CommandError::Other(e)) => e,
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        let mut functions = Functions::new(&Arc::default());
+        assert_eq!(
+            functions.load(LIBRARY.as_bytes(), false),
+            Ok(String::from("counters"))
+        );
+
+        let library = functions.libraries().next().unwrap();
+        assert_eq!(
+            library.functions.keys().collect::<Vec<_>>(),
+            ["incr", "peek"]
+        );
+        assert!(library.functions["peek"].no_writes());
+        assert_eq!(
+            library.functions["peek"].description.as_deref(),
+            Some("Reads a counter")
+        );
+
+        assert_eq!(
+            load_error(&mut functions, LIBRARY),
+            "ERR Library 'counters' already exists"
+        );
+        assert_eq!(
+            functions.load(LIBRARY.as_bytes(), true),
+            Ok(String::from("counters"))
+        );
+        assert_eq!(
+            load_error(
+                &mut functions,
+                "#!lua name=other\nredis.register_function('incr', function() end)"
+            ),
+            "ERR Function incr already exists"
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let mut functions = Functions::new(&Arc::default());
+
+        assert_eq!(
+            load_error(&mut functions, "return 1"),
+            "ERR Missing library metadata"
+        );
+        assert_eq!(
+            load_error(&mut functions, "#!python name=a\n"),
+            "ERR Engine 'python' not found"
+        );
+        assert_eq!(
+            load_error(&mut functions, "#!lua\n"),
+            "ERR Library name was not given"
+        );
+        assert_eq!(
+            load_error(&mut functions, "#!lua name=a\nlocal x = 1"),
+            "ERR No functions registered"
+        );
+        assert_eq!(
+            load_error(
+                &mut functions,
+                "#!lua name=a\nredis.register_function{function_name='f', callback=function() end, flags={'fast'}}"
+            ),
+            "ERR unknown flag given"
+        );
+        assert_eq!(
+            load_error(&mut functions, "#!lua name=a\nredis.call('PING')"),
+            "ERR Error registering functions: user_function:2: attempt to call field 'call' (a nil value)"
+        );
+        assert_eq!(
+            load_error(&mut functions, "#!lua name=a\nwhile true do end"),
+            "ERR FUNCTION LOAD timeout"
+        );
+        assert_eq!(functions.libraries().count(), 0);
+    }
+
+    #[test]
+    fn test_dump_and_restore() {
+        let mut functions = Functions::new(&Arc::default());
+        functions.load(LIBRARY.as_bytes(), false).unwrap();
+        let payload = functions.dump();
+
+        assert_eq!(
+            functions.restore(&payload, RestorePolicy::Append),
+            Err(CommandError::Other(String::from(
+                "ERR Library 'counters' already exists"
+            )))
+        );
+        assert_eq!(functions.restore(&payload, RestorePolicy::Replace), Ok(()));
+
+        let mut restored = Functions::new(&Arc::default());
+        restored
+            .load(
+                b"#!lua name=other\nredis.register_function('f', function() end)",
+                false,
+            )
+            .unwrap();
+        assert_eq!(restored.restore(&payload, RestorePolicy::Flush), Ok(()));
+        let names: Vec<_> = restored.libraries().map(|library| &library.name).collect();
+        assert_eq!(names, ["counters"]);
+
+        assert!(restored
+            .restore(b"nonsense!!", RestorePolicy::Flush)
+            .is_err());
+    }
+}
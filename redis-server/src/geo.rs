@@ -0,0 +1,414 @@
+//! Geohashes the way Redis computes them, so that the sorted set scores GEOADD stores match a
+//! real Redis and searches look at the same areas.
+//!
+//! A position is stored as a 52 bit score: 26 bits each of longitude and latitude, interleaved
+//! with the longitude in the odd bits. Latitudes are limited to what Web Mercator can show.
+
+pub const LONG_MIN: f64 = -180.0;
+pub const LONG_MAX: f64 = 180.0;
+pub const LAT_MIN: f64 = -85.05112878;
+pub const LAT_MAX: f64 = 85.05112878;
+
+/// Bits per coordinate in a full precision hash.
+const STEP_MAX: u8 = 26;
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+const MERCATOR_MAX: f64 = 20037726.37;
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// A hash of `step` bits per coordinate, so the lower the step the bigger the area it covers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hash {
+    bits: u64,
+    step: u8,
+}
+
+impl Hash {
+    const ZERO: Hash = Hash { bits: 0, step: 0 };
+
+    /// The scores of the positions inside this hash's area, as a half open range.
+    fn scores(self) -> (f64, f64) {
+        let shift = 52 - self.step as u32 * 2;
+        (
+            (self.bits << shift) as f64,
+            ((self.bits + 1) << shift) as f64,
+        )
+    }
+
+    /// The hash of the neighbouring area `dx` across and `dy` up.
+    fn moved(self, dx: i8, dy: i8) -> Hash {
+        let lanes = 64 - self.step as u32 * 2;
+        let mut x = self.bits & 0xaaaaaaaaaaaaaaaa;
+        let mut y = self.bits & 0x5555555555555555;
+
+        if dx != 0 {
+            let zz = 0x5555555555555555u64 >> lanes;
+            x = if dx > 0 {
+                x.wrapping_add(zz + 1)
+            } else {
+                (x | zz).wrapping_sub(zz + 1)
+            };
+            x &= 0xaaaaaaaaaaaaaaaa >> lanes;
+        }
+        if dy != 0 {
+            let zz = 0xaaaaaaaaaaaaaaaau64 >> lanes;
+            y = if dy > 0 {
+                y.wrapping_add(zz + 1)
+            } else {
+                (y | zz).wrapping_sub(zz + 1)
+            };
+            y &= 0x5555555555555555 >> lanes;
+        }
+
+        Hash {
+            bits: x | y,
+            step: self.step,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Range {
+    min: f64,
+    max: f64,
+}
+
+const LONG_RANGE: Range = Range {
+    min: LONG_MIN,
+    max: LONG_MAX,
+};
+const LAT_RANGE: Range = Range {
+    min: LAT_MIN,
+    max: LAT_MAX,
+};
+
+/// The area a hash covers.
+struct Area {
+    longitude: Range,
+    latitude: Range,
+}
+
+/// Whether a position is within the bounds a score can hold.
+pub fn valid(longitude: f64, latitude: f64) -> bool {
+    (LONG_MIN..=LONG_MAX).contains(&longitude) && (LAT_MIN..=LAT_MAX).contains(&latitude)
+}
+
+/// The score a position is stored with. The position must be valid.
+pub fn score(longitude: f64, latitude: f64) -> f64 {
+    encode(LONG_RANGE, LAT_RANGE, longitude, latitude, STEP_MAX).bits as f64
+}
+
+/// The position at the centre of the area a score stands for.
+pub fn position(score: f64) -> (f64, f64) {
+    let area = decode(
+        LONG_RANGE,
+        LAT_RANGE,
+        Hash {
+            bits: score as u64,
+            step: STEP_MAX,
+        },
+    );
+    let longitude = (area.longitude.min + area.longitude.max) / 2.0;
+    let latitude = (area.latitude.min + area.latitude.max) / 2.0;
+    (
+        longitude.clamp(LONG_MIN, LONG_MAX),
+        latitude.clamp(LAT_MIN, LAT_MAX),
+    )
+}
+
+/// The standard 11 character geohash of a score. Redis stores latitudes in a narrower range
+/// than the standard one, so the position is encoded again.
+pub fn geohash(score: f64) -> String {
+    let (longitude, latitude) = position(score);
+    let hash = encode(
+        LONG_RANGE,
+        Range {
+            min: -90.0,
+            max: 90.0,
+        },
+        longitude,
+        latitude,
+        STEP_MAX,
+    );
+
+    (0..11)
+        .map(|i| {
+            // Only 52 bits fit 10 characters; the last is always the first of the alphabet.
+            let index = match i {
+                10 => 0,
+                _ => (hash.bits >> (52 - (i + 1) * 5)) & 0x1f,
+            };
+            ALPHABET[index as usize] as char
+        })
+        .collect()
+}
+
+/// The distance in meters between two positions along the surface of the earth.
+pub fn distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1, lon1, lat2, lon2) = (
+        lat1.to_radians(),
+        lon1.to_radians(),
+        lat2.to_radians(),
+        lon2.to_radians(),
+    );
+    let u = ((lat2 - lat1) / 2.0).sin();
+    let v = ((lon2 - lon1) / 2.0).sin();
+    if v == 0.0 {
+        return EARTH_RADIUS_IN_METERS * (lat2 - lat1).abs();
+    }
+    2.0 * EARTH_RADIUS_IN_METERS * (u * u + lat1.cos() * lat2.cos() * v * v).sqrt().asin()
+}
+
+/// An area to search, with its sizes in meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Radius(f64),
+    Box { width: f64, height: f64 },
+}
+
+impl Shape {
+    /// The distance from the centre to a position, if the position is inside the shape.
+    pub fn contains(&self, centre: (f64, f64), position: (f64, f64)) -> Option<f64> {
+        let ((x1, y1), (x2, y2)) = (centre, position);
+        match *self {
+            Shape::Radius(radius) => Some(distance(x1, y1, x2, y2)).filter(|d| *d <= radius),
+            Shape::Box { width, height } => {
+                if distance(x2, y2, x2, y1) > height / 2.0 || distance(x2, y2, x1, y2) > width / 2.0
+                {
+                    return None;
+                }
+                Some(distance(x1, y1, x2, y2))
+            }
+        }
+    }
+
+    /// The bounding box of the shape around `centre`: the minimum longitude and latitude, then
+    /// the maximum ones.
+    fn bounds(&self, (longitude, latitude): (f64, f64)) -> [f64; 4] {
+        let (width, height) = match *self {
+            Shape::Radius(radius) => (radius, radius),
+            Shape::Box { width, height } => (width / 2.0, height / 2.0),
+        };
+
+        let lat_delta = (height / EARTH_RADIUS_IN_METERS).to_degrees();
+        let long_delta = |latitude: f64| {
+            (width / EARTH_RADIUS_IN_METERS / latitude.to_radians().cos()).to_degrees()
+        };
+        // The hemispheres curve opposite ways, so the widest edge is the one nearer the equator.
+        let long_delta = if latitude < 0.0 {
+            long_delta(latitude - lat_delta)
+        } else {
+            long_delta(latitude + lat_delta)
+        };
+
+        [
+            longitude - long_delta,
+            latitude - lat_delta,
+            longitude + long_delta,
+            latitude + lat_delta,
+        ]
+    }
+
+    /// The score ranges to look for positions inside the shape in: the area around the centre
+    /// at a precision about the size of the shape, and the areas around it, minus those that
+    /// can't overlap the shape.
+    pub fn score_ranges(&self, centre: (f64, f64)) -> Vec<(f64, f64)> {
+        let (longitude, latitude) = centre;
+        let [min_lon, min_lat, max_lon, max_lat] = self.bounds(centre);
+
+        let radius = match *self {
+            Shape::Radius(radius) => radius,
+            Shape::Box { width, height } => ((width / 2.0).powi(2) + (height / 2.0).powi(2)).sqrt(),
+        };
+        let mut step = estimate_step(radius, latitude);
+
+        let mut hash = encode(LONG_RANGE, LAT_RANGE, longitude, latitude, step);
+        let mut areas = neighbours(hash);
+
+        // Near the edge of the area the neighbours may not reach far enough; go a step coarser.
+        let decode = |hash| decode(LONG_RANGE, LAT_RANGE, hash);
+        let too_small = decode(areas[NORTH]).latitude.max < max_lat
+            || decode(areas[SOUTH]).latitude.min > min_lat
+            || decode(areas[EAST]).longitude.max < max_lon
+            || decode(areas[WEST]).longitude.min > min_lon;
+        if step > 1 && too_small {
+            step -= 1;
+            hash = encode(LONG_RANGE, LAT_RANGE, longitude, latitude, step);
+            areas = neighbours(hash);
+        }
+
+        // Skip the neighbours on the sides the shape doesn't reach.
+        if step >= 2 {
+            let area = decode(hash);
+            let mut skip = |sides: [usize; 3]| {
+                for side in sides {
+                    areas[side] = Hash::ZERO;
+                }
+            };
+            if area.latitude.min < min_lat {
+                skip([SOUTH, SOUTH_WEST, SOUTH_EAST]);
+            }
+            if area.latitude.max > max_lat {
+                skip([NORTH, NORTH_EAST, NORTH_WEST]);
+            }
+            if area.longitude.min < min_lon {
+                skip([WEST, SOUTH_WEST, NORTH_WEST]);
+            }
+            if area.longitude.max > max_lon {
+                skip([EAST, SOUTH_EAST, NORTH_EAST]);
+            }
+        }
+
+        // Neighbours can be the same area as the one before when the shape is huge.
+        let mut ranges = Vec::new();
+        let mut last: Option<Hash> = None;
+        for hash in areas {
+            if hash == Hash::ZERO || last == Some(hash) {
+                continue;
+            }
+            ranges.push(hash.scores());
+            last = Some(hash);
+        }
+        ranges
+    }
+}
+
+const NORTH: usize = 1;
+const SOUTH: usize = 2;
+const EAST: usize = 3;
+const WEST: usize = 4;
+const NORTH_EAST: usize = 5;
+const NORTH_WEST: usize = 6;
+const SOUTH_EAST: usize = 7;
+const SOUTH_WEST: usize = 8;
+
+/// The hash followed by its neighbours, in the order Redis searches them.
+fn neighbours(hash: Hash) -> [Hash; 9] {
+    [
+        hash,
+        hash.moved(0, 1),
+        hash.moved(0, -1),
+        hash.moved(1, 0),
+        hash.moved(-1, 0),
+        hash.moved(1, 1),
+        hash.moved(-1, 1),
+        hash.moved(1, -1),
+        hash.moved(-1, -1),
+    ]
+}
+
+/// The step at which an area is about `radius` meters across at `latitude`.
+fn estimate_step(mut radius: f64, latitude: f64) -> u8 {
+    if radius == 0.0 {
+        return STEP_MAX;
+    }
+
+    let mut step: i32 = 1;
+    while radius < MERCATOR_MAX {
+        radius *= 2.0;
+        step += 1;
+    }
+    // Make sure the radius fits in most cases.
+    step -= 2;
+
+    // Areas get narrower towards the poles.
+    if !(-66.0..=66.0).contains(&latitude) {
+        step -= 1;
+        if !(-80.0..=80.0).contains(&latitude) {
+            step -= 1;
+        }
+    }
+
+    step.clamp(1, STEP_MAX as i32) as u8
+}
+
+fn encode(long_range: Range, lat_range: Range, longitude: f64, latitude: f64, step: u8) -> Hash {
+    let lat_offset = (latitude - lat_range.min) / (lat_range.max - lat_range.min);
+    let long_offset = (longitude - long_range.min) / (long_range.max - long_range.min);
+    let cells = (1u64 << step) as f64;
+
+    Hash {
+        bits: interleave((lat_offset * cells) as u32, (long_offset * cells) as u32),
+        step,
+    }
+}
+
+fn decode(long_range: Range, lat_range: Range, hash: Hash) -> Area {
+    let (lat, long) = deinterleave(hash.bits);
+    let cells = (1u64 << hash.step) as f64;
+    let lat_scale = lat_range.max - lat_range.min;
+    let long_scale = long_range.max - long_range.min;
+
+    Area {
+        latitude: Range {
+            min: lat_range.min + (lat as f64 / cells) * lat_scale,
+            max: lat_range.min + ((lat as f64 + 1.0) / cells) * lat_scale,
+        },
+        longitude: Range {
+            min: long_range.min + (long as f64 / cells) * long_scale,
+            max: long_range.min + ((long as f64 + 1.0) / cells) * long_scale,
+        },
+    }
+}
+
+/// Spreads the bits of `x` over the even bits and those of `y` over the odd ones.
+fn interleave(x: u32, y: u32) -> u64 {
+    fn spread(v: u32) -> u64 {
+        let mut v = v as u64;
+        v = (v | (v << 16)) & 0x0000ffff0000ffff;
+        v = (v | (v << 8)) & 0x00ff00ff00ff00ff;
+        v = (v | (v << 4)) & 0x0f0f0f0f0f0f0f0f;
+        v = (v | (v << 2)) & 0x3333333333333333;
+        (v | (v << 1)) & 0x5555555555555555
+    }
+
+    spread(x) | (spread(y) << 1)
+}
+
+fn deinterleave(bits: u64) -> (u32, u32) {
+    fn squash(v: u64) -> u32 {
+        let mut v = v & 0x5555555555555555;
+        v = (v | (v >> 1)) & 0x3333333333333333;
+        v = (v | (v >> 2)) & 0x0f0f0f0f0f0f0f0f;
+        v = (v | (v >> 4)) & 0x00ff00ff00ff00ff;
+        v = (v | (v >> 8)) & 0x0000ffff0000ffff;
+        ((v | (v >> 16)) & 0x00000000ffffffff) as u32
+    }
+
+    (squash(bits), squash(bits >> 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_round_trip() {
+        // Palermo, from the Redis documentation.
+        let score = score(13.361389, 38.115556);
+        assert_eq!(score, 3479099956230698.0);
+        assert_eq!(geohash(score), "sqc8b49rny0");
+
+        let (longitude, latitude) = position(score);
+        assert!((longitude - 13.361389).abs() < 1e-5);
+        assert!((latitude - 38.115556).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_distance() {
+        let palermo = position(score(13.361389, 38.115556));
+        let catania = position(score(15.087269, 37.502669));
+        let d = distance(palermo.0, palermo.1, catania.0, catania.1);
+        assert_eq!(format!("{:.4}", d), "166274.1516");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let hash = encode(LONG_RANGE, LAT_RANGE, 0.0, 0.0, 4);
+        for neighbour in &neighbours(hash)[1..] {
+            assert_ne!(*neighbour, hash);
+            assert_eq!(neighbour.moved(0, 0), *neighbour);
+        }
+        assert_eq!(hash.moved(1, 0).moved(-1, 0), hash);
+        assert_eq!(hash.moved(0, 1).moved(0, -1), hash);
+    }
+}
@@ -0,0 +1,571 @@
+//! HyperLogLog values, kept byte for byte in the layout Redis uses so they can be read with GET
+//! and compared against, or restored into, a real Redis.
+//!
+//! A value is a 16 byte header followed by 2^14 registers of 6 bits each. The header holds the
+//! magic `HYLL`, the encoding, three unused bytes and the last cardinality computed, stored
+//! little endian, whose most significant bit is set when it is out of date.
+//!
+//! The dense encoding packs the registers in 12288 bytes, least significant bits first. The
+//! sparse encoding run length encodes them, which is far smaller while most registers are zero:
+//!
+//! - `00xxxxxx` is a run of 1 to 64 zero registers,
+//! - `01xxxxxx yyyyyyyy` a run of 1 to 16384 zero registers,
+//! - `1vvvvvxx` a run of 1 to 4 registers set to a value from 1 to 32.
+//!
+//! A value starts out sparse and turns dense once a register goes above 32 or the value grows
+//! past `SPARSE_MAX_BYTES`.
+
+/// Bits of the hash used to pick a register.
+const P: u32 = 14;
+const REGISTERS: usize = 1 << P;
+/// Bits of the hash left to count leading zeros in.
+const Q: u32 = 64 - P;
+const BITS: usize = 6;
+const REGISTER_MAX: u8 = (1 << BITS) - 1;
+const HEADER_SIZE: usize = 16;
+const DENSE_SIZE: usize = HEADER_SIZE + (REGISTERS * BITS).div_ceil(8);
+const DENSE: u8 = 0;
+const SPARSE: u8 = 1;
+
+/// The largest a sparse value grows to before it is made dense, as Redis's
+/// `hll-sparse-max-bytes` defaults to.
+const SPARSE_MAX_BYTES: usize = 3000;
+
+const SPARSE_VAL_MAX_VALUE: u8 = 32;
+const SPARSE_VAL_MAX_LEN: usize = 4;
+const SPARSE_ZERO_MAX_LEN: usize = 64;
+const SPARSE_XZERO_MAX_LEN: usize = 16384;
+
+/// 0.5 / ln(2), the bias correction constant as the number of registers goes to infinity.
+const ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+
+/// What went wrong with a value that was expected to be a HyperLogLog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The string doesn't look like a HyperLogLog at all.
+    Invalid,
+    /// It does, but its registers can't be decoded.
+    Corrupted,
+}
+
+/// A new HyperLogLog with every register zero, sparse encoded.
+pub fn new() -> Vec<u8> {
+    let mut hll = vec![0; HEADER_SIZE];
+    hll[..4].copy_from_slice(b"HYLL");
+    hll[4] = SPARSE;
+    push_xzero(&mut hll, REGISTERS);
+    hll
+}
+
+/// Checks that `hll` has a valid header for its encoding.
+pub fn validate(hll: &[u8]) -> Result<(), Error> {
+    if hll.len() < HEADER_SIZE || &hll[..4] != b"HYLL" || hll[4] > SPARSE {
+        return Err(Error::Invalid);
+    }
+    if hll[4] == DENSE && hll.len() != DENSE_SIZE {
+        return Err(Error::Invalid);
+    }
+    Ok(())
+}
+
+/// Adds an element. Returns true if a register changed, which means the estimate may have.
+pub fn add(hll: &mut Vec<u8>, element: &[u8]) -> Result<bool, Error> {
+    let (index, count) = pattern_len(element);
+    match hll[4] {
+        DENSE => Ok(dense_set(&mut hll[HEADER_SIZE..], index, count)),
+        _ => sparse_set(hll, index, count),
+    }
+}
+
+/// Marks the cached cardinality as out of date.
+pub fn invalidate_cache(hll: &mut [u8]) {
+    hll[15] |= 1 << 7;
+}
+
+/// The estimated number of distinct elements added, from the cache if it is up to date. The
+/// cache is refreshed otherwise.
+pub fn count(hll: &mut [u8]) -> Result<u64, Error> {
+    if hll[15] & (1 << 7) == 0 {
+        let mut card = [0; 8];
+        card.copy_from_slice(&hll[8..16]);
+        return Ok(u64::from_le_bytes(card));
+    }
+
+    let mut histogram = [0; 64];
+    match hll[4] {
+        DENSE => {
+            for index in 0..REGISTERS {
+                histogram[dense_get(&hll[HEADER_SIZE..], index) as usize] += 1;
+            }
+        }
+        _ => {
+            for_each_run(&hll[HEADER_SIZE..], |_, len, value| {
+                histogram[value as usize] += len
+            })?;
+        }
+    }
+
+    let card = estimate(&histogram);
+    hll[8..16].copy_from_slice(&card.to_le_bytes());
+    Ok(card)
+}
+
+/// Registers of a HyperLogLog laid out one per byte, used to combine several of them.
+pub type Registers = [u8; REGISTERS];
+
+pub fn registers() -> Box<Registers> {
+    Box::new([0; REGISTERS])
+}
+
+/// Sets each of `max` to the larger of it and the matching register of `hll`.
+pub fn merge(max: &mut Registers, hll: &[u8]) -> Result<(), Error> {
+    match hll[4] {
+        DENSE => {
+            for (index, max) in max.iter_mut().enumerate() {
+                *max = (*max).max(dense_get(&hll[HEADER_SIZE..], index));
+            }
+            Ok(())
+        }
+        _ => for_each_run(&hll[HEADER_SIZE..], |first, len, value| {
+            for max in &mut max[first..first + len] {
+                *max = (*max).max(value);
+            }
+        }),
+    }
+}
+
+/// The estimated number of distinct elements behind merged registers.
+pub fn count_registers(registers: &Registers) -> u64 {
+    let mut histogram = [0; 64];
+    for register in registers.iter() {
+        histogram[*register as usize] += 1;
+    }
+    estimate(&histogram)
+}
+
+/// Raises the registers of `hll` to `max` where they are lower, turning it dense first if
+/// `dense` is set, and marks its cached cardinality out of date.
+pub fn store(hll: &mut Vec<u8>, max: &Registers, dense: bool) -> Result<(), Error> {
+    if dense {
+        sparse_to_dense(hll)?;
+    }
+
+    for (index, &count) in max.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        match hll[4] {
+            DENSE => {
+                dense_set(&mut hll[HEADER_SIZE..], index, count);
+            }
+            _ => {
+                sparse_set(hll, index, count)?;
+            }
+        }
+    }
+
+    invalidate_cache(hll);
+    Ok(())
+}
+
+pub fn is_dense(hll: &[u8]) -> bool {
+    hll[4] == DENSE
+}
+
+/// Hashes an element, returning the register it goes to and the position of the first set bit
+/// in the rest of the hash, which is what the register keeps the maximum of.
+fn pattern_len(element: &[u8]) -> (usize, u8) {
+    let hash = murmur_hash64a(element, 0xadc83b19);
+    let index = (hash & (REGISTERS as u64 - 1)) as usize;
+    // The extra bit makes sure the count stops at Q + 1.
+    let rest = (hash >> P) | (1 << Q);
+    (index, rest.trailing_zeros() as u8 + 1)
+}
+
+/// MurmurHash2, 64 bit version, as Redis uses it, reading words little endian.
+fn murmur_hash64a(key: &[u8], seed: u32) -> u64 {
+    const M: u64 = 0xc6a4a7935bd1e995;
+    const R: u32 = 47;
+
+    let mut h = seed as u64 ^ (key.len() as u64).wrapping_mul(M);
+
+    let mut chunks = key.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, byte) in tail.iter().enumerate() {
+            h ^= (*byte as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h
+}
+
+/// Estimates the cardinality from how many registers hold each value, with the method from
+/// Otmar Ertl's "New cardinality estimation algorithms for HyperLogLog sketches" that Redis
+/// uses.
+fn estimate(histogram: &[usize; 64]) -> u64 {
+    let m = REGISTERS as f64;
+    let q = Q as usize;
+
+    let mut z = m * tau((m - histogram[q + 1] as f64) / m);
+    for j in (1..=q).rev() {
+        z += histogram[j] as f64;
+        z *= 0.5;
+    }
+    z += m * sigma(histogram[0] as f64 / m);
+
+    (ALPHA_INF * m * m / z).round() as u64
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if z == previous {
+            return z / 3.0;
+        }
+    }
+}
+
+fn dense_get(registers: &[u8], index: usize) -> u8 {
+    let byte = index * BITS / 8;
+    let shift = index * BITS % 8;
+    let low = registers[byte] as u16;
+    let high = registers.get(byte + 1).copied().unwrap_or(0) as u16;
+    (((low | (high << 8)) >> shift) as u8) & REGISTER_MAX
+}
+
+/// Raises the register to `count` if it is lower. Returns true if it was.
+fn dense_set(registers: &mut [u8], index: usize, count: u8) -> bool {
+    if dense_get(registers, index) >= count {
+        return false;
+    }
+
+    let byte = index * BITS / 8;
+    let shift = index * BITS % 8;
+    registers[byte] &= !(REGISTER_MAX << shift);
+    registers[byte] |= count << shift;
+    if shift + BITS > 8 {
+        let high = 8 - shift;
+        registers[byte + 1] &= !(REGISTER_MAX >> high);
+        registers[byte + 1] |= count >> high;
+    }
+    true
+}
+
+fn is_zero(op: u8) -> bool {
+    op & 0xc0 == 0
+}
+
+fn is_xzero(op: u8) -> bool {
+    op & 0xc0 == 0x40
+}
+
+fn zero_len(op: u8) -> usize {
+    (op & 0x3f) as usize + 1
+}
+
+fn xzero_len(op: u8, next: u8) -> usize {
+    (((op & 0x3f) as usize) << 8 | next as usize) + 1
+}
+
+fn val_value(op: u8) -> u8 {
+    ((op >> 2) & 0x1f) + 1
+}
+
+fn val_len(op: u8) -> usize {
+    (op & 0x3) as usize + 1
+}
+
+fn val(value: u8, len: usize) -> u8 {
+    0x80 | (value - 1) << 2 | (len - 1) as u8
+}
+
+/// Appends zero runs covering `len` registers.
+fn push_xzero(out: &mut Vec<u8>, mut len: usize) {
+    while len > 0 {
+        let run = len.min(SPARSE_XZERO_MAX_LEN);
+        push_zero(out, run);
+        len -= run;
+    }
+}
+
+/// Appends a single opcode for a run of `len` zero registers.
+fn push_zero(out: &mut Vec<u8>, len: usize) {
+    if len > SPARSE_ZERO_MAX_LEN {
+        out.push(0x40 | ((len - 1) >> 8) as u8);
+        out.push(((len - 1) & 0xff) as u8);
+    } else {
+        out.push((len - 1) as u8);
+    }
+}
+
+/// Calls `f` with the first register, length and value of each run in a sparse encoding.
+fn for_each_run(sparse: &[u8], mut f: impl FnMut(usize, usize, u8)) -> Result<(), Error> {
+    let mut index = 0;
+    let mut p = 0;
+
+    while p < sparse.len() {
+        let (len, value, op_len) = if is_zero(sparse[p]) {
+            (zero_len(sparse[p]), 0, 1)
+        } else if is_xzero(sparse[p]) {
+            let next = *sparse.get(p + 1).ok_or(Error::Corrupted)?;
+            (xzero_len(sparse[p], next), 0, 2)
+        } else {
+            (val_len(sparse[p]), val_value(sparse[p]), 1)
+        };
+        if index + len > REGISTERS {
+            return Err(Error::Corrupted);
+        }
+
+        f(index, len, value);
+        index += len;
+        p += op_len;
+    }
+
+    match index == REGISTERS {
+        true => Ok(()),
+        false => Err(Error::Corrupted),
+    }
+}
+
+/// Converts a sparse value to dense. A dense one is left alone.
+fn sparse_to_dense(hll: &mut Vec<u8>) -> Result<(), Error> {
+    if hll[4] == DENSE {
+        return Ok(());
+    }
+
+    let mut dense = vec![0; DENSE_SIZE];
+    dense[..HEADER_SIZE].copy_from_slice(&hll[..HEADER_SIZE]);
+    dense[4] = DENSE;
+    for_each_run(&hll[HEADER_SIZE..], |first, len, value| {
+        for index in first..first + len {
+            dense_set(&mut dense[HEADER_SIZE..], index, value);
+        }
+    })?;
+
+    *hll = dense;
+    Ok(())
+}
+
+/// Raises a register of a sparse value to `count` if it is lower, the way Redis does it so the
+/// bytes come out the same: the opcode covering the register is split in place, then runs of
+/// equal values around it are merged. The value is made dense if it has to be. Returns true if
+/// the register changed.
+fn sparse_set(hll: &mut Vec<u8>, index: usize, count: u8) -> Result<bool, Error> {
+    if count > SPARSE_VAL_MAX_VALUE {
+        return promote(hll, index, count);
+    }
+
+    // Find the opcode covering the register.
+    let mut p = HEADER_SIZE;
+    let mut first = 0;
+    let mut prev = None;
+    let mut span = 0;
+    while p < hll.len() {
+        let op_len;
+        (span, op_len) = if is_zero(hll[p]) {
+            (zero_len(hll[p]), 1)
+        } else if is_xzero(hll[p]) {
+            let next = *hll.get(p + 1).ok_or(Error::Corrupted)?;
+            (xzero_len(hll[p], next), 2)
+        } else {
+            (val_len(hll[p]), 1)
+        };
+        if index < first + span {
+            break;
+        }
+        prev = Some(p);
+        p += op_len;
+        first += span;
+    }
+    if span == 0 || p >= hll.len() {
+        return Err(Error::Corrupted);
+    }
+
+    let op = hll[p];
+    let is_val = !is_zero(op) && !is_xzero(op);
+    if is_val && val_value(op) >= count {
+        return Ok(false);
+    }
+
+    if span == 1 && !is_xzero(op) {
+        hll[p] = val(count, 1);
+    } else {
+        let last = first + span - 1;
+        let mut seq = Vec::with_capacity(5);
+        if is_val {
+            let current = val_value(op);
+            if index != first {
+                seq.push(val(current, index - first));
+            }
+            seq.push(val(count, 1));
+            if index != last {
+                seq.push(val(current, last - index));
+            }
+        } else {
+            if index != first {
+                push_zero(&mut seq, index - first);
+            }
+            seq.push(val(count, 1));
+            if index != last {
+                push_zero(&mut seq, last - index);
+            }
+        }
+
+        let old_len = if is_xzero(op) { 2 } else { 1 };
+        if seq.len() > old_len && hll.len() + seq.len() - old_len > SPARSE_MAX_BYTES {
+            return promote(hll, index, count);
+        }
+        hll.splice(p..p + old_len, seq);
+    }
+
+    // Merge adjacent runs of the same value, looking at up to five opcodes from the one before
+    // the change.
+    let mut p = prev.unwrap_or(HEADER_SIZE);
+    let mut scan = 5;
+    while p < hll.len() && scan > 0 {
+        scan -= 1;
+        if is_xzero(hll[p]) {
+            p += 2;
+            continue;
+        } else if is_zero(hll[p]) {
+            p += 1;
+            continue;
+        }
+
+        if p + 1 < hll.len() && !is_zero(hll[p + 1]) && !is_xzero(hll[p + 1]) {
+            let value = val_value(hll[p]);
+            let len = val_len(hll[p]) + val_len(hll[p + 1]);
+            if value == val_value(hll[p + 1]) && len <= SPARSE_VAL_MAX_LEN {
+                hll[p + 1] = val(value, len);
+                hll.remove(p);
+                // Try merging the merged run with the one after it too.
+                continue;
+            }
+        }
+        p += 1;
+    }
+
+    invalidate_cache(hll);
+    Ok(true)
+}
+
+/// Turns a sparse value dense so a register can be set that the sparse encoding can't hold.
+fn promote(hll: &mut Vec<u8>, index: usize, count: u8) -> Result<bool, Error> {
+    sparse_to_dense(hll)?;
+    Ok(dense_set(&mut hll[HEADER_SIZE..], index, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        let mut hll = new();
+        assert_eq!(hll.len(), HEADER_SIZE + 2);
+        assert_eq!(&hll[HEADER_SIZE..], &[0x7f, 0xff]);
+        assert_eq!(validate(&hll), Ok(()));
+        assert_eq!(count(&mut hll), Ok(0));
+    }
+
+    #[test]
+    fn test_sparse_and_dense_agree() {
+        let mut sparse = new();
+        let mut expected = registers();
+        for i in 0..1000 {
+            let element = format!("element:{}", i);
+            add(&mut sparse, element.as_bytes()).unwrap();
+            let (index, count) = pattern_len(element.as_bytes());
+            expected[index] = expected[index].max(count);
+        }
+        assert!(!is_dense(&sparse));
+
+        let mut dense = sparse.clone();
+        sparse_to_dense(&mut dense).unwrap();
+        assert!(is_dense(&dense));
+        assert_eq!(validate(&dense), Ok(()));
+
+        let mut from_sparse = registers();
+        let mut from_dense = registers();
+        merge(&mut from_sparse, &sparse).unwrap();
+        merge(&mut from_dense, &dense).unwrap();
+        assert_eq!(from_sparse, expected);
+        assert_eq!(from_dense, expected);
+
+        invalidate_cache(&mut sparse);
+        invalidate_cache(&mut dense);
+        let estimate = count(&mut sparse).unwrap();
+        assert_eq!(count(&mut dense), Ok(estimate));
+        assert!(estimate.abs_diff(1000) < 20, "{}", estimate);
+    }
+
+    #[test]
+    fn test_error_is_within_bounds() {
+        let mut hll = new();
+        for i in 0..100_000 {
+            add(&mut hll, format!("{}", i).as_bytes()).unwrap();
+        }
+        assert!(is_dense(&hll));
+
+        // The standard error is 0.81%; allow a few of them.
+        invalidate_cache(&mut hll);
+        let estimate = count(&mut hll).unwrap() as f64;
+        assert!(
+            (estimate - 100_000.0).abs() / 100_000.0 < 0.03,
+            "{}",
+            estimate
+        );
+    }
+
+    #[test]
+    fn test_murmur_hash() {
+        assert_eq!(murmur_hash64a(b"", 0xadc83b19), 15627466953755236146);
+        assert_eq!(murmur_hash64a(b"a", 0xadc83b19), 6039968161137406375);
+        assert_eq!(murmur_hash64a(b"hello", 0xadc83b19), 1109414937308947456);
+        assert_eq!(
+            murmur_hash64a(b"0123456789abcdef!", 0xadc83b19),
+            13337350489090520692
+        );
+    }
+}
@@ -1,23 +1,78 @@
+mod blocking;
+mod client;
 mod commands;
-mod resp;
+mod db;
+mod functions;
+mod geo;
+mod glob;
+mod hyperloglog;
+mod persistence;
+mod pubsub;
+mod rdb;
+mod registry;
+pub mod resp;
+mod scripting;
+mod watched;
 
-use resp::types::{Encoded, Error, SimpleString};
+pub use db::Db;
+
+use blocking::{Blocked, Outcome};
+use client::Client;
+use db::{Keyspace, Value};
+use registry::Handler;
+use resp::decoder::Decoder;
+use resp::types::Frame;
 
 use std::collections::VecDeque;
 use std::fmt;
-use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a blocked client's connection is checked to see if it has hung up.
+const BLOCKED_CLIENT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 
+/// Serves clients on `address`, starting from the dataset saved in `dump.rdb` if there is one,
+/// and saving it again whenever a save point is reached.
 pub fn listen(address: &str) -> std::io::Result<()> {
     let listener = TcpListener::bind(address)?;
+    let db = Db::new();
+    db.load()?;
+    db.spawn_save_scheduler()?;
+
+    serve(listener, db)
+}
+
+/// Accepts clients on `listener` and serves each one from its own thread, so a slow client only
+/// ever holds up itself. Every connection works against the same `db`, whose expired keys are
+/// reclaimed in the background.
+pub fn serve(listener: TcpListener, db: Db) -> std::io::Result<()> {
+    db.spawn_active_expire()?;
 
     for stream in listener.incoming() {
         match stream {
-            Ok(mut stream) => {
-                let _ = handle_connection(&mut stream);
+            Ok(stream) => {
+                let db = db.clone();
+                let spawned =
+                    thread::Builder::new()
+                        .name(String::from("client"))
+                        .spawn(move || {
+                            let mut stream = stream;
+                            let _ = handle_connection(&mut stream, &db);
+                        });
+
+                // Out of threads: dropping the stream closes the connection, but the server
+                // keeps accepting so it recovers once other clients go away.
+                if let Err(e) = spawned {
+                    eprintln!("Error: {:?}", e);
+                }
             }
             Err(e) => {
-                panic!("Error: {:?}", e);
+                // Accept failures such as running out of file descriptors only affect the
+                // client being accepted.
+                eprintln!("Error: {:?}", e);
             }
         }
     }
@@ -25,71 +80,354 @@ pub fn listen(address: &str) -> std::io::Result<()> {
     Ok(())
 }
 
-fn handle_connection(stream: &mut TcpStream) -> std::io::Result<()> {
-    // XXX Max length of command is 512B. We should be able to do up to 512MB based on the RESP
-    // protocol.
-    let mut buffer = [0; 512];
-    let bytes_read = stream.read(&mut buffer[..])?;
+/// Serves commands from one client until it disconnects.
+///
+/// Replies are written out by a thread of their own, which is also where messages published to
+/// the client's channels go, so they reach it even while it is waiting for its next command.
+fn handle_connection(stream: &mut TcpStream, db: &Db) -> std::io::Result<()> {
+    let (output, replies) = mpsc::channel();
+    let writer = stream.try_clone()?;
+    thread::Builder::new()
+        .name(String::from("client writer"))
+        .spawn(move || write_replies(writer, replies))?;
 
-    let mut input: Vec<u8> = vec![];
-    for i in 0..bytes_read {
-        input.push(buffer[i]);
-    }
-    println!("{:?}", input);
+    let mut client = Client::new(output);
+    let served = serve_client(stream, &mut client, db);
 
-    let mut decoded = match decode_resp(&input) {
-        Ok(decoded) => decoded,
-        Err(e) => {
-            let reply = Error::new(String::from(e.to_string())).to_encoded_string();
-            let _ = stream.write_all(reply.as_bytes());
+    // Nothing else is sent to the client once it is gone, which lets its writer finish.
+    let mut ks = db.lock();
+    ks.pubsub().remove(client.id);
+    ks.unwatch_all(client.id);
+    served
+}
+
+/// Reads and answers commands until the client hangs up.
+///
+/// Bytes are fed to a decoder as they arrive so a command split over several packets is
+/// reassembled, and every complete command already read is answered before reading again,
+/// which is what makes pipelining work.
+fn serve_client(stream: &mut TcpStream, client: &mut Client, db: &Db) -> std::io::Result<()> {
+    let mut buffer = [0; 16 * 1024];
+    let mut decoder = Decoder::new();
+
+    loop {
+        let bytes_read = stream.read(&mut buffer[..])?;
+        if bytes_read == 0 {
             return Ok(());
         }
-    };
 
-    if decoded.len() < 1 {
-        let reply = Error::new(String::from("expected a command")).to_encoded_string();
-        let _ = stream.write_all(reply.as_bytes());
-        return Ok(());
+        decoder.feed(&buffer[..bytes_read]);
+
+        loop {
+            match decoder.decode_command() {
+                Ok(Some(frame)) => match handle_request(frame, client, db) {
+                    Some(Outcome::Ready(reply)) => client.send(&reply),
+                    Some(Outcome::Blocked(blocked)) => {
+                        client.send(&wait_until_served(blocked, stream, db))
+                    }
+                    None => {}
+                },
+                Ok(None) => break,
+                Err(e) => {
+                    // There is no telling where the next command starts, so answer what was
+                    // already decoded, report the error and hang up.
+                    client.send(&Frame::error(format!("ERR {}", e)));
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Writes out everything sent to a client until it has gone. Whatever piled up meanwhile goes
+/// out in one write, so pipelined replies don't cost a write each.
+fn write_replies(mut stream: TcpStream, replies: Receiver<Vec<u8>>) {
+    while let Ok(mut buffer) = replies.recv() {
+        buffer.extend(replies.try_iter().flatten());
+        if stream.write_all(&buffer).is_err() {
+            return;
+        }
+    }
+}
+
+/// Waits for a blocked client to be served, giving up when its timeout runs out or the client
+/// hangs up, so a client that has gone away doesn't take elements meant for others.
+fn wait_until_served(blocked: Blocked, stream: &TcpStream, db: &Db) -> Frame {
+    let deadline = blocked.timeout.map(|timeout| Instant::now() + timeout);
+
+    loop {
+        let mut wait = BLOCKED_CLIENT_CHECK_INTERVAL;
+        if let Some(deadline) = deadline {
+            wait = wait.min(deadline.saturating_duration_since(Instant::now()));
+        }
+
+        if let Ok(reply) = blocked.receiver.recv_timeout(wait) {
+            return reply;
+        }
+
+        let timed_out = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        if timed_out || has_hung_up(stream) {
+            if db.lock().unblock(blocked.id) {
+                return blocked.timeout_reply;
+            }
+
+            // Served while the lock was being taken.
+            return blocked.receiver.try_recv().unwrap_or(blocked.timeout_reply);
+        }
     }
+}
 
-    let mut cmd = Command {
-        command: decoded.pop_front().unwrap(),
-        args: decoded,
+fn has_hung_up(stream: &TcpStream) -> bool {
+    // A short read timeout rather than non-blocking mode, which would also apply to the
+    // writer's handle on the socket.
+    if stream
+        .set_read_timeout(Some(Duration::from_millis(1)))
+        .is_err()
+    {
+        return false;
+    }
+    let peeked = stream.peek(&mut [0; 1]);
+    let _ = stream.set_read_timeout(None);
+
+    matches!(peeked, Ok(0))
+}
+
+fn handle_request(frame: Frame, client: &mut Client, db: &Db) -> Option<Outcome> {
+    let mut cmd = match Command::from_frame(frame) {
+        Ok(cmd) => cmd,
+        Err(e) => return Some(Outcome::Ready(Frame::error(e.to_string()))),
     };
 
-    println!("{:?}", cmd);
+    handle_reply(&mut cmd, client, db)
+}
 
-    let reply = handle_reply(&mut cmd);
+/// Runs the command, or queues it if the client is in a transaction.
+fn handle_reply(cmd: &mut Command, client: &mut Client, db: &Db) -> Option<Outcome> {
+    let name = cmd.command.to_lowercase();
+    if registry::lookup(&name).is_none() {
+        if let Some(transaction) = client.transaction.as_mut() {
+            transaction.failed = true;
+        }
+        let e = registry::unknown_command(&cmd.command, cmd.args.make_contiguous());
+        return Some(Outcome::Ready(e.into()));
+    }
+    let allowed_when_subscribed = SUBSCRIPTION_COMMANDS.contains(&name.as_str())
+        || ["ping", "quit", "reset"].contains(&name.as_str());
+    if client.in_subscribed_mode() && !allowed_when_subscribed {
+        return Some(Outcome::Ready(Frame::error(format!(
+            "ERR Can't execute '{}': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING / QUIT / RESET \
+             are allowed in this context",
+            name
+        ))));
+    }
 
-    let _ = stream.write_all(reply.as_bytes());
-    Ok(())
+    if client.transaction.is_some() && !TRANSACTION_COMMANDS.contains(&name.as_str()) {
+        return Some(Outcome::Ready(queue(name, &mut cmd.args, client)));
+    }
+
+    // A running script holds the keyspace, so killing it mustn't wait for the lock. Once the
+    // script has run for too long, other commands are told so rather than left waiting.
+    let kills_script = (name == "script" || name == "function")
+        && cmd.args.len() == 1
+        && cmd.args[0].eq_ignore_ascii_case(b"KILL");
+    if kills_script {
+        let reply = commands::script::kill(db.scripts()).unwrap_or_else(Frame::from);
+        return Some(Outcome::Ready(reply));
+    }
+
+    let Some(mut ks) = db.lock_unless_busy() else {
+        // Exiting without saving doesn't need the keyspace, so it works on a script that can't
+        // be killed.
+        if name == "shutdown" && commands::shutdown::parse(&cmd.args) == Ok(Some(false)) {
+            std::process::exit(0);
+        }
+        return Some(Outcome::Ready(Frame::error(
+            "BUSY Redis is busy running a script. You can only call SCRIPT KILL, FUNCTION KILL or SHUTDOWN NOSAVE.",
+        )));
+    };
+    dispatch(&name, &mut cmd.args, client, &mut ks)
 }
 
-fn handle_reply(cmd: &mut Command) -> String {
-    let reply: Box<dyn Encoded> = match cmd.command.to_lowercase().as_str() {
-        "ping" => commands::ping::execute(&mut cmd.args),
-        "echo" => commands::echo::execute(&mut cmd.args),
-        _ => Error::new(String::from("UNIMPLEMENTED")),
+/// Queues a command for EXEC to run. A command that can't be queued because it could never
+/// run makes EXEC discard the whole transaction.
+fn queue(name: String, args: &mut VecDeque<Vec<u8>>, client: &mut Client) -> Frame {
+    let checked = registry::resolve(&name, args.make_contiguous()).and_then(|spec| {
+        if SUBSCRIPTION_COMMANDS.contains(&name.as_str()) || spec.has_flag("no_multi") {
+            return Err(commands::CommandError::Other(String::from(
+                "ERR Command not allowed inside a transaction",
+            )));
+        }
+        Ok(())
+    });
+
+    let transaction = client.transaction.as_mut().unwrap();
+    match checked {
+        Ok(()) => {
+            transaction.commands.push((name, std::mem::take(args)));
+            Frame::SimpleString(String::from("QUEUED"))
+        }
+        Err(e) => {
+            transaction.failed = true;
+            e.into()
+        }
+    }
+}
+
+/// Runs the command against the locked keyspace. It either replies straight away or leaves the
+/// client blocked; commands that manage subscriptions send their replies themselves, so leave
+/// nothing to reply with.
+fn dispatch(
+    name: &str,
+    args: &mut VecDeque<Vec<u8>>,
+    client: &mut Client,
+    ks: &mut Keyspace,
+) -> Option<Outcome> {
+    let spec = match registry::resolve(name, args.make_contiguous()) {
+        Ok(spec) => spec,
+        Err(e) => return Some(Outcome::Ready(e.into())),
     };
+    let written = written_keys(spec, name, args, ks);
 
-    reply.to_encoded_string()
+    let reply = match spec.handler {
+        Handler::Plain(execute) => execute(args),
+        Handler::Client(execute) => execute(args, client),
+        Handler::Keyspace(execute) => execute(args, ks),
+        Handler::ClientKeyspace(execute) => execute(args, client, ks),
+        Handler::Dispatching(execute) => execute(args, client, ks, dispatch),
+        Handler::Blocking(execute) => {
+            let outcome = execute(args, ks);
+            record_write(spec, written, outcome.is_ok(), ks);
+            return Some(outcome.unwrap_or_else(|e| Outcome::Ready(e.into())));
+        }
+        Handler::Subscribe(execute) => {
+            subscribe(execute(args, client, ks), client);
+            return None;
+        }
+    };
+    record_write(spec, written, reply.is_ok(), ks);
+
+    Some(Outcome::Ready(reply.unwrap_or_else(Frame::from)))
+}
+
+/// The watched keys a write command is about to write to, with their values beforehand. They
+/// are picked out before the command runs, since running it uses up its arguments.
+fn written_keys(
+    spec: &registry::CommandSpec,
+    name: &str,
+    args: &mut VecDeque<Vec<u8>>,
+    ks: &mut Keyspace,
+) -> Vec<(Vec<u8>, Option<Value>)> {
+    if !spec.has_flag("write") || !ks.has_watched_keys() {
+        return Vec::new();
+    }
+    args.push_front(name.as_bytes().to_vec());
+    let positions = spec.key_positions(args.make_contiguous());
+    args.pop_front();
+    positions
+        .unwrap_or_default()
+        .into_iter()
+        .map(|position| args[position - 1].clone())
+        .filter_map(|key| {
+            let value = ks.is_watched(&key).then(|| ks.get(&key).cloned())?;
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Counts a write command that didn't fail as a change towards the save points, and touches
+/// the watched keys it changed in place for the transactions watching them. A command that
+/// leaves a key as it was, such as SETNX on a key that exists, doesn't touch it. Redis counts
+/// each key changed instead, which only makes a difference to how soon a save is due.
+fn record_write(
+    spec: &registry::CommandSpec,
+    written: Vec<(Vec<u8>, Option<Value>)>,
+    succeeded: bool,
+    ks: &mut Keyspace,
+) {
+    if succeeded && spec.has_flag("write") {
+        ks.persistence().add_dirty(1);
+    }
+    for (key, before) in written {
+        if ks.get(&key) != before.as_ref() {
+            ks.touch(&key);
+        }
+    }
 }
 
+/// The commands that run straight away in a transaction rather than being queued.
+const TRANSACTION_COMMANDS: [&str; 6] = ["exec", "discard", "multi", "watch", "quit", "reset"];
+
+/// The commands that manage subscriptions, which are most of what a RESP2 client may run while
+/// it is subscribed to anything.
+const SUBSCRIPTION_COMMANDS: [&str; 6] = [
+    "subscribe",
+    "unsubscribe",
+    "psubscribe",
+    "punsubscribe",
+    "ssubscribe",
+    "sunsubscribe",
+];
+
+/// Sends the replies of a command that subscribes or unsubscribes the client, one per channel.
+/// This happens before the lock is let go, so no message published to a channel can overtake
+/// the confirmation of subscribing to it.
+fn subscribe(replies: commands::Result<Vec<Frame>>, client: &mut Client) {
+    match replies {
+        Ok(replies) => replies.iter().for_each(|reply| client.send(reply)),
+        Err(e) => client.send(&e.into()),
+    }
+}
+
+/// A request from a client. Arguments are kept as the raw bytes that were sent, since keys and
+/// values may be arbitrary binary data; only the command name is read as text.
 #[derive(Debug)]
 struct Command {
     command: String,
-    args: VecDeque<String>,
+    args: VecDeque<Vec<u8>>,
+}
+
+impl Command {
+    /// Commands arrive as an array of bulk strings: the command name followed by its
+    /// arguments.
+    fn from_frame(frame: Frame) -> Result<Command> {
+        let elements = match frame {
+            Frame::Array(elements) => elements,
+            _ => return Err(RedisError::NotAnArrayError),
+        };
+
+        let mut args: VecDeque<Vec<u8>> = VecDeque::with_capacity(elements.len());
+        for element in elements {
+            match element {
+                Frame::BulkString(bytes) => args.push_back(bytes),
+                other => return Err(RedisError::NotABulkStringError(other.prefix())),
+            }
+        }
+
+        match args.pop_front() {
+            Some(command) => Ok(Command {
+                command: String::from_utf8_lossy(&command).into_owned(),
+                args,
+            }),
+            None => Err(RedisError::EmptyCommand),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 enum RedisError {
     NotAnArrayError,
+    NotABulkStringError(char),
+    EmptyCommand,
 }
 
 impl fmt::Display for RedisError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             RedisError::NotAnArrayError => write!(f, "expected an array resp type (*)"),
+            RedisError::NotABulkStringError(prefix) => {
+                write!(f, "ERR Protocol error: expected '$', got '{}'", prefix)
+            }
+            RedisError::EmptyCommand => write!(f, "expected a command"),
         }
     }
 }
@@ -98,93 +436,1155 @@ impl std::error::Error for RedisError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
             RedisError::NotAnArrayError => None,
+            RedisError::NotABulkStringError(_) => None,
+            RedisError::EmptyCommand => None,
         }
     }
 }
 
 type Result<T> = std::result::Result<T, RedisError>;
 
-fn decode_resp(input: &Vec<u8>) -> Result<VecDeque<String>> {
-    let mut cursor = Cursor::new(input);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+    use std::time::Duration;
+
+    /// Starts a server on an ephemeral port and returns the address it is listening on.
+    fn start_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
 
-    let decoded_type = read_bytes(&mut cursor, 1);
-    match decoded_type.as_str() {
-        "*" => Ok(do_decode_array(&mut cursor)),
-        _ => Err(RedisError::NotAnArrayError),
+        thread::spawn(move || serve(listener, Db::new()));
+
+        address
     }
-}
 
-/// Reads num_bytes through the cursor and returns the results as a String.
-fn read_bytes(cursor: &mut Cursor<&Vec<u8>>, num_bytes: usize) -> String {
-    let mut buf = vec![0u8; num_bytes];
-    cursor.read_exact(&mut buf).unwrap();
-    String::from_utf8(buf).unwrap()
-}
+    fn send(address: &str, request: &[u8]) -> Vec<u8> {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        stream.write_all(request).unwrap();
+        stream.shutdown(std::net::Shutdown::Write).unwrap();
 
-/// Reads sequentially through the cursor until it hits a CRLF.
-/// At that point, it parses the underlying Vec<u8> into String and from there into an integer.
-fn read_integer(mut cursor: &mut Cursor<&Vec<u8>>) -> usize {
-    let mut result = read_bytes(&mut cursor, 1);
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).unwrap();
+        reply
+    }
 
-    while !result.ends_with("\r\n") {
-        result = result + &read_bytes(&mut cursor, 1);
+    #[test]
+    fn test_slow_client_does_not_block_others() {
+        let address = start_server();
+
+        // Connects but never sends a command.
+        let _slow = TcpStream::connect(&address).unwrap();
+
+        assert_eq!(send(&address, b"*1\r\n$4\r\nPING\r\n"), b"+PONG\r\n");
     }
 
-    let trimmed = String::from(result.trim_end());
-    trimmed.parse().unwrap()
-}
+    #[test]
+    fn test_many_simultaneous_clients() {
+        let address = start_server();
 
-fn do_decode_bulk_string(mut cursor: &mut Cursor<&Vec<u8>>) -> String {
-    let bulk_string_len = read_integer(&mut cursor);
-    let result = read_bytes(&mut cursor, bulk_string_len);
+        let clients: Vec<_> = (0..200)
+            .map(|i| {
+                let address = address.clone();
+                thread::spawn(move || {
+                    let word = format!("client{}", i);
+                    let request = format!("*2\r\n$4\r\nECHO\r\n${}\r\n{}\r\n", word.len(), word);
+                    let reply = send(&address, request.as_bytes());
+                    assert_eq!(
+                        reply,
+                        format!("${}\r\n{}\r\n", word.len(), word).into_bytes()
+                    );
+                })
+            })
+            .collect();
 
-    // Seek past the TERMINATOR
-    cursor.seek(SeekFrom::Current(2)).unwrap();
+        for client in clients {
+            client.join().unwrap();
+        }
+    }
 
-    result
-}
+    #[test]
+    fn test_pipelined_commands_are_answered_in_order() {
+        let address = start_server();
 
-fn do_decode_array(mut cursor: &mut Cursor<&Vec<u8>>) -> VecDeque<String> {
-    let array_len = read_integer(&mut cursor);
+        let reply = send(
+            &address,
+            b"*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nECHO\r\n$3\r\none\r\n*2\r\n$4\r\nECHO\r\n$3\r\ntwo\r\n",
+        );
 
-    let mut elements: VecDeque<String> = VecDeque::with_capacity(array_len);
+        assert_eq!(reply, b"+PONG\r\n$3\r\none\r\n$3\r\ntwo\r\n");
+    }
 
-    for _i in 0..array_len {
-        // Seek past the bulk string type character
-        // XXX It may make more sense to check that the type is a bulk string type (defense for bad input)
-        cursor.seek(SeekFrom::Current(1)).unwrap();
+    #[test]
+    fn test_command_split_across_reads() {
+        let address = start_server();
+
+        let mut stream = TcpStream::connect(&address).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        stream.set_nodelay(true).unwrap();
 
-        let elem = do_decode_bulk_string(&mut cursor);
-        elements.push_back(elem);
+        stream.write_all(b"*2\r\n$4\r\nEC").unwrap();
+        thread::sleep(Duration::from_millis(50));
+        stream.write_all(b"HO\r\n$5\r\nhel").unwrap();
+        thread::sleep(Duration::from_millis(50));
+        stream.write_all(b"lo\r\n").unwrap();
+
+        let mut reply = [0; 11];
+        stream.read_exact(&mut reply).unwrap();
+        assert_eq!(&reply, b"$5\r\nhello\r\n");
     }
 
-    elements
-}
+    #[test]
+    fn test_client_library_reuses_connection() {
+        let address = start_server();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        for _ in 0..3 {
+            let pong: String = redis::cmd("PING").query(&mut con).unwrap();
+            assert_eq!(pong, "PONG");
+        }
+
+        let echoed: String = redis::cmd("ECHO").arg("hi").query(&mut con).unwrap();
+        assert_eq!(echoed, "hi");
+    }
+
+    #[test]
+    fn test_binary_arguments_round_trip() {
+        let address = start_server();
+
+        let reply = send(&address, b"*2\r\n$4\r\nECHO\r\n$4\r\n\xff\x00\r\n\r\n");
+        assert_eq!(reply, b"$4\r\n\xff\x00\r\n\r\n");
+
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+        let blob: Vec<u8> = (0..=255).collect();
+        let echoed: Vec<u8> = redis::cmd("ECHO").arg(&blob).query(&mut con).unwrap();
+        assert_eq!(echoed, blob);
+    }
+
+    #[test]
+    fn test_keys_are_shared_between_connections() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut writer = client.get_connection().unwrap();
+        let mut reader = client.get_connection().unwrap();
+
+        let _: () = redis::cmd("SET")
+            .arg("shared")
+            .arg(b"\xff\x00")
+            .query(&mut writer)
+            .unwrap();
+        let value: Vec<u8> = redis::cmd("GET").arg("shared").query(&mut reader).unwrap();
+        assert_eq!(value, b"\xff\x00");
+
+        let missing: Option<String> = redis::cmd("GET").arg("missing").query(&mut reader).unwrap();
+        assert_eq!(missing, None);
+
+        let reply = send(&address, b"SET shared\r\n");
+        assert_eq!(
+            reply,
+            b"-ERR wrong number of arguments for 'set' command\r\n"
+        );
+    }
+
+    #[test]
+    fn test_keys_expire() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let _: () = redis::cmd("SET")
+            .arg("cached")
+            .arg("v")
+            .query(&mut con)
+            .unwrap();
+        let set: i64 = redis::cmd("PEXPIRE")
+            .arg("cached")
+            .arg(50)
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(set, 1);
+        let ttl: i64 = redis::cmd("PTTL").arg("cached").query(&mut con).unwrap();
+        assert!(ttl > 0 && ttl <= 50);
+
+        thread::sleep(Duration::from_millis(100));
+
+        let value: Option<String> = redis::cmd("GET").arg("cached").query(&mut con).unwrap();
+        assert_eq!(value, None);
+        let ttl: i64 = redis::cmd("TTL").arg("cached").query(&mut con).unwrap();
+        assert_eq!(ttl, -2);
+    }
+
+    #[test]
+    fn test_lists_and_wrong_type() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let len: i64 = redis::cmd("RPUSH")
+            .arg("jobs")
+            .arg(&["a", "b", "c"])
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(len, 3);
+        let jobs: Vec<String> = redis::cmd("LRANGE")
+            .arg("jobs")
+            .arg(0)
+            .arg(-1)
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(jobs, vec!["a", "b", "c"]);
+
+        let reply = send(&address, b"GET jobs\r\n");
+        assert_eq!(
+            reply,
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+        );
+    }
+
+    #[test]
+    fn test_hashes_and_field_expiry() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let added: i64 = redis::cmd("HSET")
+            .arg("user")
+            .arg(&["name", "ada", "session", "abc"])
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(added, 2);
+
+        let set: Vec<i64> = redis::cmd("HPEXPIRE")
+            .arg("user")
+            .arg(50)
+            .arg("FIELDS")
+            .arg(1)
+            .arg("session")
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(set, vec![1]);
+
+        thread::sleep(Duration::from_millis(100));
+        let all: HashMap<String, String> =
+            redis::cmd("HGETALL").arg("user").query(&mut con).unwrap();
+        assert_eq!(all, HashMap::from([("name".into(), "ada".into())]));
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let _: i64 = redis::cmd("SADD")
+            .arg("tag:rust")
+            .arg(&["post:1", "post:2", "post:3"])
+            .query(&mut con)
+            .unwrap();
+        let _: i64 = redis::cmd("SADD")
+            .arg("tag:db")
+            .arg(&["post:2", "post:3", "post:4"])
+            .query(&mut con)
+            .unwrap();
+
+        let stored: i64 = redis::cmd("SINTERSTORE")
+            .arg("both")
+            .arg(&["tag:rust", "tag:db"])
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(stored, 2);
+        let both: HashSet<String> = redis::cmd("SMEMBERS").arg("both").query(&mut con).unwrap();
+        assert_eq!(both, HashSet::from(["post:2".into(), "post:3".into()]));
+
+        let only_rust: Vec<String> = redis::cmd("SDIFF")
+            .arg(&["tag:rust", "tag:db"])
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(only_rust, vec!["post:1"]);
+    }
+
+    #[test]
+    fn test_sorted_set_leaderboard() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let added: i64 = redis::cmd("ZADD")
+            .arg("scores")
+            .arg(&["10", "ada", "30", "bob", "20", "cy"])
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(added, 3);
+        let _: f64 = redis::cmd("ZINCRBY")
+            .arg("scores")
+            .arg(25)
+            .arg("ada")
+            .query(&mut con)
+            .unwrap();
+
+        let top: Vec<(String, f64)> = redis::cmd("ZRANGE")
+            .arg("scores")
+            .arg(0)
+            .arg(1)
+            .arg("REV")
+            .arg("WITHSCORES")
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(top, vec![("ada".into(), 35.0), ("bob".into(), 30.0)]);
+
+        let rank: i64 = redis::cmd("ZREVRANK")
+            .arg("scores")
+            .arg("cy")
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(rank, 2);
+    }
+
+    #[test]
+    fn test_xreadgroup_blocks_until_an_entry_is_added() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut worker = client.get_connection().unwrap();
+        let mut producer = client.get_connection().unwrap();
+
+        let _: () = redis::cmd("XGROUP")
+            .arg(&["CREATE", "events", "workers", "$", "MKSTREAM"])
+            .query(&mut producer)
+            .unwrap();
+
+        let waiting = thread::spawn(move || {
+            let read: redis::Value = redis::cmd("XREADGROUP")
+                .arg(&[
+                    "GROUP", "workers", "w1", "BLOCK", "0", "STREAMS", "events", ">",
+                ])
+                .query(&mut worker)
+                .unwrap();
+            read
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        let id: String = redis::cmd("XADD")
+            .arg(&["events", "*", "kind", "click"])
+            .query(&mut producer)
+            .unwrap();
+
+        let bulk = |s: &str| redis::Value::Data(s.as_bytes().to_vec());
+        assert_eq!(
+            waiting.join().unwrap(),
+            redis::Value::Bulk(vec![redis::Value::Bulk(vec![
+                bulk("events"),
+                redis::Value::Bulk(vec![redis::Value::Bulk(vec![
+                    bulk(&id),
+                    redis::Value::Bulk(vec![bulk("kind"), bulk("click")]),
+                ])]),
+            ])])
+        );
+        let acked: i64 = redis::cmd("XACK")
+            .arg(&["events", "workers", &id])
+            .query(&mut producer)
+            .unwrap();
+        assert_eq!(acked, 1);
+    }
+
+    #[test]
+    fn test_publish_reaches_subscribers() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut subscriber = client.get_connection().unwrap();
+        let mut publisher = client.get_connection().unwrap();
+
+        let mut pubsub = subscriber.as_pubsub();
+        pubsub.subscribe("news").unwrap();
+        pubsub.psubscribe("n*").unwrap();
+
+        let received: i64 = redis::cmd("PUBLISH")
+            .arg("news")
+            .arg("hello")
+            .query(&mut publisher)
+            .unwrap();
+        assert_eq!(received, 2);
+
+        let message = pubsub.get_message().unwrap();
+        assert_eq!(message.get_channel_name(), "news");
+        assert_eq!(message.get_payload::<String>().unwrap(), "hello");
+        assert!(!message.from_pattern());
+        let message = pubsub.get_message().unwrap();
+        assert_eq!(message.get_pattern::<String>().unwrap(), "n*");
+        assert_eq!(message.get_payload::<String>().unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_subscribed_resp2_client_may_only_manage_subscriptions() {
+        let address = start_server();
+
+        let reply = send(
+            &address,
+            b"SUBSCRIBE a\r\nGET a\r\nPING\r\nUNSUBSCRIBE\r\nPING\r\n",
+        );
+        assert_eq!(
+            String::from_utf8(reply).unwrap(),
+            "*3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n\
+             -ERR Can't execute 'get': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING / QUIT / \
+             RESET are allowed in this context\r\n\
+             *2\r\n$4\r\npong\r\n$0\r\n\r\n\
+             *3\r\n$11\r\nunsubscribe\r\n$1\r\na\r\n:0\r\n\
+             +PONG\r\n"
+        );
+    }
 
     #[test]
-    fn test_decode_algo_array() -> Result<()> {
-        // Input: *1\r\n$4\r\nPING\r\n
-        let input: Vec<u8> = vec![42, 49, 13, 10, 36, 52, 13, 10, 80, 73, 78, 71, 13, 10];
+    fn test_resp3_client_gets_messages_as_pushes() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut publisher = client.get_connection().unwrap();
 
-        assert_eq!(decode_resp(&input)?, VecDeque::from([String::from("PING")]));
+        let mut subscriber = TcpStream::connect(&address).unwrap();
+        subscriber
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        subscriber
+            .write_all(b"HELLO 3\r\nSSUBSCRIBE a\r\nGET a\r\n")
+            .unwrap();
+        read_until(
+            &mut subscriber,
+            b">3\r\n$10\r\nssubscribe\r\n$1\r\na\r\n:1\r\n_\r\n",
+        );
+
+        let received: i64 = redis::cmd("SPUBLISH")
+            .arg("a")
+            .arg("hello")
+            .query(&mut publisher)
+            .unwrap();
+        assert_eq!(received, 1);
+        read_until(
+            &mut subscriber,
+            b">3\r\n$8\r\nsmessage\r\n$1\r\na\r\n$5\r\nhello\r\n",
+        );
+    }
+
+    /// Reads from `stream` until what was read ends with `expected`.
+    fn read_until(stream: &mut TcpStream, expected: &[u8]) {
+        let mut read = Vec::new();
+        let mut buffer = [0; 1024];
+        while !read.ends_with(expected) {
+            let n = stream.read(&mut buffer).unwrap();
+            assert_ne!(n, 0, "hung up after {:?}", String::from_utf8_lossy(&read));
+            read.extend_from_slice(&buffer[..n]);
+        }
+    }
+
+    #[test]
+    fn test_transaction_runs_queued_commands() {
+        let address = start_server();
+
+        let reply = send(
+            &address,
+            b"MULTI\r\nINCR a\r\nINCRBY a 10\r\nEXEC\r\nMULTI\r\nINCR a\r\nDISCARD\r\nGET a\r\n",
+        );
+        assert_eq!(
+            String::from_utf8(reply).unwrap(),
+            "+OK\r\n+QUEUED\r\n+QUEUED\r\n*2\r\n:1\r\n:11\r\n\
+             +OK\r\n+QUEUED\r\n+OK\r\n$2\r\n11\r\n"
+        );
+    }
+
+    #[test]
+    fn test_transaction_aborts_after_a_queueing_error() {
+        let address = start_server();
+
+        let reply = send(
+            &address,
+            b"MULTI\r\nSET a 1\r\nGET\r\nNOSUCH x\r\nEXEC\r\nGET a\r\n",
+        );
+        assert_eq!(
+            String::from_utf8(reply).unwrap(),
+            "+OK\r\n+QUEUED\r\n\
+             -ERR wrong number of arguments for 'get' command\r\n\
+             -ERR unknown command 'NOSUCH', with args beginning with: 'x' \r\n\
+             -EXECABORT Transaction discarded because of previous errors.\r\n\
+             $-1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_command_can_not_inject_a_reply() {
+        let address = start_server();
+
+        let reply = send(&address, b"*2\r\n$6\r\na\r\n+OK\r\n$1\r\nx\r\nPING\r\n");
+        assert_eq!(
+            String::from_utf8(reply).unwrap(),
+            "-ERR unknown command 'a  +OK', with args beginning with: 'x' \r\n+PONG\r\n"
+        );
+    }
+
+    #[test]
+    fn test_exec_fails_when_a_watched_key_changes() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut checkout = client.get_connection().unwrap();
+        let mut other = client.get_connection().unwrap();
+
+        let stock = |con: &mut redis::Connection, change: &mut dyn FnMut()| {
+            redis::cmd("WATCH").arg("stock").query::<()>(con).unwrap();
+            change();
+            redis::pipe()
+                .atomic()
+                .cmd("DECR")
+                .arg("stock")
+                .query::<Option<(i64,)>>(con)
+                .unwrap()
+        };
+
+        redis::cmd("SET")
+            .arg("stock")
+            .arg(5)
+            .query::<()>(&mut other)
+            .unwrap();
+        assert_eq!(stock(&mut checkout, &mut || {}), Some((4,)));
+
+        let set = &mut || {
+            redis::cmd("SET")
+                .arg("stock")
+                .arg(9)
+                .query::<()>(&mut other)
+                .unwrap();
+        };
+        assert_eq!(stock(&mut checkout, set), None);
+
+        let expire = &mut || {
+            redis::cmd("PEXPIRE")
+                .arg("stock")
+                .arg(1)
+                .query::<()>(&mut other)
+                .unwrap();
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(stock(&mut checkout, expire), None);
+
+        redis::cmd("SET")
+            .arg("stock")
+            .arg(5)
+            .query::<()>(&mut other)
+            .unwrap();
+        let flush = &mut || {
+            redis::cmd("FLUSHALL").query::<()>(&mut other).unwrap();
+        };
+        assert_eq!(stock(&mut checkout, flush), None);
+
+        redis::cmd("SET")
+            .arg("stock")
+            .arg(5)
+            .query::<()>(&mut other)
+            .unwrap();
+        let read = &mut || {
+            redis::cmd("GET")
+                .arg("stock")
+                .query::<i64>(&mut other)
+                .unwrap();
+        };
+        assert_eq!(stock(&mut checkout, read), Some((4,)));
+
+        let set_same = &mut || {
+            redis::cmd("SET")
+                .arg("stock")
+                .arg(4)
+                .query::<()>(&mut other)
+                .unwrap();
+        };
+        assert_eq!(stock(&mut checkout, set_same), None);
+
+        let change_back = &mut || {
+            redis::cmd("INCR")
+                .arg("stock")
+                .query::<()>(&mut other)
+                .unwrap();
+            redis::cmd("DECR")
+                .arg("stock")
+                .query::<()>(&mut other)
+                .unwrap();
+        };
+        assert_eq!(stock(&mut checkout, change_back), None);
+    }
+
+    #[test]
+    fn test_exec_runs_when_writes_leave_watched_keys_alone() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut checkout = client.get_connection().unwrap();
+        let mut other = client.get_connection().unwrap();
+
+        redis::pipe()
+            .cmd("SET")
+            .arg("stock")
+            .arg(5)
+            .cmd("HSET")
+            .arg("hash")
+            .arg("f")
+            .arg("v")
+            .cmd("SADD")
+            .arg("set")
+            .arg("m")
+            .query::<()>(&mut other)
+            .unwrap();
+        redis::cmd("WATCH")
+            .arg(&["stock", "hash", "set", "list"])
+            .query::<()>(&mut checkout)
+            .unwrap();
+
+        redis::pipe()
+            .cmd("SETNX")
+            .arg("stock")
+            .arg(9)
+            .cmd("SET")
+            .arg("stock")
+            .arg(9)
+            .arg("NX")
+            .cmd("HDEL")
+            .arg("hash")
+            .arg("missing")
+            .cmd("SREM")
+            .arg("set")
+            .arg("missing")
+            .cmd("LPOP")
+            .arg("list")
+            .query::<()>(&mut other)
+            .unwrap();
+
+        let reply = redis::pipe()
+            .atomic()
+            .cmd("DECR")
+            .arg("stock")
+            .query::<Option<(i64,)>>(&mut checkout)
+            .unwrap();
+        assert_eq!(reply, Some((4,)));
+    }
+
+    #[test]
+    fn test_script_runs_commands_atomically() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+        let limit = redis::Script::new(
+            "local count = redis.call('INCR', KEYS[1])
+             if count == 1 then redis.call('EXPIRE', KEYS[1], ARGV[1]) end
+             return count <= tonumber(ARGV[2])",
+        );
+
+        let allowed: Vec<Option<bool>> = (0..3)
+            .map(|_| limit.key("rate").arg(60).arg(2).invoke(&mut con).unwrap())
+            .collect();
+        assert_eq!(allowed, [Some(true), Some(true), None]);
+        let ttl: i64 = redis::cmd("TTL").arg("rate").query(&mut con).unwrap();
+        assert!(ttl > 0);
+    }
+
+    #[test]
+    fn test_busy_script_can_be_killed() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("lua-time-limit")
+            .arg(50)
+            .query::<()>(&mut con)
+            .unwrap();
+
+        let script = thread::spawn(move || {
+            redis::cmd("EVAL")
+                .arg("while true do end")
+                .arg(0)
+                .query::<()>(&mut con)
+                .unwrap_err()
+        });
+
+        let mut other = client.get_connection().unwrap();
+        let busy = loop {
+            thread::sleep(Duration::from_millis(20));
+            match redis::cmd("GET")
+                .arg("a")
+                .query::<Option<String>>(&mut other)
+            {
+                Err(e) if e.code() == Some("BUSY") => break e,
+                _ => {}
+            }
+        };
+        assert_eq!(busy.code(), Some("BUSY"));
+        redis::cmd("SCRIPT")
+            .arg("KILL")
+            .query::<()>(&mut other)
+            .unwrap();
+
+        let killed = script.join().unwrap();
+        assert!(killed.to_string().contains("Script killed by user"));
+        let value: Option<String> = redis::cmd("GET").arg("a").query(&mut other).unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn test_functions_are_called_by_name() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let library: String = redis::cmd("FUNCTION")
+            .arg("LOAD")
+            .arg(
+                "#!lua name=locks
+                redis.register_function('release', function(keys, args)
+                    if redis.call('GET', keys[1]) == args[1] then
+                        redis.call('GETDEL', keys[1])
+                        return 1
+                    end
+                    return 0
+                end)",
+            )
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(library, "locks");
+
+        redis::cmd("SET")
+            .arg("lock")
+            .arg("me")
+            .query::<()>(&mut con)
+            .unwrap();
+        let release = |owner: &str, con: &mut redis::Connection| -> i64 {
+            redis::cmd("FCALL")
+                .arg("release")
+                .arg(1)
+                .arg("lock")
+                .arg(owner)
+                .query(con)
+                .unwrap()
+        };
+        assert_eq!(release("someone else", &mut con), 0);
+        assert_eq!(release("me", &mut con), 1);
+
+        let error = redis::cmd("FCALL_RO")
+            .arg("release")
+            .arg(1)
+            .arg("lock")
+            .arg("me")
+            .query::<i64>(&mut con)
+            .unwrap_err();
+        assert!(error.to_string().contains("write flag"));
+    }
+
+    #[test]
+    fn test_function_writes_count_towards_save_points() {
+        let mut ks = Keyspace::new();
+        ks.persistence().policies = vec![(0, 1)];
+        let (output, _replies) = std::sync::mpsc::channel();
+        let mut client = Client::new(output);
+        let mut function = |ks: &mut Keyspace, args: &[&str]| {
+            let mut args = args.iter().map(|arg| arg.as_bytes().to_vec()).collect();
+            dispatch("function", &mut args, &mut client, ks);
+        };
+
+        function(&mut ks, &["LIST"]);
+        function(&mut ks, &["DUMP"]);
+        assert!(!ks.persistence().save_due());
+
+        function(
+            &mut ks,
+            &[
+                "LOAD",
+                "#!lua name=lib\nredis.register_function('f', function() end)",
+            ],
+        );
+        assert!(ks.persistence().save_due());
+    }
+
+    #[test]
+    fn test_clients_can_introspect_commands() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let count: usize = redis::cmd("COMMAND").arg("COUNT").query(&mut con).unwrap();
+        let all: Vec<redis::Value> = redis::cmd("COMMAND").query(&mut con).unwrap();
+        assert_eq!(all.len(), count);
+
+        let info: Vec<(String, i64, Vec<String>, i64, i64, i64)> = redis::cmd("COMMAND")
+            .arg("INFO")
+            .arg("SET")
+            .query::<Vec<Vec<redis::Value>>>(&mut con)
+            .unwrap()
+            .into_iter()
+            .map(|entry| {
+                redis::FromRedisValue::from_redis_value(&redis::Value::Bulk(entry[..6].to_vec()))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            info,
+            [(
+                String::from("set"),
+                -3,
+                vec![String::from("write"), String::from("denyoom")],
+                1,
+                1,
+                1
+            )]
+        );
+
+        let keys: Vec<String> = redis::cmd("COMMAND")
+            .arg("GETKEYS")
+            .arg("EVAL")
+            .arg("return 1")
+            .arg(2)
+            .arg("a")
+            .arg("b")
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(keys, ["a", "b"]);
+
+        // Arity comes from the same registry, for every command alike.
+        let error = redis::cmd("LLEN").query::<()>(&mut con).unwrap_err();
+        assert_eq!(
+            error.detail(),
+            Some("wrong number of arguments for 'llen' command")
+        );
+    }
+
+    #[test]
+    fn test_saved_dataset_is_loaded_on_restart() {
+        let dir = std::env::temp_dir().join(format!("redis-server-rdb-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}", address)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("dir")
+            .arg(dir.to_str().unwrap())
+            .query::<()>(&mut con)
+            .unwrap();
+        redis::cmd("SET")
+            .arg("s")
+            .arg("hello")
+            .query::<()>(&mut con)
+            .unwrap();
+        redis::cmd("SET")
+            .arg("volatile")
+            .arg(12345)
+            .arg("EX")
+            .arg(1000)
+            .query::<()>(&mut con)
+            .unwrap();
+        redis::cmd("RPUSH")
+            .arg("l")
+            .arg("a")
+            .arg("b")
+            .query::<()>(&mut con)
+            .unwrap();
+        redis::cmd("HSET")
+            .arg("h")
+            .arg("f")
+            .arg("v")
+            .query::<()>(&mut con)
+            .unwrap();
+        redis::cmd("ZADD")
+            .arg("z")
+            .arg(1.5)
+            .arg("m")
+            .query::<()>(&mut con)
+            .unwrap();
+        redis::cmd("XADD")
+            .arg("x")
+            .arg("1-1")
+            .arg("f")
+            .arg("v")
+            .query::<()>(&mut con)
+            .unwrap();
+        redis::cmd("FUNCTION")
+            .arg("LOAD")
+            .arg("#!lua name=lib\nredis.register_function('one', function() return 1 end)")
+            .query::<()>(&mut con)
+            .unwrap();
+
+        redis::cmd("MULTI").query::<()>(&mut con).unwrap();
+        let queued: redis::RedisResult<()> = redis::cmd("SAVE").query(&mut con);
+        assert!(queued.is_err());
+        redis::cmd("DISCARD").query::<()>(&mut con).unwrap();
+
+        let before: i64 = redis::cmd("LASTSAVE").query(&mut con).unwrap();
+        redis::cmd("SAVE").query::<()>(&mut con).unwrap();
+        let after: i64 = redis::cmd("LASTSAVE").query(&mut con).unwrap();
+        assert!(after >= before);
+
+        let restarted = Db::new();
+        restarted.lock().persistence().dir = dir.clone();
+        restarted.load().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let restarted_address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || serve(listener, restarted));
+        let client = redis::Client::open(format!("redis://{}", restarted_address)).unwrap();
+        let mut restarted_con = client.get_connection().unwrap();
+
+        let s: String = redis::cmd("GET")
+            .arg("s")
+            .query(&mut restarted_con)
+            .unwrap();
+        assert_eq!(s, "hello");
+        let ttl: i64 = redis::cmd("TTL")
+            .arg("volatile")
+            .query(&mut restarted_con)
+            .unwrap();
+        assert!(ttl > 990);
+        let l: Vec<String> = redis::cmd("LRANGE")
+            .arg("l")
+            .arg(0)
+            .arg(-1)
+            .query(&mut restarted_con)
+            .unwrap();
+        assert_eq!(l, ["a", "b"]);
+        let f: String = redis::cmd("HGET")
+            .arg("h")
+            .arg("f")
+            .query(&mut restarted_con)
+            .unwrap();
+        assert_eq!(f, "v");
+        let score: f64 = redis::cmd("ZSCORE")
+            .arg("z")
+            .arg("m")
+            .query(&mut restarted_con)
+            .unwrap();
+        assert_eq!(score, 1.5);
+        let len: i64 = redis::cmd("XLEN")
+            .arg("x")
+            .query(&mut restarted_con)
+            .unwrap();
+        assert_eq!(len, 1);
+        let one: i64 = redis::cmd("FCALL")
+            .arg("one")
+            .arg(0)
+            .query(&mut restarted_con)
+            .unwrap();
+        assert_eq!(one, 1);
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("dbfilename")
+            .arg("background.rdb")
+            .query::<()>(&mut con)
+            .unwrap();
+        let started: String = redis::cmd("BGSAVE").query(&mut con).unwrap();
+        assert_eq!(started, "Background saving started");
+        let path = dir.join("background.rdb");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !path.exists() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let snapshot = rdb::Snapshot::decode(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(snapshot.entries.len(), 6);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_blpop_waits_for_a_push() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+        let mut worker = client.get_connection().unwrap();
+        let mut producer = client.get_connection().unwrap();
+
+        let waiting = thread::spawn(move || {
+            let popped: (String, String) = redis::cmd("BLPOP")
+                .arg("queue")
+                .arg(0)
+                .query(&mut worker)
+                .unwrap();
+            popped
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        let len: i64 = redis::cmd("RPUSH")
+            .arg("queue")
+            .arg("job")
+            .query(&mut producer)
+            .unwrap();
+        assert_eq!(len, 1);
+
+        assert_eq!(
+            waiting.join().unwrap(),
+            (String::from("queue"), String::from("job"))
+        );
+        let len: i64 = redis::cmd("LLEN")
+            .arg("queue")
+            .query(&mut producer)
+            .unwrap();
+        assert_eq!(len, 0);
+    }
+
+    #[test]
+    fn test_blocked_clients_are_served_in_order() {
+        let address = start_server();
+        let client = redis::Client::open(format!("redis://{}/", address)).unwrap();
+
+        let mut waiting = Vec::new();
+        for _ in 0..3 {
+            let mut con = client.get_connection().unwrap();
+            waiting.push(thread::spawn(move || {
+                let popped: (String, String) = redis::cmd("BRPOP")
+                    .arg("queue")
+                    .arg(5)
+                    .query(&mut con)
+                    .unwrap();
+                popped.1
+            }));
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        let mut con = client.get_connection().unwrap();
+        let _: i64 = redis::cmd("LPUSH")
+            .arg("queue")
+            .arg(&["1", "2", "3"])
+            .query(&mut con)
+            .unwrap();
+
+        let popped: Vec<String> = waiting.into_iter().map(|t| t.join().unwrap()).collect();
+        assert_eq!(popped, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_blocking_pop_times_out() {
+        let address = start_server();
+        let mut stream = TcpStream::connect(&address).unwrap();
+
+        let started = Instant::now();
+        stream
+            .write_all(b"BLPOP queue 0.2\r\nBLMOVE queue other LEFT LEFT 0.1\r\n")
+            .unwrap();
+
+        let expected = b"*-1\r\n$-1\r\n";
+        let mut reply = vec![0; expected.len()];
+        stream.read_exact(&mut reply).unwrap();
+        assert_eq!(reply, expected);
+        assert!(started.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_client_that_hangs_up_stops_waiting() {
+        let address = start_server();
+
+        let mut stream = TcpStream::connect(&address).unwrap();
+        stream.write_all(b"BLPOP queue 0\r\n").unwrap();
+        thread::sleep(Duration::from_millis(50));
+        drop(stream);
+        thread::sleep(Duration::from_millis(300));
+
+        // Nobody is left to hand the element to, so it stays in the list.
+        let reply = send(&address, b"RPUSH queue job\r\nLLEN queue\r\n");
+        assert_eq!(reply, b":1\r\n:1\r\n");
+    }
+
+    #[test]
+    fn test_hello_switches_protocol() {
+        let address = start_server();
+
+        let reply = send(
+            &address,
+            b"*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n*1\r\n$5\r\nHELLO\r\n",
+        );
+        let reply = String::from_utf8(reply).unwrap();
+
+        // Both replies are RESP3 maps: the first switches protocol and the second reports it.
+        assert!(reply.starts_with("%7\r\n$6\r\nserver\r\n$5\r\nredis\r\n"));
+        assert_eq!(reply.matches("%7\r\n").count(), 2);
+        assert_eq!(reply.matches("$5\r\nproto\r\n:3\r\n").count(), 2);
+    }
+
+    #[test]
+    fn test_hello_defaults_to_resp2() {
+        let address = start_server();
+
+        let reply = send(&address, b"*1\r\n$5\r\nHELLO\r\n");
+        let reply = String::from_utf8(reply).unwrap();
+
+        assert!(reply.starts_with("*14\r\n"));
+        assert!(reply.contains("$5\r\nproto\r\n:2\r\n"));
+    }
+
+    #[test]
+    fn test_hello_rejects_unknown_protocol() {
+        let address = start_server();
+
+        let reply = send(
+            &address,
+            b"*2\r\n$5\r\nHELLO\r\n$1\r\n4\r\n*2\r\n$5\r\nHELLO\r\n$1\r\nx\r\n",
+        );
+
+        assert_eq!(
+            reply,
+            b"-NOPROTO unsupported protocol version\r\n-ERR Protocol version is not an integer or out of range\r\n"
+        );
+    }
+
+    #[test]
+    fn test_inline_commands() {
+        let address = start_server();
+
+        let reply = send(&address, b"PING\r\nECHO \"hello world\"\n");
+        assert_eq!(reply, b"+PONG\r\n$11\r\nhello world\r\n");
+    }
+
+    #[test]
+    fn test_malformed_frame_closes_connection_without_crashing() {
+        let address = start_server();
+
+        let reply = send(&address, b"*1\r\n$4\r\nPING\r\n*1\r\n$x\r\n");
+        assert_eq!(
+            reply,
+            b"+PONG\r\n-ERR Protocol error: invalid bulk length\r\n"
+        );
+
+        // The server is still up for everybody else.
+        assert_eq!(send(&address, b"*1\r\n$4\r\nPING\r\n"), b"+PONG\r\n");
+    }
+
+    #[test]
+    fn test_command_from_frame() -> Result<()> {
+        let frame = Frame::Array(vec![
+            Frame::BulkString(b"ECHO".to_vec()),
+            Frame::BulkString(b"hi".to_vec()),
+        ]);
+
+        let cmd = Command::from_frame(frame)?;
+        assert_eq!(cmd.command, "ECHO");
+        assert_eq!(cmd.args, VecDeque::from([b"hi".to_vec()]));
 
         Ok(())
     }
 
     #[test]
-    fn test_decode_error_not_an_array() -> Result<()> {
+    fn test_command_from_frame_error_not_an_array() {
         // Input: $3\r\nBAD\r\n
-        let input: Vec<u8> = vec![36, 51, 13, 10, 66, 65, 68, 13, 10];
+        let frame = Frame::BulkString(b"BAD".to_vec());
 
-        match decode_resp(&input) {
+        match Command::from_frame(frame) {
             Ok(_) => panic!("Expecting error"),
             Err(e) => assert_eq!(e, RedisError::NotAnArrayError),
         }
+    }
 
-        Ok(())
+    #[test]
+    fn test_command_from_frame_error_not_a_bulk_string() {
+        let frame = Frame::Array(vec![Frame::BulkString(b"ECHO".to_vec()), Frame::Integer(1)]);
+
+        match Command::from_frame(frame) {
+            Ok(_) => panic!("Expecting error"),
+            Err(e) => assert_eq!(e, RedisError::NotABulkStringError(':')),
+        }
     }
 }
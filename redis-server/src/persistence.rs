@@ -0,0 +1,285 @@
+//! Saving the dataset to an RDB file, and loading it back when the server starts.
+//!
+//! A save writes a snapshot of the keyspace to a temporary file and renames it over the old
+//! one once it is safely on disk, so a crash partway through leaves the previous file intact.
+//! A background save clones the dataset while holding the lock, then writes it out on a thread
+//! of its own, so clients are only held up for as long as the cloning takes.
+
+use crate::db::{now_ms, Keyspace, Value};
+use crate::rdb::Snapshot;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::thread;
+
+/// Redis's default save points: after an hour if anything changed, after 5 minutes if 100 keys
+/// did, and after a minute if 10000 did.
+const DEFAULT_SAVE_POLICIES: [(u64, u64); 3] = [(3600, 1), (300, 100), (60, 10000)];
+/// How long to wait after a failed background save before a save point can trigger another.
+const RETRY_DELAY_SECS: u64 = 5;
+
+/// Where the dataset is saved and when.
+pub struct Persistence {
+    pub dir: PathBuf,
+    pub dbfilename: String,
+    /// Save after this many seconds when there have been at least this many changes.
+    pub policies: Vec<(u64, u64)>,
+    status: Arc<SaveStatus>,
+}
+
+impl Default for Persistence {
+    fn default() -> Persistence {
+        Persistence {
+            dir: PathBuf::from("."),
+            dbfilename: String::from("dump.rdb"),
+            policies: DEFAULT_SAVE_POLICIES.to_vec(),
+            status: Arc::default(),
+        }
+    }
+}
+
+/// How saving has gone, which a background save updates without the keyspace.
+pub struct SaveStatus {
+    /// How many changes have been made since the last successful save.
+    dirty: AtomicU64,
+    /// When the last successful save happened, in seconds since the epoch.
+    last_save: AtomicU64,
+    /// When the last save was tried, in seconds since the epoch.
+    last_try: AtomicU64,
+    last_ok: AtomicBool,
+    in_progress: AtomicBool,
+    /// Whether a background save was asked for while another was running.
+    scheduled: AtomicBool,
+}
+
+impl Default for SaveStatus {
+    fn default() -> SaveStatus {
+        // As in Redis, starting up counts as saving.
+        let now = now_ms() / 1000;
+        SaveStatus {
+            dirty: AtomicU64::new(0),
+            last_save: AtomicU64::new(now),
+            last_try: AtomicU64::new(now),
+            last_ok: AtomicBool::new(true),
+            in_progress: AtomicBool::new(false),
+            scheduled: AtomicBool::new(false),
+        }
+    }
+}
+
+impl SaveStatus {
+    pub fn last_save(&self) -> u64 {
+        self.last_save.load(Ordering::Relaxed)
+    }
+
+    pub fn in_progress(&self) -> bool {
+        self.in_progress.load(Ordering::Relaxed)
+    }
+
+    /// Records that a save of the dataset as it was after `dirty` changes has finished.
+    fn finish(&self, result: &io::Result<()>, dirty: u64) {
+        let now = now_ms() / 1000;
+        self.last_try.store(now, Ordering::Relaxed);
+        self.last_ok.store(result.is_ok(), Ordering::Relaxed);
+        if result.is_ok() {
+            self.dirty.fetch_sub(dirty, Ordering::Relaxed);
+            self.last_save.store(now, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Persistence {
+    pub fn status(&self) -> &SaveStatus {
+        &self.status
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(&self.dbfilename)
+    }
+
+    /// Counts changes to the dataset towards the save points.
+    pub fn add_dirty(&self, changes: u64) {
+        self.status.dirty.fetch_add(changes, Ordering::Relaxed);
+    }
+
+    /// Saves the snapshot straight away.
+    pub fn save(&self, snapshot: &Snapshot) -> io::Result<()> {
+        let dirty = self.status.dirty.load(Ordering::Relaxed);
+        let result = write_file(&self.path(), &snapshot.encode());
+        self.status.finish(&result, dirty);
+        result
+    }
+
+    /// Saves the snapshot on a thread of its own. Returns false if a background save is already
+    /// running.
+    pub fn background_save(&self, snapshot: Snapshot) -> io::Result<bool> {
+        if self.status.in_progress.swap(true, Ordering::Relaxed) {
+            return Ok(false);
+        }
+        self.status.scheduled.store(false, Ordering::Relaxed);
+
+        let dirty = self.status.dirty.load(Ordering::Relaxed);
+        let path = self.path();
+        let status = Arc::clone(&self.status);
+        let spawned = thread::Builder::new()
+            .name(String::from("bgsave"))
+            .spawn(move || {
+                let result = write_file(&path, &snapshot.encode());
+                if let Err(e) = &result {
+                    eprintln!("Background saving error: {}", e);
+                }
+                status.finish(&result, dirty);
+                status.in_progress.store(false, Ordering::Relaxed);
+            });
+
+        if let Err(e) = spawned {
+            self.status.in_progress.store(false, Ordering::Relaxed);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Asks for a background save once the running one is done.
+    pub fn schedule_save(&self) {
+        self.status.scheduled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether a background save should start: one was scheduled, or a save point has been
+    /// reached. After a failed save, save points wait a little before trying again.
+    pub fn save_due(&self) -> bool {
+        let status = &self.status;
+        if status.in_progress() {
+            return false;
+        }
+        if status.scheduled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let now = now_ms() / 1000;
+        let dirty = status.dirty.load(Ordering::Relaxed);
+        let since_save = now.saturating_sub(status.last_save());
+        let can_retry = status.last_ok.load(Ordering::Relaxed)
+            || now.saturating_sub(status.last_try.load(Ordering::Relaxed)) >= RETRY_DELAY_SECS;
+        can_retry
+            && self
+                .policies
+                .iter()
+                .any(|&(seconds, changes)| dirty >= changes && since_save >= seconds)
+    }
+}
+
+/// A copy of everything in the keyspace that gets saved, leaving out keys that have expired.
+pub fn snapshot(ks: &Keyspace) -> Snapshot {
+    let now = now_ms();
+    let entries = ks
+        .iter()
+        .filter(|(_, _, expires)| expires.is_none_or(|when| when > now))
+        .filter_map(|(key, value, expires)| {
+            let mut value = value.clone();
+            if let Value::Hash(hash) = &mut value {
+                hash.remove_expired(now);
+                if hash.is_empty() {
+                    return None;
+                }
+            }
+            Some((key.clone(), value, expires))
+        })
+        .collect();
+
+    let scripting = ks.scripting();
+    let scripting = scripting.lock().unwrap_or_else(PoisonError::into_inner);
+    let libraries = scripting
+        .functions
+        .libraries()
+        .map(|library| library.code.clone())
+        .collect();
+
+    Snapshot { entries, libraries }
+}
+
+/// Fills the keyspace from a snapshot, leaving out keys that have expired since it was taken.
+pub fn restore(ks: &mut Keyspace, snapshot: Snapshot) -> io::Result<()> {
+    let now = now_ms();
+    for (key, value, expires) in snapshot.entries {
+        if expires.is_some_and(|when| when <= now) {
+            continue;
+        }
+        ks.set(key.clone(), value);
+        if let Some(when) = expires {
+            ks.set_expire_time(&key, when);
+        }
+    }
+
+    let scripting = ks.scripting();
+    let mut scripting = scripting.lock().unwrap_or_else(PoisonError::into_inner);
+    for code in snapshot.libraries {
+        scripting
+            .functions
+            .load(&code, false)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    }
+
+    Ok(())
+}
+
+/// Writes the file under a temporary name and renames it into place once it is on disk.
+fn write_file(path: &PathBuf, contents: &[u8]) -> io::Result<()> {
+    let temp = path.with_file_name(format!("temp-{}.rdb", std::process::id()));
+    let written = File::create(&temp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_points() {
+        let persistence = Persistence::default();
+        assert!(!persistence.save_due());
+
+        persistence.add_dirty(20000);
+        assert!(!persistence.save_due());
+        persistence
+            .status
+            .last_save
+            .fetch_sub(60, Ordering::Relaxed);
+        assert!(persistence.save_due());
+
+        persistence.status.finish(&Ok(()), 20000);
+        assert!(!persistence.save_due());
+
+        persistence.schedule_save();
+        assert!(persistence.save_due());
+    }
+
+    #[test]
+    fn test_failed_save_waits_to_retry() {
+        let persistence = Persistence::default();
+        persistence.add_dirty(1);
+        persistence
+            .status
+            .last_save
+            .fetch_sub(3600, Ordering::Relaxed);
+        persistence
+            .status
+            .finish(&Err(io::ErrorKind::Other.into()), 1);
+        assert!(!persistence.save_due());
+
+        persistence
+            .status
+            .last_try
+            .fetch_sub(RETRY_DELAY_SECS, Ordering::Relaxed);
+        assert!(persistence.save_due());
+    }
+}
@@ -0,0 +1,273 @@
+use crate::glob::glob_match;
+use crate::resp::types::{Encoded, Frame, Protocol};
+
+use indexmap::{IndexMap, IndexSet};
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+
+/// What a client can subscribe to: channels by name, channels matching a pattern, or shard
+/// channels, which are kept apart from the others the way Redis Cluster needs them to be.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Channel,
+    Pattern,
+    ShardChannel,
+}
+
+impl Kind {
+    const ALL: [Kind; 3] = [Kind::Channel, Kind::Pattern, Kind::ShardChannel];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// How confirmations of subscribing to this kind of channel are introduced.
+    pub fn subscribe_name(self) -> &'static str {
+        match self {
+            Kind::Channel => "subscribe",
+            Kind::Pattern => "psubscribe",
+            Kind::ShardChannel => "ssubscribe",
+        }
+    }
+
+    pub fn unsubscribe_name(self) -> &'static str {
+        match self {
+            Kind::Channel => "unsubscribe",
+            Kind::Pattern => "punsubscribe",
+            Kind::ShardChannel => "sunsubscribe",
+        }
+    }
+}
+
+/// Where messages for a subscribed client go. The connection writes out whatever arrives, so
+/// publishing never waits on a slow subscriber.
+#[derive(Clone)]
+pub struct Subscriber {
+    pub id: u64,
+    pub protocol: Protocol,
+    pub sender: Sender<Vec<u8>>,
+}
+
+impl Subscriber {
+    fn send(&self, message: &Frame) {
+        // The connection may be going away; it unsubscribes from everything once it has.
+        let _ = self.sender.send(message.encode(self.protocol));
+    }
+}
+
+/// Fans published messages out to the clients subscribed to them.
+#[derive(Default)]
+pub struct Broker {
+    /// Subscribers by channel, pattern and shard channel, each in the order they subscribed.
+    subscribers: [HashMap<Vec<u8>, IndexMap<u64, Subscriber>>; 3],
+    /// What each client is subscribed to, by kind, in the order it subscribed.
+    subscriptions: HashMap<u64, [IndexSet<Vec<u8>>; 3]>,
+}
+
+impl Broker {
+    /// Returns false if the client was already subscribed.
+    pub fn subscribe(&mut self, kind: Kind, subscriber: &Subscriber, channel: &[u8]) -> bool {
+        let subscribed = self.subscriptions.entry(subscriber.id).or_default()[kind.index()]
+            .insert(channel.to_vec());
+
+        self.subscribers[kind.index()]
+            .entry(channel.to_vec())
+            .or_default()
+            .insert(subscriber.id, subscriber.clone());
+        subscribed
+    }
+
+    /// Returns false if the client wasn't subscribed.
+    pub fn unsubscribe(&mut self, kind: Kind, id: u64, channel: &[u8]) -> bool {
+        let Some(subscriptions) = self.subscriptions.get_mut(&id) else {
+            return false;
+        };
+        if !subscriptions[kind.index()].shift_remove(channel) {
+            return false;
+        }
+        if subscriptions.iter().all(IndexSet::is_empty) {
+            self.subscriptions.remove(&id);
+        }
+
+        let subscribers = &mut self.subscribers[kind.index()];
+        if let Some(clients) = subscribers.get_mut(channel) {
+            clients.shift_remove(&id);
+            if clients.is_empty() {
+                subscribers.remove(channel);
+            }
+        }
+        true
+    }
+
+    /// Unsubscribes a client that has gone away from everything.
+    pub fn remove(&mut self, id: u64) {
+        for kind in Kind::ALL {
+            for channel in self.subscribed(kind, id) {
+                self.unsubscribe(kind, id, &channel);
+            }
+        }
+    }
+
+    /// The channels of a kind that the client is subscribed to, in the order it subscribed.
+    pub fn subscribed(&self, kind: Kind, id: u64) -> Vec<Vec<u8>> {
+        self.subscriptions
+            .get(&id)
+            .map(|subscriptions| subscriptions[kind.index()].iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// How many channels and patterns the client is subscribed to, which is the count
+    /// confirmations report. Shard channels are counted on their own.
+    pub fn count(&self, kind: Kind, id: u64) -> usize {
+        let Some(subscriptions) = self.subscriptions.get(&id) else {
+            return 0;
+        };
+
+        match kind {
+            Kind::Channel | Kind::Pattern => {
+                subscriptions[Kind::Channel.index()].len()
+                    + subscriptions[Kind::Pattern.index()].len()
+            }
+            Kind::ShardChannel => subscriptions[Kind::ShardChannel.index()].len(),
+        }
+    }
+
+    /// Everything the client is subscribed to, of every kind.
+    pub fn total(&self, id: u64) -> usize {
+        self.subscriptions.get(&id).map_or(0, |subscriptions| {
+            subscriptions.iter().map(IndexSet::len).sum()
+        })
+    }
+
+    /// Sends `message` to the clients subscribed to `channel` and to those subscribed to a
+    /// pattern it matches, returning how many received it.
+    pub fn publish(&self, channel: &[u8], message: &[u8]) -> usize {
+        let mut received = 0;
+
+        if let Some(clients) = self.subscribers[Kind::Channel.index()].get(channel) {
+            let push = Frame::Push(vec![
+                Frame::bulk("message"),
+                Frame::bulk(channel),
+                Frame::bulk(message),
+            ]);
+            for subscriber in clients.values() {
+                subscriber.send(&push);
+            }
+            received += clients.len();
+        }
+
+        for (pattern, clients) in &self.subscribers[Kind::Pattern.index()] {
+            if !glob_match(pattern, channel) {
+                continue;
+            }
+
+            let push = Frame::Push(vec![
+                Frame::bulk("pmessage"),
+                Frame::bulk(pattern.as_slice()),
+                Frame::bulk(channel),
+                Frame::bulk(message),
+            ]);
+            for subscriber in clients.values() {
+                subscriber.send(&push);
+            }
+            received += clients.len();
+        }
+
+        received
+    }
+
+    /// Sends `message` to the clients subscribed to the shard channel.
+    pub fn spublish(&self, channel: &[u8], message: &[u8]) -> usize {
+        let Some(clients) = self.subscribers[Kind::ShardChannel.index()].get(channel) else {
+            return 0;
+        };
+
+        let push = Frame::Push(vec![
+            Frame::bulk("smessage"),
+            Frame::bulk(channel),
+            Frame::bulk(message),
+        ]);
+        for subscriber in clients.values() {
+            subscriber.send(&push);
+        }
+        clients.len()
+    }
+
+    /// The channels of a kind with at least one subscriber, optionally only those matching
+    /// `pattern`.
+    pub fn channels(&self, kind: Kind, pattern: Option<&[u8]>) -> Vec<Vec<u8>> {
+        self.subscribers[kind.index()]
+            .keys()
+            .filter(|channel| pattern.is_none_or(|pattern| glob_match(pattern, channel)))
+            .cloned()
+            .collect()
+    }
+
+    /// How many clients are subscribed to the channel.
+    pub fn subscriber_count(&self, kind: Kind, channel: &[u8]) -> usize {
+        self.subscribers[kind.index()]
+            .get(channel)
+            .map_or(0, IndexMap::len)
+    }
+
+    /// How many distinct patterns clients are subscribed to.
+    pub fn pattern_count(&self) -> usize {
+        self.subscribers[Kind::Pattern.index()].len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{self, Receiver};
+
+    fn subscriber(id: u64) -> (Subscriber, Receiver<Vec<u8>>) {
+        let (sender, receiver) = mpsc::channel();
+        let subscriber = Subscriber {
+            id,
+            protocol: Protocol::Resp3,
+            sender,
+        };
+        (subscriber, receiver)
+    }
+
+    #[test]
+    fn test_publish_reaches_channel_and_pattern_subscribers() {
+        let mut broker = Broker::default();
+        let (one, one_messages) = subscriber(1);
+        let (two, two_messages) = subscriber(2);
+        assert!(broker.subscribe(Kind::Channel, &one, b"news"));
+        assert!(!broker.subscribe(Kind::Channel, &one, b"news"));
+        broker.subscribe(Kind::Pattern, &two, b"n*");
+
+        assert_eq!(broker.publish(b"news", b"hi"), 2);
+        assert_eq!(
+            one_messages.try_recv().unwrap(),
+            b">3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$2\r\nhi\r\n"
+        );
+        assert_eq!(
+            two_messages.try_recv().unwrap(),
+            b">4\r\n$8\r\npmessage\r\n$2\r\nn*\r\n$4\r\nnews\r\n$2\r\nhi\r\n"
+        );
+        assert_eq!(broker.publish(b"sport", b"hi"), 0);
+        assert_eq!(broker.spublish(b"news", b"hi"), 0);
+    }
+
+    #[test]
+    fn test_remove_unsubscribes_from_everything() {
+        let mut broker = Broker::default();
+        let (one, _messages) = subscriber(1);
+        broker.subscribe(Kind::Channel, &one, b"a");
+        broker.subscribe(Kind::Pattern, &one, b"b*");
+        broker.subscribe(Kind::ShardChannel, &one, b"c");
+        assert_eq!(broker.count(Kind::Pattern, 1), 2);
+        assert_eq!(broker.count(Kind::ShardChannel, 1), 1);
+        assert_eq!(broker.total(1), 3);
+
+        broker.remove(1);
+        assert_eq!(broker.total(1), 0);
+        assert!(broker.channels(Kind::Channel, None).is_empty());
+        assert_eq!(broker.pattern_count(), 0);
+        assert_eq!(broker.subscriber_count(Kind::ShardChannel, b"c"), 0);
+    }
+}
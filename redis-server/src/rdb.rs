@@ -0,0 +1,325 @@
+//! The RDB format Redis persists its dataset in, which DUMP payloads share.
+//!
+//! Lengths are encoded in one to nine bytes, with the top two bits of the first saying how:
+//!
+//! - `00xxxxxx` is a length up to 63,
+//! - `01xxxxxx xxxxxxxx` a length up to 16383, big endian,
+//! - `10000000` followed by 4 bytes, or `10000001` followed by 8, a bigger one, big endian,
+//! - `11xxxxxx` a string stored in a special encoding: an 8, 16 or 32 bit integer, little
+//!   endian, for 0 to 2, or LZF compressed data for 3, preceded by its compressed and
+//!   uncompressed lengths.
+//!
+//! A DUMP payload ends with the RDB version it was written in and a CRC64 of everything before
+//! it, both little endian. A whole file starts with `REDIS` and its version in four digits,
+//! then is a series of opcodes and values, and ends with `OPCODE_EOF` and the CRC64 of
+//! everything before that.
+
+mod listpack;
+mod lzf;
+mod snapshot;
+mod ziplist;
+
+pub use snapshot::Snapshot;
+
+use crc::{Crc, CRC_64_REDIS};
+
+pub const RDB_VERSION: u16 = 11;
+/// The version Redis 7.4 added the types of hashes whose fields expire in. Files holding such
+/// a hash are written in it, and files and payloads of any version up to it can be read.
+pub const MAX_READABLE_VERSION: u16 = 12;
+
+/// Records the position of a slot in a cluster, which doesn't matter here.
+pub const OPCODE_SLOT_INFO: u8 = 244;
+/// Introduces a function library, stored as its source code.
+pub const OPCODE_FUNCTION2: u8 = 245;
+/// Function libraries as Redis 7.0's release candidates stored them.
+pub const OPCODE_FUNCTION_PRE_GA: u8 = 246;
+/// How often the next key was used, for the LFU eviction policy.
+pub const OPCODE_FREQ: u8 = 247;
+/// How long the next key had been idle, for the LRU eviction policy.
+pub const OPCODE_IDLE: u8 = 248;
+pub const OPCODE_MODULE_AUX: u8 = 249;
+/// A named string about the file, such as the version of Redis that wrote it.
+pub const OPCODE_AUX: u8 = 250;
+/// How many keys, and how many volatile ones, the next database has.
+pub const OPCODE_RESIZEDB: u8 = 251;
+/// When the next key expires, in milliseconds since the epoch.
+pub const OPCODE_EXPIRETIME_MS: u8 = 252;
+/// When the next key expires, in seconds since the epoch.
+pub const OPCODE_EXPIRETIME: u8 = 253;
+pub const OPCODE_SELECTDB: u8 = 254;
+pub const OPCODE_EOF: u8 = 255;
+
+const CRC64: Crc<u64> = Crc::<u64>::new(&CRC_64_REDIS);
+
+const ENCODING_6BIT: u8 = 0;
+const ENCODING_14BIT: u8 = 1;
+const ENCODING_32BIT: u8 = 0x80;
+const ENCODING_64BIT: u8 = 0x81;
+const ENCODING_SPECIAL: u8 = 3;
+const ENCODING_INT8: u8 = 0;
+const ENCODING_INT16: u8 = 1;
+const ENCODING_INT32: u8 = 2;
+const ENCODING_LZF: u8 = 3;
+
+/// Strings shorter than this aren't worth compressing.
+const MIN_COMPRESSED_LEN: usize = 21;
+
+pub fn checksum(bytes: &[u8]) -> u64 {
+    CRC64.checksum(bytes)
+}
+
+pub fn write_length(out: &mut Vec<u8>, len: u64) {
+    if len < 1 << 6 {
+        out.push((ENCODING_6BIT << 6) | len as u8);
+    } else if len < 1 << 14 {
+        out.push((ENCODING_14BIT << 6) | (len >> 8) as u8);
+        out.push(len as u8);
+    } else if len <= u32::MAX as u64 {
+        out.push(ENCODING_32BIT);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    } else {
+        out.push(ENCODING_64BIT);
+        out.extend_from_slice(&len.to_be_bytes());
+    }
+}
+
+/// Writes a string the way Redis does: as an integer if it is a small one written the usual
+/// way, else compressed if that makes it shorter, else as it is.
+pub fn write_string(out: &mut Vec<u8>, s: &[u8]) {
+    if let Some(n) = small_integer(s) {
+        let special = ENCODING_SPECIAL << 6;
+        if let Ok(n) = i8::try_from(n) {
+            out.push(special | ENCODING_INT8);
+            out.extend_from_slice(&n.to_le_bytes());
+        } else if let Ok(n) = i16::try_from(n) {
+            out.push(special | ENCODING_INT16);
+            out.extend_from_slice(&n.to_le_bytes());
+        } else {
+            out.push(special | ENCODING_INT32);
+            out.extend_from_slice(&n.to_le_bytes());
+        }
+        return;
+    }
+
+    if s.len() >= MIN_COMPRESSED_LEN {
+        if let Some(compressed) = lzf::compress(s) {
+            out.push((ENCODING_SPECIAL << 6) | ENCODING_LZF);
+            write_length(out, compressed.len() as u64);
+            write_length(out, s.len() as u64);
+            out.extend_from_slice(&compressed);
+            return;
+        }
+    }
+
+    write_length(out, s.len() as u64);
+    out.extend_from_slice(s);
+}
+
+/// The 32 bit integer `s` is, if it is written exactly as the integer would be.
+fn small_integer(s: &[u8]) -> Option<i32> {
+    if s.len() > 11 {
+        return None;
+    }
+    let n: i32 = std::str::from_utf8(s).ok()?.parse().ok()?;
+    (n.to_string().as_bytes() == s).then_some(n)
+}
+
+/// Ends a DUMP payload with the RDB version and its checksum.
+pub fn seal(payload: &mut Vec<u8>) {
+    payload.extend_from_slice(&RDB_VERSION.to_le_bytes());
+    let crc = checksum(payload);
+    payload.extend_from_slice(&crc.to_le_bytes());
+}
+
+/// Checks a DUMP payload's version and checksum, returning what comes before them.
+pub fn unseal(payload: &[u8]) -> Option<&[u8]> {
+    let body_len = payload.len().checked_sub(10)?;
+    let (checked, crc) = payload.split_at(body_len + 2);
+    let version = u16::from_le_bytes([checked[body_len], checked[body_len + 1]]);
+    let crc = u64::from_le_bytes(crc.try_into().ok()?);
+
+    (version <= MAX_READABLE_VERSION && checksum(checked) == crc).then_some(&payload[..body_len])
+}
+
+/// Reads RDB encoded values one after another. Each read returns `None` if the bytes run out
+/// or don't hold what was asked for.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        let (&byte, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(byte)
+    }
+
+    pub fn read_bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < n {
+            return None;
+        }
+        let (bytes, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Some(bytes)
+    }
+
+    /// Reads a little endian number, as times in milliseconds are stored.
+    pub fn read_u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.read_bytes(8)?.try_into().ok()?))
+    }
+
+    pub fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.read_bytes(4)?.try_into().ok()?))
+    }
+
+    pub fn read_f64(&mut self) -> Option<f64> {
+        Some(f64::from_le_bytes(self.read_bytes(8)?.try_into().ok()?))
+    }
+
+    pub fn read_length(&mut self) -> Option<u64> {
+        match self.read_encoded_length()? {
+            (len, false) => Some(len),
+            (_, true) => None,
+        }
+    }
+
+    pub fn read_string(&mut self) -> Option<Vec<u8>> {
+        match self.read_encoded_length()? {
+            (len, false) => Some(self.read_bytes(usize::try_from(len).ok()?)?.to_vec()),
+            (encoding, true) if encoding as u8 == ENCODING_LZF => {
+                let compressed_len = usize::try_from(self.read_length()?).ok()?;
+                let len = usize::try_from(self.read_length()?).ok()?;
+                lzf::decompress(self.read_bytes(compressed_len)?, len)
+            }
+            (encoding, true) => {
+                let n = match encoding as u8 {
+                    ENCODING_INT8 => self.read_u8()? as i8 as i64,
+                    ENCODING_INT16 => {
+                        i16::from_le_bytes(self.read_bytes(2)?.try_into().ok()?) as i64
+                    }
+                    ENCODING_INT32 => {
+                        i32::from_le_bytes(self.read_bytes(4)?.try_into().ok()?) as i64
+                    }
+                    _ => return None,
+                };
+                Some(n.to_string().into_bytes())
+            }
+        }
+    }
+
+    /// Reads a length, or the special encoding a string is in, which the flag is set for.
+    fn read_encoded_length(&mut self) -> Option<(u64, bool)> {
+        let first = self.read_u8()?;
+        match first >> 6 {
+            ENCODING_6BIT => Some(((first & 0x3f) as u64, false)),
+            ENCODING_14BIT => Some((
+                (((first & 0x3f) as u64) << 8) | self.read_u8()? as u64,
+                false,
+            )),
+            ENCODING_SPECIAL => Some(((first & 0x3f) as u64, true)),
+            _ => match first {
+                ENCODING_32BIT => {
+                    let len = u32::from_be_bytes(self.read_bytes(4)?.try_into().ok()?);
+                    Some((len as u64, false))
+                }
+                ENCODING_64BIT => {
+                    let len = u64::from_be_bytes(self.read_bytes(8)?.try_into().ok()?);
+                    Some((len, false))
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(b"123456789"), 0xe9c6d914c4b8d9ca);
+    }
+
+    #[test]
+    fn test_lengths_round_trip() {
+        let lengths = [
+            0,
+            63,
+            64,
+            16383,
+            16384,
+            u32::MAX as u64,
+            u32::MAX as u64 + 1,
+        ];
+        let mut out = Vec::new();
+        for len in lengths {
+            write_length(&mut out, len);
+        }
+        assert_eq!(&out[..4], [0x00, 0x3f, 0x40, 0x40]);
+
+        let mut reader = Reader::new(&out);
+        for len in lengths {
+            assert_eq!(reader.read_encoded_length(), Some((len, false)));
+        }
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_read_integer_encoded_strings() {
+        let mut reader = Reader::new(&[0xc0, 0xff, 0xc1, 0x39, 0x30, 0xc2, 0x87, 0xd6, 0x12, 0x00]);
+        assert_eq!(reader.read_string(), Some(b"-1".to_vec()));
+        assert_eq!(reader.read_string(), Some(b"12345".to_vec()));
+        assert_eq!(reader.read_string(), Some(b"1234567".to_vec()));
+        assert_eq!(reader.read_string(), None);
+    }
+
+    #[test]
+    fn test_strings_round_trip() {
+        let long = vec![b'a'; 100];
+        let strings: [&[u8]; 5] = [b"", b"-70000", b"007", b"2147483648", &long];
+        let mut out = Vec::new();
+        for s in strings {
+            write_string(&mut out, s);
+        }
+        // The run of 100 a's is compressed.
+        assert_eq!(&out[out.len() - 9..out.len() - 5], [0xc3, 0x05, 0x40, 100]);
+
+        let mut reader = Reader::new(&out);
+        for s in strings {
+            assert_eq!(reader.read_string().as_deref(), Some(s));
+        }
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_seal_and_unseal() {
+        let mut payload = Vec::new();
+        write_string(&mut payload, b"hello");
+        seal(&mut payload);
+        assert_eq!(unseal(&payload), Some(&b"\x05hello"[..]));
+
+        let last = payload.len() - 1;
+        payload[last] ^= 1;
+        assert_eq!(unseal(&payload), None);
+        assert_eq!(unseal(b"short"), None);
+
+        // Payloads from Redis 7.4, and none from newer versions.
+        for (version, readable) in [(12u16, true), (13, false)] {
+            let mut payload = b"\x05hello".to_vec();
+            payload.extend_from_slice(&version.to_le_bytes());
+            let crc = checksum(&payload);
+            payload.extend_from_slice(&crc.to_le_bytes());
+            assert_eq!(unseal(&payload).is_some(), readable);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Listpacks, the compact encoding Redis keeps small collections in and writes to RDB files
+//! as they are.
+//!
+//! A listpack starts with its total size in 4 bytes and its number of elements in 2, both
+//! little endian, and ends with a `0xFF` byte. Each element in between is an encoding byte and
+//! its data, followed by the size of those two in 1 to 5 bytes, so the listpack can be walked
+//! backwards as well. Strings that are integers are stored as integers, in as few bytes as
+//! they fit in.
+
+const HEADER_SIZE: usize = 6;
+const EOF: u8 = 0xff;
+/// The element count a listpack with too many elements to count in its header says it has.
+const UNKNOWN_COUNT: u16 = u16::MAX;
+
+/// Builds a listpack one element at a time.
+#[derive(Default)]
+pub struct Listpack {
+    elements: Vec<u8>,
+    count: usize,
+}
+
+impl Listpack {
+    pub fn new() -> Listpack {
+        Listpack::default()
+    }
+
+    /// The size of the listpack as it would be finished now.
+    pub fn size(&self) -> usize {
+        HEADER_SIZE + self.elements.len() + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Appends a string, as an integer if it is one written the usual way.
+    pub fn push(&mut self, element: &[u8]) {
+        let integer = std::str::from_utf8(element)
+            .ok()
+            .and_then(|text| text.parse::<i64>().ok())
+            .filter(|n| n.to_string().as_bytes() == element);
+        if let Some(n) = integer {
+            return self.push_integer(n);
+        }
+
+        let start = self.elements.len();
+        let len = element.len();
+        if len < 1 << 6 {
+            self.elements.push(0x80 | len as u8);
+        } else if len < 1 << 12 {
+            self.elements.push(0xe0 | (len >> 8) as u8);
+            self.elements.push(len as u8);
+        } else {
+            self.elements.push(0xf0);
+            self.elements.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+        self.elements.extend_from_slice(element);
+        self.end_element(start);
+    }
+
+    pub fn push_integer(&mut self, n: i64) {
+        let start = self.elements.len();
+        match n {
+            0..=127 => self.elements.push(n as u8),
+            -4096..=4095 => {
+                let n = n as u16 & 0x1fff;
+                self.elements.push(0xc0 | (n >> 8) as u8);
+                self.elements.push(n as u8);
+            }
+            -32768..=32767 => {
+                self.elements.push(0xf1);
+                self.elements.extend_from_slice(&(n as i16).to_le_bytes());
+            }
+            -8388608..=8388607 => {
+                self.elements.push(0xf2);
+                self.elements
+                    .extend_from_slice(&(n as i32).to_le_bytes()[..3]);
+            }
+            -2147483648..=2147483647 => {
+                self.elements.push(0xf3);
+                self.elements.extend_from_slice(&(n as i32).to_le_bytes());
+            }
+            _ => {
+                self.elements.push(0xf4);
+                self.elements.extend_from_slice(&n.to_le_bytes());
+            }
+        }
+        self.end_element(start);
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        let mut listpack = Vec::with_capacity(self.size());
+        listpack.extend_from_slice(&(self.size() as u32).to_le_bytes());
+        let count = u16::try_from(self.count).unwrap_or(UNKNOWN_COUNT);
+        listpack.extend_from_slice(&count.to_le_bytes());
+        listpack.extend_from_slice(&self.elements);
+        listpack.push(EOF);
+        listpack
+    }
+
+    /// Writes the size of the element that starts at `start`, most significant 7 bits first.
+    /// Every byte but the first has its top bit set.
+    fn end_element(&mut self, start: usize) {
+        let len = self.elements.len() - start;
+        let bytes = backlen_size(len);
+        for i in (0..bytes).rev() {
+            let digit = ((len >> (7 * i)) & 0x7f) as u8;
+            self.elements
+                .push(if i == bytes - 1 { digit } else { digit | 0x80 });
+        }
+        self.count += 1;
+    }
+}
+
+/// The elements of a listpack, with integers written out as strings. Returns `None` if the
+/// bytes aren't a listpack.
+pub fn decode(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let size = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+    if size != bytes.len() || size < HEADER_SIZE + 1 {
+        return None;
+    }
+
+    let mut elements = Vec::new();
+    let mut i = HEADER_SIZE;
+    loop {
+        let encoding = *bytes.get(i)?;
+        let data = &bytes[i + 1..];
+        let (element, len) = match encoding {
+            EOF => break,
+            0x00..=0x7f => (Element::Integer(encoding as i64), 1),
+            0x80..=0xbf => {
+                let len = (encoding & 0x3f) as usize;
+                (Element::String(data.get(..len)?), 1 + len)
+            }
+            0xc0..=0xdf => {
+                let n = (((encoding & 0x1f) as i64) << 8) | *data.first()? as i64;
+                (
+                    Element::Integer(if n >= 1 << 12 { n - (1 << 13) } else { n }),
+                    2,
+                )
+            }
+            0xe0..=0xef => {
+                let len = (((encoding & 0x0f) as usize) << 8) | *data.first()? as usize;
+                (Element::String(data.get(1..1 + len)?), 2 + len)
+            }
+            0xf0 => {
+                let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+                (Element::String(data.get(4..4 + len)?), 5 + len)
+            }
+            0xf1 => {
+                let n = i16::from_le_bytes(data.get(..2)?.try_into().ok()?);
+                (Element::Integer(n as i64), 3)
+            }
+            0xf2 => {
+                let bytes = data.get(..3)?;
+                // Shifted up into an i32 and back down again to carry the sign.
+                let n = i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8;
+                (Element::Integer(n as i64), 4)
+            }
+            0xf3 => {
+                let n = i32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+                (Element::Integer(n as i64), 5)
+            }
+            0xf4 => {
+                let n = i64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+                (Element::Integer(n), 9)
+            }
+            _ => return None,
+        };
+
+        elements.push(match element {
+            Element::Integer(n) => n.to_string().into_bytes(),
+            Element::String(s) => s.to_vec(),
+        });
+        i += len + backlen_size(len);
+    }
+
+    (i == bytes.len() - 1).then_some(elements)
+}
+
+enum Element<'a> {
+    Integer(i64),
+    String(&'a [u8]),
+}
+
+/// How many bytes it takes to write an element's size after it, 7 bits to a byte.
+fn backlen_size(len: usize) -> usize {
+    match len {
+        0..=127 => 1,
+        128..=16382 => 2,
+        16383..=2097150 => 3,
+        2097151..=268435454 => 4,
+        _ => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        let mut listpack = Listpack::new();
+        listpack.push(b"a");
+        listpack.push(b"5");
+        listpack.push(b"-1");
+        assert_eq!(
+            listpack.finish(),
+            [15, 0, 0, 0, 3, 0, 0x81, b'a', 2, 5, 1, 0xdf, 0xff, 2, 0xff]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let long = vec![b'x'; 5000];
+        let elements: Vec<Vec<u8>> = [
+            &b""[..],
+            b"0",
+            b"007",
+            b"-0",
+            b"4095",
+            b"-4096",
+            b"30000",
+            b"-8388608",
+            b"2147483647",
+            b"-9223372036854775808",
+            b"9223372036854775808",
+            &[b'y'; 100],
+            &long,
+        ]
+        .iter()
+        .map(|element| element.to_vec())
+        .collect();
+
+        let mut listpack = Listpack::new();
+        for element in &elements {
+            listpack.push(element);
+        }
+        let size = listpack.size();
+        let bytes = listpack.finish();
+        assert_eq!(bytes.len(), size);
+        assert_eq!(decode(&bytes), Some(elements));
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_sizes() {
+        let mut listpack = Listpack::new();
+        listpack.push(b"hello");
+        let mut bytes = listpack.finish();
+        assert_eq!(decode(&bytes[..bytes.len() - 1]), None);
+
+        bytes[0] += 1;
+        bytes.push(EOF);
+        assert_eq!(decode(&bytes), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! LZF, the compression Redis uses for longer strings in RDB files.
+//!
+//! Compressed data is a series of chunks, each starting with a control byte:
+//!
+//! - `000LLLLL` is followed by `L + 1` literal bytes,
+//! - `LLLooooo oooooooo` copies `L + 2` bytes from `o + 1` bytes back in the output, where
+//!   `L` of 7 means the length continues in an extra byte before the second offset byte.
+//!
+//! A copy may overlap the bytes it produces, which is how runs are stored.
+
+/// The most literal bytes one chunk holds.
+const MAX_LITERALS: usize = 32;
+/// The furthest back a copy can reach.
+const MAX_OFFSET: usize = 1 << 13;
+/// The longest copy: 7 in the control byte plus 255 in the extra one, plus 2.
+const MAX_COPY: usize = 264;
+const HASH_BITS: u32 = 14;
+
+/// Compresses `input`, returning `None` if that wouldn't save at least 4 bytes, which is when
+/// Redis stores a string as it is instead.
+pub fn compress(input: &[u8]) -> Option<Vec<u8>> {
+    let limit = input.len().checked_sub(4)?;
+    let mut out = Vec::with_capacity(limit);
+    // Where each 3 byte sequence was last seen, keyed by its hash.
+    let mut seen = vec![usize::MAX; 1 << HASH_BITS];
+    let mut literals = 0;
+    let mut i = 0;
+
+    while i + 2 < input.len() {
+        let hash = hash(&input[i..i + 3]);
+        let candidate = std::mem::replace(&mut seen[hash], i);
+        let matches = candidate != usize::MAX
+            && i - candidate <= MAX_OFFSET
+            && input[candidate..candidate + 3] == input[i..i + 3];
+        if !matches {
+            i += 1;
+            continue;
+        }
+
+        let longest = MAX_COPY.min(input.len() - i);
+        let mut len = 3;
+        while len < longest && input[candidate + len] == input[i + len] {
+            len += 1;
+        }
+
+        push_literals(&mut out, &input[literals..i]);
+        let offset = i - candidate - 1;
+        if len - 2 < 7 {
+            out.push((((len - 2) << 5) | (offset >> 8)) as u8);
+        } else {
+            out.push(((7 << 5) | (offset >> 8)) as u8);
+            out.push((len - 2 - 7) as u8);
+        }
+        out.push(offset as u8);
+
+        i += len;
+        literals = i;
+        if out.len() > limit {
+            return None;
+        }
+    }
+    push_literals(&mut out, &input[literals..]);
+
+    (out.len() <= limit).then_some(out)
+}
+
+/// Decompresses `input` into exactly `len` bytes, returning `None` if it doesn't hold that.
+pub fn decompress(input: &[u8], len: usize) -> Option<Vec<u8>> {
+    // The length comes from the file, so it is only trusted as far as the input could hold.
+    let mut out = Vec::with_capacity(len.min(input.len().saturating_mul(MAX_COPY)));
+    let mut i = 0;
+
+    while i < input.len() && out.len() <= len {
+        let control = input[i] as usize;
+        i += 1;
+
+        if control < MAX_LITERALS {
+            let literals = input.get(i..i + control + 1)?;
+            out.extend_from_slice(literals);
+            i += control + 1;
+            continue;
+        }
+
+        let mut copy = control >> 5;
+        if copy == 7 {
+            copy += *input.get(i)? as usize;
+            i += 1;
+        }
+        let offset = (((control & 0x1f) << 8) | *input.get(i)? as usize) + 1;
+        i += 1;
+
+        let start = out.len().checked_sub(offset)?;
+        for j in start..start + copy + 2 {
+            out.push(out[j]);
+        }
+    }
+
+    (out.len() == len).then_some(out)
+}
+
+fn push_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_LITERALS) {
+        out.push((chunk.len() - 1) as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let v = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32;
+    (v.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let inputs: [&[u8]; 3] = [
+            b"abcabcabcabcabcabcabcabcabcabcabcabc",
+            &[b'x'; 1000],
+            b"the quick brown fox jumps over the lazy dog, the quick brown fox jumps again",
+        ];
+        for input in inputs {
+            let compressed = compress(input).unwrap();
+            assert!(compressed.len() < input.len());
+            assert_eq!(decompress(&compressed, input.len()).as_deref(), Some(input));
+        }
+    }
+
+    #[test]
+    fn test_incompressible_input_is_left_alone() {
+        assert_eq!(compress(b"abcdefghijklmnopqrstuvwxyz"), None);
+        assert_eq!(compress(b"abc"), None);
+    }
+
+    #[test]
+    fn test_decompress_overlapping_copy() {
+        // One literal "a", then 29 bytes copied from 1 byte back.
+        let compressed = [0x00, b'a', 0xe0, 0x14, 0x00];
+        assert_eq!(decompress(&compressed, 30), Some(vec![b'a'; 30]));
+        assert_eq!(decompress(&compressed, 31), None);
+        assert_eq!(decompress(&compressed[..4], 30), None);
+        assert_eq!(decompress(&[0xe0, 0x14, 0x00], 30), None);
+    }
+}
@@ -0,0 +1,995 @@
+use super::listpack::{self, Listpack};
+use super::ziplist;
+use super::{
+    checksum, write_length, write_string, Reader, MAX_READABLE_VERSION, OPCODE_AUX, OPCODE_EOF,
+    OPCODE_EXPIRETIME, OPCODE_EXPIRETIME_MS, OPCODE_FREQ, OPCODE_FUNCTION2, OPCODE_FUNCTION_PRE_GA,
+    OPCODE_IDLE, OPCODE_MODULE_AUX, OPCODE_RESIZEDB, OPCODE_SELECTDB, OPCODE_SLOT_INFO,
+    RDB_VERSION,
+};
+use crate::commands::hello::SERVER_VERSION;
+use crate::db::{
+    now_ms, Consumer, ConsumerGroup, Fields, Hash, PendingEntry, SortedSet, Stream, StreamId, Value,
+};
+use indexmap::IndexSet;
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+/// A sorted set with its scores written out as text.
+const TYPE_ZSET: u8 = 3;
+const TYPE_HASH: u8 = 4;
+/// A sorted set with its scores as little endian doubles.
+const TYPE_ZSET_2: u8 = 5;
+const TYPE_HASH_ZIPMAP: u8 = 9;
+const TYPE_LIST_ZIPLIST: u8 = 10;
+const TYPE_SET_INTSET: u8 = 11;
+const TYPE_ZSET_ZIPLIST: u8 = 12;
+const TYPE_HASH_ZIPLIST: u8 = 13;
+/// A list as a series of ziplists, as Redis wrote them before 7.0.
+const TYPE_LIST_QUICKLIST: u8 = 14;
+const TYPE_STREAM_LISTPACKS: u8 = 15;
+const TYPE_HASH_LISTPACK: u8 = 16;
+const TYPE_ZSET_LISTPACK: u8 = 17;
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+/// A stream that also records its first ID, deletions and how many entries its groups read.
+const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+const TYPE_SET_LISTPACK: u8 = 20;
+/// A stream that also records when each consumer last got anything.
+const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+/// A hash with fields that expire, their times relative to the earliest of them.
+const TYPE_HASH_METADATA: u8 = 24;
+const TYPE_HASH_LISTPACK_EX: u8 = 25;
+
+const VALUE_TYPES: [u8; 21] = [
+    TYPE_STRING,
+    TYPE_LIST,
+    TYPE_SET,
+    TYPE_ZSET,
+    TYPE_HASH,
+    TYPE_ZSET_2,
+    TYPE_HASH_ZIPMAP,
+    TYPE_LIST_ZIPLIST,
+    TYPE_SET_INTSET,
+    TYPE_ZSET_ZIPLIST,
+    TYPE_HASH_ZIPLIST,
+    TYPE_LIST_QUICKLIST,
+    TYPE_STREAM_LISTPACKS,
+    TYPE_HASH_LISTPACK,
+    TYPE_ZSET_LISTPACK,
+    TYPE_LIST_QUICKLIST_2,
+    TYPE_STREAM_LISTPACKS_2,
+    TYPE_SET_LISTPACK,
+    TYPE_STREAM_LISTPACKS_3,
+    TYPE_HASH_METADATA,
+    TYPE_HASH_LISTPACK_EX,
+];
+
+/// A node of a list holding a single element as it is.
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+/// A node of a list holding a listpack of elements.
+const QUICKLIST_NODE_PACKED: u64 = 2;
+/// How big the listpacks of a list get, as with Redis's default `list-max-listpack-size`.
+const LIST_NODE_SIZE: usize = 8 * 1024;
+/// How many entries the listpacks of a stream hold, as with Redis's default
+/// `stream-node-max-entries`.
+const STREAM_NODE_ENTRIES: usize = 100;
+
+const STREAM_ITEM_DELETED: i64 = 1;
+/// The entry has the same fields as the first entry of its listpack, so only its values are
+/// written.
+const STREAM_ITEM_SAMEFIELDS: i64 = 2;
+
+/// Everything an RDB file holds that the server keeps: the keys, and the function libraries.
+#[derive(Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// Each key with its value and when it expires, if it does.
+    pub entries: Vec<(Vec<u8>, Value, Option<u64>)>,
+    /// The source code of each function library.
+    pub libraries: Vec<Vec<u8>>,
+}
+
+impl Snapshot {
+    /// Writes the snapshot out as an RDB file that Redis 7.2 and later can load, or only 7.4 and
+    /// later if a hash has fields that expire, as those need the newer version.
+    ///
+    /// Collections are written in the plain encodings, which Redis converts to its compact
+    /// ones as it loads them, except for lists and streams, whose only encodings are built from
+    /// listpacks.
+    pub fn encode(&self) -> Vec<u8> {
+        let expiring_fields = self.entries.iter().any(|(_, value, _)| {
+            matches!(value, Value::Hash(hash) if hash.earliest_expire_time().is_some())
+        });
+        let version = match expiring_fields {
+            true => MAX_READABLE_VERSION,
+            false => RDB_VERSION,
+        };
+        let mut out = format!("REDIS{:04}", version).into_bytes();
+        let ctime = (now_ms() / 1000).to_string();
+        let aux: [(&[u8], &[u8]); 4] = [
+            (b"redis-ver", SERVER_VERSION.as_bytes()),
+            (b"redis-bits", b"64"),
+            (b"ctime", ctime.as_bytes()),
+            (b"aof-base", b"0"),
+        ];
+        for (name, value) in aux {
+            out.push(OPCODE_AUX);
+            write_string(&mut out, name);
+            write_string(&mut out, value);
+        }
+
+        for code in &self.libraries {
+            out.push(OPCODE_FUNCTION2);
+            write_string(&mut out, code);
+        }
+
+        out.push(OPCODE_SELECTDB);
+        write_length(&mut out, 0);
+        out.push(OPCODE_RESIZEDB);
+        write_length(&mut out, self.entries.len() as u64);
+        let volatile = self.entries.iter().filter(|(_, _, when)| when.is_some());
+        write_length(&mut out, volatile.count() as u64);
+
+        for (key, value, expires) in &self.entries {
+            if let Some(when) = expires {
+                out.push(OPCODE_EXPIRETIME_MS);
+                out.extend_from_slice(&when.to_le_bytes());
+            }
+            write_value(&mut out, key, value);
+        }
+
+        out.push(OPCODE_EOF);
+        let crc = checksum(&out);
+        out.extend_from_slice(&crc.to_le_bytes());
+        out
+    }
+
+    /// Reads an RDB file. Empty collections are left out, as Redis doesn't keep them either. A
+    /// file with keys in databases other than the first is refused, as there is only the one
+    /// database here.
+    pub fn decode(bytes: &[u8]) -> io::Result<Snapshot> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let corrupt = || invalid(String::from("Short read or corrupt RDB file"));
+
+        let version = bytes
+            .strip_prefix(b"REDIS")
+            .and_then(|rest| rest.get(..4))
+            .and_then(|version| std::str::from_utf8(version).ok()?.parse::<u16>().ok())
+            .ok_or_else(|| invalid(String::from("Wrong signature trying to load DB from file")))?;
+        if version == 0 || version > MAX_READABLE_VERSION {
+            return Err(invalid(format!(
+                "Can't handle RDB format version {}",
+                version
+            )));
+        }
+
+        // Files have ended in a checksum since version 5. One of 0 wasn't worked out.
+        let mut body = &bytes[9..];
+        if version >= 5 {
+            let len = body.len().checked_sub(8).ok_or_else(corrupt)?;
+            let crc = u64::from_le_bytes(body[len..].try_into().unwrap());
+            if crc != 0 && checksum(&bytes[..bytes.len() - 8]) != crc {
+                return Err(invalid(String::from("Wrong RDB checksum")));
+            }
+            body = &body[..len];
+        }
+
+        let mut reader = Reader::new(body);
+        let mut snapshot = Snapshot::default();
+        let mut expires = None;
+        loop {
+            let opcode = reader.read_u8().ok_or_else(corrupt)?;
+            match opcode {
+                OPCODE_EOF => break,
+                OPCODE_SELECTDB => {
+                    if reader.read_length().ok_or_else(corrupt)? != 0 {
+                        return Err(invalid(String::from(
+                            "FATAL: Data file was created with a Redis server configured to handle more than 1 databases",
+                        )));
+                    }
+                }
+                OPCODE_RESIZEDB => {
+                    reader.read_length().ok_or_else(corrupt)?;
+                    reader.read_length().ok_or_else(corrupt)?;
+                }
+                OPCODE_SLOT_INFO => {
+                    for _ in 0..3 {
+                        reader.read_length().ok_or_else(corrupt)?;
+                    }
+                }
+                OPCODE_AUX => {
+                    reader.read_string().ok_or_else(corrupt)?;
+                    reader.read_string().ok_or_else(corrupt)?;
+                }
+                OPCODE_IDLE => {
+                    reader.read_length().ok_or_else(corrupt)?;
+                }
+                OPCODE_FREQ => {
+                    reader.read_u8().ok_or_else(corrupt)?;
+                }
+                OPCODE_EXPIRETIME_MS => expires = Some(reader.read_u64().ok_or_else(corrupt)?),
+                OPCODE_EXPIRETIME => {
+                    let seconds = reader.read_u32().ok_or_else(corrupt)?;
+                    expires = Some(seconds as u64 * 1000);
+                }
+                OPCODE_FUNCTION2 => {
+                    let code = reader.read_string().ok_or_else(corrupt)?;
+                    snapshot.libraries.push(code);
+                }
+                OPCODE_FUNCTION_PRE_GA => {
+                    return Err(invalid(String::from(
+                        "Pre-release function format not supported",
+                    )))
+                }
+                OPCODE_MODULE_AUX => {
+                    return Err(invalid(String::from("Modules are not supported")))
+                }
+                kind if VALUE_TYPES.contains(&kind) => {
+                    let key = reader.read_string().ok_or_else(corrupt)?;
+                    let value = read_value(&mut reader, kind).ok_or_else(corrupt)?;
+                    let expires = expires.take();
+                    if !is_empty_collection(&value) {
+                        snapshot.entries.push((key, value, expires));
+                    }
+                }
+                kind => return Err(invalid(format!("Unknown RDB encoding type {}", kind))),
+            }
+        }
+
+        Ok(snapshot)
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, key: &[u8], value: &Value) {
+    match value {
+        Value::String(s) => {
+            out.push(TYPE_STRING);
+            write_string(out, key);
+            write_string(out, s);
+        }
+        Value::List(elements) => {
+            out.push(TYPE_LIST_QUICKLIST_2);
+            write_string(out, key);
+            write_list(out, elements);
+        }
+        Value::Set(members) => {
+            out.push(TYPE_SET);
+            write_string(out, key);
+            write_length(out, members.len() as u64);
+            for member in members {
+                write_string(out, member);
+            }
+        }
+        Value::SortedSet(zset) => {
+            out.push(TYPE_ZSET_2);
+            write_string(out, key);
+            write_length(out, zset.len() as u64);
+            for (member, score) in zset.iter() {
+                write_string(out, member);
+                out.extend_from_slice(&score.to_le_bytes());
+            }
+        }
+        Value::Hash(hash) => write_hash(out, key, hash),
+        Value::Stream(stream) => {
+            out.push(TYPE_STREAM_LISTPACKS_3);
+            write_string(out, key);
+            write_stream(out, stream);
+        }
+    }
+}
+
+fn write_list(out: &mut Vec<u8>, elements: &VecDeque<Vec<u8>>) {
+    let mut nodes = Vec::new();
+    let mut node = Listpack::new();
+    for element in elements {
+        if !node.is_empty() && node.size() + element.len() > LIST_NODE_SIZE {
+            nodes.push(std::mem::take(&mut node).finish());
+        }
+        node.push(element);
+    }
+    if !node.is_empty() {
+        nodes.push(node.finish());
+    }
+
+    write_length(out, nodes.len() as u64);
+    for node in nodes {
+        write_length(out, QUICKLIST_NODE_PACKED);
+        write_string(out, &node);
+    }
+}
+
+/// Writes a hash whose fields expire the way Redis 7.4 does, and any other hash the way every
+/// version does.
+fn write_hash(out: &mut Vec<u8>, key: &[u8], hash: &Hash) {
+    let Some(earliest) = hash.earliest_expire_time() else {
+        out.push(TYPE_HASH);
+        write_string(out, key);
+        write_length(out, hash.len() as u64);
+        for (field, value) in hash.iter() {
+            write_string(out, field);
+            write_string(out, value);
+        }
+        return;
+    };
+
+    out.push(TYPE_HASH_METADATA);
+    write_string(out, key);
+    out.extend_from_slice(&earliest.to_le_bytes());
+    write_length(out, hash.len() as u64);
+    for (field, value) in hash.iter() {
+        // 0 is for fields that don't expire, so the earliest time is written as 1.
+        let ttl = hash
+            .expire_time(field)
+            .map_or(0, |when| when - earliest + 1);
+        write_length(out, ttl);
+        write_string(out, field);
+        write_string(out, value);
+    }
+}
+
+/// Writes a stream's entries as listpacks, each starting with a master entry whose ID and
+/// fields the entries after it are written relative to, followed by the stream's consumer
+/// groups.
+fn write_stream(out: &mut Vec<u8>, stream: &Stream) {
+    let entries: Vec<(StreamId, &Fields)> = stream.range(StreamId::MIN..=StreamId::MAX).collect();
+    write_length(out, entries.len().div_ceil(STREAM_NODE_ENTRIES) as u64);
+    for node in entries.chunks(STREAM_NODE_ENTRIES) {
+        let (master_id, master_fields) = node[0];
+        let mut listpack = Listpack::new();
+        listpack.push_integer(node.len() as i64);
+        // None of the entries are deleted.
+        listpack.push_integer(0);
+        listpack.push_integer(master_fields.len() as i64);
+        for (field, _) in master_fields {
+            listpack.push(field);
+        }
+        listpack.push_integer(0);
+
+        for (id, fields) in node {
+            let same_fields = fields.len() == master_fields.len()
+                && fields
+                    .iter()
+                    .zip(master_fields)
+                    .all(|((field, _), (master_field, _))| field == master_field);
+            listpack.push_integer(if same_fields {
+                STREAM_ITEM_SAMEFIELDS
+            } else {
+                0
+            });
+            listpack.push_integer(id.ms.wrapping_sub(master_id.ms) as i64);
+            listpack.push_integer(id.seq.wrapping_sub(master_id.seq) as i64);
+            if same_fields {
+                for (_, value) in fields.iter() {
+                    listpack.push(value);
+                }
+            } else {
+                listpack.push_integer(fields.len() as i64);
+                for (field, value) in fields.iter() {
+                    listpack.push(field);
+                    listpack.push(value);
+                }
+            }
+            // How many elements the entry took, so it can be walked backwards.
+            let fields_written = if same_fields { 0 } else { fields.len() + 1 };
+            listpack.push_integer((3 + fields.len() + fields_written) as i64);
+        }
+
+        write_string(out, &raw_id(master_id));
+        write_string(out, &listpack.finish());
+    }
+
+    let first_id = stream.first().map_or(StreamId::MIN, |(id, _)| id);
+    write_length(out, stream.len() as u64);
+    for id in [stream.last_id(), first_id, stream.max_deleted_id()] {
+        write_length(out, id.ms);
+        write_length(out, id.seq);
+    }
+    write_length(out, stream.entries_added());
+
+    write_length(out, stream.groups().count() as u64);
+    for (name, group) in stream.groups() {
+        write_string(out, name);
+        write_length(out, group.last_id.ms);
+        write_length(out, group.last_id.seq);
+        // Redis keeps an unknown count as -1.
+        write_length(out, group.entries_read.unwrap_or(u64::MAX));
+
+        write_length(out, group.pending.len() as u64);
+        for (id, entry) in &group.pending {
+            out.extend_from_slice(&raw_id(*id));
+            out.extend_from_slice(&entry.delivery_time.to_le_bytes());
+            write_length(out, entry.delivery_count);
+        }
+
+        write_length(out, group.consumers.len() as u64);
+        for (name, consumer) in &group.consumers {
+            write_string(out, name);
+            out.extend_from_slice(&consumer.seen_time.to_le_bytes());
+            out.extend_from_slice(&consumer.active_time.unwrap_or(u64::MAX).to_le_bytes());
+            let pending: Vec<&StreamId> = group
+                .pending
+                .iter()
+                .filter(|(_, entry)| entry.consumer == *name)
+                .map(|(id, _)| id)
+                .collect();
+            write_length(out, pending.len() as u64);
+            for id in pending {
+                out.extend_from_slice(&raw_id(*id));
+            }
+        }
+    }
+}
+
+/// A stream ID as Redis writes one out in full: both parts in 8 bytes, big endian.
+fn raw_id(id: StreamId) -> [u8; 16] {
+    let mut raw = [0; 16];
+    raw[..8].copy_from_slice(&id.ms.to_be_bytes());
+    raw[8..].copy_from_slice(&id.seq.to_be_bytes());
+    raw
+}
+
+fn read_raw_id(raw: &[u8]) -> Option<StreamId> {
+    let ms = u64::from_be_bytes(raw.get(..8)?.try_into().ok()?);
+    let seq = u64::from_be_bytes(raw.get(8..16)?.try_into().ok()?);
+    (raw.len() == 16).then_some(StreamId::new(ms, seq))
+}
+
+fn read_value(reader: &mut Reader, kind: u8) -> Option<Value> {
+    let value = match kind {
+        TYPE_STRING => Value::String(reader.read_string()?),
+        TYPE_LIST => Value::List(read_strings(reader)?.into()),
+        TYPE_LIST_ZIPLIST => Value::List(ziplist::decode(&reader.read_string()?)?.into()),
+        TYPE_LIST_QUICKLIST => {
+            let mut elements = VecDeque::new();
+            for _ in 0..reader.read_length()? {
+                elements.extend(ziplist::decode(&reader.read_string()?)?);
+            }
+            Value::List(elements)
+        }
+        TYPE_LIST_QUICKLIST_2 => {
+            let mut elements = VecDeque::new();
+            for _ in 0..reader.read_length()? {
+                let container = reader.read_length()?;
+                let node = reader.read_string()?;
+                match container {
+                    QUICKLIST_NODE_PLAIN => elements.push_back(node),
+                    QUICKLIST_NODE_PACKED => elements.extend(listpack::decode(&node)?),
+                    _ => return None,
+                }
+            }
+            Value::List(elements)
+        }
+        TYPE_SET => Value::Set(read_strings(reader)?.into_iter().collect()),
+        TYPE_SET_INTSET => Value::Set(read_intset(&reader.read_string()?)?),
+        TYPE_SET_LISTPACK => {
+            let members = listpack::decode(&reader.read_string()?)?;
+            Value::Set(members.into_iter().collect())
+        }
+        TYPE_ZSET | TYPE_ZSET_2 => {
+            let mut zset = SortedSet::new();
+            for _ in 0..reader.read_length()? {
+                let member = reader.read_string()?;
+                let score = match kind {
+                    TYPE_ZSET_2 => reader.read_f64()?,
+                    _ => read_text_score(reader)?,
+                };
+                if score.is_nan() {
+                    return None;
+                }
+                zset.insert(member, score);
+            }
+            Value::SortedSet(zset)
+        }
+        TYPE_ZSET_LISTPACK | TYPE_ZSET_ZIPLIST => {
+            let mut zset = SortedSet::new();
+            let elements = read_packed(reader, kind)?;
+            for pair in elements.chunks(2) {
+                let [member, score] = pair else {
+                    return None;
+                };
+                let score: f64 = std::str::from_utf8(score).ok()?.parse().ok()?;
+                if score.is_nan() {
+                    return None;
+                }
+                zset.insert(member.clone(), score);
+            }
+            Value::SortedSet(zset)
+        }
+        TYPE_HASH => {
+            let mut hash = Hash::new();
+            for _ in 0..reader.read_length()? {
+                hash.insert(reader.read_string()?, reader.read_string()?);
+            }
+            Value::Hash(hash)
+        }
+        TYPE_HASH_LISTPACK | TYPE_HASH_ZIPLIST | TYPE_HASH_ZIPMAP => {
+            let mut hash = Hash::new();
+            let elements = read_packed(reader, kind)?;
+            for pair in elements.chunks(2) {
+                let [field, value] = pair else {
+                    return None;
+                };
+                hash.insert(field.clone(), value.clone());
+            }
+            Value::Hash(hash)
+        }
+        TYPE_HASH_METADATA => {
+            let earliest = reader.read_u64()?;
+            let mut hash = Hash::new();
+            for _ in 0..reader.read_length()? {
+                let ttl = reader.read_length()?;
+                let field = reader.read_string()?;
+                hash.insert(field.clone(), reader.read_string()?);
+                if ttl != 0 {
+                    hash.set_expire_time(&field, (ttl + earliest).checked_sub(1)?);
+                }
+            }
+            Value::Hash(hash)
+        }
+        TYPE_HASH_LISTPACK_EX => {
+            // The earliest time is only there for Redis to schedule expiry with.
+            reader.read_u64()?;
+            let mut hash = Hash::new();
+            let elements = listpack::decode(&reader.read_string()?)?;
+            for triple in elements.chunks(3) {
+                let [field, value, when] = triple else {
+                    return None;
+                };
+                hash.insert(field.clone(), value.clone());
+                let when: u64 = std::str::from_utf8(when).ok()?.parse().ok()?;
+                if when != 0 {
+                    hash.set_expire_time(field, when);
+                }
+            }
+            Value::Hash(hash)
+        }
+        TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+            Value::Stream(read_stream(reader, kind)?)
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Reads the elements of a collection kept in a listpack, ziplist or zipmap, which `kind` says.
+fn read_packed(reader: &mut Reader, kind: u8) -> Option<Vec<Vec<u8>>> {
+    let packed = reader.read_string()?;
+    match kind {
+        TYPE_ZSET_ZIPLIST | TYPE_HASH_ZIPLIST => ziplist::decode(&packed),
+        TYPE_HASH_ZIPMAP => ziplist::decode_zipmap(&packed),
+        _ => listpack::decode(&packed),
+    }
+}
+
+fn read_strings(reader: &mut Reader) -> Option<Vec<Vec<u8>>> {
+    (0..reader.read_length()?)
+        .map(|_| reader.read_string())
+        .collect()
+}
+
+/// Reads a score written as text after its length in one byte, with lengths 253 to 255 saying
+/// it is NaN, infinity or negative infinity instead.
+fn read_text_score(reader: &mut Reader) -> Option<f64> {
+    match reader.read_u8()? {
+        253 => Some(f64::NAN),
+        254 => Some(f64::INFINITY),
+        255 => Some(f64::NEG_INFINITY),
+        len => std::str::from_utf8(reader.read_bytes(len as usize)?)
+            .ok()?
+            .parse()
+            .ok(),
+    }
+}
+
+/// Reads the members of an intset: how wide its integers are and how many there are, then the
+/// integers themselves, all little endian.
+fn read_intset(intset: &[u8]) -> Option<IndexSet<Vec<u8>>> {
+    let width = u32::from_le_bytes(intset.get(..4)?.try_into().ok()?) as usize;
+    let len = u32::from_le_bytes(intset.get(4..8)?.try_into().ok()?) as usize;
+    if ![2, 4, 8].contains(&width) || intset.len() != 8 + width * len {
+        return None;
+    }
+
+    let members = intset[8..].chunks(width).map(|n| {
+        let n = match width {
+            2 => i16::from_le_bytes([n[0], n[1]]) as i64,
+            4 => i32::from_le_bytes([n[0], n[1], n[2], n[3]]) as i64,
+            _ => i64::from_le_bytes(n.try_into().unwrap()),
+        };
+        n.to_string().into_bytes()
+    });
+    Some(members.collect())
+}
+
+fn read_stream(reader: &mut Reader, kind: u8) -> Option<Stream> {
+    let mut stream = Stream::new();
+    for _ in 0..reader.read_length()? {
+        let master_id = read_raw_id(&reader.read_string()?)?;
+        let elements = listpack::decode(&reader.read_string()?)?;
+        read_stream_node(&mut stream, master_id, &elements)?;
+    }
+
+    // The number of entries, which is known from reading them.
+    reader.read_length()?;
+    let last_id = StreamId::new(reader.read_length()?, reader.read_length()?);
+    let (max_deleted_id, entries_added) = if kind >= TYPE_STREAM_LISTPACKS_2 {
+        // The first ID, which is also known.
+        reader.read_length()?;
+        reader.read_length()?;
+        let max_deleted_id = StreamId::new(reader.read_length()?, reader.read_length()?);
+        (max_deleted_id, reader.read_length()?)
+    } else {
+        (StreamId::MIN, stream.len() as u64)
+    };
+    stream.set_history(last_id, max_deleted_id, entries_added);
+
+    for _ in 0..reader.read_length()? {
+        let name = reader.read_string()?;
+        let last_id = StreamId::new(reader.read_length()?, reader.read_length()?);
+        let entries_read = match kind {
+            TYPE_STREAM_LISTPACKS => None,
+            _ => Some(reader.read_length()?).filter(|read| *read != u64::MAX),
+        };
+        let mut group = ConsumerGroup::new(last_id, entries_read);
+
+        // The group's pending entries come first, then which consumer each belongs to.
+        let mut unowned = BTreeMap::new();
+        for _ in 0..reader.read_length()? {
+            let id = read_raw_id(reader.read_bytes(16)?)?;
+            let delivery_time = reader.read_u64()?;
+            let delivery_count = reader.read_length()?;
+            unowned.insert(id, (delivery_time, delivery_count));
+        }
+
+        for _ in 0..reader.read_length()? {
+            let name = reader.read_string()?;
+            let seen_time = reader.read_u64()?;
+            let active_time = match kind {
+                TYPE_STREAM_LISTPACKS_3 => Some(reader.read_u64()?).filter(|t| *t != u64::MAX),
+                // The best guess there is.
+                _ => Some(seen_time),
+            };
+            for _ in 0..reader.read_length()? {
+                let id = read_raw_id(reader.read_bytes(16)?)?;
+                let (delivery_time, delivery_count) = unowned.remove(&id)?;
+                let entry = PendingEntry {
+                    consumer: name.clone(),
+                    delivery_time,
+                    delivery_count,
+                };
+                group.pending.insert(id, entry);
+            }
+            let consumer = Consumer {
+                seen_time,
+                active_time,
+            };
+            group.consumers.insert(name, consumer);
+        }
+
+        stream.create_group(name, group);
+    }
+
+    Some(stream)
+}
+
+/// Reads the entries of one of a stream's listpacks, leaving out those marked deleted.
+fn read_stream_node(stream: &mut Stream, master_id: StreamId, elements: &[Vec<u8>]) -> Option<()> {
+    let mut elements = elements.iter();
+    let count = next_integer(&mut elements)?;
+    let deleted = next_integer(&mut elements)?;
+    let master_fields: Vec<&Vec<u8>> = (0..next_integer(&mut elements)?)
+        .map(|_| elements.next())
+        .collect::<Option<_>>()?;
+    if next_integer(&mut elements)? != 0 {
+        return None;
+    }
+
+    for _ in 0..count + deleted {
+        let flags = next_integer(&mut elements)?;
+        let ms = master_id
+            .ms
+            .wrapping_add(next_integer(&mut elements)? as u64);
+        let seq = master_id
+            .seq
+            .wrapping_add(next_integer(&mut elements)? as u64);
+        let fields: Fields = if flags & STREAM_ITEM_SAMEFIELDS != 0 {
+            master_fields
+                .iter()
+                .map(|field| Some(((*field).clone(), elements.next()?.clone())))
+                .collect::<Option<_>>()?
+        } else {
+            (0..next_integer(&mut elements)?)
+                .map(|_| Some((elements.next()?.clone(), elements.next()?.clone())))
+                .collect::<Option<_>>()?
+        };
+        next_integer(&mut elements)?;
+
+        if flags & STREAM_ITEM_DELETED == 0 {
+            stream.insert(StreamId::new(ms, seq), fields);
+        }
+    }
+
+    Some(())
+}
+
+fn next_integer<'a>(elements: &mut impl Iterator<Item = &'a Vec<u8>>) -> Option<i64> {
+    std::str::from_utf8(elements.next()?).ok()?.parse().ok()
+}
+
+fn is_empty_collection(value: &Value) -> bool {
+    match value {
+        Value::List(elements) => elements.is_empty(),
+        Value::Set(members) => members.is_empty(),
+        Value::SortedSet(zset) => zset.is_empty(),
+        Value::Hash(hash) => hash.is_empty(),
+        Value::String(_) | Value::Stream(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What Redis 7.2 writes for an empty dataset.
+    const EMPTY_REDIS_RDB: &[u8] = &[
+        0x52, 0x45, 0x44, 0x49, 0x53, 0x30, 0x30, 0x31, 0x31, 0xfa, 0x09, 0x72, 0x65, 0x64, 0x69,
+        0x73, 0x2d, 0x76, 0x65, 0x72, 0x05, 0x37, 0x2e, 0x32, 0x2e, 0x30, 0xfa, 0x0a, 0x72, 0x65,
+        0x64, 0x69, 0x73, 0x2d, 0x62, 0x69, 0x74, 0x73, 0xc0, 0x40, 0xfa, 0x05, 0x63, 0x74, 0x69,
+        0x6d, 0x65, 0xc2, 0x6d, 0x08, 0xbc, 0x65, 0xfa, 0x08, 0x75, 0x73, 0x65, 0x64, 0x2d, 0x6d,
+        0x65, 0x6d, 0xc2, 0xb0, 0xc4, 0x10, 0x00, 0xfa, 0x08, 0x61, 0x6f, 0x66, 0x2d, 0x62, 0x61,
+        0x73, 0x65, 0xc0, 0x00, 0xff, 0xf0, 0x6e, 0x3b, 0xfe, 0xc0, 0xff, 0x5a, 0xa2,
+    ];
+
+    fn bytes(values: &[&str]) -> Vec<Vec<u8>> {
+        values
+            .iter()
+            .map(|value| value.as_bytes().to_vec())
+            .collect()
+    }
+
+    fn stream() -> Stream {
+        let mut stream = Stream::new();
+        for i in 1..=150 {
+            let fields = match i % 3 {
+                0 => vec![(b"other".to_vec(), b"x".to_vec())],
+                _ => vec![
+                    (b"n".to_vec(), i.to_string().into_bytes()),
+                    (b"name".to_vec(), b"reading".to_vec()),
+                ],
+            };
+            stream.insert(StreamId::new(1000 + i / 2, i % 2), fields);
+        }
+        stream.remove(StreamId::new(1001, 1));
+
+        let mut group = ConsumerGroup::new(StreamId::new(1010, 0), Some(20));
+        group.consumers.insert(
+            b"alice".to_vec(),
+            Consumer {
+                seen_time: 5000,
+                active_time: None,
+            },
+        );
+        group.consumers.insert(
+            b"bob".to_vec(),
+            Consumer {
+                seen_time: 6000,
+                active_time: Some(5500),
+            },
+        );
+        group.pending.insert(
+            StreamId::new(1005, 0),
+            PendingEntry {
+                consumer: b"bob".to_vec(),
+                delivery_time: 5500,
+                delivery_count: 2,
+            },
+        );
+        stream.create_group(b"readers".to_vec(), group);
+        stream
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut hash = Hash::new();
+        hash.insert(b"plain".to_vec(), b"1".to_vec());
+        let mut volatile_hash = hash.clone();
+        volatile_hash.insert(b"soon".to_vec(), b"2".to_vec());
+        volatile_hash.insert(b"later".to_vec(), b"3".to_vec());
+        volatile_hash.set_expire_time(b"soon", 1_000_000);
+        volatile_hash.set_expire_time(b"later", 2_000_000);
+
+        let mut zset = SortedSet::new();
+        zset.insert(b"a".to_vec(), 1.5);
+        zset.insert(b"b".to_vec(), f64::NEG_INFINITY);
+
+        let long_list: VecDeque<Vec<u8>> = (0..5000)
+            .map(|i| format!("item {}", i).into_bytes())
+            .collect();
+
+        let snapshot = Snapshot {
+            entries: vec![
+                (b"string".to_vec(), Value::String(b"hello".to_vec()), None),
+                (
+                    b"12345".to_vec(),
+                    Value::String(b"-70000".to_vec()),
+                    Some(u64::MAX / 2),
+                ),
+                (b"long".to_vec(), Value::String(vec![b'z'; 300]), None),
+                (
+                    b"list".to_vec(),
+                    Value::List(bytes(&["a", "1", "b"]).into()),
+                    None,
+                ),
+                (b"long list".to_vec(), Value::List(long_list), None),
+                (
+                    b"set".to_vec(),
+                    Value::Set(bytes(&["x", "y"]).into_iter().collect()),
+                    None,
+                ),
+                (b"zset".to_vec(), Value::SortedSet(zset), None),
+                (b"hash".to_vec(), Value::Hash(hash), None),
+                (b"volatile hash".to_vec(), Value::Hash(volatile_hash), None),
+                (b"stream".to_vec(), Value::Stream(stream()), None),
+                (b"empty stream".to_vec(), Value::Stream(Stream::new()), None),
+            ],
+            libraries: vec![
+                b"#!lua name=lib\nredis.register_function('f', function() return 1 end)".to_vec(),
+            ],
+        };
+
+        let encoded = snapshot.encode();
+        assert!(encoded.starts_with(b"REDIS0012"));
+        assert_eq!(Snapshot::decode(&encoded).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_version_is_12_only_with_expiring_fields() {
+        let mut hash = Hash::new();
+        hash.insert(b"f".to_vec(), b"v".to_vec());
+        let mut snapshot = Snapshot {
+            entries: vec![(b"hash".to_vec(), Value::Hash(hash.clone()), None)],
+            libraries: Vec::new(),
+        };
+        assert!(snapshot.encode().starts_with(b"REDIS0011"));
+
+        hash.set_expire_time(b"f", 1_000_000);
+        snapshot.entries[0].1 = Value::Hash(hash);
+        let encoded = snapshot.encode();
+        assert!(encoded.starts_with(b"REDIS0012"));
+        assert_eq!(Snapshot::decode(&encoded).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_decode_file_written_by_redis() {
+        assert_eq!(
+            Snapshot::decode(EMPTY_REDIS_RDB).unwrap(),
+            Snapshot::default()
+        );
+    }
+
+    #[test]
+    fn test_decode_refuses_other_databases() {
+        let mut file = b"REDIS0011".to_vec();
+        file.push(OPCODE_SELECTDB);
+        write_length(&mut file, 1);
+        file.push(TYPE_STRING);
+        write_string(&mut file, b"elsewhere");
+        write_string(&mut file, b"value");
+        file.push(OPCODE_EOF);
+        file.extend_from_slice(&[0; 8]);
+
+        let err = Snapshot::decode(&file).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("more than 1 databases"));
+    }
+
+    #[test]
+    fn test_decode_compact_encodings() {
+        let mut file = b"REDIS0011".to_vec();
+        file.push(OPCODE_SELECTDB);
+        write_length(&mut file, 0);
+
+        let mut listpack = Listpack::new();
+        for element in ["f", "v", "n", "7"] {
+            listpack.push(element.as_bytes());
+        }
+        file.push(TYPE_HASH_LISTPACK);
+        write_string(&mut file, b"hash");
+        write_string(&mut file, &listpack.finish());
+
+        let mut listpack = Listpack::new();
+        for element in ["m", "2.5", "n", "3"] {
+            listpack.push(element.as_bytes());
+        }
+        file.push(TYPE_ZSET_LISTPACK);
+        write_string(&mut file, b"zset");
+        write_string(&mut file, &listpack.finish());
+
+        // An intset of two 16 bit integers.
+        file.push(TYPE_SET_INTSET);
+        write_string(&mut file, b"intset");
+        write_string(&mut file, &[2, 0, 0, 0, 2, 0, 0, 0, 0xff, 0xff, 0x00, 0x01]);
+
+        file.push(OPCODE_EOF);
+        file.extend_from_slice(&[0; 8]);
+
+        let snapshot = Snapshot::decode(&file).unwrap();
+        let keys: Vec<&[u8]> = snapshot
+            .entries
+            .iter()
+            .map(|(key, _, _)| &key[..])
+            .collect();
+        assert_eq!(keys, [&b"hash"[..], b"zset", b"intset"]);
+
+        let Value::Hash(hash) = &snapshot.entries[0].1 else {
+            panic!("expected a hash");
+        };
+        assert_eq!(hash.get(b"n"), Some(&b"7".to_vec()));
+        let Value::SortedSet(zset) = &snapshot.entries[1].1 else {
+            panic!("expected a sorted set");
+        };
+        assert_eq!(zset.score(b"m"), Some(2.5));
+        assert_eq!(
+            snapshot.entries[2].1,
+            Value::Set(bytes(&["-1", "256"]).into_iter().collect())
+        );
+    }
+
+    #[test]
+    fn test_decode_ziplist_encodings() {
+        let mut file = b"REDIS0006".to_vec();
+        file.push(OPCODE_SELECTDB);
+        write_length(&mut file, 0);
+
+        // The ziplist "a", 5.
+        let ziplist = [
+            16, 0, 0, 0, 13, 0, 0, 0, 2, 0, 0x00, 0x01, b'a', 0x03, 0xf6, 0xff,
+        ];
+        file.push(TYPE_LIST_QUICKLIST);
+        write_string(&mut file, b"list");
+        write_length(&mut file, 2);
+        write_string(&mut file, &ziplist);
+        write_string(&mut file, &ziplist);
+
+        file.push(TYPE_ZSET_ZIPLIST);
+        write_string(&mut file, b"zset");
+        write_string(&mut file, &ziplist);
+
+        file.push(TYPE_HASH_ZIPMAP);
+        write_string(&mut file, b"hash");
+        write_string(&mut file, b"\x01\x01f\x01\x00v\xff");
+
+        file.push(OPCODE_EOF);
+        file.extend_from_slice(&[0; 8]);
+
+        let snapshot = Snapshot::decode(&file).unwrap();
+        assert_eq!(
+            snapshot.entries[0].1,
+            Value::List(bytes(&["a", "5", "a", "5"]).into())
+        );
+        let Value::SortedSet(zset) = &snapshot.entries[1].1 else {
+            panic!("expected a sorted set");
+        };
+        assert_eq!(zset.score(b"a"), Some(5.0));
+        let Value::Hash(hash) = &snapshot.entries[2].1 else {
+            panic!("expected a hash");
+        };
+        assert_eq!(hash.get(b"f"), Some(&b"v".to_vec()));
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_files() {
+        let mut corrupted = EMPTY_REDIS_RDB.to_vec();
+        corrupted[20] ^= 1;
+        assert!(Snapshot::decode(&corrupted).is_err());
+
+        let mut newer = EMPTY_REDIS_RDB.to_vec();
+        newer[5..9].copy_from_slice(b"0099");
+        assert!(Snapshot::decode(&newer).is_err());
+
+        assert!(Snapshot::decode(b"NOTRDB").is_err());
+        assert!(Snapshot::decode(&EMPTY_REDIS_RDB[..40]).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Ziplists and zipmaps, the compact encodings Redis kept small collections in before
+//! listpacks replaced them in Redis 7.0. Files written by older versions still hold them, so
+//! they are read but never written.
+//!
+//! A ziplist starts with its total size and the offset of its last element in 4 bytes each,
+//! then its number of elements in 2, all little endian, and ends with a `0xFF` byte. Each
+//! element starts with the size of the element before it, in 1 byte or `0xFE` and 4 more, so
+//! the ziplist can be walked backwards. Then comes the element's encoding:
+//!
+//! - `00xxxxxx` is a string of up to 63 bytes,
+//! - `01xxxxxx xxxxxxxx` one of up to 16383, its length big endian,
+//! - `10000000` followed by a 4 byte big endian length a longer one,
+//! - `11000000`, `11010000`, `11100000`, `11110000` and `11111110` a 16, 32, 64, 24 and 8 bit
+//!   integer, little endian,
+//! - `1111xxxx` an integer from 0 to 12, stored as one more than it.
+//!
+//! A zipmap is a hash whose fields and values follow its number of fields, in 1 byte. Each
+//! length is 1 byte, or `0xFE` and 4 more, and a value's length is followed by a byte saying
+//! how many unused bytes come after the value. A `0xFF` byte ends it.
+
+const HEADER_SIZE: usize = 10;
+const EOF: u8 = 0xff;
+/// The first byte of a length that takes 4 more bytes.
+const BIG_LENGTH: u8 = 0xfe;
+
+const INT16: u8 = 0xc0;
+const INT32: u8 = 0xd0;
+const INT64: u8 = 0xe0;
+const INT24: u8 = 0xf0;
+const INT8: u8 = 0xfe;
+
+/// Reads the elements of a ziplist, integers as their decimal strings.
+pub fn decode(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let size = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+    if size != bytes.len() || size < HEADER_SIZE + 1 {
+        return None;
+    }
+
+    let mut elements = Vec::new();
+    let mut i = HEADER_SIZE;
+    while *bytes.get(i)? != EOF {
+        i += match bytes[i] {
+            BIG_LENGTH => 5,
+            _ => 1,
+        };
+
+        let encoding = *bytes.get(i)?;
+        let data = bytes.get(i + 1..)?;
+        let (element, len) = match encoding >> 6 {
+            0 => {
+                let len = (encoding & 0x3f) as usize;
+                (data.get(..len)?.to_vec(), 1 + len)
+            }
+            1 => {
+                let len = (((encoding & 0x3f) as usize) << 8) | *data.first()? as usize;
+                (data.get(1..1 + len)?.to_vec(), 2 + len)
+            }
+            2 => {
+                let len = u32::from_be_bytes(data.get(..4)?.try_into().ok()?) as usize;
+                (data.get(4..4 + len)?.to_vec(), 5 + len)
+            }
+            _ => {
+                let (n, len) = read_integer(encoding, data)?;
+                (n.to_string().into_bytes(), 1 + len)
+            }
+        };
+        elements.push(element);
+        i += len;
+    }
+
+    (i == bytes.len() - 1).then_some(elements)
+}
+
+/// Reads an integer element's value, and how many bytes after the encoding it took.
+fn read_integer(encoding: u8, data: &[u8]) -> Option<(i64, usize)> {
+    let integer = match encoding {
+        INT16 => (
+            i16::from_le_bytes(data.get(..2)?.try_into().ok()?) as i64,
+            2,
+        ),
+        INT32 => (
+            i32::from_le_bytes(data.get(..4)?.try_into().ok()?) as i64,
+            4,
+        ),
+        INT64 => (i64::from_le_bytes(data.get(..8)?.try_into().ok()?), 8),
+        INT24 => {
+            let bytes = data.get(..3)?;
+            // Shifted up into an i32 and back down again to carry the sign.
+            let n = i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8;
+            (n as i64, 3)
+        }
+        INT8 => (data.get(..1)?[0] as i8 as i64, 1),
+        0xf1..=0xfd => ((encoding & 0x0f) as i64 - 1, 0),
+        _ => return None,
+    };
+
+    Some(integer)
+}
+
+/// Reads the fields and values of a zipmap, one after the other.
+pub fn decode_zipmap(bytes: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut elements = Vec::new();
+    // The field count comes first, but the end marker is what says where the zipmap ends.
+    let mut i = 1;
+    loop {
+        let (len, size) = match *bytes.get(i)? {
+            EOF => break,
+            BIG_LENGTH => {
+                let len = u32::from_le_bytes(bytes.get(i + 1..i + 5)?.try_into().ok()?);
+                (len as usize, 5)
+            }
+            len => (len as usize, 1),
+        };
+        i += size;
+
+        // Every other string is a value, which is followed by unused bytes.
+        let is_value = elements.len() % 2 == 1;
+        let free = if is_value {
+            i += 1;
+            *bytes.get(i - 1)? as usize
+        } else {
+            0
+        };
+        elements.push(bytes.get(i..i + len)?.to_vec());
+        i += len + free;
+    }
+
+    (i == bytes.len() - 1 && elements.len() % 2 == 0).then_some(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ziplist() {
+        let mut ziplist = vec![24, 0, 0, 0, 19, 0, 0, 0, 3, 0];
+        ziplist.extend_from_slice(&[0x00, 0x05]);
+        ziplist.extend_from_slice(b"hello");
+        ziplist.extend_from_slice(&[0x07, 0xf6]);
+        ziplist.extend_from_slice(&[0x02, INT16, 0xe8, 0x03]);
+        ziplist.push(EOF);
+
+        assert_eq!(
+            decode(&ziplist),
+            Some(vec![b"hello".to_vec(), b"5".to_vec(), b"1000".to_vec()])
+        );
+
+        ziplist[0] = 25;
+        assert_eq!(decode(&ziplist), None);
+    }
+
+    #[test]
+    fn test_decode_ziplist_integers() {
+        let mut ziplist = vec![0; HEADER_SIZE];
+        ziplist.extend_from_slice(&[0x00, INT8, 0x9c]);
+        ziplist.extend_from_slice(&[0x03, INT24, 0xff, 0xff, 0xff]);
+        ziplist.extend_from_slice(&[0x05, INT32, 0x00, 0x00, 0x00, 0x80]);
+        ziplist.extend_from_slice(&[0x06, INT64]);
+        ziplist.extend_from_slice(&i64::MAX.to_le_bytes());
+        ziplist.push(EOF);
+        let size = ziplist.len() as u32;
+        ziplist[..4].copy_from_slice(&size.to_le_bytes());
+        ziplist[8] = 4;
+
+        assert_eq!(
+            decode(&ziplist),
+            Some(vec![
+                b"-100".to_vec(),
+                b"-1".to_vec(),
+                b"-2147483648".to_vec(),
+                i64::MAX.to_string().into_bytes(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decode_zipmap() {
+        let mut zipmap = vec![2];
+        zipmap.extend_from_slice(b"\x03foo\x03\x00bar");
+        zipmap.extend_from_slice(b"\x01a\x02\x01xy?");
+        zipmap.push(EOF);
+
+        assert_eq!(
+            decode_zipmap(&zipmap),
+            Some(vec![
+                b"foo".to_vec(),
+                b"bar".to_vec(),
+                b"a".to_vec(),
+                b"xy".to_vec()
+            ])
+        );
+        assert_eq!(decode_zipmap(&zipmap[..zipmap.len() - 1]), None);
+    }
+}
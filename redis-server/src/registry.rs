@@ -0,0 +1,3378 @@
+use crate::blocking::Outcome;
+use crate::client::Client;
+use crate::commands::exec::Dispatch;
+use crate::commands::{self, CommandError, Result};
+use crate::db::Keyspace;
+use crate::resp::decoder::parse_integer;
+use crate::resp::types::Frame;
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+
+type Args = VecDeque<Vec<u8>>;
+
+/// What the server knows about a command without running it.
+pub struct CommandSpec {
+    pub name: &'static str,
+    /// How many arguments the command takes, counting its name. A negative arity means at
+    /// least that many.
+    pub arity: i64,
+    /// Redis's flags for the command, such as `write` or `noscript`.
+    pub flags: &'static [&'static str],
+    /// Which of its arguments are keys.
+    pub keys: Keys,
+    /// The ACL categories the command is in besides those that follow from its flags.
+    pub categories: &'static [&'static str],
+    /// The group the command is documented under, such as `string` or `server`.
+    pub group: &'static str,
+    pub summary: &'static str,
+    /// The Redis version that introduced the command.
+    pub since: &'static str,
+    pub handler: Handler,
+    /// For a container command such as CONFIG, the specs of its subcommands, which are named
+    /// after both, as in `config|get`. Their arities count the container's name too.
+    pub subcommands: &'static [CommandSpec],
+}
+
+/// Where a command's keys are among its arguments, counting the command's name as argument 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Keys {
+    None,
+    /// Every `step`th argument from `first` to `last`. A negative `last` counts back from the
+    /// last argument, so -1 is the last argument itself.
+    Range {
+        first: usize,
+        last: i64,
+        step: usize,
+    },
+    /// The argument at `count` says how many keys follow it. A command with a `destination`
+    /// stores its result in the key at argument 1 as well.
+    Counted {
+        count: usize,
+        destination: bool,
+    },
+    /// The keys are the first half of the arguments after STREAMS.
+    Streams,
+}
+
+/// How a command is run, which depends on what it needs besides its arguments.
+#[derive(Clone, Copy)]
+pub enum Handler {
+    Plain(fn(&mut Args) -> Result<Frame>),
+    Client(fn(&mut Args, &mut Client) -> Result<Frame>),
+    Keyspace(fn(&mut Args, &mut Keyspace) -> Result<Frame>),
+    ClientKeyspace(fn(&mut Args, &mut Client, &mut Keyspace) -> Result<Frame>),
+    /// May leave the client waiting until it can be served.
+    Blocking(fn(&mut Args, &mut Keyspace) -> Result<Outcome>),
+    /// Replies once for every channel subscribed to or unsubscribed from.
+    Subscribe(fn(&mut Args, &mut Client, &mut Keyspace) -> Result<Vec<Frame>>),
+    /// Runs other commands, as transactions, scripts and functions do.
+    Dispatching(fn(&mut Args, &mut Client, &mut Keyspace, Dispatch) -> Result<Frame>),
+}
+
+/// Every ACL category, in the order Redis lists them.
+pub const ACL_CATEGORIES: [&str; 21] = [
+    "@keyspace",
+    "@read",
+    "@write",
+    "@set",
+    "@sortedset",
+    "@list",
+    "@hash",
+    "@string",
+    "@bitmap",
+    "@hyperloglog",
+    "@geo",
+    "@stream",
+    "@pubsub",
+    "@admin",
+    "@fast",
+    "@slow",
+    "@blocking",
+    "@dangerous",
+    "@connection",
+    "@transaction",
+    "@scripting",
+];
+
+impl CommandSpec {
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    /// Checks the number of arguments, counting the command's name.
+    pub fn check_arity(&self, argc: usize) -> Result<()> {
+        let argc = argc as i64;
+        if (self.arity >= 0 && argc != self.arity) || argc < -self.arity {
+            return Err(CommandError::WrongArity(self.name));
+        }
+
+        Ok(())
+    }
+
+    /// The spec of the subcommand named by the first of `args`, the arguments after the
+    /// command's name. Commands that aren't containers, and containers run without a
+    /// subcommand, are their own spec.
+    pub fn subcommand(&'static self, args: &[Vec<u8>]) -> Result<&'static CommandSpec> {
+        let Some(subcommand) = args.first().filter(|_| !self.subcommands.is_empty()) else {
+            return Ok(self);
+        };
+
+        let name = format!(
+            "{}|{}",
+            self.name,
+            String::from_utf8_lossy(subcommand).to_lowercase()
+        );
+        self.subcommands
+            .iter()
+            .find(|spec| spec.name == name)
+            .ok_or_else(|| {
+                CommandError::Other(format!(
+                    "ERR unknown subcommand '{}'. Try {} HELP.",
+                    String::from_utf8_lossy(subcommand),
+                    self.name.to_uppercase()
+                ))
+            })
+    }
+
+    /// Whether where the keys are depends on the arguments, so no fixed range describes them.
+    pub fn has_movable_keys(&self) -> bool {
+        matches!(self.keys, Keys::Counted { .. } | Keys::Streams)
+    }
+
+    /// The first key, last key and step between keys, as the arguments a command always has
+    /// would place them. Commands with movable keys only report the keys that don't move.
+    pub fn key_range(&self) -> (i64, i64, i64) {
+        match self.keys {
+            Keys::Range { first, last, step } => (first as i64, last, step as i64),
+            Keys::Counted {
+                destination: true, ..
+            } => (1, 1, 1),
+            Keys::None | Keys::Counted { .. } | Keys::Streams => (0, 0, 0),
+        }
+    }
+
+    /// The command's ACL categories: those implied by its flags along with its own.
+    pub fn acl_categories(&self) -> Vec<&'static str> {
+        let implied = [
+            ("write", "@write"),
+            ("readonly", "@read"),
+            ("admin", "@admin"),
+            ("admin", "@dangerous"),
+            ("pubsub", "@pubsub"),
+            ("fast", "@fast"),
+            ("blocking", "@blocking"),
+        ];
+        ACL_CATEGORIES
+            .into_iter()
+            .filter(|category| {
+                self.categories.contains(category)
+                    || implied
+                        .iter()
+                        .any(|(flag, implied)| implied == category && self.has_flag(flag))
+                    || (*category == "@slow" && !self.has_flag("fast"))
+            })
+            .collect()
+    }
+
+    /// Finds which of `argv`, the command's name followed by its arguments, are keys. Arguments
+    /// that don't say where the keys are, such as a key count that isn't a number, are an
+    /// error.
+    pub fn key_positions(&self, argv: &[Vec<u8>]) -> Result<Vec<usize>> {
+        let invalid =
+            || CommandError::Other(String::from("ERR Invalid arguments specified for command"));
+
+        let positions = match self.keys {
+            Keys::None => Vec::new(),
+            Keys::Range { first, last, step } => {
+                let last = if last < 0 {
+                    argv.len() as i64 + last
+                } else {
+                    last
+                };
+                if first >= argv.len() || last < first as i64 || last >= argv.len() as i64 {
+                    return Err(invalid());
+                }
+                (first..=last as usize).step_by(step).collect()
+            }
+            Keys::Counted { count, destination } => {
+                let numkeys = argv
+                    .get(count)
+                    .and_then(|numkeys| parse_integer(numkeys))
+                    .filter(|numkeys| *numkeys >= 0)
+                    .ok_or_else(invalid)? as usize;
+                if count + numkeys >= argv.len() {
+                    return Err(invalid());
+                }
+                let destination = destination.then_some(1);
+                destination
+                    .into_iter()
+                    .chain(count + 1..=count + numkeys)
+                    .collect()
+            }
+            Keys::Streams => {
+                let streams = argv
+                    .iter()
+                    .position(|arg| arg.eq_ignore_ascii_case(b"STREAMS"))
+                    .ok_or_else(invalid)?;
+                let rest = argv.len() - streams - 1;
+                if rest == 0 || !rest.is_multiple_of(2) {
+                    return Err(invalid());
+                }
+                (streams + 1..=streams + rest / 2).collect()
+            }
+        };
+
+        Ok(positions)
+    }
+}
+
+/// Finds a command by its lowercase name.
+pub fn lookup(name: &str) -> Option<&'static CommandSpec> {
+    static BY_NAME: LazyLock<HashMap<&str, &CommandSpec>> =
+        LazyLock::new(|| COMMANDS.iter().map(|spec| (spec.name, spec)).collect());
+
+    BY_NAME.get(name).copied()
+}
+
+/// Finds the spec that applies to a command run with `args`, which for a container is its
+/// subcommand's, and checks the number of arguments against it.
+pub fn resolve(name: &str, args: &[Vec<u8>]) -> Result<&'static CommandSpec> {
+    let spec = lookup(name)
+        .ok_or_else(|| unknown_command(name, args))?
+        .subcommand(args)?;
+    spec.check_arity(args.len() + 1)?;
+    Ok(spec)
+}
+
+/// Every command the server has.
+pub fn commands() -> &'static [CommandSpec] {
+    COMMANDS
+}
+
+/// The error for a command the server doesn't have, quoting the name as it was sent and the
+/// start of its arguments. Like Redis, it stops quoting arguments after 128 bytes.
+pub fn unknown_command(name: &str, args: &[Vec<u8>]) -> CommandError {
+    let mut quoted = String::new();
+    for arg in args {
+        if quoted.len() >= QUOTED_ARGS_LEN {
+            break;
+        }
+        let arg = String::from_utf8_lossy(arg);
+        let arg = truncate(&arg, QUOTED_ARGS_LEN - quoted.len());
+        quoted.push_str(&format!("'{}' ", arg));
+    }
+    let message = format!(
+        "ERR unknown command '{}', with args beginning with: {}",
+        truncate(name, QUOTED_ARGS_LEN),
+        quoted
+    );
+    CommandError::Other(message.replace(['\r', '\n'], " "))
+}
+
+const QUOTED_ARGS_LEN: usize = 128;
+
+/// The longest start of `s` that is at most `len` bytes.
+fn truncate(s: &str, len: usize) -> &str {
+    let mut end = len.min(s.len());
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "ping",
+        arity: -1,
+        flags: &["fast"],
+        keys: Keys::None,
+        categories: &["@connection"],
+        group: "connection",
+        summary: "Returns the server's liveliness response.",
+        since: "1.0.0",
+        handler: Handler::Client(commands::ping::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "echo",
+        arity: 2,
+        flags: &["fast"],
+        keys: Keys::None,
+        categories: &["@connection"],
+        group: "connection",
+        summary: "Returns the given string.",
+        since: "1.0.0",
+        handler: Handler::Plain(commands::echo::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hello",
+        arity: -1,
+        flags: &["noscript", "loading", "stale", "fast", "no_auth", "allow_busy"],
+        keys: Keys::None,
+        categories: &["@connection"],
+        group: "connection",
+        summary: "Handshakes with the Redis server.",
+        since: "6.0.0",
+        handler: Handler::Client(commands::hello::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "get",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Returns the string value of a key.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::get::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "set",
+        arity: -3,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Sets the string value of a key, ignoring its type. The key is created if it doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::set::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "getset",
+        arity: 3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Returns the previous string value of a key after setting it to a new value.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::getset::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "getdel",
+        arity: 2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Returns the string value of a key after deleting the key.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::getdel::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "getex",
+        arity: -2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Returns the string value of a key after setting its expiration time.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::getex::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "setnx",
+        arity: 3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Set the string value of a key only when the key doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::setnx::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "setex",
+        arity: 4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Sets the string value and expiration time of a key. Creates the key if it doesn't exist.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::setex::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "psetex",
+        arity: 4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Sets both string value and expiration time in milliseconds of a key. The key is created if it doesn't exist.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::psetex::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "expire",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Sets the expiration time of a key in seconds.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::expire::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pexpire",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Sets the expiration time of a key in milliseconds.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::pexpire::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "expireat",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Sets the expiration time of a key to a Unix timestamp.",
+        since: "1.2.0",
+        handler: Handler::Keyspace(commands::expireat::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pexpireat",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Sets the expiration time of a key to a Unix milliseconds timestamp.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::pexpireat::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "ttl",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Returns the expiration time in seconds of a key.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::ttl::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pttl",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Returns the expiration time in milliseconds of a key.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::pttl::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "expiretime",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Returns the expiration time of a key as a Unix timestamp.",
+        since: "7.0.0",
+        handler: Handler::Keyspace(commands::expiretime::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pexpiretime",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Returns the expiration time of a key as a Unix milliseconds timestamp.",
+        since: "7.0.0",
+        handler: Handler::Keyspace(commands::pexpiretime::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "persist",
+        arity: 2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@keyspace"],
+        group: "generic",
+        summary: "Removes the expiration time of a key.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::persist::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "incr",
+        arity: 2,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Increments the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::incr::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "decr",
+        arity: 2,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Decrements the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::decr::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "incrby",
+        arity: 3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Increments the integer value of a key by a number. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::incrby::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "decrby",
+        arity: 3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Decrements a number from the integer value of a key. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::decrby::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "incrbyfloat",
+        arity: 3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Increment the floating point value of a key by a number. Uses 0 as initial value if the key doesn't exist.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::incrbyfloat::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "append",
+        arity: 3,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Appends a string to the value of a key. Creates the key if it doesn't exist.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::append::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "strlen",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Returns the length of a string value.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::strlen::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "getrange",
+        arity: 4,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Returns a substring of the string stored at a key.",
+        since: "2.4.0",
+        handler: Handler::Keyspace(commands::getrange::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "setrange",
+        arity: 4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Overwrites a part of a string value with another by an offset. Creates the key if it doesn't exist.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::setrange::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "mget",
+        arity: -2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Atomically returns the string values of one or more keys.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::mget::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "mset",
+        arity: -3,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 2,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Atomically creates or modifies the string values of one or more keys.",
+        since: "1.0.1",
+        handler: Handler::Keyspace(commands::mset::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "msetnx",
+        arity: -3,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 2,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Atomically modifies the string values of one or more keys only when all keys don't exist.",
+        since: "1.0.1",
+        handler: Handler::Keyspace(commands::msetnx::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lcs",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 2,
+            step: 1,
+        },
+        categories: &["@string"],
+        group: "string",
+        summary: "Finds the longest common substring.",
+        since: "7.0.0",
+        handler: Handler::Keyspace(commands::lcs::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lpush",
+        arity: -3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Prepends one or more elements to a list. Creates the key if it doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::lpush::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "rpush",
+        arity: -3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Appends one or more elements to a list. Creates the key if it doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::rpush::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lpushx",
+        arity: -3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Prepends one or more elements to a list only when the list exists.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::lpushx::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "rpushx",
+        arity: -3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Appends an element to a list only when the list exists.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::rpushx::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lpop",
+        arity: -2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns the first elements in a list after removing it. Deletes the list if the last element was popped.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::lpop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "rpop",
+        arity: -2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns and removes the last elements of a list. Deletes the list if the last element was popped.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::rpop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "llen",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns the length of a list.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::llen::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lrange",
+        arity: 4,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns a range of elements from a list.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::lrange::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lindex",
+        arity: 3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns an element from a list by its index.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::lindex::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lset",
+        arity: 4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Sets the value of an element in a list by its index.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::lset::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "linsert",
+        arity: 5,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Inserts an element before or after another element in a list.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::linsert::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lrem",
+        arity: 4,
+        flags: &["write"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Removes elements from a list. Deletes the list if the last element was removed.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::lrem::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "ltrim",
+        arity: 4,
+        flags: &["write"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Removes elements from both ends a list. Deletes the list if all elements were trimmed.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::ltrim::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lpos",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns the index of matching elements in a list.",
+        since: "6.0.6",
+        handler: Handler::Keyspace(commands::lpos::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lmove",
+        arity: 5,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 2,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns an element after popping it from one list and pushing it to another. Deletes the list if the last element was moved.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::lmove::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lmpop",
+        arity: -4,
+        flags: &["write"],
+        keys: Keys::Counted {
+            count: 1,
+            destination: false,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Returns multiple elements from a list after removing them. Deletes the list if the last element was popped.",
+        since: "7.0.0",
+        handler: Handler::Keyspace(commands::lmpop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hset",
+        arity: -4,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Creates or modifies the value of a field in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hset::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hsetnx",
+        arity: 4,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Sets the value of a field in a hash only when the field doesn't exist.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hsetnx::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hget",
+        arity: 3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns the value of a field in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hget::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hmget",
+        arity: -3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns the values of all fields in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hmget::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hdel",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Deletes one or more fields and their values from a hash. Deletes the hash if no fields remain.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hdel::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hexists",
+        arity: 3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Determines whether a field exists in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hexists::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hlen",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns the number of fields in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hlen::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hstrlen",
+        arity: 3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns the length of the value of a field.",
+        since: "3.2.0",
+        handler: Handler::Keyspace(commands::hstrlen::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hkeys",
+        arity: 2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns all fields in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hkeys::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hvals",
+        arity: 2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns all values in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hvals::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hgetall",
+        arity: 2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns all fields and values in a hash.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hgetall::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hincrby",
+        arity: 4,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Increments the integer value of a field in a hash by a number. Uses 0 as initial value if the field doesn't exist.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::hincrby::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hincrbyfloat",
+        arity: 4,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Increments the floating point value of a field by a number. Uses 0 as initial value if the field doesn't exist.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::hincrbyfloat::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hrandfield",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns one or more random fields from a hash.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::hrandfield::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hscan",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Iterates over fields and values of a hash.",
+        since: "2.8.0",
+        handler: Handler::Keyspace(commands::hscan::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hexpire",
+        arity: -6,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Set expiry for hash field using relative time to expire (seconds)",
+        since: "7.4.0",
+        handler: Handler::Keyspace(commands::hexpire::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hpexpire",
+        arity: -6,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Set expiry for hash field using relative time to expire (milliseconds)",
+        since: "7.4.0",
+        handler: Handler::Keyspace(commands::hpexpire::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "httl",
+        arity: -5,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns the TTL in seconds of a hash field.",
+        since: "7.4.0",
+        handler: Handler::Keyspace(commands::httl::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hpttl",
+        arity: -5,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Returns the TTL in milliseconds of a hash field.",
+        since: "7.4.0",
+        handler: Handler::Keyspace(commands::hpttl::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "hpersist",
+        arity: -5,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hash"],
+        group: "hash",
+        summary: "Removes the expiration time for each specified field",
+        since: "7.4.0",
+        handler: Handler::Keyspace(commands::hpersist::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sadd",
+        arity: -3,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Adds one or more members to a set. Creates the key if it doesn't exist.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sadd::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "srem",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Removes one or more members from a set. Deletes the set if the last member was removed.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::srem::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sismember",
+        arity: 3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Determines whether a member belongs to a set.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sismember::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "smismember",
+        arity: -3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Determines whether multiple members belong to a set.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::smismember::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "smembers",
+        arity: 2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Returns all members of a set.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::smembers::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "scard",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Returns the number of members in a set.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::scard::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "spop",
+        arity: -2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Returns one or more random members from a set after removing them. Deletes the set if the last member was popped.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::spop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "srandmember",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Get one or multiple random members from a set",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::srandmember::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "smove",
+        arity: 4,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 2,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Moves a member from one set to another.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::smove::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sscan",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Iterates over members of a set.",
+        since: "2.8.0",
+        handler: Handler::Keyspace(commands::sscan::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sinter",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Returns the intersect of multiple sets.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sinter::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sinterstore",
+        arity: -3,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Stores the intersect of multiple sets in a key.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sinterstore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sunion",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Returns the union of multiple sets.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sunion::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sunionstore",
+        arity: -3,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Stores the union of multiple sets in a key.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sunionstore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sdiff",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Returns the difference of multiple sets.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sdiff::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sdiffstore",
+        arity: -3,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Stores the difference of multiple sets in a key.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::sdiffstore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sintercard",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Counted {
+            count: 1,
+            destination: false,
+        },
+        categories: &["@set"],
+        group: "set",
+        summary: "Returns the number of members of the intersect of multiple sets.",
+        since: "7.0.0",
+        handler: Handler::Keyspace(commands::sintercard::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zadd",
+        arity: -4,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Adds one or more members to a sorted set, or updates their scores. Creates the key if it doesn't exist.",
+        since: "1.2.0",
+        handler: Handler::Keyspace(commands::zadd::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zrem",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Removes one or more members from a sorted set. Deletes the sorted set if all members were removed.",
+        since: "1.2.0",
+        handler: Handler::Keyspace(commands::zrem::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zscore",
+        arity: 3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the score of a member in a sorted set.",
+        since: "1.2.0",
+        handler: Handler::Keyspace(commands::zscore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zmscore",
+        arity: -3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the score of one or more members in a sorted set.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::zmscore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zincrby",
+        arity: 4,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Increments the score of a member in a sorted set.",
+        since: "1.2.0",
+        handler: Handler::Keyspace(commands::zincrby::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zcard",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the number of members in a sorted set.",
+        since: "1.2.0",
+        handler: Handler::Keyspace(commands::zcard::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zcount",
+        arity: 4,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the count of members in a sorted set that have scores within a range.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::zcount::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zrank",
+        arity: -3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the index of a member in a sorted set ordered by ascending scores.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::zrank::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zrevrank",
+        arity: -3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the index of a member in a sorted set ordered by descending scores.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::zrevrank::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zrange",
+        arity: -4,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns members in a sorted set within a range of indexes.",
+        since: "1.2.0",
+        handler: Handler::Keyspace(commands::zrange::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zrangestore",
+        arity: -5,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 2,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Stores a range of members from sorted set in a key.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::zrangestore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zpopmin",
+        arity: -2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the lowest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::zpopmin::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zpopmax",
+        arity: -2,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Returns the highest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::zpopmax::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zunionstore",
+        arity: -4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: true,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Stores the union of multiple sorted sets in a key.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::zunionstore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zinterstore",
+        arity: -4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: true,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Stores the intersect of multiple sorted sets in a key.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::zinterstore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "zscan",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Iterates over members and scores of a sorted set.",
+        since: "2.8.0",
+        handler: Handler::Keyspace(commands::zscan::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xack",
+        arity: -4,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Returns the number of messages that were successfully acknowledged by the consumer group member of a stream.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xack::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xadd",
+        arity: -5,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Appends a new message to a stream. Creates the key if it doesn't exist.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xadd::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xautoclaim",
+        arity: -6,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Changes, or acquires, ownership of messages in a consumer group, as if the messages were delivered to as consumer group member.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::xautoclaim::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xclaim",
+        arity: -6,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Changes, or acquires, ownership of a message in a consumer group, as if the message was delivered a consumer group member.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xclaim::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xdel",
+        arity: -3,
+        flags: &["write", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Returns the number of messages after removing them from a stream.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xdel::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xgroup",
+        arity: -2,
+        flags: &[],
+        keys: Keys::None,
+        categories: &[],
+        group: "stream",
+        summary: "A container for consumer groups commands.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xgroup::execute),
+        subcommands: &[
+            CommandSpec {
+                name: "xgroup|create",
+                arity: -5,
+                flags: &["write", "denyoom"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Creates a consumer group.",
+                since: "5.0.0",
+                handler: Handler::Keyspace(commands::xgroup::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "xgroup|createconsumer",
+                arity: 5,
+                flags: &["write", "denyoom"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Creates a consumer in a consumer group.",
+                since: "6.2.0",
+                handler: Handler::Keyspace(commands::xgroup::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "xgroup|delconsumer",
+                arity: 5,
+                flags: &["write"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Deletes a consumer from a consumer group.",
+                since: "5.0.0",
+                handler: Handler::Keyspace(commands::xgroup::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "xgroup|destroy",
+                arity: 4,
+                flags: &["write"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Destroys a consumer group.",
+                since: "5.0.0",
+                handler: Handler::Keyspace(commands::xgroup::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "xgroup|setid",
+                arity: -5,
+                flags: &["write"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Sets the last-delivered ID of a consumer group.",
+                since: "5.0.0",
+                handler: Handler::Keyspace(commands::xgroup::execute),
+                subcommands: &[],
+            },
+        ],
+    },
+    CommandSpec {
+        name: "xinfo",
+        arity: -2,
+        flags: &[],
+        keys: Keys::None,
+        categories: &[],
+        group: "stream",
+        summary: "A container for stream introspection commands.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xinfo::execute),
+        subcommands: &[
+            CommandSpec {
+                name: "xinfo|consumers",
+                arity: 4,
+                flags: &["readonly"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Returns a list of the consumers in a consumer group.",
+                since: "5.0.0",
+                handler: Handler::Keyspace(commands::xinfo::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "xinfo|groups",
+                arity: 3,
+                flags: &["readonly"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Returns a list of the consumer groups of a stream.",
+                since: "5.0.0",
+                handler: Handler::Keyspace(commands::xinfo::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "xinfo|stream",
+                arity: 3,
+                flags: &["readonly"],
+                keys: Keys::Range {
+                    first: 2,
+                    last: 2,
+                    step: 1,
+                },
+                categories: &["@stream"],
+                group: "stream",
+                summary: "Returns information about a stream.",
+                since: "5.0.0",
+                handler: Handler::Keyspace(commands::xinfo::execute),
+                subcommands: &[],
+            },
+        ],
+    },
+    CommandSpec {
+        name: "xlen",
+        arity: 2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Return the number of messages in a stream.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xlen::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xpending",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Returns the information and entries from a stream consumer group's pending entries list.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xpending::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xrange",
+        arity: -4,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Returns the messages from a stream within a range of IDs.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xrange::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xrevrange",
+        arity: -4,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Returns the messages from a stream within a range of IDs in reverse order.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xrevrange::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xtrim",
+        arity: -4,
+        flags: &["write"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Deletes messages from the beginning of a stream.",
+        since: "5.0.0",
+        handler: Handler::Keyspace(commands::xtrim::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pfadd",
+        arity: -2,
+        flags: &["write", "denyoom", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@hyperloglog"],
+        group: "hyperloglog",
+        summary: "Adds elements to a HyperLogLog key. Creates the key if it doesn't exist.",
+        since: "2.8.9",
+        handler: Handler::Keyspace(commands::pfadd::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pfcount",
+        arity: -2,
+        flags: &["readonly", "may_replicate"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@hyperloglog"],
+        group: "hyperloglog",
+        summary: "Returns the approximated cardinality of the set(s) observed by the HyperLogLog key(s).",
+        since: "2.8.9",
+        handler: Handler::Keyspace(commands::pfcount::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pfmerge",
+        arity: -2,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@hyperloglog"],
+        group: "hyperloglog",
+        summary: "Merges one or more HyperLogLog values into a single key.",
+        since: "2.8.9",
+        handler: Handler::Keyspace(commands::pfmerge::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "setbit",
+        arity: 4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@bitmap"],
+        group: "bitmap",
+        summary: "Sets or clears the bit at offset of the string value. Creates the key if it doesn't exist.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::setbit::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "getbit",
+        arity: 3,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@bitmap"],
+        group: "bitmap",
+        summary: "Returns a bit value by offset.",
+        since: "2.2.0",
+        handler: Handler::Keyspace(commands::getbit::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bitcount",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@bitmap"],
+        group: "bitmap",
+        summary: "Counts the number of set bits (population counting) in a string.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::bitcount::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bitpos",
+        arity: -3,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@bitmap"],
+        group: "bitmap",
+        summary: "Finds the first set (1) or clear (0) bit in a string.",
+        since: "2.8.7",
+        handler: Handler::Keyspace(commands::bitpos::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bitop",
+        arity: -4,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 2,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@bitmap"],
+        group: "bitmap",
+        summary: "Performs bitwise operations on multiple strings, and stores the result.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::bitop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bitfield",
+        arity: -2,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@bitmap"],
+        group: "bitmap",
+        summary: "Performs arbitrary bitfield integer operations on strings.",
+        since: "3.2.0",
+        handler: Handler::Keyspace(commands::bitfield::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bitfield_ro",
+        arity: -2,
+        flags: &["readonly", "fast"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@bitmap"],
+        group: "bitmap",
+        summary: "Performs arbitrary read-only bitfield integer operations on strings.",
+        since: "6.0.0",
+        handler: Handler::Keyspace(commands::bitfield_ro::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "geoadd",
+        arity: -5,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@geo"],
+        group: "geo",
+        summary: "Adds one or more members to a geospatial index. The key is created if it doesn't exist.",
+        since: "3.2.0",
+        handler: Handler::Keyspace(commands::geoadd::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "geopos",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@geo"],
+        group: "geo",
+        summary: "Returns the longitude and latitude of members from a geospatial index.",
+        since: "3.2.0",
+        handler: Handler::Keyspace(commands::geopos::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "geodist",
+        arity: -4,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@geo"],
+        group: "geo",
+        summary: "Returns the distance between two members of a geospatial index.",
+        since: "3.2.0",
+        handler: Handler::Keyspace(commands::geodist::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "geohash",
+        arity: -2,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@geo"],
+        group: "geo",
+        summary: "Returns members from a geospatial index as geohash strings.",
+        since: "3.2.0",
+        handler: Handler::Keyspace(commands::geohash::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "geosearch",
+        arity: -7,
+        flags: &["readonly"],
+        keys: Keys::Range {
+            first: 1,
+            last: 1,
+            step: 1,
+        },
+        categories: &["@geo"],
+        group: "geo",
+        summary: "Queries a geospatial index for members inside an area of a box or a circle.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::geosearch::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "geosearchstore",
+        arity: -8,
+        flags: &["write", "denyoom"],
+        keys: Keys::Range {
+            first: 1,
+            last: 2,
+            step: 1,
+        },
+        categories: &["@geo"],
+        group: "geo",
+        summary: "Queries a geospatial index for members inside an area of a box or a circle, optionally stores the result.",
+        since: "6.2.0",
+        handler: Handler::Keyspace(commands::geosearchstore::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "publish",
+        arity: 3,
+        flags: &["pubsub", "loading", "stale", "fast", "may_replicate"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Posts a message to a channel.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::publish::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "spublish",
+        arity: 3,
+        flags: &["pubsub", "loading", "stale", "fast"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Post a message to a shard channel",
+        since: "7.0.0",
+        handler: Handler::Keyspace(commands::spublish::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "pubsub",
+        arity: -2,
+        flags: &[],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "A container for Pub/Sub commands.",
+        since: "2.8.0",
+        handler: Handler::Keyspace(commands::pubsub::execute),
+        subcommands: &[
+            CommandSpec {
+                name: "pubsub|channels",
+                arity: -2,
+                flags: &["pubsub", "loading", "stale"],
+                keys: Keys::None,
+                categories: &[],
+                group: "pubsub",
+                summary: "Returns the active channels.",
+                since: "2.8.0",
+                handler: Handler::Keyspace(commands::pubsub::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "pubsub|numpat",
+                arity: 2,
+                flags: &["pubsub", "loading", "stale"],
+                keys: Keys::None,
+                categories: &[],
+                group: "pubsub",
+                summary: "Returns a count of unique pattern subscriptions.",
+                since: "2.8.0",
+                handler: Handler::Keyspace(commands::pubsub::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "pubsub|numsub",
+                arity: -2,
+                flags: &["pubsub", "loading", "stale"],
+                keys: Keys::None,
+                categories: &[],
+                group: "pubsub",
+                summary: "Returns a count of subscribers to channels.",
+                since: "2.8.0",
+                handler: Handler::Keyspace(commands::pubsub::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "pubsub|shardchannels",
+                arity: -2,
+                flags: &["pubsub", "loading", "stale"],
+                keys: Keys::None,
+                categories: &[],
+                group: "pubsub",
+                summary: "Returns the active shard channels.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::pubsub::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "pubsub|shardnumsub",
+                arity: -2,
+                flags: &["pubsub", "loading", "stale"],
+                keys: Keys::None,
+                categories: &[],
+                group: "pubsub",
+                summary: "Returns the count of subscribers of shard channels.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::pubsub::execute),
+                subcommands: &[],
+            },
+        ],
+    },
+    CommandSpec {
+        name: "subscribe",
+        arity: -2,
+        flags: &["pubsub", "noscript", "loading", "stale"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Listens for messages published to channels.",
+        since: "2.0.0",
+        handler: Handler::Subscribe(commands::subscribe::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "unsubscribe",
+        arity: -1,
+        flags: &["pubsub", "noscript", "loading", "stale"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Stops listening to messages posted to channels.",
+        since: "2.0.0",
+        handler: Handler::Subscribe(commands::unsubscribe::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "psubscribe",
+        arity: -2,
+        flags: &["pubsub", "noscript", "loading", "stale"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Listens for messages published to channels that match one or more patterns.",
+        since: "2.0.0",
+        handler: Handler::Subscribe(commands::psubscribe::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "punsubscribe",
+        arity: -1,
+        flags: &["pubsub", "noscript", "loading", "stale"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Stops listening to messages published to channels that match one or more patterns.",
+        since: "2.0.0",
+        handler: Handler::Subscribe(commands::punsubscribe::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "ssubscribe",
+        arity: -2,
+        flags: &["pubsub", "noscript", "loading", "stale"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Listens for messages published to shard channels.",
+        since: "7.0.0",
+        handler: Handler::Subscribe(commands::ssubscribe::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "sunsubscribe",
+        arity: -1,
+        flags: &["pubsub", "noscript", "loading", "stale"],
+        keys: Keys::None,
+        categories: &[],
+        group: "pubsub",
+        summary: "Stops listening to messages posted to shard channels.",
+        since: "7.0.0",
+        handler: Handler::Subscribe(commands::sunsubscribe::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "blpop",
+        arity: -3,
+        flags: &["write", "blocking"],
+        keys: Keys::Range {
+            first: 1,
+            last: -2,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Removes and returns the first element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+        since: "2.0.0",
+        handler: Handler::Blocking(commands::blpop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "brpop",
+        arity: -3,
+        flags: &["write", "blocking"],
+        keys: Keys::Range {
+            first: 1,
+            last: -2,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Removes and returns the last element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+        since: "2.0.0",
+        handler: Handler::Blocking(commands::brpop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "blmove",
+        arity: 6,
+        flags: &["write", "denyoom", "blocking"],
+        keys: Keys::Range {
+            first: 1,
+            last: 2,
+            step: 1,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Pops an element from a list, pushes it to another list and returns it. Blocks until an element is available otherwise. Deletes the list if the last element was moved.",
+        since: "6.2.0",
+        handler: Handler::Blocking(commands::blmove::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "blmpop",
+        arity: -5,
+        flags: &["write", "blocking"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: false,
+        },
+        categories: &["@list"],
+        group: "list",
+        summary: "Pops the first element from one of multiple lists. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+        since: "7.0.0",
+        handler: Handler::Blocking(commands::blmpop::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bzpopmin",
+        arity: -3,
+        flags: &["write", "fast", "blocking"],
+        keys: Keys::Range {
+            first: 1,
+            last: -2,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Removes and returns the member with the lowest score from one or more sorted sets. Blocks until a member is available otherwise. Deletes the sorted set if the last element was popped.",
+        since: "5.0.0",
+        handler: Handler::Blocking(commands::bzpopmin::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bzpopmax",
+        arity: -3,
+        flags: &["write", "fast", "blocking"],
+        keys: Keys::Range {
+            first: 1,
+            last: -2,
+            step: 1,
+        },
+        categories: &["@sortedset"],
+        group: "sorted_set",
+        summary: "Removes and returns the member with the highest score from one or more sorted sets. Blocks until a member available otherwise.  Deletes the sorted set if the last element was popped.",
+        since: "5.0.0",
+        handler: Handler::Blocking(commands::bzpopmax::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xread",
+        arity: -4,
+        flags: &["readonly", "blocking"],
+        keys: Keys::Streams,
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Returns messages from multiple streams with IDs greater than the ones requested. Blocks until a message is available otherwise.",
+        since: "5.0.0",
+        handler: Handler::Blocking(commands::xread::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "xreadgroup",
+        arity: -7,
+        flags: &["write", "blocking"],
+        keys: Keys::Streams,
+        categories: &["@stream"],
+        group: "stream",
+        summary: "Returns new or historical messages from a stream for a consumer in a group. Blocks until a message is available otherwise.",
+        since: "5.0.0",
+        handler: Handler::Blocking(commands::xreadgroup::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "multi",
+        arity: 1,
+        flags: &["noscript", "loading", "stale", "fast", "allow_busy"],
+        keys: Keys::None,
+        categories: &["@transaction"],
+        group: "transactions",
+        summary: "Starts a transaction.",
+        since: "1.2.0",
+        handler: Handler::Client(commands::multi::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "exec",
+        arity: 1,
+        flags: &["noscript", "loading", "stale", "skip_slowlog"],
+        keys: Keys::None,
+        categories: &["@transaction"],
+        group: "transactions",
+        summary: "Executes all commands in a transaction.",
+        since: "1.2.0",
+        handler: Handler::Dispatching(commands::exec::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "discard",
+        arity: 1,
+        flags: &["noscript", "loading", "stale", "fast", "allow_busy"],
+        keys: Keys::None,
+        categories: &["@transaction"],
+        group: "transactions",
+        summary: "Discards a transaction.",
+        since: "2.0.0",
+        handler: Handler::ClientKeyspace(commands::discard::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "watch",
+        arity: -2,
+        flags: &["noscript", "loading", "stale", "fast", "allow_busy"],
+        keys: Keys::Range {
+            first: 1,
+            last: -1,
+            step: 1,
+        },
+        categories: &["@transaction"],
+        group: "transactions",
+        summary: "Monitors changes to keys to determine the execution of a transaction.",
+        since: "2.2.0",
+        handler: Handler::ClientKeyspace(commands::watch::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "unwatch",
+        arity: 1,
+        flags: &["noscript", "loading", "stale", "fast", "allow_busy"],
+        keys: Keys::None,
+        categories: &["@transaction"],
+        group: "transactions",
+        summary: "Forgets about watched keys of a transaction.",
+        since: "2.2.0",
+        handler: Handler::ClientKeyspace(commands::unwatch::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "flushall",
+        arity: -1,
+        flags: &["write"],
+        keys: Keys::None,
+        categories: &["@keyspace", "@dangerous"],
+        group: "server",
+        summary: "Removes all keys from all databases.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::flushall::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "flushdb",
+        arity: -1,
+        flags: &["write"],
+        keys: Keys::None,
+        categories: &["@keyspace", "@dangerous"],
+        group: "server",
+        summary: "Remove all keys from the current database.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::flushdb::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "eval",
+        arity: -3,
+        flags: &["noscript", "skip_monitor", "may_replicate", "no_mandatory_keys", "stale"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: false,
+        },
+        categories: &["@scripting"],
+        group: "scripting",
+        summary: "Executes a server-side Lua script.",
+        since: "2.6.0",
+        handler: Handler::Dispatching(commands::eval::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "evalsha",
+        arity: -3,
+        flags: &["noscript", "skip_monitor", "may_replicate", "no_mandatory_keys", "stale"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: false,
+        },
+        categories: &["@scripting"],
+        group: "scripting",
+        summary: "Executes a server-side Lua script by SHA1 digest.",
+        since: "2.6.0",
+        handler: Handler::Dispatching(commands::evalsha::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "eval_ro",
+        arity: -3,
+        flags: &["noscript", "skip_monitor", "no_mandatory_keys", "stale", "readonly"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: false,
+        },
+        categories: &["@scripting"],
+        group: "scripting",
+        summary: "Executes a read-only server-side Lua script.",
+        since: "7.0.0",
+        handler: Handler::Dispatching(commands::eval_ro::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "evalsha_ro",
+        arity: -3,
+        flags: &["noscript", "skip_monitor", "no_mandatory_keys", "stale", "readonly"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: false,
+        },
+        categories: &["@scripting"],
+        group: "scripting",
+        summary: "Executes a read-only server-side Lua script by SHA1 digest.",
+        since: "7.0.0",
+        handler: Handler::Dispatching(commands::evalsha_ro::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "script",
+        arity: -2,
+        flags: &[],
+        keys: Keys::None,
+        categories: &[],
+        group: "scripting",
+        summary: "A container for Lua scripts management commands.",
+        since: "2.6.0",
+        handler: Handler::Keyspace(commands::script::execute),
+        subcommands: &[
+            CommandSpec {
+                name: "script|exists",
+                arity: -3,
+                flags: &["noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Determines whether server-side Lua scripts exist in the script cache.",
+                since: "2.6.0",
+                handler: Handler::Keyspace(commands::script::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "script|flush",
+                arity: -2,
+                flags: &["noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Removes all server-side Lua scripts from the script cache.",
+                since: "2.6.0",
+                handler: Handler::Keyspace(commands::script::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "script|kill",
+                arity: 2,
+                flags: &["noscript", "allow_busy"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Terminates a server-side Lua script during execution.",
+                since: "2.6.0",
+                handler: Handler::Keyspace(commands::script::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "script|load",
+                arity: 3,
+                flags: &["noscript", "stale"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Loads a server-side Lua script to the script cache.",
+                since: "2.6.0",
+                handler: Handler::Keyspace(commands::script::execute),
+                subcommands: &[],
+            },
+        ],
+    },
+    CommandSpec {
+        name: "function",
+        arity: -2,
+        flags: &[],
+        keys: Keys::None,
+        categories: &[],
+        group: "scripting",
+        summary: "A container for function commands.",
+        since: "7.0.0",
+        handler: Handler::Keyspace(commands::function::execute),
+        subcommands: &[
+            CommandSpec {
+                name: "function|delete",
+                arity: 3,
+                flags: &["write", "noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Deletes a library and its functions.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::function::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "function|dump",
+                arity: 2,
+                flags: &["noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Dumps all libraries into a serialized binary payload.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::function::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "function|flush",
+                arity: -2,
+                flags: &["write", "noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Deletes all libraries and functions.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::function::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "function|kill",
+                arity: 2,
+                flags: &["noscript", "allow_busy"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Terminates a function during execution.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::function::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "function|list",
+                arity: -2,
+                flags: &["noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Returns information about all libraries.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::function::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "function|load",
+                arity: -3,
+                flags: &["write", "denyoom", "noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Creates a library.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::function::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "function|restore",
+                arity: -3,
+                flags: &["write", "denyoom", "noscript"],
+                keys: Keys::None,
+                categories: &["@scripting"],
+                group: "scripting",
+                summary: "Restores all libraries from a payload.",
+                since: "7.0.0",
+                handler: Handler::Keyspace(commands::function::execute),
+                subcommands: &[],
+            },
+        ],
+    },
+    CommandSpec {
+        name: "fcall",
+        arity: -3,
+        flags: &["noscript", "skip_monitor", "may_replicate", "no_mandatory_keys", "stale"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: false,
+        },
+        categories: &["@scripting"],
+        group: "scripting",
+        summary: "Invokes a function.",
+        since: "7.0.0",
+        handler: Handler::Dispatching(commands::fcall::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "fcall_ro",
+        arity: -3,
+        flags: &["noscript", "skip_monitor", "no_mandatory_keys", "stale", "readonly"],
+        keys: Keys::Counted {
+            count: 2,
+            destination: false,
+        },
+        categories: &["@scripting"],
+        group: "scripting",
+        summary: "Invokes a read-only function.",
+        since: "7.0.0",
+        handler: Handler::Dispatching(commands::fcall_ro::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "config",
+        arity: -2,
+        flags: &[],
+        keys: Keys::None,
+        categories: &[],
+        group: "server",
+        summary: "A container for server configuration commands.",
+        since: "2.0.0",
+        handler: Handler::Keyspace(commands::config::execute),
+        subcommands: &[
+            CommandSpec {
+                name: "config|get",
+                arity: -3,
+                flags: &["admin", "noscript", "loading", "stale"],
+                keys: Keys::None,
+                categories: &[],
+                group: "server",
+                summary: "Returns the effective values of configuration parameters.",
+                since: "2.0.0",
+                handler: Handler::Keyspace(commands::config::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "config|set",
+                arity: -4,
+                flags: &["admin", "noscript", "loading", "stale"],
+                keys: Keys::None,
+                categories: &[],
+                group: "server",
+                summary: "Sets configuration parameters in-flight.",
+                since: "2.0.0",
+                handler: Handler::Keyspace(commands::config::execute),
+                subcommands: &[],
+            },
+        ],
+    },
+    CommandSpec {
+        name: "command",
+        arity: -1,
+        flags: &["loading", "stale"],
+        keys: Keys::None,
+        categories: &["@connection"],
+        group: "server",
+        summary: "Returns detailed information about all commands.",
+        since: "2.8.13",
+        handler: Handler::Plain(commands::command::execute),
+        subcommands: &[
+            CommandSpec {
+                name: "command|count",
+                arity: 2,
+                flags: &["loading", "stale"],
+                keys: Keys::None,
+                categories: &["@connection"],
+                group: "server",
+                summary: "Returns a count of commands.",
+                since: "2.8.13",
+                handler: Handler::Plain(commands::command::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "command|docs",
+                arity: -2,
+                flags: &["loading", "stale"],
+                keys: Keys::None,
+                categories: &["@connection"],
+                group: "server",
+                summary: "Returns documentary information about one, multiple or all commands.",
+                since: "7.0.0",
+                handler: Handler::Plain(commands::command::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "command|getkeys",
+                arity: -3,
+                flags: &["loading", "stale"],
+                keys: Keys::None,
+                categories: &["@connection"],
+                group: "server",
+                summary: "Extracts the key names from an arbitrary command.",
+                since: "2.8.13",
+                handler: Handler::Plain(commands::command::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "command|info",
+                arity: -2,
+                flags: &["loading", "stale"],
+                keys: Keys::None,
+                categories: &["@connection"],
+                group: "server",
+                summary: "Returns information about one, multiple or all commands.",
+                since: "2.8.13",
+                handler: Handler::Plain(commands::command::execute),
+                subcommands: &[],
+            },
+            CommandSpec {
+                name: "command|list",
+                arity: -2,
+                flags: &["loading", "stale"],
+                keys: Keys::None,
+                categories: &["@connection"],
+                group: "server",
+                summary: "Returns a list of command names.",
+                since: "7.0.0",
+                handler: Handler::Plain(commands::command::execute),
+                subcommands: &[],
+            },
+        ],
+    },
+    CommandSpec {
+        name: "save",
+        arity: 1,
+        flags: &["admin", "noscript", "no_async_loading", "no_multi"],
+        keys: Keys::None,
+        categories: &[],
+        group: "server",
+        summary: "Synchronously saves the database(s) to disk.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::save::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "bgsave",
+        arity: -1,
+        flags: &["admin", "noscript", "no_async_loading"],
+        keys: Keys::None,
+        categories: &[],
+        group: "server",
+        summary: "Asynchronously saves the database(s) to disk.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::bgsave::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "lastsave",
+        arity: 1,
+        flags: &["loading", "stale", "fast"],
+        keys: Keys::None,
+        categories: &["@admin", "@dangerous"],
+        group: "server",
+        summary: "Returns the Unix timestamp of the last successful save to disk.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::lastsave::execute),
+        subcommands: &[],
+    },
+    CommandSpec {
+        name: "shutdown",
+        arity: -1,
+        flags: &[
+            "admin",
+            "noscript",
+            "loading",
+            "stale",
+            "no_multi",
+            "allow_busy",
+        ],
+        keys: Keys::None,
+        categories: &[],
+        group: "server",
+        summary: "Synchronously saves the database(s) to disk and shuts down the Redis server.",
+        since: "1.0.0",
+        handler: Handler::Keyspace(commands::shutdown::execute),
+        subcommands: &[],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_arity() {
+        let get = lookup("get").unwrap();
+        assert_eq!(get.check_arity(2), Ok(()));
+        assert_eq!(get.check_arity(3), Err(CommandError::WrongArity("get")));
+
+        let set = lookup("set").unwrap();
+        assert_eq!(set.check_arity(2), Err(CommandError::WrongArity("set")));
+        assert_eq!(set.check_arity(5), Ok(()));
+
+        assert!(lookup("nosuchcommand").is_none());
+    }
+
+    #[test]
+    fn test_acl_categories_follow_from_flags() {
+        assert_eq!(
+            lookup("set").unwrap().acl_categories(),
+            ["@write", "@string", "@slow"]
+        );
+        assert_eq!(
+            lookup("blpop").unwrap().acl_categories(),
+            ["@write", "@list", "@slow", "@blocking"]
+        );
+        assert_eq!(lookup("config").unwrap().acl_categories(), ["@slow"]);
+        assert_eq!(
+            lookup("config").unwrap().subcommands[0].acl_categories(),
+            ["@admin", "@slow", "@dangerous"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_subcommands() {
+        let args = |args: &[&str]| -> Vec<Vec<u8>> {
+            args.iter().map(|arg| arg.as_bytes().to_vec()).collect()
+        };
+
+        assert_eq!(
+            resolve("config", &args(&["GET", "dir"])).unwrap().name,
+            "config|get"
+        );
+        assert_eq!(resolve("command", &args(&[])).unwrap().name, "command");
+        assert_eq!(
+            resolve("config", &args(&[])).err(),
+            Some(CommandError::WrongArity("config"))
+        );
+        assert_eq!(
+            resolve("config", &args(&["get"])).err(),
+            Some(CommandError::WrongArity("config|get"))
+        );
+        assert_eq!(
+            resolve("config", &args(&["nosuch"])).err(),
+            Some(CommandError::Other(String::from(
+                "ERR unknown subcommand 'nosuch'. Try CONFIG HELP."
+            )))
+        );
+    }
+
+    #[test]
+    fn test_unknown_command_quotes_one_line() {
+        assert_eq!(
+            unknown_command("Nosuch\r\n+OK", &[b"a\nb".to_vec()]),
+            CommandError::Other(String::from(
+                "ERR unknown command 'Nosuch  +OK', with args beginning with: 'a b' "
+            ))
+        );
+
+        let long = "x".repeat(100);
+        let args = [
+            long.as_bytes().to_vec(),
+            long.as_bytes().to_vec(),
+            b"c".to_vec(),
+        ];
+        let CommandError::Other(message) = unknown_command("nosuch", &args) else {
+            panic!("expected an error message");
+        };
+        assert!(message.ends_with(&format!("'{}' '{}' ", long, "x".repeat(25))));
+    }
+
+    #[test]
+    fn test_key_positions() {
+        let argv = |args: &[&str]| -> Vec<Vec<u8>> {
+            args.iter().map(|arg| arg.as_bytes().to_vec()).collect()
+        };
+
+        let mset = lookup("mset").unwrap();
+        assert_eq!(
+            mset.key_positions(&argv(&["mset", "a", "1", "b", "2"])),
+            Ok(vec![1, 3])
+        );
+
+        let lmpop = lookup("lmpop").unwrap();
+        assert_eq!(
+            lmpop.key_positions(&argv(&["lmpop", "2", "a", "b", "LEFT"])),
+            Ok(vec![2, 3])
+        );
+        assert!(lmpop
+            .key_positions(&argv(&["lmpop", "x", "a", "LEFT"]))
+            .is_err());
+
+        let xreadgroup = lookup("xreadgroup").unwrap();
+        assert_eq!(
+            xreadgroup.key_positions(&argv(&[
+                "xreadgroup",
+                "GROUP",
+                "g",
+                "c",
+                "STREAMS",
+                "s",
+                ">"
+            ])),
+            Ok(vec![5])
+        );
+    }
+}
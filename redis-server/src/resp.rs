@@ -1,3 +1,5 @@
+pub mod decoder;
+pub mod inline;
 pub mod types;
 
 use bitstream_io::{BigEndian, ByteWrite, ByteWriter};
@@ -8,12 +10,10 @@ pub fn from_binary(b: Vec<u8>) -> String {
 }
 
 pub fn to_encoded_binary<W: std::io::Write>(
-    t: Box<dyn Encoded>,
+    t: &dyn Encoded,
     writer: &mut ByteWriter<W, BigEndian>,
 ) {
-    let binding = t.to_encoded_string();
-    let bytes = binding.as_bytes();
-    writer.write_bytes(bytes).unwrap();
+    writer.write_bytes(&t.to_encoded_bytes()).unwrap();
 }
 
 #[cfg(test)]
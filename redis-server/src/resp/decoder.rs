@@ -0,0 +1,445 @@
+use crate::resp::inline::split_args;
+use crate::resp::types::Frame;
+
+use std::fmt;
+
+/// The largest bulk string the protocol allows.
+pub const MAX_BULK_LEN: usize = 512 * 1024 * 1024;
+
+/// Type and length lines are tiny, so a peer that sends this much without a CRLF is not
+/// speaking RESP.
+const MAX_LINE_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtocolError {
+    UnknownType(u8),
+    InvalidMultibulkLength,
+    InvalidBulkLength,
+    InvalidInteger,
+    MissingTerminator,
+    LineTooLong,
+    TooBigInlineRequest,
+    UnbalancedQuotes,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProtocolError::UnknownType(b) => {
+                write!(f, "Protocol error: unknown type '{}'", b.escape_ascii())
+            }
+            ProtocolError::InvalidMultibulkLength => {
+                write!(f, "Protocol error: invalid multibulk length")
+            }
+            ProtocolError::InvalidBulkLength => write!(f, "Protocol error: invalid bulk length"),
+            ProtocolError::InvalidInteger => write!(f, "Protocol error: invalid integer"),
+            ProtocolError::MissingTerminator => write!(f, "Protocol error: expected CRLF"),
+            ProtocolError::LineTooLong => write!(f, "Protocol error: too big count string"),
+            ProtocolError::TooBigInlineRequest => {
+                write!(f, "Protocol error: too big inline request")
+            }
+            ProtocolError::UnbalancedQuotes => {
+                write!(f, "Protocol error: unbalanced quotes in request")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// An array whose header has been read but whose elements are still arriving.
+struct PartialArray {
+    remaining: usize,
+    elements: Vec<Frame>,
+}
+
+/// What a single step of decoding produced.
+enum Element {
+    Frame(Frame),
+    ArrayHeader(usize),
+}
+
+/// Incrementally decodes RESP2 frames from bytes as they arrive.
+///
+/// Bytes are handed over with `feed` in whatever chunks the socket produced, and `decode` hands
+/// back each frame once all of its bytes are in. Elements of an array are decoded as soon as
+/// they are complete, so a large array that trickles in is not re-parsed from the start on every
+/// read.
+#[derive(Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+    /// Everything before this offset in `buffer` has already been decoded.
+    position: usize,
+    /// Arrays still waiting for elements, innermost last.
+    partial: Vec<PartialArray>,
+}
+
+impl Decoder {
+    pub fn new() -> Decoder {
+        Decoder::default()
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.position > 0 {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Number of bytes fed but not yet part of a decoded frame or array element.
+    pub fn pending(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    /// Returns the next complete frame, or `None` when more bytes are needed to finish it.
+    pub fn decode(&mut self) -> Result<Option<Frame>, ProtocolError> {
+        loop {
+            let mut frame = match self.decode_element()? {
+                None => return Ok(None),
+                Some(Element::ArrayHeader(len)) => {
+                    self.partial.push(PartialArray {
+                        remaining: len,
+                        // The length comes from the peer, so don't trust it for the allocation.
+                        elements: Vec::with_capacity(len.min(1024)),
+                    });
+                    continue;
+                }
+                Some(Element::Frame(frame)) => frame,
+            };
+
+            // Hand the frame to the array it belongs to, completing as many arrays as it
+            // finishes off.
+            loop {
+                match self.partial.last_mut() {
+                    None => return Ok(Some(frame)),
+                    Some(array) => {
+                        array.elements.push(frame);
+                        array.remaining -= 1;
+                        if array.remaining > 0 {
+                            break;
+                        }
+                    }
+                }
+
+                let array = self.partial.pop().unwrap();
+                frame = Frame::Array(array.elements);
+            }
+        }
+    }
+
+    /// Returns the next complete command. Commands normally arrive as arrays, but anything else
+    /// is taken to be an inline command typed by a person: a line of whitespace separated
+    /// arguments, which comes back as an array of bulk strings. Blank lines are skipped.
+    pub fn decode_command(&mut self) -> Result<Option<Frame>, ProtocolError> {
+        loop {
+            let input = &self.buffer[self.position..];
+            if !self.partial.is_empty() || input.is_empty() || input[0] == b'*' {
+                return self.decode();
+            }
+
+            let end = match input.iter().position(|b| *b == b'\n') {
+                Some(end) if end > MAX_LINE_LEN => return Err(ProtocolError::TooBigInlineRequest),
+                Some(end) => end,
+                None if input.len() > MAX_LINE_LEN => {
+                    return Err(ProtocolError::TooBigInlineRequest)
+                }
+                None => return Ok(None),
+            };
+
+            let line = input[..end].strip_suffix(b"\r").unwrap_or(&input[..end]);
+            let args = split_args(line).ok_or(ProtocolError::UnbalancedQuotes)?;
+            self.position += end + 1;
+
+            if !args.is_empty() {
+                let args = args.into_iter().map(Frame::BulkString).collect();
+                return Ok(Some(Frame::Array(args)));
+            }
+        }
+    }
+
+    /// Decodes one scalar value or array header at the current position. Nothing is consumed
+    /// unless the whole element is available.
+    fn decode_element(&mut self) -> Result<Option<Element>, ProtocolError> {
+        let input = &self.buffer[self.position..];
+
+        let (line, line_len) = match read_line(input)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        let (element, consumed) = match input[0] {
+            b'+' => (
+                Element::Frame(Frame::SimpleString(lossy(&line[1..]))),
+                line_len,
+            ),
+            b'-' => (Element::Frame(Frame::Error(lossy(&line[1..]))), line_len),
+            b':' => {
+                let n = parse_integer(&line[1..]).ok_or(ProtocolError::InvalidInteger)?;
+                (Element::Frame(Frame::Integer(n)), line_len)
+            }
+            b'$' => {
+                let len = parse_integer(&line[1..]).ok_or(ProtocolError::InvalidBulkLength)?;
+                if len == -1 {
+                    (Element::Frame(Frame::NullBulkString), line_len)
+                } else if len < 0 || len as u64 > MAX_BULK_LEN as u64 {
+                    return Err(ProtocolError::InvalidBulkLength);
+                } else {
+                    let len = len as usize;
+                    let end = line_len + len;
+                    if input.len() < end + 2 {
+                        return Ok(None);
+                    }
+                    if &input[end..end + 2] != b"\r\n" {
+                        return Err(ProtocolError::MissingTerminator);
+                    }
+                    let bulk = input[line_len..end].to_vec();
+                    (Element::Frame(Frame::BulkString(bulk)), end + 2)
+                }
+            }
+            b'*' => {
+                let len = parse_integer(&line[1..]).ok_or(ProtocolError::InvalidMultibulkLength)?;
+                if len == -1 {
+                    (Element::Frame(Frame::NullArray), line_len)
+                } else if len < 0 || len > i32::MAX as i64 {
+                    return Err(ProtocolError::InvalidMultibulkLength);
+                } else if len == 0 {
+                    (Element::Frame(Frame::Array(Vec::new())), line_len)
+                } else {
+                    (Element::ArrayHeader(len as usize), line_len)
+                }
+            }
+            b => return Err(ProtocolError::UnknownType(b)),
+        };
+
+        self.position += consumed;
+        Ok(Some(element))
+    }
+}
+
+/// Finds the CRLF terminated line at the start of input. Returns the line without its CRLF
+/// and the number of bytes taken up including the CRLF.
+fn read_line(input: &[u8]) -> Result<Option<(&[u8], usize)>, ProtocolError> {
+    match input.windows(2).position(|w| w == b"\r\n") {
+        Some(end) if end > MAX_LINE_LEN => Err(ProtocolError::LineTooLong),
+        Some(end) => Ok(Some((&input[..end], end + 2))),
+        None if input.len() > MAX_LINE_LEN => Err(ProtocolError::LineTooLong),
+        None => Ok(None),
+    }
+}
+
+/// Parses a base 10 integer the way Redis does: an optional minus sign followed by digits, with
+/// no sign prefix, whitespace or leading zeroes.
+pub fn parse_integer(bytes: &[u8]) -> Option<i64> {
+    let digits = bytes.strip_prefix(b"-").unwrap_or(bytes);
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    if digits.len() > 1 && digits[0] == b'0' {
+        return None;
+    }
+
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(input: &[u8]) -> Result<Vec<Frame>, ProtocolError> {
+        let mut decoder = Decoder::new();
+        decoder.feed(input);
+
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.decode()? {
+            frames.push(frame);
+        }
+
+        Ok(frames)
+    }
+
+    fn bulk(s: &str) -> Frame {
+        Frame::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_decode_command() {
+        assert_eq!(
+            decode_all(b"*2\r\n$4\r\nECHO\r\n$5\r\nhello\r\n"),
+            Ok(vec![Frame::Array(vec![bulk("ECHO"), bulk("hello")])])
+        );
+    }
+
+    #[test]
+    fn test_decode_scalars() {
+        assert_eq!(
+            decode_all(b"+OK\r\n-ERR bad\r\n:-42\r\n$0\r\n\r\n$-1\r\n*-1\r\n*0\r\n"),
+            Ok(vec![
+                Frame::SimpleString(String::from("OK")),
+                Frame::Error(String::from("ERR bad")),
+                Frame::Integer(-42),
+                bulk(""),
+                Frame::NullBulkString,
+                Frame::NullArray,
+                Frame::Array(vec![]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decode_nested_arrays() {
+        assert_eq!(
+            decode_all(b"*3\r\n*2\r\n:1\r\n*1\r\n$1\r\na\r\n*0\r\n$-1\r\n"),
+            Ok(vec![Frame::Array(vec![
+                Frame::Array(vec![Frame::Integer(1), Frame::Array(vec![bulk("a")])]),
+                Frame::Array(vec![]),
+                Frame::NullBulkString,
+            ])])
+        );
+    }
+
+    #[test]
+    fn test_decode_binary_bulk_string() {
+        assert_eq!(
+            decode_all(b"$4\r\n\xff\r\n\x00\r\n"),
+            Ok(vec![Frame::BulkString(vec![0xff, b'\r', b'\n', 0])])
+        );
+    }
+
+    #[test]
+    fn test_decode_byte_at_a_time() {
+        let input = b"*2\r\n$4\r\nECHO\r\n*1\r\n:7\r\n+PONG\r\n";
+        let mut decoder = Decoder::new();
+        let mut frames = Vec::new();
+
+        for b in input.iter() {
+            decoder.feed(&[*b]);
+            while let Some(frame) = decoder.decode().unwrap() {
+                frames.push(frame);
+            }
+        }
+
+        assert_eq!(
+            frames,
+            vec![
+                Frame::Array(vec![bulk("ECHO"), Frame::Array(vec![Frame::Integer(7)])]),
+                Frame::SimpleString(String::from("PONG")),
+            ]
+        );
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_decode_needs_more_bytes() {
+        let mut decoder = Decoder::new();
+        decoder.feed(b"$10\r\nhello");
+
+        assert_eq!(decoder.decode(), Ok(None));
+
+        decoder.feed(b"world\r\n");
+        assert_eq!(decoder.decode(), Ok(Some(bulk("helloworld"))));
+    }
+
+    #[test]
+    fn test_decode_large_bulk_length_is_accepted() {
+        let mut decoder = Decoder::new();
+        decoder.feed(format!("${}\r\n", MAX_BULK_LEN).as_bytes());
+
+        assert_eq!(decoder.decode(), Ok(None));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode_all(format!("${}\r\n", MAX_BULK_LEN + 1).as_bytes()),
+            Err(ProtocolError::InvalidBulkLength)
+        );
+        assert_eq!(
+            decode_all(b"$-2\r\n"),
+            Err(ProtocolError::InvalidBulkLength)
+        );
+        assert_eq!(
+            decode_all(b"$abc\r\n"),
+            Err(ProtocolError::InvalidBulkLength)
+        );
+        assert_eq!(
+            decode_all(b"*x\r\n"),
+            Err(ProtocolError::InvalidMultibulkLength)
+        );
+        assert_eq!(
+            decode_all(b"*-5\r\n"),
+            Err(ProtocolError::InvalidMultibulkLength)
+        );
+        assert_eq!(decode_all(b":1.5\r\n"), Err(ProtocolError::InvalidInteger));
+        assert_eq!(
+            decode_all(b"$3\r\nabcde\r\n"),
+            Err(ProtocolError::MissingTerminator)
+        );
+        assert_eq!(decode_all(b"?\r\n"), Err(ProtocolError::UnknownType(b'?')));
+        assert_eq!(
+            decode_all(&[b'$'; MAX_LINE_LEN + 1]),
+            Err(ProtocolError::LineTooLong)
+        );
+    }
+
+    #[test]
+    fn test_decode_inline_commands() {
+        let mut decoder = Decoder::new();
+        decoder.feed(b"PING\r\n\r\necho \"hello world\"\n*1\r\n$4\r\nPING\r\nEC");
+
+        assert_eq!(
+            decoder.decode_command(),
+            Ok(Some(Frame::Array(vec![bulk("PING")])))
+        );
+        assert_eq!(
+            decoder.decode_command(),
+            Ok(Some(Frame::Array(vec![bulk("echo"), bulk("hello world")])))
+        );
+        assert_eq!(
+            decoder.decode_command(),
+            Ok(Some(Frame::Array(vec![bulk("PING")])))
+        );
+        assert_eq!(decoder.decode_command(), Ok(None));
+
+        decoder.feed(b"HO hi\r\n");
+        assert_eq!(
+            decoder.decode_command(),
+            Ok(Some(Frame::Array(vec![bulk("ECHO"), bulk("hi")])))
+        );
+    }
+
+    #[test]
+    fn test_decode_inline_errors() {
+        let mut decoder = Decoder::new();
+        decoder.feed(b"ECHO \"oops\r\n");
+        assert_eq!(
+            decoder.decode_command(),
+            Err(ProtocolError::UnbalancedQuotes)
+        );
+
+        let mut decoder = Decoder::new();
+        decoder.feed(&[b'a'; MAX_LINE_LEN + 1]);
+        assert_eq!(
+            decoder.decode_command(),
+            Err(ProtocolError::TooBigInlineRequest)
+        );
+    }
+
+    #[test]
+    fn test_parse_integer() {
+        assert_eq!(parse_integer(b"0"), Some(0));
+        assert_eq!(parse_integer(b"-17"), Some(-17));
+        assert_eq!(parse_integer(b"-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(parse_integer(b"9223372036854775808"), None);
+        assert_eq!(parse_integer(b"+1"), None);
+        assert_eq!(parse_integer(b"01"), None);
+        assert_eq!(parse_integer(b" 1"), None);
+        assert_eq!(parse_integer(b"-"), None);
+        assert_eq!(parse_integer(b""), None);
+    }
+}
@@ -0,0 +1,165 @@
+/// Splits an inline command line into arguments the way redis-cli and Redis itself do.
+///
+/// Arguments are separated by whitespace. Double quoted arguments may contain spaces and the
+/// escapes `\n`, `\r`, `\t`, `\b`, `\a`, `\\`, `\"` and `\xHH`; single quoted arguments only
+/// understand `\'`. A closing quote must be followed by whitespace or the end of the line.
+/// Returns `None` when the quotes are unbalanced.
+pub fn split_args(line: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut args = Vec::new();
+    let mut i = 0;
+
+    loop {
+        while i < line.len() && line[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i == line.len() {
+            return Some(args);
+        }
+
+        let mut arg = Vec::new();
+        let mut in_double_quotes = false;
+        let mut in_single_quotes = false;
+
+        loop {
+            if in_double_quotes {
+                match line.get(i) {
+                    None => return None,
+                    Some(b'\\') if hex_escape(&line[i..]).is_some() => {
+                        arg.push(hex_escape(&line[i..]).unwrap());
+                        i += 3;
+                    }
+                    Some(b'\\') if i + 1 < line.len() => {
+                        i += 1;
+                        arg.push(match line[i] {
+                            b'n' => b'\n',
+                            b'r' => b'\r',
+                            b't' => b'\t',
+                            b'b' => 0x08,
+                            b'a' => 0x07,
+                            other => other,
+                        });
+                    }
+                    Some(b'"') => {
+                        if i + 1 < line.len() && !line[i + 1].is_ascii_whitespace() {
+                            return None;
+                        }
+                        i += 1;
+                        break;
+                    }
+                    Some(b) => arg.push(*b),
+                }
+            } else if in_single_quotes {
+                match line.get(i) {
+                    None => return None,
+                    Some(b'\\') if i + 1 < line.len() && line[i + 1] == b'\'' => {
+                        i += 1;
+                        arg.push(b'\'');
+                    }
+                    Some(b'\'') => {
+                        if i + 1 < line.len() && !line[i + 1].is_ascii_whitespace() {
+                            return None;
+                        }
+                        i += 1;
+                        break;
+                    }
+                    Some(b) => arg.push(*b),
+                }
+            } else {
+                match line.get(i) {
+                    None => break,
+                    Some(b) if b.is_ascii_whitespace() => break,
+                    Some(b'"') => in_double_quotes = true,
+                    Some(b'\'') => in_single_quotes = true,
+                    Some(b) => arg.push(*b),
+                }
+            }
+            i += 1;
+        }
+
+        args.push(arg);
+    }
+}
+
+/// Decodes a `\xHH` escape at the start of input.
+fn hex_escape(input: &[u8]) -> Option<u8> {
+    match input {
+        [b'\\', b'x', high, low, ..] => {
+            let high = (*high as char).to_digit(16)?;
+            let low = (*low as char).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> Option<Vec<String>> {
+        split_args(line.as_bytes()).map(|args| {
+            args.into_iter()
+                .map(|arg| String::from_utf8(arg).unwrap())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_split_plain() {
+        assert_eq!(
+            split("  SET   key value "),
+            Some(vec![
+                String::from("SET"),
+                String::from("key"),
+                String::from("value")
+            ])
+        );
+        assert_eq!(split(""), Some(vec![]));
+        assert_eq!(split(" \t "), Some(vec![]));
+    }
+
+    #[test]
+    fn test_split_double_quotes() {
+        assert_eq!(
+            split(r#"SET "hello world" "a\"b\n\x41""#),
+            Some(vec![
+                String::from("SET"),
+                String::from("hello world"),
+                String::from("a\"b\nA"),
+            ])
+        );
+        assert_eq!(
+            split(r#"ECHO """#),
+            Some(vec![String::from("ECHO"), String::new()])
+        );
+    }
+
+    #[test]
+    fn test_split_single_quotes() {
+        assert_eq!(
+            split(r#"ECHO 'it\'s \n'"#),
+            Some(vec![String::from("ECHO"), String::from("it's \\n")])
+        );
+    }
+
+    #[test]
+    fn test_split_quotes_in_the_middle_of_an_argument() {
+        assert_eq!(
+            split(r#"ECHO ab"c d""#),
+            Some(vec![String::from("ECHO"), String::from("abc d")])
+        );
+    }
+
+    #[test]
+    fn test_split_binary_escape() {
+        assert_eq!(split_args(br#""\xff\x00""#), Some(vec![vec![0xff, 0x00]]));
+        assert_eq!(split_args(br#""\xzz""#), Some(vec![b"xzz".to_vec()]));
+    }
+
+    #[test]
+    fn test_split_unbalanced_quotes() {
+        assert_eq!(split(r#"ECHO "hello"#), None);
+        assert_eq!(split("ECHO 'hello"), None);
+        assert_eq!(split(r#"ECHO "hello"world"#), None);
+    }
+}
@@ -1,119 +1,241 @@
-const TERMINATOR: &str = "\r\n";
-
-pub trait Encoded {
-    fn to_encoded_string(&self) -> String;
+const TERMINATOR: &[u8] = b"\r\n";
+
+/// The protocol version a connection speaks. Connections start out on RESP2 and may switch with
+/// `HELLO`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Protocol {
+    #[default]
+    Resp2,
+    Resp3,
 }
 
-pub struct SimpleString {
-    value: String,
-}
+pub trait Encoded {
+    /// Encodes the value for a connection speaking `protocol`.
+    fn encode(&self, protocol: Protocol) -> Vec<u8>;
 
-impl SimpleString {
-    pub fn new(v: String) -> Box<SimpleString> {
-        Box::new(SimpleString { value: v })
+    /// Encodes the value for a connection that hasn't switched protocols.
+    fn to_encoded_bytes(&self) -> Vec<u8> {
+        self.encode(Protocol::default())
     }
 }
 
-impl Encoded for SimpleString {
-    fn to_encoded_string(&self) -> String {
-        let mut result = String::from("+");
-        result.push_str(&self.value);
-        result.push_str(TERMINATOR);
-
-        result
-    }
+/// A single RESP value. The decoder produces these and every command reply is built from them,
+/// so a reply can nest any mix of types, nulls included.
+///
+/// The RESP3 only types are sent to RESP2 clients as the closest RESP2 type, the same way Redis
+/// does it: maps become flat arrays of keys and values, doubles and big numbers become bulk
+/// strings, and so on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    SimpleString(String),
+    Error(String),
+    Integer(i64),
+    /// Bulk strings are binary safe: they carry arbitrary bytes, not just text.
+    BulkString(Vec<u8>),
+    NullBulkString,
+    Array(Vec<Frame>),
+    NullArray,
+    Null,
+    Boolean(bool),
+    Double(f64),
+    BigNumber(String),
+    BulkError(String),
+    /// The three character encoding hint, such as `txt` or `mkd`, and the text itself.
+    VerbatimString(String, String),
+    Map(Vec<(Frame, Frame)>),
+    /// Pairs such as members and their scores. RESP3 clients get an array of two element
+    /// arrays, but RESP2 clients get them flattened into one array, as Redis does. Unlike a
+    /// map, the same first element may appear more than once.
+    Pairs(Vec<(Frame, Frame)>),
+    Set(Vec<Frame>),
+    /// Out of band metadata about the value that follows it.
+    Attribute(Vec<(Frame, Frame)>, Box<Frame>),
+    /// Data the server sends without it being a reply to a command, such as Pub/Sub messages.
+    Push(Vec<Frame>),
 }
 
-pub struct Integer {
-    value: i64,
-}
+impl Frame {
+    pub fn ok() -> Frame {
+        Frame::SimpleString(String::from("OK"))
+    }
 
-impl Integer {
-    pub fn new(n: i64) -> Box<Integer> {
-        Box::new(Integer { value: n })
+    pub fn bulk(v: impl Into<Vec<u8>>) -> Frame {
+        Frame::BulkString(v.into())
     }
-}
 
-impl Encoded for Integer {
-    fn to_encoded_string(&self) -> String {
-        let mut result = String::from(":");
-        result.push_str(&self.value.to_string());
-        result.push_str(TERMINATOR);
+    pub fn error(v: impl Into<String>) -> Frame {
+        Frame::Error(v.into())
+    }
 
-        result
+    /// An array of bulk strings.
+    pub fn bulks<T: Into<Vec<u8>>>(values: impl IntoIterator<Item = T>) -> Frame {
+        Frame::Array(values.into_iter().map(Frame::bulk).collect())
     }
-}
 
-pub struct Error {
-    value: String,
-}
+    /// The type byte that introduces this value on the wire.
+    pub fn prefix(&self) -> char {
+        match self {
+            Frame::SimpleString(_) => '+',
+            Frame::Error(_) => '-',
+            Frame::Integer(_) => ':',
+            Frame::BulkString(_) | Frame::NullBulkString => '$',
+            Frame::Array(_) | Frame::NullArray => '*',
+            Frame::Null => '_',
+            Frame::Boolean(_) => '#',
+            Frame::Double(_) => ',',
+            Frame::BigNumber(_) => '(',
+            Frame::BulkError(_) => '!',
+            Frame::VerbatimString(_, _) => '=',
+            Frame::Map(_) => '%',
+            Frame::Pairs(_) => '*',
+            Frame::Set(_) => '~',
+            Frame::Attribute(_, _) => '|',
+            Frame::Push(_) => '>',
+        }
+    }
 
-impl Error {
-    pub fn new(v: String) -> Box<Error> {
-        Box::new(Error { value: v })
+    fn encode_into(&self, result: &mut Vec<u8>, protocol: Protocol) {
+        match (self, protocol) {
+            (Frame::SimpleString(s), _) => encode_line(result, '+', s),
+            (Frame::Error(s), _) => encode_line(result, '-', s),
+            (Frame::Integer(n), _) => encode_line(result, ':', &n.to_string()),
+            (Frame::BulkString(b), _) => encode_bulk(result, '$', b),
+            (Frame::Array(entries), _) => encode_aggregate(result, '*', entries, protocol),
+            (Frame::NullBulkString | Frame::Null, Protocol::Resp2) => {
+                result.extend_from_slice(b"$-1\r\n")
+            }
+            (Frame::NullArray, Protocol::Resp2) => result.extend_from_slice(b"*-1\r\n"),
+            (Frame::NullBulkString | Frame::NullArray | Frame::Null, Protocol::Resp3) => {
+                result.extend_from_slice(b"_\r\n")
+            }
+            (Frame::Boolean(b), Protocol::Resp2) => {
+                encode_line(result, ':', if *b { "1" } else { "0" })
+            }
+            (Frame::Boolean(b), Protocol::Resp3) => {
+                encode_line(result, '#', if *b { "t" } else { "f" })
+            }
+            (Frame::Double(n), Protocol::Resp2) => {
+                encode_bulk(result, '$', format_double(*n).as_bytes())
+            }
+            (Frame::Double(n), Protocol::Resp3) => encode_line(result, ',', &format_double(*n)),
+            (Frame::BigNumber(n), Protocol::Resp2) => encode_bulk(result, '$', n.as_bytes()),
+            (Frame::BigNumber(n), Protocol::Resp3) => encode_line(result, '(', n),
+            (Frame::BulkError(e), Protocol::Resp2) => encode_line(result, '-', e),
+            (Frame::BulkError(e), Protocol::Resp3) => encode_bulk(result, '!', e.as_bytes()),
+            (Frame::VerbatimString(_, text), Protocol::Resp2) => {
+                encode_bulk(result, '$', text.as_bytes())
+            }
+            (Frame::VerbatimString(format, text), Protocol::Resp3) => {
+                encode_bulk(result, '=', format!("{}:{}", format, text).as_bytes())
+            }
+            // RESP2 has no map type, so the keys and values are sent alternately in a flat
+            // array.
+            (Frame::Map(pairs), Protocol::Resp2) => {
+                encode_header(result, '*', pairs.len() * 2);
+                encode_pairs(result, pairs, protocol);
+            }
+            (Frame::Map(pairs), Protocol::Resp3) => {
+                encode_header(result, '%', pairs.len());
+                encode_pairs(result, pairs, protocol);
+            }
+            (Frame::Pairs(pairs), Protocol::Resp2) => {
+                encode_header(result, '*', pairs.len() * 2);
+                encode_pairs(result, pairs, protocol);
+            }
+            (Frame::Pairs(pairs), Protocol::Resp3) => {
+                encode_header(result, '*', pairs.len());
+                for (first, second) in pairs {
+                    encode_header(result, '*', 2);
+                    first.encode_into(result, protocol);
+                    second.encode_into(result, protocol);
+                }
+            }
+            (Frame::Set(entries), Protocol::Resp2) => {
+                encode_aggregate(result, '*', entries, protocol)
+            }
+            (Frame::Set(entries), Protocol::Resp3) => {
+                encode_aggregate(result, '~', entries, protocol)
+            }
+            // RESP2 clients can't receive attributes, so they only get the value.
+            (Frame::Attribute(_, value), Protocol::Resp2) => value.encode_into(result, protocol),
+            (Frame::Attribute(pairs, value), Protocol::Resp3) => {
+                encode_header(result, '|', pairs.len());
+                encode_pairs(result, pairs, protocol);
+                value.encode_into(result, protocol);
+            }
+            (Frame::Push(entries), Protocol::Resp2) => {
+                encode_aggregate(result, '*', entries, protocol)
+            }
+            (Frame::Push(entries), Protocol::Resp3) => {
+                encode_aggregate(result, '>', entries, protocol)
+            }
+        }
     }
 }
 
-impl Encoded for Error {
-    fn to_encoded_string(&self) -> String {
-        let mut result = String::from("-");
-        result.push_str(&self.value);
-        result.push_str(TERMINATOR);
+impl Encoded for Frame {
+    fn encode(&self, protocol: Protocol) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.encode_into(&mut result, protocol);
 
         result
     }
 }
 
-pub struct BulkString {
-    value: String,
+/// Writes a one line value. A line break in it would end the value early and leave the rest to
+/// be read as another reply, so any are written as spaces.
+fn encode_line(result: &mut Vec<u8>, prefix: char, line: &str) {
+    result.push(prefix as u8);
+    result.extend(line.bytes().map(|b| match b {
+        b'\r' | b'\n' => b' ',
+        b => b,
+    }));
+    result.extend_from_slice(TERMINATOR);
 }
 
-impl BulkString {
-    pub fn new(v: String) -> Box<BulkString> {
-        Box::new(BulkString { value: v })
-    }
+fn encode_header(result: &mut Vec<u8>, prefix: char, len: usize) {
+    encode_line(result, prefix, &len.to_string());
 }
 
-impl Encoded for BulkString {
-    fn to_encoded_string(&self) -> String {
-        let mut result = String::from("$");
-        result.push_str(&self.value.len().to_string());
-        result.push_str(TERMINATOR);
-
-        result.push_str(&self.value);
-        result.push_str(TERMINATOR);
+fn encode_bulk(result: &mut Vec<u8>, prefix: char, value: &[u8]) {
+    encode_header(result, prefix, value.len());
+    result.extend_from_slice(value);
+    result.extend_from_slice(TERMINATOR);
+}
 
-        result
+fn encode_aggregate(result: &mut Vec<u8>, prefix: char, entries: &[Frame], protocol: Protocol) {
+    encode_header(result, prefix, entries.len());
+    for e in entries {
+        e.encode_into(result, protocol);
     }
 }
 
-pub struct Array {
-    entries: Vec<Box<BulkString>>,
+fn encode_pairs(result: &mut Vec<u8>, pairs: &[(Frame, Frame)], protocol: Protocol) {
+    for (k, v) in pairs {
+        k.encode_into(result, protocol);
+        v.encode_into(result, protocol);
+    }
 }
 
-impl Array {
-    pub fn new() -> Box<Array> {
-        Box::new(Array {
-            entries: Vec::new(),
-        })
+/// Formats a double the way Redis prints them: the shortest digits that read back as the same
+/// number, switching to an exponent for very large or very small magnitudes.
+pub fn format_double(n: f64) -> String {
+    if n.is_nan() {
+        return String::from("nan");
     }
-
-    pub fn push_bulk_string(&mut self, s: Box<BulkString>) {
-        self.entries.push(s);
+    if n.is_infinite() {
+        return String::from(if n > 0.0 { "inf" } else { "-inf" });
     }
-}
 
-impl Encoded for Array {
-    fn to_encoded_string(&self) -> String {
-        let mut result = String::from("*");
-        result.push_str(&self.entries.len().to_string());
-        result.push_str(TERMINATOR);
-
-        for e in self.entries.iter() {
-            result.push_str(&e.to_encoded_string());
-        }
+    let scientific = format!("{:e}", n);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
 
-        result
+    if (-4..17).contains(&exponent) {
+        format!("{}", n)
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exponent.abs())
     }
 }
 
@@ -126,31 +248,43 @@ mod tests {
 
     #[test]
     fn test_integer_to_encoded_string() {
-        assert_eq!(Integer::new(1).to_encoded_string(), ":1\r\n");
-        assert_eq!(Integer::new(0).to_encoded_string(), ":0\r\n");
-        assert_eq!(Integer::new(-1).to_encoded_string(), ":-1\r\n");
+        assert_eq!(Frame::Integer(1).to_encoded_bytes(), b":1\r\n");
+        assert_eq!(Frame::Integer(0).to_encoded_bytes(), b":0\r\n");
+        assert_eq!(Frame::Integer(-1).to_encoded_bytes(), b":-1\r\n");
 
         assert_eq!(
-            Integer::new(i64::MAX).to_encoded_string(),
-            ":9223372036854775807\r\n"
+            Frame::Integer(i64::MAX).to_encoded_bytes(),
+            b":9223372036854775807\r\n"
         );
 
         assert_eq!(
-            Integer::new(i64::MIN).to_encoded_string(),
-            ":-9223372036854775808\r\n"
+            Frame::Integer(i64::MIN).to_encoded_bytes(),
+            b":-9223372036854775808\r\n"
         );
     }
 
     #[test]
     fn test_error_to_encoded_string() {
-        let s: Box<Error> = Error::new(String::from("error message"));
-        assert_eq!(s.to_encoded_string(), "-error message\r\n");
+        let s = Frame::error("error message");
+        assert_eq!(s.to_encoded_bytes(), b"-error message\r\n");
     }
 
     #[test]
     fn test_error_to_encoded_string_empty() {
-        let s: Box<Error> = Error::new(String::from(""));
-        assert_eq!(s.to_encoded_string(), "-\r\n");
+        let s = Frame::error("");
+        assert_eq!(s.to_encoded_bytes(), b"-\r\n");
+    }
+
+    #[test]
+    fn test_line_breaks_in_simple_values_are_spaces() {
+        assert_eq!(
+            Frame::error("ERR a\r\n+OK").to_encoded_bytes(),
+            b"-ERR a  +OK\r\n"
+        );
+        assert_eq!(
+            Frame::SimpleString(String::from("x\ny")).to_encoded_bytes(),
+            b"+x y\r\n"
+        );
     }
 
     #[test]
@@ -158,9 +292,9 @@ mod tests {
         let mut actual: Vec<u8> = Vec::new();
         let mut writer = ByteWriter::endian(&mut actual, BigEndian);
 
-        let s: Box<Error> = Error::new(String::from("error message"));
+        let s = Frame::error("error message");
 
-        to_encoded_binary(s, &mut writer);
+        to_encoded_binary(&s, &mut writer);
 
         assert_eq!(
             actual,
@@ -173,23 +307,23 @@ mod tests {
         let mut actual: Vec<u8> = Vec::new();
         let mut writer = ByteWriter::endian(&mut actual, BigEndian);
 
-        let s: Box<Error> = Error::new(String::from(""));
+        let s = Frame::error("");
 
-        to_encoded_binary(s, &mut writer);
+        to_encoded_binary(&s, &mut writer);
 
         assert_eq!(actual, vec![45, 13, 10]);
     }
 
     #[test]
     fn test_simple_string_to_encoded_string() {
-        let s: Box<SimpleString> = SimpleString::new(String::from("meow"));
-        assert_eq!(s.to_encoded_string(), "+meow\r\n");
+        let s = Frame::SimpleString(String::from("meow"));
+        assert_eq!(s.to_encoded_bytes(), b"+meow\r\n");
     }
 
     #[test]
     fn test_simple_string_to_encoded_string_empty() {
-        let s: Box<SimpleString> = SimpleString::new(String::from(""));
-        assert_eq!(s.to_encoded_string(), "+\r\n");
+        let s = Frame::SimpleString(String::from(""));
+        assert_eq!(s.to_encoded_bytes(), b"+\r\n");
     }
 
     #[test]
@@ -197,9 +331,9 @@ mod tests {
         let mut actual: Vec<u8> = Vec::new();
         let mut writer = ByteWriter::endian(&mut actual, BigEndian);
 
-        let s: Box<SimpleString> = SimpleString::new(String::from("hello"));
+        let s = Frame::SimpleString(String::from("hello"));
 
-        to_encoded_binary(s, &mut writer);
+        to_encoded_binary(&s, &mut writer);
 
         assert_eq!(actual, vec![43, 104, 101, 108, 108, 111, 13, 10]);
     }
@@ -209,9 +343,9 @@ mod tests {
         let mut actual: Vec<u8> = Vec::new();
         let mut writer = ByteWriter::endian(&mut actual, BigEndian);
 
-        let s: Box<SimpleString> = SimpleString::new(String::from(""));
+        let s = Frame::SimpleString(String::from(""));
 
-        to_encoded_binary(s, &mut writer);
+        to_encoded_binary(&s, &mut writer);
 
         assert_eq!(actual, vec![43, 13, 10]);
     }
@@ -221,11 +355,9 @@ mod tests {
         let mut actual: Vec<u8> = Vec::new();
         let mut writer = ByteWriter::endian(&mut actual, BigEndian);
 
-        let mut array: Box<Array> = Array::new();
-        array.push_bulk_string(BulkString::new(String::from("hello")));
-        array.push_bulk_string(BulkString::new(String::from("world")));
+        let array = Frame::bulks(["hello", "world"]);
 
-        to_encoded_binary(array, &mut writer);
+        to_encoded_binary(&array, &mut writer);
 
         assert_eq!(
             actual,
@@ -238,21 +370,43 @@ mod tests {
 
     #[test]
     fn test_array_to_encoded_string() {
-        let mut array = Array::new();
-        array.push_bulk_string(BulkString::new(String::from("hello")));
-        array.push_bulk_string(BulkString::new(String::from("world")));
+        let array = Frame::Array(vec![Frame::bulk("hello"), Frame::bulk("world")]);
 
         assert_eq!(
-            array.to_encoded_string(),
-            "*2\r\n$5\r\nhello\r\n$5\r\nworld\r\n"
+            array.to_encoded_bytes(),
+            b"*2\r\n$5\r\nhello\r\n$5\r\nworld\r\n"
         );
     }
 
     #[test]
     fn test_array_to_encoded_string_empty() {
-        let array = Array::new();
+        let array = Frame::Array(vec![]);
+
+        assert_eq!(array.to_encoded_bytes(), b"*0\r\n",);
+    }
 
-        assert_eq!(array.to_encoded_string(), "*0\r\n",);
+    #[test]
+    fn test_array_of_mixed_types() {
+        let array = Frame::Array(vec![
+            Frame::Integer(1),
+            Frame::Array(vec![Frame::bulk("a"), Frame::NullBulkString]),
+            Frame::NullArray,
+            Frame::ok(),
+            Frame::error("ERR oops"),
+        ]);
+
+        assert_eq!(
+            array.to_encoded_bytes(),
+            b"*5\r\n:1\r\n*2\r\n$1\r\na\r\n$-1\r\n*-1\r\n+OK\r\n-ERR oops\r\n"
+        );
+    }
+
+    #[test]
+    fn test_nulls() {
+        assert_eq!(Frame::NullBulkString.to_encoded_bytes(), b"$-1\r\n");
+        assert_eq!(Frame::NullArray.to_encoded_bytes(), b"*-1\r\n");
+        assert_eq!(Frame::NullBulkString.encode(Protocol::Resp3), b"_\r\n");
+        assert_eq!(Frame::NullArray.encode(Protocol::Resp3), b"_\r\n");
     }
 
     #[test]
@@ -260,8 +414,8 @@ mod tests {
         let mut actual: Vec<u8> = Vec::new();
         let mut writer = ByteWriter::endian(&mut actual, BigEndian);
 
-        let bulk_string = BulkString::new(String::from("hello"));
-        to_encoded_binary(bulk_string, &mut writer);
+        let bulk_string = Frame::bulk("hello");
+        to_encoded_binary(&bulk_string, &mut writer);
 
         assert_eq!(
             actual,
@@ -269,15 +423,164 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bulk_string_is_binary_safe() {
+        let bulk_string = Frame::bulk(vec![0xff, 0x00, b'\r', b'\n']);
+
+        assert_eq!(bulk_string.to_encoded_bytes(), b"$4\r\n\xff\x00\r\n\r\n");
+    }
+
     #[test]
     fn test_bulk_string_to_encoded_binary_empty() {
         let mut actual: Vec<u8> = Vec::new();
         let mut writer = ByteWriter::endian(&mut actual, BigEndian);
 
-        let bulk_string = BulkString::new(String::from(""));
-        to_encoded_binary(bulk_string, &mut writer);
+        let bulk_string = Frame::bulk("");
+        to_encoded_binary(&bulk_string, &mut writer);
 
         // expected: "+0\r\n\r\n" as bytes
         assert_eq!(actual, vec![36, 48, 13, 10, 13, 10]);
     }
+
+    #[test]
+    fn test_null_encoding() {
+        assert_eq!(Frame::Null.encode(Protocol::Resp3), b"_\r\n");
+        assert_eq!(Frame::Null.encode(Protocol::Resp2), b"$-1\r\n");
+    }
+
+    #[test]
+    fn test_boolean_encoding() {
+        assert_eq!(Frame::Boolean(true).encode(Protocol::Resp3), b"#t\r\n");
+        assert_eq!(Frame::Boolean(false).encode(Protocol::Resp3), b"#f\r\n");
+        assert_eq!(Frame::Boolean(true).encode(Protocol::Resp2), b":1\r\n");
+        assert_eq!(Frame::Boolean(false).encode(Protocol::Resp2), b":0\r\n");
+    }
+
+    #[test]
+    fn test_double_encoding() {
+        assert_eq!(Frame::Double(1.5).encode(Protocol::Resp3), b",1.5\r\n");
+        assert_eq!(
+            Frame::Double(f64::INFINITY).encode(Protocol::Resp3),
+            b",inf\r\n"
+        );
+        assert_eq!(Frame::Double(1.5).encode(Protocol::Resp2), b"$3\r\n1.5\r\n");
+    }
+
+    #[test]
+    fn test_format_double() {
+        assert_eq!(format_double(0.0), "0");
+        assert_eq!(format_double(10.0), "10");
+        assert_eq!(format_double(-3.25), "-3.25");
+        assert_eq!(format_double(0.1), "0.1");
+        assert_eq!(format_double(0.0001), "0.0001");
+        assert_eq!(format_double(0.00001), "1e-05");
+        assert_eq!(format_double(1e300), "1e+300");
+        assert_eq!(format_double(1.5e17), "1.5e+17");
+        assert_eq!(format_double(12345678901234567.0), "12345678901234568");
+        assert_eq!(format_double(f64::NEG_INFINITY), "-inf");
+        assert_eq!(format_double(f64::NAN), "nan");
+    }
+
+    #[test]
+    fn test_big_number_encoding() {
+        let n = String::from("3492890328409238509324850943850943825024385");
+        assert_eq!(
+            Frame::BigNumber(n.clone()).encode(Protocol::Resp3),
+            format!("({}\r\n", n).into_bytes()
+        );
+        assert_eq!(
+            Frame::BigNumber(n.clone()).encode(Protocol::Resp2),
+            format!("${}\r\n{}\r\n", n.len(), n).into_bytes()
+        );
+    }
+
+    #[test]
+    fn test_bulk_error_encoding() {
+        let e = String::from("SYNTAX invalid syntax");
+        assert_eq!(
+            Frame::BulkError(e.clone()).encode(Protocol::Resp3),
+            b"!21\r\nSYNTAX invalid syntax\r\n"
+        );
+        assert_eq!(
+            Frame::BulkError(e).encode(Protocol::Resp2),
+            b"-SYNTAX invalid syntax\r\n"
+        );
+    }
+
+    #[test]
+    fn test_verbatim_string_encoding() {
+        let v = Frame::VerbatimString(String::from("txt"), String::from("Some string"));
+        assert_eq!(v.encode(Protocol::Resp3), b"=15\r\ntxt:Some string\r\n");
+        assert_eq!(v.encode(Protocol::Resp2), b"$11\r\nSome string\r\n");
+    }
+
+    #[test]
+    fn test_map_encoding() {
+        let map = Frame::Map(vec![
+            (Frame::bulk("first"), Frame::Integer(1)),
+            (Frame::bulk("second"), Frame::Null),
+        ]);
+
+        assert_eq!(
+            map.encode(Protocol::Resp3),
+            b"%2\r\n$5\r\nfirst\r\n:1\r\n$6\r\nsecond\r\n_\r\n"
+        );
+        assert_eq!(
+            map.encode(Protocol::Resp2),
+            b"*4\r\n$5\r\nfirst\r\n:1\r\n$6\r\nsecond\r\n$-1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_pairs_encoding() {
+        let pairs = Frame::Pairs(vec![
+            (Frame::bulk("a"), Frame::Double(1.5)),
+            (Frame::bulk("a"), Frame::Double(2.0)),
+        ]);
+
+        assert_eq!(
+            pairs.encode(Protocol::Resp3),
+            b"*2\r\n*2\r\n$1\r\na\r\n,1.5\r\n*2\r\n$1\r\na\r\n,2\r\n"
+        );
+        assert_eq!(
+            pairs.encode(Protocol::Resp2),
+            b"*4\r\n$1\r\na\r\n$3\r\n1.5\r\n$1\r\na\r\n$1\r\n2\r\n"
+        );
+    }
+
+    #[test]
+    fn test_set_encoding() {
+        let set = Frame::Set(vec![Frame::Boolean(true), Frame::Integer(2)]);
+
+        assert_eq!(set.encode(Protocol::Resp3), b"~2\r\n#t\r\n:2\r\n");
+        assert_eq!(set.encode(Protocol::Resp2), b"*2\r\n:1\r\n:2\r\n");
+    }
+
+    #[test]
+    fn test_attribute_encoding() {
+        let attribute = Frame::Attribute(
+            vec![(Frame::bulk("ttl"), Frame::Integer(3600))],
+            Box::new(Frame::bulks(["a"])),
+        );
+
+        assert_eq!(
+            attribute.encode(Protocol::Resp3),
+            b"|1\r\n$3\r\nttl\r\n:3600\r\n*1\r\n$1\r\na\r\n"
+        );
+        assert_eq!(attribute.encode(Protocol::Resp2), b"*1\r\n$1\r\na\r\n");
+    }
+
+    #[test]
+    fn test_push_encoding() {
+        let push = Frame::Push(vec![Frame::bulk("message"), Frame::Double(2.5)]);
+
+        assert_eq!(
+            push.encode(Protocol::Resp3),
+            b">2\r\n$7\r\nmessage\r\n,2.5\r\n"
+        );
+        assert_eq!(
+            push.encode(Protocol::Resp2),
+            b"*2\r\n$7\r\nmessage\r\n$3\r\n2.5\r\n"
+        );
+    }
 }
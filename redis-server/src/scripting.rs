@@ -0,0 +1,875 @@
+use crate::blocking::Outcome;
+use crate::client::Client;
+use crate::commands::exec::Dispatch;
+use crate::commands::hello::SERVER_VERSION;
+use crate::commands::CommandError;
+use crate::db::Keyspace;
+use crate::functions::Functions;
+use crate::registry;
+use crate::resp::types::{format_double, Frame, Protocol};
+
+use mlua::{
+    Function, HookTriggers, Lua, LuaOptions, MultiValue, RegistryKey, StdLib, Table, Value,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How long a script may run, in milliseconds, before other clients are told the server is
+/// busy and the script may be killed.
+const DEFAULT_TIME_LIMIT: u64 = 5000;
+/// How many Lua instructions a script runs between checks on whether it has been killed.
+pub const KILL_CHECK_INTERVAL: u32 = 100_000;
+
+/// Whether a script is running. Connections look at this without the keyspace lock, which the
+/// script holds for as long as it runs.
+pub struct ScriptStatus {
+    running: Mutex<Option<Running>>,
+    time_limit: AtomicU64,
+}
+
+struct Running {
+    started: Instant,
+    wrote: bool,
+    killed: bool,
+}
+
+impl Default for ScriptStatus {
+    fn default() -> ScriptStatus {
+        ScriptStatus {
+            running: Mutex::default(),
+            time_limit: AtomicU64::new(DEFAULT_TIME_LIMIT),
+        }
+    }
+}
+
+impl ScriptStatus {
+    /// True once a script has been running for longer than the time limit. Clients are then
+    /// told the server is busy rather than left waiting, and may kill the script.
+    pub fn is_busy(&self) -> bool {
+        self.running()
+            .as_ref()
+            .is_some_and(|running| running.started.elapsed() > self.time_limit())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running().is_some()
+    }
+
+    /// Stops the running script, unless it has written to the keyspace already: its writes
+    /// can't be undone, and stopping it halfway would leave them incomplete.
+    pub fn kill(&self) -> Result<(), CommandError> {
+        match self.running().as_mut() {
+            None => Err(CommandError::Other(String::from(
+                "NOTBUSY No scripts in execution right now.",
+            ))),
+            Some(running) if running.wrote => Err(CommandError::Other(String::from(
+                "UNKILLABLE Sorry the script already executed write commands against the \
+                 dataset. You can either wait the script termination or kill the server in a \
+                 hard way using the SHUTDOWN NOSAVE command.",
+            ))),
+            Some(running) => {
+                running.killed = true;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn time_limit(&self) -> Duration {
+        Duration::from_millis(self.time_limit.load(Ordering::Relaxed))
+    }
+
+    pub fn set_time_limit(&self, limit: Duration) {
+        self.time_limit
+            .store(limit.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    fn start(&self) {
+        *self.running() = Some(Running {
+            started: Instant::now(),
+            wrote: false,
+            killed: false,
+        });
+    }
+
+    fn finish(&self) {
+        *self.running() = None;
+    }
+
+    fn wrote(&self) {
+        if let Some(running) = self.running().as_mut() {
+            running.wrote = true;
+        }
+    }
+
+    fn is_killed(&self) -> bool {
+        self.running()
+            .as_ref()
+            .is_some_and(|running| running.killed)
+    }
+
+    fn running(&self) -> MutexGuard<'_, Option<Running>> {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A script compiled and ready to run.
+struct Script {
+    function: RegistryKey,
+    /// Set by the `no-writes` flag, which makes the script read-only wherever it runs.
+    no_writes: bool,
+}
+
+/// The Lua interpreter scripts run in, and the scripts loaded into it by the SHA1 digest of
+/// their source.
+///
+/// Every script runs in the same interpreter, as in Redis, so a script is only compiled once
+/// however often it runs. Scripts can't create globals, which keeps one from affecting the
+/// next.
+pub struct Scripting {
+    lua: Lua,
+    scripts: HashMap<String, Script>,
+    /// Function libraries, which live in an interpreter of their own.
+    pub functions: Functions,
+    status: Arc<ScriptStatus>,
+}
+
+impl Default for Scripting {
+    fn default() -> Scripting {
+        Scripting::new(Arc::default())
+    }
+}
+
+impl Scripting {
+    fn new(status: Arc<ScriptStatus>) -> Scripting {
+        Scripting {
+            lua: new_interpreter(&status),
+            scripts: HashMap::new(),
+            functions: Functions::new(&status),
+            status,
+        }
+    }
+
+    pub fn status(&self) -> Arc<ScriptStatus> {
+        Arc::clone(&self.status)
+    }
+
+    /// Compiles the script unless it already was, returning its SHA1 digest.
+    pub fn load(&mut self, source: &[u8]) -> Result<String, CommandError> {
+        let sha = sha1_hex(source);
+        if self.scripts.contains_key(&sha) {
+            return Ok(sha);
+        }
+
+        let (flags, body) = shebang(source)?;
+        let mut no_writes = false;
+        for flag in flags {
+            match flag.as_str() {
+                "no-writes" => no_writes = true,
+                "allow-oom" | "allow-stale" | "no-cluster" | "allow-cross-slot-keys" => {}
+                _ => {
+                    return Err(CommandError::Other(format!(
+                        "ERR Unexpected flag in script shebang: {}",
+                        flag
+                    )))
+                }
+            }
+        }
+
+        let function = self
+            .lua
+            .load(body)
+            .set_name("@user_script")
+            .into_function()
+            .and_then(|function| self.lua.create_registry_value(function))
+            .map_err(|e| {
+                CommandError::Other(format!(
+                    "ERR Error compiling script (new function): {}",
+                    lua_message(&e)
+                ))
+            })?;
+
+        self.scripts.insert(
+            sha.clone(),
+            Script {
+                function,
+                no_writes,
+            },
+        );
+        Ok(sha)
+    }
+
+    pub fn exists(&self, sha: &str) -> bool {
+        self.scripts.contains_key(&sha.to_ascii_lowercase())
+    }
+
+    /// Forgets every script, starting over with a fresh interpreter. Functions are kept.
+    pub fn flush(&mut self) {
+        self.lua = new_interpreter(&self.status);
+        self.scripts.clear();
+    }
+
+    /// Runs a loaded script, with `KEYS` and `ARGV` set from `keys` and `argv`. Commands the
+    /// script calls go through `dispatch`, with the keyspace held for the whole run, so no
+    /// other client's commands see the script half done.
+    ///
+    /// The reply is converted for a client speaking `protocol`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &mut self,
+        sha: &str,
+        keys: Vec<Vec<u8>>,
+        argv: Vec<Vec<u8>>,
+        read_only: bool,
+        protocol: Protocol,
+        ks: &mut Keyspace,
+        dispatch: Dispatch,
+    ) -> Result<Frame, CommandError> {
+        let sha = sha.to_ascii_lowercase();
+        let Some(script) = self.scripts.get(&sha) else {
+            return Err(CommandError::Other(String::from(
+                "NOSCRIPT No matching script. Please use EVAL.",
+            )));
+        };
+
+        let invocation = Invocation {
+            source: Source::Script(&sha),
+            keys,
+            argv,
+            read_only: read_only || script.no_writes,
+            protocol,
+        };
+        invoke(
+            &self.lua,
+            &script.function,
+            invocation,
+            &self.status,
+            ks,
+            dispatch,
+        )
+    }
+}
+
+/// Where the Lua being run came from, which decides how it is given its keys and arguments and
+/// how errors point at it.
+#[derive(Clone, Copy)]
+pub enum Source<'a> {
+    /// A script, by its SHA1 digest. It finds its keys and arguments in `KEYS` and `ARGV`.
+    Script(&'a str),
+    /// A function, by name. It is called with its keys and arguments.
+    Function(&'a str),
+}
+
+impl Source<'_> {
+    fn name(&self) -> &str {
+        match self {
+            Source::Script(name) | Source::Function(name) => name,
+        }
+    }
+
+    /// The name Lua gives the chunk in error messages.
+    fn chunk(&self) -> &'static str {
+        match self {
+            Source::Script(_) => "user_script",
+            Source::Function(_) => "user_function",
+        }
+    }
+}
+
+/// A script or function to run, and what to run it with.
+pub struct Invocation<'a> {
+    pub source: Source<'a>,
+    pub keys: Vec<Vec<u8>>,
+    pub argv: Vec<Vec<u8>>,
+    pub read_only: bool,
+    /// The protocol of the client that asked for the run, which the reply is converted for.
+    pub protocol: Protocol,
+}
+
+/// Runs a script or function. Commands it calls go through `dispatch`, with the keyspace held
+/// for the whole run, so no other client's commands see it half done.
+pub fn invoke(
+    lua: &Lua,
+    function: &RegistryKey,
+    invocation: Invocation,
+    status: &ScriptStatus,
+    ks: &mut Keyspace,
+    dispatch: Dispatch,
+) -> Result<Frame, CommandError> {
+    let (sender, _) = mpsc::channel();
+    let source = invocation.source;
+    let context = Mutex::new(Context {
+        ks,
+        client: Client::new(sender),
+        dispatch,
+        read_only: invocation.read_only,
+        source,
+        status,
+    });
+
+    status.start();
+    let reply = run(lua, function, invocation, &context);
+    status.finish();
+
+    reply.map_err(|e| script_error(&e, source))
+}
+
+/// What a script's calls to Redis need while it runs.
+struct Context<'a> {
+    ks: &'a mut Keyspace,
+    /// Stands in for a client connection. Its protocol is the one the script asked for with
+    /// `redis.setresp`.
+    client: Client,
+    dispatch: Dispatch,
+    read_only: bool,
+    source: Source<'a>,
+    status: &'a ScriptStatus,
+}
+
+fn run(
+    lua: &Lua,
+    function: &RegistryKey,
+    invocation: Invocation,
+    context: &Mutex<Context>,
+) -> mlua::Result<Frame> {
+    let function: Function = lua.registry_value(function)?;
+    let keys = strings(lua, invocation.keys)?;
+    let argv = strings(lua, invocation.argv)?;
+    let globals = lua.globals();
+
+    let reply = lua.scope(|scope| {
+        let redis: Table = globals.raw_get("redis")?;
+        redis.raw_set(
+            "call",
+            scope.create_function(|lua, args| call(lua, context, args, true))?,
+        )?;
+        redis.raw_set(
+            "pcall",
+            scope.create_function(|lua, args| call(lua, context, args, false))?,
+        )?;
+        redis.raw_set(
+            "setresp",
+            scope.create_function(|_, version: i64| {
+                lock(context).client.protocol = match version {
+                    2 => Protocol::Resp2,
+                    3 => Protocol::Resp3,
+                    _ => return Err(raised("ERR RESP version must be 2 or 3.")),
+                };
+                Ok(())
+            })?,
+        )?;
+
+        let value: Value = match invocation.source {
+            Source::Script(_) => {
+                globals.raw_set("KEYS", keys)?;
+                globals.raw_set("ARGV", argv)?;
+                function.call(())?
+            }
+            Source::Function(_) => function.call((keys, argv))?,
+        };
+        Ok(to_frame(&value, invocation.protocol))
+    });
+
+    // The functions only work while the script runs, so they mustn't outlive it.
+    let redis: Table = globals.raw_get("redis")?;
+    for name in ["call", "pcall", "setresp"] {
+        redis.raw_set(name, Value::Nil)?;
+    }
+    reply
+}
+
+fn lock<'a, 'b>(context: &'a Mutex<Context<'b>>) -> MutexGuard<'a, Context<'b>> {
+    context.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn strings(lua: &Lua, values: Vec<Vec<u8>>) -> mlua::Result<Table<'_>> {
+    let values = values
+        .iter()
+        .map(|value| lua.create_string(value))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    lua.create_sequence_from(values)
+}
+
+/// `redis.call` and `redis.pcall`: runs a command, converting its reply to Lua. An error reply
+/// is raised as a Lua error by `redis.call`, while `redis.pcall` returns it as a table.
+fn call<'lua>(
+    lua: &'lua Lua,
+    context: &Mutex<Context>,
+    args: MultiValue<'lua>,
+    raise: bool,
+) -> mlua::Result<Value<'lua>> {
+    let mut context = lock(context);
+    let reply = command(lua, &mut context, args).unwrap_or_else(Frame::from);
+
+    match reply {
+        Frame::Error(message) | Frame::BulkError(message) if raise => {
+            let line = lua.inspect_stack(1).map_or(0, |debug| debug.curr_line());
+            Err(raised(format!(
+                "{} script: {}, on @{}:{}.",
+                message,
+                context.source.name(),
+                context.source.chunk(),
+                line
+            )))
+        }
+        reply => to_lua(lua, &reply, context.client.protocol),
+    }
+}
+
+fn command(lua: &Lua, context: &mut Context, args: MultiValue) -> Result<Frame, CommandError> {
+    let mut args = args
+        .into_iter()
+        .map(|arg| match arg {
+            Value::String(_) | Value::Integer(_) | Value::Number(_) => lua
+                .coerce_string(arg)
+                .ok()
+                .flatten()
+                .map(|arg| arg.as_bytes().to_vec()),
+            _ => None,
+        })
+        .collect::<Option<std::collections::VecDeque<Vec<u8>>>>()
+        .ok_or_else(|| {
+            CommandError::Other(String::from(
+                "ERR Lua redis lib command arguments must be strings or integers",
+            ))
+        })?;
+
+    let Some(name) = args.pop_front() else {
+        return Err(CommandError::Other(String::from(
+            "ERR Please specify at least one argument for this redis lib call",
+        )));
+    };
+    let name = String::from_utf8_lossy(&name).to_lowercase();
+
+    let spec = registry::lookup(&name)
+        .and_then(|spec| spec.subcommand(args.make_contiguous()).ok())
+        .ok_or_else(|| {
+            CommandError::Other(String::from("ERR Unknown Redis command called from script"))
+        })?;
+    if spec.check_arity(args.len() + 1).is_err() {
+        return Err(CommandError::Other(String::from(
+            "ERR Wrong number of args calling Redis command from script",
+        )));
+    }
+    if spec.has_flag("noscript") {
+        return Err(CommandError::Other(String::from(
+            "ERR This Redis command is not allowed from script",
+        )));
+    }
+    if spec.has_flag("write") {
+        if context.read_only {
+            return Err(CommandError::Other(String::from(
+                "ERR Write commands are not allowed from read-only scripts.",
+            )));
+        }
+        context.status.wrote();
+    }
+
+    let Context {
+        ks,
+        client,
+        dispatch,
+        ..
+    } = context;
+    Ok(match dispatch(&name, &mut args, client, ks) {
+        Some(Outcome::Ready(reply)) => reply,
+        // Nothing else runs until the script is done, so a command that would block times out
+        // straight away.
+        Some(Outcome::Blocked(blocked)) => {
+            ks.unblock(blocked.id);
+            blocked.timeout_reply
+        }
+        None => Frame::Null,
+    })
+}
+
+/// Converts a command's reply for Lua, following Redis's conversion table for the protocol the
+/// script asked for.
+fn to_lua<'lua>(lua: &'lua Lua, frame: &Frame, protocol: Protocol) -> mlua::Result<Value<'lua>> {
+    let resp3 = protocol == Protocol::Resp3;
+    let field = |name: &str, value: Value<'lua>| -> mlua::Result<Value<'lua>> {
+        let table = lua.create_table()?;
+        table.raw_set(name, value)?;
+        Ok(Value::Table(table))
+    };
+    let string =
+        |s: &[u8]| -> mlua::Result<Value<'lua>> { Ok(Value::String(lua.create_string(s)?)) };
+
+    match frame {
+        Frame::Integer(n) => Ok(Value::Number(*n as f64)),
+        Frame::BulkString(bytes) => string(bytes),
+        Frame::SimpleString(s) => field("ok", string(s.as_bytes())?),
+        Frame::Error(s) | Frame::BulkError(s) => field("err", string(s.as_bytes())?),
+        Frame::NullBulkString | Frame::NullArray | Frame::Null if resp3 => Ok(Value::Nil),
+        Frame::NullBulkString | Frame::NullArray | Frame::Null => Ok(Value::Boolean(false)),
+        Frame::Boolean(b) if resp3 => Ok(Value::Boolean(*b)),
+        Frame::Boolean(b) => Ok(Value::Number(if *b { 1.0 } else { 0.0 })),
+        Frame::Double(n) if resp3 => field("double", Value::Number(*n)),
+        Frame::Double(n) => string(format_double(*n).as_bytes()),
+        Frame::BigNumber(n) if resp3 => field("big_number", string(n.as_bytes())?),
+        Frame::BigNumber(n) => string(n.as_bytes()),
+        Frame::VerbatimString(format, text) if resp3 => {
+            let verbatim = lua.create_table()?;
+            verbatim.raw_set("format", format.as_str())?;
+            verbatim.raw_set("string", text.as_str())?;
+            field("verbatim_string", Value::Table(verbatim))
+        }
+        Frame::VerbatimString(_, text) => string(text.as_bytes()),
+        Frame::Array(items) | Frame::Push(items) => sequence(lua, items, protocol),
+        Frame::Set(items) if resp3 => {
+            let set = lua.create_table()?;
+            for item in items {
+                set.raw_set(to_lua(lua, item, protocol)?, true)?;
+            }
+            field("set", Value::Table(set))
+        }
+        Frame::Set(items) => sequence(lua, items, protocol),
+        Frame::Map(pairs) if resp3 => {
+            let map = lua.create_table()?;
+            for (key, value) in pairs {
+                map.raw_set(to_lua(lua, key, protocol)?, to_lua(lua, value, protocol)?)?;
+            }
+            field("map", Value::Table(map))
+        }
+        Frame::Pairs(pairs) if resp3 => {
+            let pairs: Vec<Frame> = pairs
+                .iter()
+                .map(|(first, second)| Frame::Array(vec![first.clone(), second.clone()]))
+                .collect();
+            sequence(lua, &pairs, protocol)
+        }
+        Frame::Map(pairs) | Frame::Pairs(pairs) => {
+            let flat: Vec<Frame> = pairs
+                .iter()
+                .flat_map(|(key, value)| [key.clone(), value.clone()])
+                .collect();
+            sequence(lua, &flat, protocol)
+        }
+        Frame::Attribute(_, value) => to_lua(lua, value, protocol),
+    }
+}
+
+fn sequence<'lua>(
+    lua: &'lua Lua,
+    items: &[Frame],
+    protocol: Protocol,
+) -> mlua::Result<Value<'lua>> {
+    let table = lua.create_table_with_capacity(items.len(), 0)?;
+    for (i, item) in items.iter().enumerate() {
+        table.raw_set(i + 1, to_lua(lua, item, protocol)?)?;
+    }
+    Ok(Value::Table(table))
+}
+
+/// Converts what a script returned into a reply for a client speaking `protocol`, following
+/// Redis's conversion table.
+fn to_frame(value: &Value, protocol: Protocol) -> Frame {
+    match value {
+        Value::Boolean(b) if protocol == Protocol::Resp3 => Frame::Boolean(*b),
+        Value::Boolean(true) => Frame::Integer(1),
+        Value::Integer(n) => Frame::Integer(*n),
+        Value::Number(n) => Frame::Integer(*n as i64),
+        Value::String(s) => Frame::bulk(s.as_bytes()),
+        Value::Table(table) => table_to_frame(table, protocol),
+        _ => Frame::Null,
+    }
+}
+
+/// Tables with an `err` or `ok` field are errors and status replies, and RESP3 types are
+/// wrapped the same way. Any other table is an array, up to its first nil.
+fn table_to_frame(table: &Table, protocol: Protocol) -> Frame {
+    let get = |name: &str| table.raw_get::<_, Value>(name).unwrap_or(Value::Nil);
+    let text = |value: &Value| match value {
+        Value::String(s) => Some(s.to_string_lossy().into_owned()),
+        _ => None,
+    };
+
+    if let Some(error) = text(&get("err")) {
+        return Frame::Error(single_line(&error));
+    }
+    if let Some(status) = text(&get("ok")) {
+        return Frame::SimpleString(single_line(&status));
+    }
+    if let Value::Number(n) = get("double") {
+        return Frame::Double(n);
+    }
+    if let Value::Integer(n) = get("double") {
+        return Frame::Double(n as f64);
+    }
+    if let Some(n) = text(&get("big_number")) {
+        return Frame::BigNumber(n);
+    }
+    if let Value::Table(map) = get("map") {
+        return Frame::Map(
+            map.pairs::<Value, Value>()
+                .filter_map(Result::ok)
+                .map(|(key, value)| (to_frame(&key, protocol), to_frame(&value, protocol)))
+                .collect(),
+        );
+    }
+    if let Value::Table(set) = get("set") {
+        return Frame::Set(
+            set.pairs::<Value, Value>()
+                .filter_map(Result::ok)
+                .map(|(member, _)| to_frame(&member, protocol))
+                .collect(),
+        );
+    }
+    if let Value::Table(verbatim) = get("verbatim_string") {
+        let format = verbatim.raw_get::<_, String>("format");
+        let text = verbatim.raw_get::<_, String>("string");
+        if let (Ok(format), Ok(text)) = (format, text) {
+            return Frame::VerbatimString(format, text);
+        }
+    }
+
+    let mut items = Vec::new();
+    for i in 1.. {
+        match table.raw_get::<_, Value>(i) {
+            Ok(Value::Nil) | Err(_) => break,
+            Ok(item) => items.push(to_frame(&item, protocol)),
+        }
+    }
+    Frame::Array(items)
+}
+
+/// An error raised by the server rather than the script itself, whose message is already what
+/// the client should see.
+#[derive(Debug)]
+pub struct Raised(pub String);
+
+impl fmt::Display for Raised {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Raised {}
+
+pub fn raised(message: impl Into<String>) -> mlua::Error {
+    mlua::Error::external(Raised(message.into()))
+}
+
+/// The error a client gets when its script fails.
+fn script_error(e: &mlua::Error, source: Source) -> CommandError {
+    if let Some(Raised(message)) = find_raised(e) {
+        return CommandError::Other(single_line(message));
+    }
+
+    // Lua puts where the error happened in front of the message, as in "user_script:3: ...".
+    let message = lua_message(e);
+    let line = message
+        .strip_prefix(source.chunk())
+        .and_then(|rest| rest.strip_prefix(':'))
+        .and_then(|rest| rest.split(':').next())
+        .and_then(|line| line.parse::<u32>().ok())
+        .unwrap_or(0);
+    CommandError::Other(format!(
+        "ERR {} script: {}, on @{}:{}.",
+        single_line(&message),
+        source.name(),
+        source.chunk(),
+        line
+    ))
+}
+
+/// Error and status replies are a single line, so line breaks in what a script gives for one
+/// are turned into spaces, as Redis does.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+pub fn find_raised(e: &mlua::Error) -> Option<&Raised> {
+    match e {
+        mlua::Error::CallbackError { cause, .. } => find_raised(cause),
+        mlua::Error::ExternalError(e) => e.downcast_ref::<Raised>(),
+        _ => None,
+    }
+}
+
+/// The message of a Lua error, without the traceback.
+pub fn lua_message(e: &mlua::Error) -> String {
+    match e {
+        mlua::Error::SyntaxError { message, .. } | mlua::Error::RuntimeError(message) => message
+            .split("\nstack traceback:")
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        mlua::Error::CallbackError { cause, .. } => lua_message(cause),
+        e => e.to_string(),
+    }
+}
+
+/// Splits the flags off a script that starts with a `#!lua` line. The line itself is left
+/// empty rather than removed, so line numbers in errors still match the source.
+pub fn shebang(source: &[u8]) -> Result<(Vec<String>, Vec<u8>), CommandError> {
+    if !source.starts_with(b"#!") {
+        return Ok((Vec::new(), source.to_vec()));
+    }
+
+    let end = source
+        .iter()
+        .position(|&b| b == b'\n')
+        .unwrap_or(source.len());
+    let line = String::from_utf8_lossy(&source[2..end]);
+    let mut parts = line.split_whitespace();
+    if parts.next() != Some("lua") {
+        return Err(CommandError::Other(String::from(
+            "ERR Unexpected engine in script shebang",
+        )));
+    }
+
+    let mut flags = Vec::new();
+    for part in parts {
+        match part.strip_prefix("flags=") {
+            Some(list) => flags.extend(
+                list.split(',')
+                    .filter(|flag| !flag.is_empty())
+                    .map(String::from),
+            ),
+            None => {
+                return Err(CommandError::Other(format!(
+                    "ERR Unknown lua shebang option: {}",
+                    part
+                )))
+            }
+        }
+    }
+
+    Ok((flags, source[end..].to_vec()))
+}
+
+pub fn sha1_hex(bytes: &[u8]) -> String {
+    sha1_smol::Sha1::from(bytes).digest().to_string()
+}
+
+/// Sets up an interpreter with the libraries Redis gives scripts and the `redis` table, and
+/// stops scripts from creating globals.
+pub fn new_interpreter(status: &Arc<ScriptStatus>) -> Lua {
+    interpreter(status).expect("the Lua interpreter could not be set up")
+}
+
+fn interpreter(status: &Arc<ScriptStatus>) -> mlua::Result<Lua> {
+    let lua = Lua::new_with(
+        StdLib::TABLE | StdLib::STRING | StdLib::MATH,
+        LuaOptions::default(),
+    )?;
+    let globals = lua.globals();
+    // Scripts have no business reading files.
+    globals.raw_set("loadfile", Value::Nil)?;
+    globals.raw_set("dofile", Value::Nil)?;
+
+    let redis = lua.create_table()?;
+    redis.raw_set(
+        "sha1hex",
+        lua.create_function(|_, s: mlua::String| Ok(sha1_hex(s.as_bytes())))?,
+    )?;
+    redis.raw_set(
+        "status_reply",
+        lua.create_function(|lua, s: mlua::String| lua.create_table_from([("ok", s)]))?,
+    )?;
+    redis.raw_set(
+        "error_reply",
+        lua.create_function(|lua, s: mlua::String| lua.create_table_from([("err", s)]))?,
+    )?;
+    // There is no log file to write to.
+    redis.raw_set("log", lua.create_function(|_, _: MultiValue| Ok(()))?)?;
+    redis.raw_set("set_repl", lua.create_function(|_, _: MultiValue| Ok(()))?)?;
+    redis.raw_set("replicate_commands", lua.create_function(|_, ()| Ok(true))?)?;
+    for (name, value) in [
+        ("LOG_DEBUG", 0),
+        ("LOG_VERBOSE", 1),
+        ("LOG_NOTICE", 2),
+        ("LOG_WARNING", 3),
+        ("REPL_NONE", 0),
+        ("REPL_AOF", 1),
+        ("REPL_SLAVE", 2),
+        ("REPL_REPLICA", 2),
+        ("REPL_ALL", 3),
+    ] {
+        redis.raw_set(name, value)?;
+    }
+    redis.raw_set("REDIS_VERSION", SERVER_VERSION)?;
+    let version_num = SERVER_VERSION
+        .split('.')
+        .fold(0, |num, part| num * 256 + part.parse::<i64>().unwrap_or(0));
+    redis.raw_set("REDIS_VERSION_NUM", version_num)?;
+    globals.raw_set("redis", redis)?;
+
+    lua.load(
+        r#"
+        setmetatable(_G, {
+            __newindex = function(_, name)
+                error("Script attempted to create global variable '" .. tostring(name) .. "'", 2)
+            end,
+            __index = function(_, name)
+                error("Script attempted to access nonexistent global variable '" .. tostring(name) .. "'", 2)
+            end,
+        })
+        "#,
+    )
+    .exec()?;
+
+    watch_for_kill(&lua, status);
+
+    drop(globals);
+    Ok(lua)
+}
+
+/// Has the interpreter stop whatever it runs once the script is killed.
+pub fn watch_for_kill(lua: &Lua, status: &Arc<ScriptStatus>) {
+    let status = Arc::clone(status);
+    lua.set_hook(
+        HookTriggers::new().every_nth_instruction(KILL_CHECK_INTERVAL),
+        move |_, _| {
+            if status.is_killed() {
+                return Err(raised("ERR Script killed by user with SCRIPT KILL..."));
+            }
+            Ok(())
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha1_hex() {
+        assert_eq!(
+            sha1_hex(b"return 1"),
+            "e0e1f9fabfc9d4800c877a703b823ac0578ff8db"
+        );
+    }
+
+    #[test]
+    fn test_shebang() {
+        assert_eq!(
+            shebang(b"#!lua flags=no-writes,allow-oom\nreturn 1"),
+            Ok((
+                vec![String::from("no-writes"), String::from("allow-oom")],
+                b"\nreturn 1".to_vec()
+            ))
+        );
+        assert_eq!(shebang(b"return 1"), Ok((Vec::new(), b"return 1".to_vec())));
+        assert!(shebang(b"#!python\n").is_err());
+    }
+
+    #[test]
+    fn test_kill_needs_a_running_script_that_has_not_written() {
+        let status = ScriptStatus::default();
+        assert!(status.kill().is_err());
+
+        status.start();
+        assert_eq!(status.kill(), Ok(()));
+        assert!(status.is_killed());
+
+        status.start();
+        status.wrote();
+        assert!(status.kill().is_err());
+        status.finish();
+        assert!(!status.is_busy());
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::{HashMap, HashSet};
+
+/// The keys clients are watching for their transactions, and which of those clients have seen
+/// a watched key change since.
+///
+/// Anything that writes to a key, deletes it, expires it or flushes it away touches it, which
+/// flags every client watching it. That catches a key changed and then changed back just as
+/// well as one that stays changed, which comparing values couldn't.
+#[derive(Default)]
+pub struct WatchedKeys {
+    watchers: HashMap<Vec<u8>, HashSet<u64>>,
+    /// The keys each client watches, so they can all be let go of at once.
+    keys: HashMap<u64, Vec<Vec<u8>>>,
+    touched: HashSet<u64>,
+}
+
+impl WatchedKeys {
+    pub fn is_empty(&self) -> bool {
+        self.watchers.is_empty()
+    }
+
+    pub fn watch(&mut self, client: u64, key: Vec<u8>) {
+        if self.watchers.entry(key.clone()).or_default().insert(client) {
+            self.keys.entry(client).or_default().push(key);
+        }
+    }
+
+    /// Forgets the client's watched keys, and whether any of them were touched.
+    pub fn unwatch_all(&mut self, client: u64) {
+        self.touched.remove(&client);
+        for key in self.keys.remove(&client).unwrap_or_default() {
+            if let Some(watchers) = self.watchers.get_mut(&key) {
+                watchers.remove(&client);
+                if watchers.is_empty() {
+                    self.watchers.remove(&key);
+                }
+            }
+        }
+    }
+
+    pub fn is_watched(&self, key: &[u8]) -> bool {
+        self.watchers.contains_key(key)
+    }
+
+    /// The keys the client watches.
+    pub fn keys(&self, client: u64) -> &[Vec<u8>] {
+        self.keys.get(&client).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every key some client watches.
+    pub fn all_keys(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.watchers.keys()
+    }
+
+    /// Flags every client watching `key`.
+    pub fn touch(&mut self, key: &[u8]) {
+        if let Some(watchers) = self.watchers.get(key) {
+            self.touched.extend(watchers);
+        }
+    }
+
+    /// Whether any of the client's watched keys have been touched since it watched them.
+    pub fn is_touched(&self, client: u64) -> bool {
+        self.touched.contains(&client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_touch_flags_only_watchers_of_the_key() {
+        let mut watched = WatchedKeys::default();
+        watched.watch(1, b"a".to_vec());
+        watched.watch(2, b"b".to_vec());
+        watched.watch(2, b"b".to_vec());
+        assert_eq!(watched.keys(2), [b"b".to_vec()]);
+
+        watched.touch(b"a");
+        watched.touch(b"other");
+        assert!(watched.is_touched(1));
+        assert!(!watched.is_touched(2));
+
+        watched.unwatch_all(1);
+        assert!(!watched.is_touched(1));
+        watched.touch(b"a");
+        assert!(!watched.is_touched(1));
+
+        watched.unwatch_all(2);
+        assert!(watched.is_empty());
+    }
+}